@@ -9,6 +9,9 @@ use log::warn;
 
 use kas::draw::{Colour, InputState, TextClass};
 
+// Fraction by which disabled text is mixed towards the background colour
+const DISABLED_DIM: f32 = 0.5;
+
 /// Provides standard theme colours
 #[derive(Clone, Debug)]
 pub struct ThemeColours {
@@ -16,6 +19,10 @@ pub struct ThemeColours {
     pub background: Colour,
     /// Colour for frames (not always used)
     pub frame: Colour,
+    /// Background colour of alternate rows in a striped grid
+    pub stripe: Colour,
+    /// Colour of lines between grid cells
+    pub grid_line: Colour,
     /// Background colour of `EditBox`
     pub bg: Colour,
     /// Background colour of `EditBox` (disabled state)
@@ -44,6 +51,10 @@ pub struct ThemeColours {
     pub button_depressed: Colour,
     /// Colour of mark within a `CheckBox` or `RadioBox`
     pub checkbox: Colour,
+    /// Colour of links within text
+    pub link: Colour,
+    /// Colour of links within text when hovered by the mouse
+    pub link_highlighted: Colour,
 }
 
 impl ThemeColours {
@@ -69,6 +80,8 @@ impl ThemeColours {
         ThemeColours {
             background: Colour::grey(1.0),
             frame: Colour::grey(0.7),
+            stripe: Colour::grey(0.94),
+            grid_line: Colour::grey(0.8),
             bg: Colour::grey(1.0),
             bg_disabled: Colour::grey(0.85),
             bg_error: Colour::new(1.0, 0.5, 0.5),
@@ -83,6 +96,8 @@ impl ThemeColours {
             button_highlighted: Colour::new(0.25, 0.8, 1.0),
             button_depressed: Colour::new(0.15, 0.525, 0.75),
             checkbox: Colour::new(0.2, 0.7, 1.0),
+            link: Colour::new(0.1, 0.35, 0.8),
+            link_highlighted: Colour::new(0.2, 0.55, 1.0),
         }
     }
 
@@ -90,6 +105,7 @@ impl ThemeColours {
     pub fn grey() -> Self {
         let mut col = ThemeColours::new();
         col.background = Colour::grey(0.8);
+        col.stripe = Colour::grey(0.75);
         col
     }
 
@@ -98,6 +114,8 @@ impl ThemeColours {
         ThemeColours {
             background: Colour::grey(0.9),
             frame: Colour::new(0.8, 0.8, 0.9),
+            stripe: Colour::grey(0.85),
+            grid_line: Colour::new(0.8, 0.8, 0.9),
             bg: Colour::grey(1.0),
             bg_disabled: Colour::grey(0.85),
             bg_error: Colour::new(1.0, 0.5, 0.5),
@@ -112,6 +130,8 @@ impl ThemeColours {
             button_highlighted: Colour::new(1.0, 0.95, 0.6),
             button_depressed: Colour::new(0.8, 0.72, 0.24),
             checkbox: Colour::grey(0.4),
+            link: Colour::new(0.1, 0.3, 0.7),
+            link_highlighted: Colour::new(0.2, 0.5, 0.9),
         }
     }
 
@@ -120,6 +140,8 @@ impl ThemeColours {
         ThemeColours {
             background: Colour::grey(0.2),
             frame: Colour::grey(0.4),
+            stripe: Colour::grey(0.25),
+            grid_line: Colour::grey(0.35),
             bg: Colour::grey(0.1),
             bg_disabled: Colour::grey(0.3),
             bg_error: Colour::new(1.0, 0.5, 0.5),
@@ -134,6 +156,8 @@ impl ThemeColours {
             button_highlighted: Colour::new(0.6, 0.3, 0.1),
             button_depressed: Colour::new(0.3, 0.1, 0.1),
             checkbox: Colour::new(0.5, 0.1, 0.1),
+            link: Colour::new(0.5, 0.7, 1.0),
+            link_highlighted: Colour::new(0.75, 0.85, 1.0),
         }
     }
 
@@ -189,6 +213,17 @@ impl ThemeColours {
         })
     }
 
+    /// Get colour for a link within text, depending on state
+    pub fn link_state(&self, state: InputState) -> Colour {
+        if state.disabled {
+            self.button_disabled
+        } else if state.hover || state.depress || state.nav_focus {
+            self.link_highlighted
+        } else {
+            self.link
+        }
+    }
+
     /// Get background highlight colour of a menu entry, if any
     pub fn menu_entry(&self, state: InputState) -> Option<Colour> {
         if state.depress || state.nav_focus {
@@ -214,4 +249,80 @@ impl ThemeColours {
             TextClass::Edit | TextClass::EditMulti => self.text,
         }
     }
+
+    /// Get text colour from class and disabled state
+    ///
+    /// Disabled text is dimmed by mixing its colour half-way towards the
+    /// background colour.
+    pub fn text_state(&self, class: TextClass, disabled: bool) -> Colour {
+        let col = self.text_class(class);
+        match disabled {
+            false => col,
+            true => mix(col, self.background, DISABLED_DIM),
+        }
+    }
+}
+
+// Linear interpolation from a to b
+fn mix(a: Colour, b: Colour, f: f32) -> Colour {
+    let g = 1.0 - f;
+    Colour {
+        r: a.r * g + b.r * f,
+        g: a.g * g + b.g * f,
+        b: a.b * g + b.b * f,
+        a: a.a * g + b.a * f,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn check_mark_only_when_checked() {
+        let cols = ThemeColours::new();
+        let hover = InputState {
+            hover: true,
+            ..Default::default()
+        };
+        let disabled = InputState {
+            disabled: true,
+            ..Default::default()
+        };
+        for state in [InputState::default(), hover, disabled].iter().cloned() {
+            assert!(cols.check_mark_state(state, true).is_some());
+            assert_eq!(cols.check_mark_state(state, false), None);
+        }
+
+        // While depressed, a mark previews the new state
+        let depress = InputState {
+            depress: true,
+            ..Default::default()
+        };
+        assert!(cols.check_mark_state(depress, false).is_some());
+    }
+
+    #[test]
+    fn disabled_dimmed() {
+        for cols in [ThemeColours::new(), ThemeColours::dark()].iter() {
+            let label = cols.text_state(TextClass::Label, false);
+            assert_eq!(label, cols.label_text);
+            let dimmed = cols.text_state(TextClass::Label, true);
+            assert_ne!(dimmed, label);
+            // Dimmed text lies between the text and background colours
+            let (a, b) = (
+                label.r.min(cols.background.r),
+                label.r.max(cols.background.r),
+            );
+            assert!(a <= dimmed.r && dimmed.r <= b);
+
+            let disabled = InputState {
+                disabled: true,
+                ..Default::default()
+            };
+            let enabled = InputState::default();
+            assert_ne!(cols.button_state(disabled), cols.button_state(enabled));
+            assert_ne!(cols.bg_col(disabled), cols.bg_col(enabled));
+        }
+    }
 }
@@ -9,6 +9,7 @@
 
 use std::any::Any;
 use std::f32;
+use std::time::Duration;
 
 use kas::draw::{self, TextClass};
 use kas::geom::{Size, Vec2};
@@ -33,6 +34,8 @@ pub struct DimensionsParams {
     pub scrollbar_size: Vec2,
     /// Slider minimum handle size
     pub slider_size: Vec2,
+    /// Edit marker blink interval (`None`: no blinking)
+    pub edit_marker_blink: Option<Duration>,
 }
 
 /// Dimensions available within [`DimensionsWindow`]
@@ -42,6 +45,7 @@ pub struct Dimensions {
     pub dpp: f32,
     pub pt_size: f32,
     pub font_marker_width: f32,
+    pub font_marker_blink: Option<Duration>,
     pub line_height: u32,
     pub min_line_length: u32,
     pub ideal_line_length: u32,
@@ -69,6 +73,7 @@ impl Dimensions {
             dpp,
             pt_size,
             font_marker_width: (1.6 * scale_factor).round().max(1.0),
+            font_marker_blink: params.edit_marker_blink,
             line_height,
             min_line_length: (8.0 * dpem).round() as u32,
             ideal_line_length: (24.0 * dpem).round() as u32,
@@ -216,6 +221,10 @@ impl<'a> draw::SizeHandle for SizeHandle<'a> {
         self.dims.font_marker_width
     }
 
+    fn edit_marker_blink(&self) -> Option<Duration> {
+        self.dims.font_marker_blink
+    }
+
     fn button_surround(&self) -> (Size, Size) {
         let s = Size::uniform(self.dims.button_frame);
         (s, s)
@@ -226,6 +235,16 @@ impl<'a> draw::SizeHandle for SizeHandle<'a> {
         (s, s)
     }
 
+    fn content_inset(&self, class: TextClass) -> Margins {
+        // Rounded frames are drawn with corner radius equal to frame width
+        let frame = match class {
+            TextClass::Label | TextClass::LabelSingle => return Margins::ZERO,
+            TextClass::Button => self.dims.button_frame,
+            TextClass::Edit | TextClass::EditMulti => self.dims.frame,
+        };
+        Margins::uniform(corner_inset(frame, frame) as u16)
+    }
+
     fn checkbox(&self) -> Size {
         Size::uniform(self.dims.checkbox)
     }
@@ -245,3 +264,59 @@ impl<'a> draw::SizeHandle for SizeHandle<'a> {
         (size, 2 * size.0)
     }
 }
+
+/// Inset required to keep a rectangle clear of a frame with rounded corners
+///
+/// Given the frame's width and the corner radius of its outer edge, this
+/// returns the distance from the outer edge at which a rectangle's corners
+/// do not overlap the frame.
+fn corner_inset(frame: u32, radius: u32) -> u32 {
+    // The corner of the inset rect must lie within the corner circle:
+    // (radius - inset) * sqrt(2) <= radius - frame
+    let r = radius as f32;
+    let inset = r - (r - frame as f32) * f32::consts::FRAC_1_SQRT_2;
+    frame.max(inset.ceil() as u32)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn corner_inset_radius() {
+        // Square corners or corners within the frame: use the frame width
+        assert_eq!(corner_inset(4, 0), 4);
+        assert_eq!(corner_inset(4, 4), 4);
+        // Larger radius requires a larger inset
+        assert!(corner_inset(4, 12) > corner_inset(4, 4));
+        assert_eq!(corner_inset(4, 12), 7);
+    }
+
+    #[test]
+    fn custom_dimensions() {
+        use kas::event::VoidMsg;
+        use kas::layout::AxisInfo;
+        use kas::widget::CheckBoxBare;
+        use kas::Layout;
+
+        let _ = kas::text::fonts::fonts().load_default();
+        let defaults = crate::FlatTheme::new().dimensions().clone();
+        let roomy = DimensionsParams {
+            outer_margin: defaults.outer_margin + 4.0,
+            frame_size: defaults.frame_size + 2.0,
+            ..defaults.clone()
+        };
+        let rules = |params: DimensionsParams| {
+            let dims = Dimensions::new(params, 12.0, 1.0);
+            let mut size_handle = SizeHandle::new(&dims);
+            let axis = AxisInfo::new(false, None);
+            CheckBoxBare::<VoidMsg>::new().size_rules(&mut size_handle, axis)
+        };
+        let (a, b) = (rules(defaults), rules(roomy));
+
+        // A check box includes the frame on each side; margins are outside
+        assert_eq!(b.min_size(), a.min_size() + 4);
+        assert_eq!(b.margins().0, a.margins().0 + 4);
+        assert_eq!(b.margins().1, a.margins().1 + 4);
+    }
+}
@@ -9,21 +9,23 @@
 
 use std::f32;
 use std::ops::Range;
+use std::time::Duration;
 
 use crate::{Dimensions, DimensionsParams, DimensionsWindow, Theme, ThemeColours, Window};
 use kas::draw::{
-    self, ClipRegion, Colour, Draw, DrawRounded, DrawShared, DrawText, InputState, Pass,
-    SizeHandle, TextClass,
+    self, CaretShape, ClipRegion, Colour, Draw, DrawRounded, DrawShared, DrawText, ImageId,
+    InputState, Pass, SizeHandle, TextClass,
 };
 use kas::geom::*;
 use kas::text::format::FormattableText;
-use kas::text::{AccelString, Effect, Text, TextApi, TextDisplay};
+use kas::text::{AccelString, Effect, RichText, Text, TextApi, TextDisplay};
 use kas::{Direction, Directional, ThemeAction, ThemeApi};
 
 /// A theme with flat (unshaded) rendering
 #[derive(Clone, Debug)]
 pub struct FlatTheme {
     pt_size: f32,
+    dims: DimensionsParams,
     cols: ThemeColours,
 }
 
@@ -32,6 +34,7 @@ impl FlatTheme {
     pub fn new() -> Self {
         FlatTheme {
             pt_size: 12.0,
+            dims: DIMS,
             cols: ThemeColours::new(),
         }
     }
@@ -44,6 +47,21 @@ impl FlatTheme {
         self
     }
 
+    /// Set dimensions (margins, frame sizes, etc.)
+    ///
+    /// Dimensions are specified in logical pixels and are scaled by the
+    /// window's DPI factor. Use [`FlatTheme::dimensions`] to start from the
+    /// defaults of this theme.
+    pub fn with_dimensions(mut self, dims: DimensionsParams) -> Self {
+        self.dims = dims;
+        self
+    }
+
+    /// Get the current dimensions
+    pub fn dimensions(&self) -> &DimensionsParams {
+        &self.dims
+    }
+
     /// Set the colour scheme
     ///
     /// If no scheme by this name is found the scheme is left unchanged.
@@ -62,6 +80,7 @@ const DIMS: DimensionsParams = DimensionsParams {
     button_frame: 6.0,
     scrollbar_size: Vec2::splat(8.0),
     slider_size: Vec2(12.0, 25.0),
+    edit_marker_blink: Some(Duration::from_millis(500)),
 };
 
 pub struct DrawHandle<'a, D: Draw> {
@@ -91,11 +110,11 @@ where
     }
 
     fn new_window(&self, _draw: &mut D::Draw, dpi_factor: f32) -> Self::Window {
-        DimensionsWindow::new(DIMS, self.pt_size, dpi_factor)
+        DimensionsWindow::new(self.dims.clone(), self.pt_size, dpi_factor)
     }
 
     fn update_window(&self, window: &mut Self::Window, dpi_factor: f32) {
-        window.dims = Dimensions::new(DIMS, self.pt_size, dpi_factor);
+        window.dims = Dimensions::new(self.dims.clone(), self.pt_size, dpi_factor);
     }
 
     #[cfg(not(feature = "gat"))]
@@ -219,7 +238,7 @@ impl<'a, D: Draw + DrawRounded + DrawText> draw::DrawHandle for DrawHandle<'a, D
         class: ClipRegion,
         f: &mut dyn FnMut(&mut dyn draw::DrawHandle),
     ) {
-        let rect = rect + self.offset;
+        let rect = class.clip_rect(rect + self.offset, self.rect);
         let depth = self.pass.depth() + super::relative_region_depth(class);
         let pass = self.draw.add_clip_region(rect, depth);
         if depth < self.pass.depth() {
@@ -250,6 +269,18 @@ impl<'a, D: Draw + DrawRounded + DrawText> draw::DrawHandle for DrawHandle<'a, D
             .rounded_frame(self.pass, outer, inner, 0.5, self.cols.frame);
     }
 
+    fn titled_frame(&mut self, rect: Rect, title: Rect) {
+        self.outer_frame(rect);
+        // Clear the gap in the top edge by drawing over it
+        let margin = self.window.dims.inner_margin;
+        let gap = Rect::new(
+            title.pos - Coord(margin as i32, 0),
+            title.size + Size(2 * margin, 0),
+        );
+        let quad = Quad::from(gap + self.offset);
+        self.draw.rect(self.pass, quad, self.cols.background);
+    }
+
     fn menu_frame(&mut self, rect: Rect) {
         let outer = Quad::from(rect + self.offset);
         let inner = outer.shrink(self.window.dims.frame as f32);
@@ -266,6 +297,31 @@ impl<'a, D: Draw + DrawRounded + DrawText> draw::DrawHandle for DrawHandle<'a, D
             .rounded_frame(self.pass, outer, inner, 0.5, self.cols.frame);
     }
 
+    fn grid_stripe(&mut self, rect: Rect) {
+        let quad = Quad::from(rect + self.offset);
+        self.draw.rect(self.pass, quad, self.cols.stripe);
+    }
+
+    fn grid_line(&mut self, rect: Rect) {
+        let quad = Quad::from(rect + self.offset);
+        self.draw.rect(self.pass, quad, self.cols.grid_line);
+    }
+
+    fn gradient(&mut self, rect: Rect, cols: (Colour, Colour), dir: Direction) {
+        let quad = Quad::from(rect + self.offset);
+        self.draw.gradient_rect(self.pass, quad, cols, dir);
+    }
+
+    fn swatch(&mut self, rect: Rect, colour: Colour) {
+        let quad = Quad::from(rect + self.offset);
+        self.draw.rect(self.pass, quad, Colour { a: 1.0, ..colour });
+    }
+
+    fn image(&mut self, id: ImageId, rect: Rect) {
+        let quad = Quad::from(rect + self.offset);
+        self.draw.image(self.pass, id, quad);
+    }
+
     fn text_offset(
         &mut self,
         pos: Coord,
@@ -273,30 +329,45 @@ impl<'a, D: Draw + DrawRounded + DrawText> draw::DrawHandle for DrawHandle<'a, D
         offset: Coord,
         text: &TextDisplay,
         class: TextClass,
+        disabled: bool,
     ) {
         let pos = pos + self.offset;
-        let col = self.cols.text_class(class);
+        let col = self.cols.text_state(class, disabled);
         self.draw
             .text(self.pass, pos.into(), bounds, offset.into(), text, col);
     }
 
-    fn text_effects(&mut self, pos: Coord, offset: Coord, text: &dyn TextApi, class: TextClass) {
+    fn text_effects(
+        &mut self,
+        pos: Coord,
+        offset: Coord,
+        text: &dyn TextApi,
+        class: TextClass,
+        disabled: bool,
+    ) {
         self.draw.text_col_effects(
             self.pass,
             (pos + self.offset).into(),
             text.env().bounds.into(),
             offset.into(),
             text.display(),
-            self.cols.text_class(class),
+            self.cols.text_state(class, disabled),
             text.effect_tokens(),
         );
     }
 
-    fn text_accel(&mut self, pos: Coord, text: &Text<AccelString>, state: bool, class: TextClass) {
+    fn text_accel(
+        &mut self,
+        pos: Coord,
+        text: &Text<AccelString>,
+        state: bool,
+        class: TextClass,
+        disabled: bool,
+    ) {
         let pos = Vec2::from(pos + self.offset);
         let offset = Vec2::ZERO;
         let bounds = text.env().bounds.into();
-        let col = self.cols.text_class(class);
+        let col = self.cols.text_state(class, disabled);
         if state {
             let effects = text.text().effect_tokens();
             self.draw
@@ -307,6 +378,48 @@ impl<'a, D: Draw + DrawRounded + DrawText> draw::DrawHandle for DrawHandle<'a, D
         }
     }
 
+    fn text_rich(&mut self, pos: Coord, text: &Text<RichText>, class: TextClass, disabled: bool) {
+        let pos = Vec2::from(pos + self.offset);
+        let bounds = text.env().bounds.into();
+        let effects = text
+            .text()
+            .colour_effects(self.cols.text_state(class, disabled));
+        self.draw
+            .text_effects(self.pass, pos, bounds, Vec2::ZERO, text.as_ref(), &effects);
+    }
+
+    fn text_links(
+        &mut self,
+        pos: Coord,
+        text: &Text<RichText>,
+        links: &[(Range<usize>, InputState)],
+        class: TextClass,
+        disabled: bool,
+    ) {
+        let pos = Vec2::from(pos + self.offset);
+        let bounds = text.env().bounds.into();
+
+        // Highlight the background of links with nav focus
+        for (range, state) in links {
+            if let Some(col) = self.cols.nav_region(*state) {
+                for (p1, p2) in &text.as_ref().highlight_lines(range.clone()) {
+                    let quad = Quad::with_coords(pos + Vec2::from(*p1), pos + Vec2::from(*p2));
+                    self.draw.rect(self.pass, quad, col);
+                }
+            }
+        }
+
+        let links: Vec<_> = links
+            .iter()
+            .map(|(range, state)| (range.clone(), self.cols.link_state(*state)))
+            .collect();
+        let effects = text
+            .text()
+            .link_effects(self.cols.text_state(class, disabled), &links);
+        self.draw
+            .text_effects(self.pass, pos, bounds, Vec2::ZERO, text.as_ref(), &effects);
+    }
+
     fn text_selected_range(
         &mut self,
         pos: Coord,
@@ -315,10 +428,11 @@ impl<'a, D: Draw + DrawRounded + DrawText> draw::DrawHandle for DrawHandle<'a, D
         text: &TextDisplay,
         range: Range<usize>,
         class: TextClass,
+        disabled: bool,
     ) {
         let pos = Vec2::from(pos + self.offset);
         let offset = Vec2::from(offset);
-        let col = self.cols.text_class(class);
+        let col = self.cols.text_state(class, disabled);
 
         // Draw background:
         for (p1, p2) in &text.highlight_lines(range.clone()) {
@@ -362,7 +476,8 @@ impl<'a, D: Draw + DrawRounded + DrawText> draw::DrawHandle for DrawHandle<'a, D
         offset: Coord,
         text: &TextDisplay,
         class: TextClass,
-        byte: usize,
+        range: Range<usize>,
+        shape: CaretShape,
     ) {
         let width = self.window.dims.font_marker_width;
         let p = Vec2::from(pos + self.offset);
@@ -370,18 +485,30 @@ impl<'a, D: Draw + DrawRounded + DrawText> draw::DrawHandle for DrawHandle<'a, D
         let bounds = Quad::with_pos_and_size(p, bounds);
         let pos = Vec2::from(pos - offset + self.offset);
 
-        let mut col = self.cols.text_class(class);
-        for cursor in text.text_glyph_pos(byte).rev() {
-            let mut p1 = pos + Vec2::from(cursor.pos);
-            let mut p2 = p1;
-            p1.1 -= cursor.ascent;
-            p2.1 -= cursor.descent;
-            p2.0 += width;
+        // At the end of the text we have no next glyph, so we use half the
+        // line height.
+        let default_width = self.window.dims.line_height as f32 / 2.0;
+        let cell_width = caret_cell_width(text, range.clone(), default_width);
+
+        let mut primary = true;
+        for cursor in text.text_glyph_pos(range.start).rev() {
+            // Secondary markers are drawn grey; text is drawn over a block
+            // marker, so we use the selection background colour.
+            let col = match (primary, shape) {
+                (false, _) => self.cols.button_disabled,
+                (true, CaretShape::Block) => self.cols.text_sel_bg,
+                (true, _) => self.cols.text_class(class),
+            };
+            primary = false;
+
+            let p = pos + Vec2::from(cursor.pos);
+            let metrics = (cursor.ascent, cursor.descent);
+            let (p1, p2) = caret_coords(p, metrics, cursor.is_ltr(), shape, width, cell_width);
             let quad = Quad::with_coords(p1, p2);
             if let Some(quad) = bounds.intersection(&quad) {
                 self.draw.rect(self.pass, quad, col);
             }
-            if cursor.embedding_level() > 0 {
+            if shape == CaretShape::Bar && cursor.embedding_level() > 0 {
                 // Add a hat to indicate directionality.
                 let height = width;
                 let quad = if cursor.is_ltr() {
@@ -393,8 +520,6 @@ impl<'a, D: Draw + DrawRounded + DrawText> draw::DrawHandle for DrawHandle<'a, D
                     self.draw.rect(self.pass, quad, col);
                 }
             }
-            // hack to make secondary marker grey:
-            col = self.cols.button_disabled;
         }
     }
 
@@ -405,6 +530,66 @@ impl<'a, D: Draw + DrawRounded + DrawText> draw::DrawHandle for DrawHandle<'a, D
         }
     }
 
+    fn menu_mark(&mut self, rect: Rect, radio: bool, state: InputState) {
+        let col = match self.cols.check_mark_state(state, true) {
+            Some(col) => col,
+            None => return,
+        };
+        let quad = Quad::from(rect + self.offset);
+        let margin = self.window.dims.inner_margin as f32 + quad.size().min_comp() / 6.0;
+        let inner = quad.shrink(margin);
+        if radio {
+            self.draw.circle(self.pass, inner, 0.0, col);
+        } else {
+            // A check mark: a short stroke down then a long stroke up
+            let radius = quad.size().min_comp() / 16.0;
+            let size = inner.size();
+            let p1 = Vec2(inner.a.0, inner.a.1 + size.1 * 0.5);
+            let p2 = Vec2(inner.a.0 + size.0 * 0.4, inner.b.1);
+            self.draw.rounded_line(self.pass, p1, p2, radius, col);
+            self.draw
+                .rounded_line(self.pass, p2, inner.ba(), radius, col);
+        }
+    }
+
+    fn badge(&mut self, rect: Rect, state: InputState) {
+        let outer = Quad::from(rect + self.offset);
+        let inner = outer.shrink(outer.size().min_comp() / 2.0);
+        let col = self.cols.button_state(state);
+        self.draw.rounded_frame(self.pass, outer, inner, 0.0, col);
+    }
+
+    fn chevron(&mut self, rect: Rect, dir: Direction, state: InputState) {
+        let quad = Quad::from(rect + self.offset);
+        if let Some(col) = self.cols.nav_region(state) {
+            let inner = quad.shrink(self.window.dims.inner_margin as f32);
+            self.draw.rounded_frame(self.pass, quad, inner, 0.5, col);
+        }
+
+        let col = match state.disabled {
+            true => self.cols.button_disabled,
+            false => self.cols.text_class(TextClass::Label),
+        };
+        let size = quad.size().min_comp();
+        let radius = size / 16.0;
+        let half = (size / 2.0 - self.window.dims.inner_margin as f32) / 2.0 - radius;
+        let centre = (quad.a + quad.b) * 0.5;
+        // Unit vector in direction dir and its perpendicular
+        let d = match dir {
+            Direction::Right => Vec2(1.0, 0.0),
+            Direction::Down => Vec2(0.0, 1.0),
+            Direction::Left => Vec2(-1.0, 0.0),
+            Direction::Up => Vec2(0.0, -1.0),
+        };
+        let p = Vec2(-d.1, d.0);
+        let tip = centre + d * (half * 0.5);
+        let base = centre - d * (half * 0.5);
+        let (b1, b2) = (base + p * half, base - p * half);
+        self.draw.rounded_line(self.pass, b1, tip, radius, col);
+        self.draw.rounded_line(self.pass, tip, b2, radius, col);
+        self.draw.rounded_line(self.pass, b2, b1, radius, col);
+    }
+
     fn button(&mut self, rect: Rect, state: InputState) {
         let outer = Quad::from(rect + self.offset);
         let col = self.cols.button_state(state);
@@ -479,3 +664,80 @@ impl<'a, D: Draw + DrawRounded + DrawText> draw::DrawHandle for DrawHandle<'a, D
         self.draw_handle(h_rect, state);
     }
 }
+
+// Width of the character cell covered by `range` (see
+// `DrawHandle::edit_marker`), or `default` if unknown
+fn caret_cell_width(text: &TextDisplay, range: Range<usize>, default: f32) -> f32 {
+    if range.end > range.start {
+        let start = text.text_glyph_pos(range.start).next_back();
+        let end = text.text_glyph_pos(range.end).next_back();
+        if let (Some(start), Some(end)) = (start, end) {
+            if start.pos.1 == end.pos.1 {
+                return (end.pos.0 - start.pos.0).abs();
+            }
+        }
+    }
+    default
+}
+
+// Corners of a caret of the given `shape` at marker position `p`
+//
+// Here `metrics` are the marker's `(ascent, descent)` and `width` is the
+// line width of bar and underline markers.
+fn caret_coords(
+    p: Vec2,
+    metrics: (f32, f32),
+    ltr: bool,
+    shape: CaretShape,
+    width: f32,
+    cell_width: f32,
+) -> (Vec2, Vec2) {
+    let (mut p1, mut p2) = (p, p);
+    p1.1 -= metrics.0;
+    p2.1 -= metrics.1;
+    match shape {
+        CaretShape::Bar => p2.0 += width,
+        CaretShape::Block | CaretShape::Underline => {
+            if ltr {
+                p2.0 += cell_width;
+            } else {
+                p1.0 -= cell_width;
+            }
+            if shape == CaretShape::Underline {
+                p1.1 = p2.1 - width;
+            }
+        }
+    }
+    (p1, p2)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn caret_shapes() {
+        let _ = kas::text::fonts::fonts().load_default();
+        let mut text = Text::new_single("ab");
+        text.update_env(|env| env.set_bounds(Vec2(200.0, 50.0)));
+        let x = |index| text.text_glyph_pos(index).next_back().unwrap().pos.0;
+        let advance = x(1) - x(0);
+        assert!(advance > 0.0);
+
+        // Block and underline carets cover the next glyph
+        let cell_width = caret_cell_width(text.as_ref(), 0..1, 5.0);
+        assert_eq!(cell_width, advance);
+        let (p, metrics, width) = (Vec2(10.0, 20.0), (12.0, -4.0), 2.0);
+        let (p1, p2) = caret_coords(p, metrics, true, CaretShape::Block, width, cell_width);
+        assert_eq!((p1, p2), (Vec2(10.0, 8.0), Vec2(10.0 + advance, 24.0)));
+        let (p1, p2) = caret_coords(p, metrics, true, CaretShape::Underline, width, cell_width);
+        assert_eq!((p1, p2), (Vec2(10.0, 22.0), Vec2(10.0 + advance, 24.0)));
+
+        // A bar caret has fixed width
+        let (p1, p2) = caret_coords(p, metrics, true, CaretShape::Bar, width, cell_width);
+        assert_eq!((p1, p2), (Vec2(10.0, 8.0), Vec2(12.0, 24.0)));
+
+        // At the end of the text, the default width is used
+        assert_eq!(caret_cell_width(text.as_ref(), 2..2, 5.0), 5.0);
+    }
+}
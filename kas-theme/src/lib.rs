@@ -55,9 +55,31 @@ pub type StackDst<T> = stack_dst_::ValueA<T, [usize; 8]>;
 /// The initial [`Pass`] value for a window
 // NOTE: depth values between 0 and 1 are drawn.
 pub const START_PASS: Pass = Pass::new_pass_with_depth(0, 0.01);
+
+// Depth of a new clip region relative to its parent; see ClipRegion for the
+// ordering of layers. Each layer step must exceed the total of any nested
+// Scroll regions.
 fn relative_region_depth(class: ClipRegion) -> f32 {
     match class {
         ClipRegion::Popup => 0.01,
+        ClipRegion::Drag => 0.02,
+        ClipRegion::Tooltip => 0.03,
         ClipRegion::Scroll => -1e-5,
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn region_layers() {
+        let depth = |class| START_PASS.depth() + relative_region_depth(class);
+        // A deeply nested scroll region remains below the pop-up layer
+        let scroll = START_PASS.depth() + 100.0 * relative_region_depth(ClipRegion::Scroll);
+        assert!(scroll < depth(ClipRegion::Popup));
+        assert!(depth(ClipRegion::Popup) < depth(ClipRegion::Drag));
+        assert!(depth(ClipRegion::Drag) < depth(ClipRegion::Tooltip));
+        assert!(depth(ClipRegion::Tooltip) < 1.0);
+    }
+}
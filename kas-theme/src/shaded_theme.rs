@@ -7,20 +7,22 @@
 
 use std::f32;
 use std::ops::Range;
+use std::time::Duration;
 
 use crate::{Dimensions, DimensionsParams, DimensionsWindow, Theme, ThemeColours, Window};
 use kas::draw::{
-    self, ClipRegion, Colour, Draw, DrawRounded, DrawShaded, DrawShared, DrawText, InputState,
-    Pass, SizeHandle, TextClass,
+    self, CaretShape, ClipRegion, Colour, Draw, DrawRounded, DrawShaded, DrawShared, DrawText,
+    ImageId, InputState, Pass, SizeHandle, TextClass,
 };
 use kas::geom::*;
-use kas::text::{AccelString, Text, TextApi, TextDisplay};
+use kas::text::{AccelString, RichText, Text, TextApi, TextDisplay};
 use kas::{Direction, Directional, ThemeAction, ThemeApi};
 
 /// A theme using simple shading to give apparent depth to elements
 #[derive(Clone, Debug)]
 pub struct ShadedTheme {
     pt_size: f32,
+    dims: DimensionsParams,
     cols: ThemeColours,
 }
 
@@ -29,6 +31,7 @@ impl ShadedTheme {
     pub fn new() -> Self {
         ShadedTheme {
             pt_size: 12.0,
+            dims: DIMS,
             cols: ThemeColours::new(),
         }
     }
@@ -41,6 +44,21 @@ impl ShadedTheme {
         self
     }
 
+    /// Set dimensions (margins, frame sizes, etc.)
+    ///
+    /// Dimensions are specified in logical pixels and are scaled by the
+    /// window's DPI factor. Use [`ShadedTheme::dimensions`] to start from the
+    /// defaults of this theme.
+    pub fn with_dimensions(mut self, dims: DimensionsParams) -> Self {
+        self.dims = dims;
+        self
+    }
+
+    /// Get the current dimensions
+    pub fn dimensions(&self) -> &DimensionsParams {
+        &self.dims
+    }
+
     /// Set the colour scheme
     ///
     /// If no scheme by this name is found the scheme is left unchanged.
@@ -59,6 +77,7 @@ const DIMS: DimensionsParams = DimensionsParams {
     button_frame: 5.0,
     scrollbar_size: Vec2::splat(8.0),
     slider_size: Vec2(12.0, 25.0),
+    edit_marker_blink: Some(Duration::from_millis(500)),
 };
 
 pub struct DrawHandle<'a, D: Draw> {
@@ -88,11 +107,11 @@ where
     }
 
     fn new_window(&self, _draw: &mut D::Draw, dpi_factor: f32) -> Self::Window {
-        DimensionsWindow::new(DIMS, self.pt_size, dpi_factor)
+        DimensionsWindow::new(self.dims.clone(), self.pt_size, dpi_factor)
     }
 
     fn update_window(&self, window: &mut Self::Window, dpi_factor: f32) {
-        window.dims = Dimensions::new(DIMS, self.pt_size, dpi_factor);
+        window.dims = Dimensions::new(self.dims.clone(), self.pt_size, dpi_factor);
     }
 
     #[cfg(not(feature = "gat"))]
@@ -234,7 +253,7 @@ where
         class: ClipRegion,
         f: &mut dyn FnMut(&mut dyn draw::DrawHandle),
     ) {
-        let rect = rect + self.offset;
+        let rect = class.clip_rect(rect + self.offset, self.rect);
         let depth = self.pass.depth() + super::relative_region_depth(class);
         let pass = self.draw.add_clip_region(rect, depth);
         if depth < self.pass.depth() {
@@ -267,6 +286,17 @@ where
             .shaded_round_frame(self.pass, outer, inner, norm, col);
     }
 
+    fn titled_frame(&mut self, rect: Rect, title: Rect) {
+        self.outer_frame(rect);
+        let margin = self.window.dims.inner_margin;
+        let gap = Rect::new(
+            title.pos - Coord(margin as i32, 0),
+            title.size + Size(2 * margin, 0),
+        );
+        let quad = Quad::from(gap + self.offset);
+        self.draw.rect(self.pass, quad, self.cols.background);
+    }
+
     fn menu_frame(&mut self, rect: Rect) {
         let outer = Quad::from(rect + self.offset);
         let inner = outer.shrink(self.window.dims.frame as f32);
@@ -286,6 +316,26 @@ where
             .shaded_round_frame(self.pass, outer, inner, norm, col);
     }
 
+    fn grid_stripe(&mut self, rect: Rect) {
+        self.as_flat().grid_stripe(rect);
+    }
+
+    fn grid_line(&mut self, rect: Rect) {
+        self.as_flat().grid_line(rect);
+    }
+
+    fn gradient(&mut self, rect: Rect, cols: (Colour, Colour), dir: Direction) {
+        self.as_flat().gradient(rect, cols, dir);
+    }
+
+    fn swatch(&mut self, rect: Rect, colour: Colour) {
+        self.as_flat().swatch(rect, colour);
+    }
+
+    fn image(&mut self, id: ImageId, rect: Rect) {
+        self.as_flat().image(id, rect);
+    }
+
     fn text_offset(
         &mut self,
         pos: Coord,
@@ -293,16 +343,48 @@ where
         offset: Coord,
         text: &TextDisplay,
         class: TextClass,
+        disabled: bool,
     ) {
-        self.as_flat().text_offset(pos, bounds, offset, text, class);
+        self.as_flat()
+            .text_offset(pos, bounds, offset, text, class, disabled);
     }
 
-    fn text_effects(&mut self, pos: Coord, offset: Coord, text: &dyn TextApi, class: TextClass) {
-        self.as_flat().text_effects(pos, offset, text, class);
+    fn text_effects(
+        &mut self,
+        pos: Coord,
+        offset: Coord,
+        text: &dyn TextApi,
+        class: TextClass,
+        disabled: bool,
+    ) {
+        self.as_flat()
+            .text_effects(pos, offset, text, class, disabled);
     }
 
-    fn text_accel(&mut self, pos: Coord, text: &Text<AccelString>, state: bool, class: TextClass) {
-        self.as_flat().text_accel(pos, text, state, class);
+    fn text_accel(
+        &mut self,
+        pos: Coord,
+        text: &Text<AccelString>,
+        state: bool,
+        class: TextClass,
+        disabled: bool,
+    ) {
+        self.as_flat().text_accel(pos, text, state, class, disabled);
+    }
+
+    fn text_rich(&mut self, pos: Coord, text: &Text<RichText>, class: TextClass, disabled: bool) {
+        self.as_flat().text_rich(pos, text, class, disabled);
+    }
+
+    fn text_links(
+        &mut self,
+        pos: Coord,
+        text: &Text<RichText>,
+        links: &[(Range<usize>, InputState)],
+        class: TextClass,
+        disabled: bool,
+    ) {
+        self.as_flat().text_links(pos, text, links, class, disabled);
     }
 
     fn text_selected_range(
@@ -313,9 +395,10 @@ where
         text: &TextDisplay,
         range: Range<usize>,
         class: TextClass,
+        disabled: bool,
     ) {
         self.as_flat()
-            .text_selected_range(pos, bounds, offset, text, range, class);
+            .text_selected_range(pos, bounds, offset, text, range, class, disabled);
     }
 
     fn edit_marker(
@@ -325,16 +408,33 @@ where
         offset: Coord,
         text: &TextDisplay,
         class: TextClass,
-        byte: usize,
+        range: Range<usize>,
+        shape: CaretShape,
     ) {
         self.as_flat()
-            .edit_marker(pos, bounds, offset, text, class, byte);
+            .edit_marker(pos, bounds, offset, text, class, range, shape);
     }
 
     fn menu_entry(&mut self, rect: Rect, state: InputState) {
         self.as_flat().menu_entry(rect, state);
     }
 
+    fn menu_mark(&mut self, rect: Rect, radio: bool, state: InputState) {
+        self.as_flat().menu_mark(rect, radio, state);
+    }
+
+    fn chevron(&mut self, rect: Rect, dir: Direction, state: InputState) {
+        self.as_flat().chevron(rect, dir, state);
+    }
+
+    fn badge(&mut self, rect: Rect, state: InputState) {
+        let outer = Quad::from(rect + self.offset);
+        let inner = outer.shrink(outer.size().min_comp() / 2.0);
+        let col = self.cols.button_state(state);
+        self.draw
+            .shaded_round_frame(self.pass, outer, inner, (0.0, 0.6), col);
+    }
+
     fn button(&mut self, rect: Rect, state: InputState) {
         let outer = Quad::from(rect + self.offset);
         let inner = outer.shrink(self.window.dims.button_frame as f32);
@@ -0,0 +1,99 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Animation example
+//!
+//! Click the swatch to fade to the next colour.
+
+use std::time::Duration;
+
+use kas::anim::{Easing, Tween};
+use kas::draw::{Colour, Draw};
+use kas::geom::Quad;
+use kas::widget::Window;
+use kas::{event, prelude::*};
+use kas_wgpu::draw::DrawWindow;
+
+const COLOURS: [Colour; 4] = [
+    Colour::new(0.8, 0.2, 0.2),
+    Colour::new(0.9, 0.7, 0.1),
+    Colour::new(0.2, 0.6, 0.3),
+    Colour::new(0.2, 0.3, 0.8),
+];
+const FADE_DUR: Duration = Duration::from_millis(600);
+
+#[handler(handle=noauto)]
+#[derive(Clone, Debug, kas :: macros :: Widget)]
+struct Swatch {
+    #[widget_core]
+    core: kas::CoreData,
+    index: usize,
+    fade: Tween<Colour>,
+}
+
+impl Swatch {
+    fn new() -> Self {
+        let colour = COLOURS[0];
+        Swatch {
+            core: Default::default(),
+            index: 0,
+            fade: Tween::new(colour, colour, Duration::default()),
+        }
+    }
+}
+
+impl Layout for Swatch {
+    fn size_rules(&mut self, _: &mut dyn SizeHandle, _: AxisInfo) -> SizeRules {
+        SizeRules::new(100, 200, (0, 0), StretchPolicy::HighUtility)
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, _: &ManagerState, _: bool) {
+        let (pass, offset, draw) = draw_handle.draw_device();
+        let draw = draw.as_any_mut().downcast_mut::<DrawWindow<()>>().unwrap();
+        let rect = Quad::from(self.core.rect + offset);
+        draw.rect(pass, rect, self.fade.value());
+    }
+}
+
+impl event::Handler for Swatch {
+    type Msg = event::VoidMsg;
+
+    fn activation_via_press(&self) -> bool {
+        true
+    }
+
+    fn handle(&mut self, mgr: &mut Manager, event: Event) -> Response<Self::Msg> {
+        match event {
+            Event::Activate => {
+                if self.fade.is_finished() {
+                    let from = COLOURS[self.index];
+                    self.index = (self.index + 1) % COLOURS.len();
+                    let to = COLOURS[self.index];
+                    self.fade = Tween::new(from, to, FADE_DUR).with_easing(Easing::EaseInOut);
+                    mgr.request_animation_frame(self.id());
+                }
+                Response::None
+            }
+            Event::AnimationFrame { delta } => {
+                if self.fade.advance(delta) {
+                    mgr.request_animation_frame(self.id());
+                } else {
+                    mgr.redraw(self.id());
+                }
+                Response::None
+            }
+            event => Response::Unhandled(event),
+        }
+    }
+}
+
+fn main() -> Result<(), kas_wgpu::Error> {
+    env_logger::init();
+
+    let window = Window::new("Animation", Swatch::new());
+
+    let theme = kas_theme::FlatTheme::new();
+    kas_wgpu::Toolkit::new(theme)?.with(window)?.run()
+}
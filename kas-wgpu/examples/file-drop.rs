@@ -0,0 +1,61 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! File drop example
+//!
+//! Drag a file from a file manager onto the window to load it.
+
+use kas::class::HasString;
+use kas::event::{Event, Handler, Manager, Response, VoidMsg};
+use kas::macros::make_widget;
+use kas::widget::{Frame, Label, Window};
+
+fn main() -> Result<(), kas_wgpu::Error> {
+    env_logger::init();
+
+    // Labels do not accept dropped files, thus file events pass to the parent
+    let content = make_widget! {
+        #[layout(column)]
+        struct {
+            #[widget] status: Label<String> = Label::new("Drop a file here".to_string()),
+            #[widget] info: impl HasString = Frame::new(Label::new(String::new())),
+        }
+        impl Handler {
+            type Msg = VoidMsg;
+            fn handle(&mut self, mgr: &mut Manager, event: Event) -> Response<VoidMsg> {
+                match event {
+                    Event::FileHovered(path) => {
+                        let text = format!("Release to load {}", path.display());
+                        *mgr += self.status.set_string(text);
+                    }
+                    Event::FileHoverCancelled => {
+                        *mgr += self.status.set_string("Drop a file here".to_string());
+                    }
+                    Event::FileDropped(path) => {
+                        *mgr += self.status.set_string(format!("{}", path.display()));
+                        let text = match std::fs::read(&path) {
+                            Ok(data) => match String::from_utf8(data) {
+                                Ok(text) => {
+                                    let lines = text.lines().count();
+                                    format!("{} bytes of text in {} lines", text.len(), lines)
+                                }
+                                Err(e) => format!("{} bytes of binary data", e.as_bytes().len()),
+                            },
+                            Err(e) => format!("Failed to load: {}", e),
+                        };
+                        *mgr += self.info.set_string(text);
+                    }
+                    event => return Response::Unhandled(event),
+                }
+                Response::None
+            }
+        }
+    };
+
+    let window = Window::new("File drop", content);
+
+    let theme = kas_theme::FlatTheme::new();
+    kas_wgpu::Toolkit::new(theme)?.with(window)?.run()
+}
@@ -469,8 +469,8 @@ impl event::Handler for Mandlebrot {
             }
             Event::Scroll(delta) => {
                 let factor = match delta {
-                    event::ScrollDelta::LineDelta(_, y) => -0.5 * y as f64,
-                    event::ScrollDelta::PixelDelta(coord) => -0.01 * coord.1 as f64,
+                    event::ScrollDelta::LineDelta(_, y) => 0.5 * y as f64,
+                    event::ScrollDelta::PixelDelta(coord) => 0.01 * coord.1 as f64,
                 };
                 self.alpha = self.alpha * 2f64.powf(factor);
                 mgr.redraw(self.id());
@@ -0,0 +1,186 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Clipboard support
+
+use kas::ClipboardFormat;
+use log::warn;
+
+#[cfg(feature = "clipboard")]
+use ::clipboard::{ClipboardContext, ClipboardProvider};
+
+/// Access to the system clipboard (plain text only)
+pub trait SystemClipboard {
+    fn get_text(&mut self) -> Option<String>;
+    fn set_text(&mut self, text: String);
+}
+
+#[cfg(feature = "clipboard")]
+pub type System = Option<ClipboardContext>;
+#[cfg(not(feature = "clipboard"))]
+pub type System = ();
+
+/// Open the system clipboard
+#[cfg(feature = "clipboard")]
+pub fn system() -> System {
+    match ClipboardContext::new() {
+        Ok(cb) => Some(cb),
+        Err(e) => {
+            warn!("Unable to open clipboard: {:?}", e);
+            None
+        }
+    }
+}
+
+/// Open the system clipboard
+#[cfg(not(feature = "clipboard"))]
+#[inline]
+pub fn system() -> System {}
+
+#[cfg(feature = "clipboard")]
+impl SystemClipboard for Option<ClipboardContext> {
+    fn get_text(&mut self) -> Option<String> {
+        self.as_mut().and_then(|cb| match cb.get_contents() {
+            Ok(c) => Some(c),
+            Err(e) => {
+                warn!("Failed to get clipboard contents: {:?}", e);
+                None
+            }
+        })
+    }
+
+    fn set_text(&mut self, text: String) {
+        if let Some(cb) = self.as_mut() {
+            cb.set_contents(text)
+                .unwrap_or_else(|e| warn!("Failed to set clipboard contents: {:?}", e));
+        }
+    }
+}
+
+#[cfg(not(feature = "clipboard"))]
+impl SystemClipboard for () {
+    #[inline]
+    fn get_text(&mut self) -> Option<String> {
+        None
+    }
+
+    #[inline]
+    fn set_text(&mut self, _: String) {}
+}
+
+/// Clipboard supporting multiple formats
+///
+/// Plain text uses the system clipboard; empty text is treated as no content.
+/// Content in other formats is held in-process (the system clipboard backend
+/// supports text only) and is thus only available to this application. Setting
+/// such content leaves the system clipboard unchanged; if the system clipboard
+/// text later changes (e.g. by another application copying text), the content
+/// held in-process is discarded.
+pub struct Clipboard<S: SystemClipboard = System> {
+    system: S,
+    data: Option<(ClipboardFormat, Vec<u8>)>,
+    // System clipboard text when data was set
+    system_text: Option<String>,
+}
+
+impl<S: SystemClipboard> Clipboard<S> {
+    /// Construct, using the given system clipboard
+    pub fn new(system: S) -> Self {
+        Clipboard {
+            system,
+            data: None,
+            system_text: None,
+        }
+    }
+
+    /// Get clipboard contents
+    pub fn get(&mut self, format: &ClipboardFormat) -> Option<Vec<u8>> {
+        if *format == ClipboardFormat::PlainText {
+            let text = self.system.get_text().filter(|text| !text.is_empty());
+            return text.map(String::into_bytes);
+        }
+
+        self.data.as_ref()?;
+        if self.system.get_text() != self.system_text {
+            self.data = None;
+        }
+        match self.data {
+            Some((ref f, ref data)) if f == format => Some(data.clone()),
+            _ => None,
+        }
+    }
+
+    /// Set clipboard contents
+    pub fn set(&mut self, format: ClipboardFormat, content: Vec<u8>) {
+        match format {
+            ClipboardFormat::PlainText => match String::from_utf8(content) {
+                Ok(text) => {
+                    self.data = None;
+                    self.system.set_text(text);
+                }
+                Err(e) => warn!("Failed to set clipboard contents: {}", e),
+            },
+            format => {
+                self.system_text = self.system.get_text();
+                self.data = Some((format, content));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    impl SystemClipboard for String {
+        fn get_text(&mut self) -> Option<String> {
+            Some(self.clone())
+        }
+
+        fn set_text(&mut self, text: String) {
+            *self = text;
+        }
+    }
+
+    #[test]
+    fn formats() {
+        let mut cb = Clipboard::new("old".to_string());
+        let html = ClipboardFormat::Html;
+        let text = ClipboardFormat::PlainText;
+        assert_eq!(cb.get(&text), Some(b"old".to_vec()));
+        assert_eq!(cb.get(&html), None);
+
+        // Setting HTML leaves the system clipboard unchanged
+        cb.set(html.clone(), b"<b>new</b>".to_vec());
+        assert_eq!(cb.get(&html), Some(b"<b>new</b>".to_vec()));
+        assert_eq!(cb.get(&ClipboardFormat::Custom("x".into())), None);
+        assert_eq!(cb.get(&text), Some(b"old".to_vec()));
+        assert_eq!(cb.get(&html), Some(b"<b>new</b>".to_vec()));
+
+        // Setting text replaces content in other formats
+        cb.set(text.clone(), b"text".to_vec());
+        assert_eq!(cb.get(&text), Some(b"text".to_vec()));
+        assert_eq!(cb.get(&html), None);
+    }
+
+    #[test]
+    fn system_replaced() {
+        let mut cb = Clipboard::new(String::new());
+        let html = ClipboardFormat::Html;
+        // Empty system text is no content
+        assert_eq!(cb.get(&ClipboardFormat::PlainText), None);
+        cb.set(html.clone(), b"<i>x</i>".to_vec());
+        assert_eq!(cb.get(&html), Some(b"<i>x</i>".to_vec()));
+
+        // Another application sets the system clipboard
+        cb.system = "other".to_string();
+        assert_eq!(cb.get(&html), None);
+        assert_eq!(cb.get(&ClipboardFormat::PlainText), Some(b"other".to_vec()));
+
+        // Held content is discarded, not hidden
+        cb.system.clear();
+        assert_eq!(cb.get(&html), None);
+    }
+}
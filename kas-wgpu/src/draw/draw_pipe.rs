@@ -11,11 +11,16 @@ use wgpu::TextureView;
 use wgpu_glyph::{ab_glyph::FontRef, GlyphBrushBuilder};
 
 use super::{
-    flat_round, shaded_round, shaded_square, CustomPipe, CustomPipeBuilder, CustomWindow, DrawPipe,
-    DrawWindow, ShaderManager, TEX_FORMAT,
+    flat_round, images, shaded_round, shaded_square, CustomPipe, CustomPipeBuilder, CustomWindow,
+    DrawPipe, DrawWindow, ShaderManager, TEX_FORMAT,
+};
+use kas::draw::{
+    Colour, Draw, DrawRounded, DrawShaded, DrawShared, DrawText, FontInfo, ImageFilter,
+    ImageHandle, ImageId, Pass,
 };
-use kas::draw::{Colour, Draw, DrawRounded, DrawShaded, DrawShared, Pass};
 use kas::geom::{Coord, Quad, Rect, Size, Vec2};
+use kas::text::fonts::FontId;
+use kas::Direction;
 
 fn make_depth_texture(device: &wgpu::Device, size: Size) -> Option<TextureView> {
     // NOTE: initially the DrawWindow is created with Size::ZERO to calculate
@@ -55,6 +60,7 @@ impl<C: CustomPipe> DrawPipe<C> {
         let shaded_square = shaded_square::Pipeline::new(device, shaders);
         let shaded_round = shaded_round::Pipeline::new(device, shaders);
         let flat_round = flat_round::Pipeline::new(device, shaders);
+        let images = images::Pipeline::new(device, shaders);
         let custom = custom.build(&device, TEX_FORMAT, super::DEPTH_FORMAT);
 
         DrawPipe {
@@ -63,7 +69,10 @@ impl<C: CustomPipe> DrawPipe<C> {
             shaded_square,
             shaded_round,
             flat_round,
+            images,
             custom,
+            fonts: vec![],
+            preload: vec![],
         }
     }
 
@@ -88,6 +97,7 @@ impl<C: CustomPipe> DrawPipe<C> {
         let shaded_square = self.shaded_square.new_window(device, size, norm);
         let shaded_round = self.shaded_round.new_window(device, size, norm);
         let flat_round = self.flat_round.new_window(device, size);
+        let images = self.images.new_window(device, size);
         let custom = self.custom.new_window(device, size);
 
         // TODO: use extra caching so we don't load font for each window
@@ -107,8 +117,10 @@ impl<C: CustomPipe> DrawPipe<C> {
             shaded_square,
             shaded_round,
             flat_round,
+            images,
             custom,
             glyph_brush,
+            preloaded: 0,
             dur_text: Default::default(),
         }
     }
@@ -130,9 +142,22 @@ impl<C: CustomPipe> DrawPipe<C> {
         self.custom
             .resize(&mut window.custom, device, &mut encoder, size);
         window.flat_round.resize(device, &mut encoder, size);
+        window.images.resize(device, &mut encoder, size);
         encoder.finish()
     }
 
+    /// Load an image
+    pub fn load_image(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        size: Size,
+        data: &[u8],
+        filter: ImageFilter,
+    ) -> ImageHandle {
+        self.images.load_image(device, queue, size, data, filter)
+    }
+
     /// Render batched draw instructions via `rpass`
     pub fn render(
         &mut self,
@@ -142,6 +167,8 @@ impl<C: CustomPipe> DrawPipe<C> {
         frame_view: &wgpu::TextureView,
         clear_color: wgpu::Color,
     ) {
+        self.images.free_released();
+
         let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
             label: Some("render"),
         });
@@ -173,6 +200,7 @@ impl<C: CustomPipe> DrawPipe<C> {
             let ss = self
                 .shaded_square
                 .render_buf(&mut window.shaded_square, device, pass);
+            let im = self.images.render_buf(&mut window.images, device, pass);
             let sr = self
                 .shaded_round
                 .render_buf(&mut window.shaded_round, device, pass);
@@ -193,6 +221,7 @@ impl<C: CustomPipe> DrawPipe<C> {
                 );
 
                 ss.as_ref().map(|buf| buf.render(&mut rpass));
+                im.as_ref().map(|buf| buf.render(&mut rpass));
                 sr.as_ref().map(|buf| buf.render(&mut rpass));
                 fr.as_ref().map(|buf| buf.render(&mut rpass));
                 self.custom
@@ -213,6 +242,15 @@ impl<C: CustomPipe> DrawPipe<C> {
         // Fonts and custom pipes use their own render pass(es).
         let size = window.clip_regions[0].size;
 
+        // Glyphs are rasterised by draw_queued; queue any pending preloads
+        if window.preloaded < self.preload.len() {
+            window.prepare_fonts();
+            for (font_id, height, chars) in &self.preload[window.preloaded..] {
+                window.queue_preload(*font_id, *height, chars);
+            }
+            window.preloaded = self.preload.len();
+        }
+
         self.custom.render_final(
             &mut window.custom,
             device,
@@ -253,6 +291,29 @@ impl<C: CustomPipe> DrawPipe<C> {
 
 impl<C: CustomPipe> DrawShared for DrawPipe<C> {
     type Draw = DrawWindow<C::Window>;
+
+    fn fonts(&mut self) -> &[FontInfo] {
+        // Fonts are indexed as in DrawText::prepare_fonts
+        let n = kas::text::fonts::fonts().num_fonts();
+        if n > self.fonts.len() {
+            let font_data = kas::text::fonts::fonts().font_data();
+            for i in self.fonts.len()..n {
+                let (data, index) = font_data.get_data(i);
+                self.fonts
+                    .push(FontInfo::parse(data, index).unwrap_or_default());
+            }
+        }
+        &self.fonts
+    }
+
+    fn preload_glyphs(&mut self, font: FontId, dpem: f32, chars: &str) {
+        // ab_glyph scales such that height = ascent - descent
+        let height = match self.font_info(font) {
+            Some(info) => info.ascent(dpem) - info.descent(dpem),
+            None => return,
+        };
+        self.preload.push((font, height, chars.to_string()));
+    }
 }
 
 impl<CW: CustomWindow + 'static> Draw for DrawWindow<CW> {
@@ -272,10 +333,20 @@ impl<CW: CustomWindow + 'static> Draw for DrawWindow<CW> {
         self.shaded_square.rect(pass, rect, col);
     }
 
+    #[inline]
+    fn gradient_rect(&mut self, pass: Pass, rect: Quad, cols: (Colour, Colour), dir: Direction) {
+        self.shaded_square.gradient_rect(pass, rect, cols, dir);
+    }
+
     #[inline]
     fn frame(&mut self, pass: Pass, outer: Quad, inner: Quad, col: Colour) {
         self.shaded_square.frame(pass, outer, inner, col);
     }
+
+    #[inline]
+    fn image(&mut self, pass: Pass, id: ImageId, rect: Quad) {
+        self.images.image(pass, id, rect);
+    }
 }
 
 impl<CW: CustomWindow + 'static> DrawRounded for DrawWindow<CW> {
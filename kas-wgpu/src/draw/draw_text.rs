@@ -5,7 +5,8 @@
 
 //! Text drawing API for `kas_wgpu`
 
-use wgpu_glyph::{ab_glyph, Extra, SectionGlyph};
+use wgpu_glyph::ab_glyph::{self, Font};
+use wgpu_glyph::{Extra, SectionGlyph};
 
 use super::{CustomWindow, DrawWindow};
 use kas::draw::{Colour, Draw, DrawText, Pass};
@@ -21,6 +22,44 @@ fn ktv_to_point(kas::text::Vec2(x, y): kas::text::Vec2) -> ab_glyph::Point {
     ab_glyph::Point { x, y }
 }
 
+impl<CW: CustomWindow> DrawWindow<CW> {
+    /// Queue glyphs for rasterisation without drawing them
+    pub(crate) fn queue_preload(&mut self, font_id: FontId, height: f32, chars: &str) {
+        let font = match self.glyph_brush.fonts().get(font_id.get()) {
+            Some(font) => font,
+            None => return,
+        };
+        let glyphs: Vec<SectionGlyph> = chars
+            .chars()
+            .map(|c| font.glyph_id(c))
+            // Missing glyphs map to the .notdef glyph, which we skip
+            .filter(|id| id.0 != 0)
+            .map(|id| SectionGlyph {
+                section_index: 0,
+                byte_index: 0, // not used
+                glyph: ab_glyph::Glyph {
+                    id,
+                    scale: height.into(),
+                    position: ab_glyph::Point { x: 0.0, y: 0.0 },
+                },
+                font_id: wgpu_glyph::FontId(font_id.get()),
+            })
+            .collect();
+
+        // Glyphs are cached but clipped by the empty bounds, thus not drawn
+        let origin = ab_glyph::Point { x: 0.0, y: 0.0 };
+        let bounds = ab_glyph::Rect {
+            min: origin,
+            max: origin,
+        };
+        let extra = vec![Extra {
+            color: [0.0; 4],
+            z: 0.0,
+        }];
+        self.glyph_brush.queue_pre_positioned(glyphs, extra, bounds);
+    }
+}
+
 impl<CW: CustomWindow + 'static> DrawText for DrawWindow<CW> {
     fn prepare_fonts(&mut self) {
         let fonts = fonts();
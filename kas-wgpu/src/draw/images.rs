@@ -0,0 +1,422 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Image loading and rendering pipeline
+
+use std::collections::HashMap;
+use std::mem::size_of;
+use std::ops::Range;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use wgpu::util::DeviceExt;
+
+use crate::draw::{Rgb, ShaderManager};
+use kas::draw::{ImageFilter, ImageHandle, ImageId, Pass};
+use kas::geom::{Quad, Size, Vec2, Vec3};
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+struct Vertex(Vec3, Rgb, Vec2);
+unsafe impl bytemuck::Zeroable for Vertex {}
+unsafe impl bytemuck::Pod for Vertex {}
+
+/// A loaded image
+struct Image {
+    _texture: wgpu::Texture,
+    bind_group: wgpu::BindGroup,
+}
+
+/// A pipeline for rendering images
+///
+/// This also owns image resources (shared between windows).
+pub struct Pipeline {
+    bind_group_layout: wgpu::BindGroupLayout,
+    tex_bind_group_layout: wgpu::BindGroupLayout,
+    render_pipeline: wgpu::RenderPipeline,
+    sampler_nearest: wgpu::Sampler,
+    sampler_linear: wgpu::Sampler,
+    images: HashMap<ImageId, Image>,
+    next_id: u32,
+    release_send: Sender<ImageId>,
+    release_recv: Receiver<ImageId>,
+}
+
+/// Per-window state
+pub struct Window {
+    bind_group: wgpu::BindGroup,
+    scale_buf: wgpu::Buffer,
+    passes: Vec<Vec<(ImageId, [Vertex; 6])>>,
+}
+
+/// Buffer used during render pass
+///
+/// This buffer must not be dropped before the render pass.
+pub struct RenderBuffer<'a> {
+    pipe: &'a wgpu::RenderPipeline,
+    images: &'a mut Vec<(ImageId, [Vertex; 6])>,
+    bind_group: &'a wgpu::BindGroup,
+    draws: Vec<(&'a wgpu::BindGroup, Range<u32>)>,
+    buffer: wgpu::Buffer,
+}
+
+impl<'a> RenderBuffer<'a> {
+    /// Do the render
+    pub fn render(&'a self, rpass: &mut wgpu::RenderPass<'a>) {
+        rpass.set_pipeline(self.pipe);
+        rpass.set_bind_group(0, self.bind_group, &[]);
+        rpass.set_vertex_buffer(0, self.buffer.slice(..));
+        for (tex_bind_group, range) in &self.draws {
+            rpass.set_bind_group(1, tex_bind_group, &[]);
+            rpass.draw(range.clone(), 0..1);
+        }
+    }
+}
+
+impl<'a> Drop for RenderBuffer<'a> {
+    fn drop(&mut self) {
+        self.images.clear();
+    }
+}
+
+impl Pipeline {
+    /// Construct
+    pub fn new(device: &wgpu::Device, shaders: &ShaderManager) -> Self {
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("IM bind_group_layout"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStage::VERTEX,
+                ty: wgpu::BindingType::UniformBuffer {
+                    dynamic: false,
+                    min_binding_size: None, // TODO
+                },
+                count: None,
+            }],
+        });
+
+        let tex_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("IM tex_bind_group_layout"),
+                entries: &[
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStage::FRAGMENT,
+                        ty: wgpu::BindingType::SampledTexture {
+                            dimension: wgpu::TextureViewDimension::D2,
+                            component_type: wgpu::TextureComponentType::Float,
+                            multisampled: false,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: wgpu::ShaderStage::FRAGMENT,
+                        ty: wgpu::BindingType::Sampler { comparison: false },
+                        count: None,
+                    },
+                ],
+            });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("IM pipeline_layout"),
+            bind_group_layouts: &[&bind_group_layout, &tex_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("IM render_pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex_stage: wgpu::ProgrammableStageDescriptor {
+                module: &shaders.vert_32,
+                entry_point: "main",
+            },
+            fragment_stage: Some(wgpu::ProgrammableStageDescriptor {
+                module: &shaders.frag_image,
+                entry_point: "main",
+            }),
+            rasterization_state: Some(wgpu::RasterizationStateDescriptor {
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: wgpu::CullMode::None,
+                clamp_depth: false,
+                depth_bias: 0,
+                depth_bias_slope_scale: 0.0,
+                depth_bias_clamp: 0.0,
+            }),
+            primitive_topology: wgpu::PrimitiveTopology::TriangleList,
+            color_states: &[wgpu::ColorStateDescriptor {
+                format: wgpu::TextureFormat::Bgra8UnormSrgb,
+                color_blend: wgpu::BlendDescriptor {
+                    src_factor: wgpu::BlendFactor::SrcAlpha,
+                    dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                    operation: wgpu::BlendOperation::Add,
+                },
+                alpha_blend: wgpu::BlendDescriptor {
+                    src_factor: wgpu::BlendFactor::Zero,
+                    dst_factor: wgpu::BlendFactor::One,
+                    operation: wgpu::BlendOperation::Add,
+                },
+                write_mask: wgpu::ColorWrite::ALL,
+            }],
+            depth_stencil_state: Some(super::DEPTH_DESC),
+            vertex_state: wgpu::VertexStateDescriptor {
+                index_format: wgpu::IndexFormat::Uint16,
+                vertex_buffers: &[wgpu::VertexBufferDescriptor {
+                    stride: size_of::<Vertex>() as wgpu::BufferAddress,
+                    step_mode: wgpu::InputStepMode::Vertex,
+                    attributes: &wgpu::vertex_attr_array![
+                        0 => Float3,
+                        1 => Float3,
+                        2 => Float2
+                    ],
+                }],
+            },
+            sample_count: 1,
+            sample_mask: !0,
+            alpha_to_coverage_enabled: false,
+        });
+
+        let sampler = |label, filter| {
+            device.create_sampler(&wgpu::SamplerDescriptor {
+                label: Some(label),
+                address_mode_u: wgpu::AddressMode::ClampToEdge,
+                address_mode_v: wgpu::AddressMode::ClampToEdge,
+                address_mode_w: wgpu::AddressMode::ClampToEdge,
+                mag_filter: filter,
+                min_filter: filter,
+                mipmap_filter: wgpu::FilterMode::Nearest,
+                ..Default::default()
+            })
+        };
+        let sampler_nearest = sampler("IM sampler_nearest", wgpu::FilterMode::Nearest);
+        let sampler_linear = sampler("IM sampler_linear", wgpu::FilterMode::Linear);
+
+        let (release_send, release_recv) = channel();
+
+        Pipeline {
+            bind_group_layout,
+            tex_bind_group_layout,
+            render_pipeline,
+            sampler_nearest,
+            sampler_linear,
+            images: HashMap::new(),
+            next_id: 0,
+            release_send,
+            release_recv,
+        }
+    }
+
+    /// Load an image
+    ///
+    /// Data is expected in RGBA format, as documented by
+    /// [`kas::TkWindow::load_image`].
+    pub fn load_image(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        size: Size,
+        data: &[u8],
+        filter: ImageFilter,
+    ) -> ImageHandle {
+        let id = ImageId::new(self.next_id);
+        self.next_id += 1;
+        let handle = ImageHandle::new(id, size, self.release_send.clone());
+
+        // Wgpu does not support zero-sized textures; such images are simply
+        // never drawn.
+        if size.0 * size.1 == 0 {
+            return handle;
+        }
+
+        let extent = wgpu::Extent3d {
+            width: size.0,
+            height: size.1,
+            depth: 1,
+        };
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("IM texture"),
+            size: extent,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            usage: wgpu::TextureUsage::SAMPLED | wgpu::TextureUsage::COPY_DST,
+        });
+        queue.write_texture(
+            wgpu::TextureCopyView {
+                texture: &texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+            },
+            data,
+            wgpu::TextureDataLayout {
+                offset: 0,
+                bytes_per_row: 4 * size.0,
+                rows_per_image: size.1,
+            },
+            extent,
+        );
+
+        let view = texture.create_view(&Default::default());
+        let sampler = match filter {
+            ImageFilter::Nearest => &self.sampler_nearest,
+            ImageFilter::Linear => &self.sampler_linear,
+        };
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("IM tex_bind_group"),
+            layout: &self.tex_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(sampler),
+                },
+            ],
+        });
+
+        let image = Image {
+            _texture: texture,
+            bind_group,
+        };
+        self.images.insert(id, image);
+        handle
+    }
+
+    /// Free images whose handles have been dropped
+    pub fn free_released(&mut self) {
+        for id in self.release_recv.try_iter() {
+            self.images.remove(&id);
+        }
+    }
+
+    /// Construct per-window state
+    pub fn new_window(&self, device: &wgpu::Device, size: Size) -> Window {
+        type Scale = [f32; 2];
+        let scale_factor: Scale = [2.0 / size.0 as f32, -2.0 / size.1 as f32];
+        let scale_buf = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("IM scale_buf"),
+            contents: bytemuck::cast_slice(&scale_factor),
+            usage: wgpu::BufferUsage::UNIFORM | wgpu::BufferUsage::COPY_DST,
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("IM bind_group"),
+            layout: &self.bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::Buffer(scale_buf.slice(..)),
+            }],
+        });
+
+        Window {
+            bind_group,
+            scale_buf,
+            passes: vec![],
+        }
+    }
+
+    /// Construct a render buffer
+    pub fn render_buf<'a>(
+        &'a self,
+        window: &'a mut Window,
+        device: &wgpu::Device,
+        pass: usize,
+    ) -> Option<RenderBuffer<'a>> {
+        if pass >= window.passes.len() || window.passes[pass].len() == 0 {
+            return None;
+        }
+
+        let images = &mut window.passes[pass];
+        let mut vertices = Vec::with_capacity(6 * images.len());
+        let mut draws = Vec::with_capacity(images.len());
+        for (id, quad) in images.iter() {
+            // Images freed since the draw command was issued are skipped
+            if let Some(image) = self.images.get(id) {
+                let start = vertices.len() as u32;
+                vertices.extend_from_slice(quad);
+                draws.push((&image.bind_group, start..(start + 6)));
+            }
+        }
+        if draws.is_empty() {
+            images.clear();
+            return None;
+        }
+
+        let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("IM render_buf"),
+            contents: bytemuck::cast_slice(&vertices),
+            usage: wgpu::BufferUsage::VERTEX,
+        });
+
+        Some(RenderBuffer {
+            pipe: &self.render_pipeline,
+            images,
+            bind_group: &window.bind_group,
+            draws,
+            buffer,
+        })
+    }
+}
+
+impl Window {
+    pub fn resize(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        size: Size,
+    ) {
+        type Scale = [f32; 2];
+        let scale_factor: Scale = [2.0 / size.0 as f32, -2.0 / size.1 as f32];
+        let scale_buf = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("IM scale_buf copy"),
+            contents: bytemuck::cast_slice(&scale_factor),
+            usage: wgpu::BufferUsage::COPY_SRC,
+        });
+        let byte_len = size_of::<Scale>() as u64;
+
+        encoder.copy_buffer_to_buffer(&scale_buf, 0, &self.scale_buf, 0, byte_len);
+    }
+
+    /// Add an image to the buffer
+    pub fn image(&mut self, pass: Pass, id: ImageId, rect: Quad) {
+        let aa = rect.a;
+        let bb = rect.b;
+
+        if !aa.lt(bb) {
+            // zero / negative size: nothing to draw
+            return;
+        }
+
+        let depth = pass.depth();
+        let ab = Vec3(aa.0, bb.1, depth);
+        let ba = Vec3(bb.0, aa.1, depth);
+        let aa = Vec3::from2(aa, depth);
+        let bb = Vec3::from2(bb, depth);
+
+        // The fragment colour is multiplied by the texture; use white
+        let col = Rgb {
+            r: 1.0,
+            g: 1.0,
+            b: 1.0,
+        };
+        let taa = Vec2(0.0, 0.0);
+        let tab = Vec2(0.0, 1.0);
+        let tba = Vec2(1.0, 0.0);
+        let tbb = Vec2(1.0, 1.0);
+
+        let pass = pass.pass();
+        if self.passes.len() <= pass {
+            // We only need one more, but no harm in adding extra
+            self.passes.resize(pass + 8, vec![]);
+        }
+
+        #[rustfmt::skip]
+        self.passes[pass].push((id, [
+            Vertex(aa, col, taa), Vertex(ba, col, tba), Vertex(ab, col, tab),
+            Vertex(ab, col, tab), Vertex(ba, col, tba), Vertex(bb, col, tbb),
+        ]));
+    }
+}
@@ -11,11 +11,14 @@ mod custom;
 mod draw_pipe;
 mod draw_text;
 mod flat_round;
+mod images;
 mod shaded_round;
 mod shaded_square;
 mod shaders;
 
+use kas::draw::FontInfo;
 use kas::geom::Rect;
+use kas::text::fonts::FontId;
 use wgpu::{CompareFunction, DepthStencilStateDescriptor, TextureFormat};
 use wgpu_glyph::ab_glyph::FontRef;
 
@@ -68,7 +71,11 @@ pub struct DrawPipe<C> {
     shaded_square: shaded_square::Pipeline,
     shaded_round: shaded_round::Pipeline,
     flat_round: flat_round::Pipeline,
+    images: images::Pipeline,
     custom: C,
+    fonts: Vec<FontInfo>,
+    /// Glyphs to preload: font, height (as used by `ab_glyph`) and chars
+    preload: Vec<(FontId, f32, String)>,
 }
 
 type GlyphBrush = wgpu_glyph::GlyphBrush<DepthStencilStateDescriptor, FontRef<'static>>;
@@ -80,7 +87,10 @@ pub struct DrawWindow<CW: CustomWindow> {
     shaded_square: shaded_square::Window,
     shaded_round: shaded_round::Window,
     flat_round: flat_round::Window,
+    images: images::Window,
     custom: CW,
     glyph_brush: GlyphBrush, // TODO: should be in DrawPipe
+    /// Number of `DrawPipe::preload` entries queued
+    preloaded: usize,
     pub(crate) dur_text: std::time::Duration,
 }
@@ -12,6 +12,7 @@ use wgpu::util::DeviceExt;
 use crate::draw::{Rgb, ShaderManager};
 use kas::draw::{Colour, Pass};
 use kas::geom::{Quad, Size, Vec2, Vec3};
+use kas::Direction;
 
 #[repr(C)]
 #[derive(Clone, Copy, Debug)]
@@ -248,6 +249,48 @@ impl Window {
         ]);
     }
 
+    /// Add a rectangle with a linear gradient to the buffer
+    ///
+    /// Colour is interpolated (linearly, per vertex) from `cols.0` on the
+    /// starting edge to `cols.1` on the final edge, in direction `dir`.
+    pub fn gradient_rect(
+        &mut self,
+        pass: Pass,
+        rect: Quad,
+        cols: (Colour, Colour),
+        dir: Direction,
+    ) {
+        let aa = rect.a;
+        let bb = rect.b;
+
+        if !aa.lt(bb) {
+            // zero / negative size: nothing to draw
+            return;
+        }
+
+        let depth = pass.depth();
+        let ab = Vec3(aa.0, bb.1, depth);
+        let ba = Vec3(bb.0, aa.1, depth);
+        let aa = Vec3::from2(aa, depth);
+        let bb = Vec3::from2(bb, depth);
+
+        let (c0, c1): (Rgb, Rgb) = (cols.0.into(), cols.1.into());
+        // colours at corners aa, ba, ab, bb
+        let (caa, cba, cab, cbb) = match dir {
+            Direction::Right => (c0, c1, c0, c1),
+            Direction::Left => (c1, c0, c1, c0),
+            Direction::Down => (c0, c0, c1, c1),
+            Direction::Up => (c1, c1, c0, c0),
+        };
+        let t = Vec2(0.0, 0.0);
+
+        #[rustfmt::skip]
+        self.add_vertices(pass.pass(), &[
+            Vertex(aa, caa, t), Vertex(ba, cba, t), Vertex(ab, cab, t),
+            Vertex(ab, cab, t), Vertex(ba, cba, t), Vertex(bb, cbb, t),
+        ]);
+    }
+
     /// Add a rect to the buffer, defined by two outer corners, `aa` and `bb`.
     ///
     /// Bounds on input: `aa < cc` and `-1 ≤ norm ≤ 1`.
@@ -20,6 +20,7 @@ pub struct ShaderManager {
     pub frag_flat_round: ShaderModule,
     pub frag_shaded_square: ShaderModule,
     pub frag_shaded_round: ShaderModule,
+    pub frag_image: ShaderModule,
 }
 
 macro_rules! compile {
@@ -38,6 +39,7 @@ impl ShaderManager {
         let frag_flat_round = compile!(device, "shaders/flat_round.frag.spv");
         let frag_shaded_square = compile!(device, "shaders/shaded_square.frag.spv");
         let frag_shaded_round = compile!(device, "shaders/shaded_round.frag.spv");
+        let frag_image = compile!(device, "shaders/image.frag.spv");
 
         ShaderManager {
             vert_3122,
@@ -47,6 +49,7 @@ impl ShaderManager {
             frag_flat_round,
             frag_shaded_square,
             frag_shaded_round,
+            frag_image,
         }
     }
 }
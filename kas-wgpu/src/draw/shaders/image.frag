@@ -0,0 +1,21 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+#version 450
+#extension GL_ARB_separate_shader_objects : enable
+
+precision mediump float;
+
+layout(location = 0) in vec3 fragColor;
+layout(location = 1) in vec2 texCoord;
+
+layout(location = 0) out vec4 outColor;
+
+layout(set = 1, binding = 0) uniform texture2D tex;
+layout(set = 1, binding = 1) uniform sampler samp;
+
+void main() {
+    outColor = texture(sampler2D(tex, samp), texCoord) * vec4(fragColor, 1.0);
+}
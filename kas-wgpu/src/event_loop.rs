@@ -90,6 +90,20 @@ where
                         .pending
                         .push(PendingAction::Update(handle, payload));
                 }
+                ProxyAction::ImePreedit(id, text, cursor_range) => {
+                    if let Some(id) = self.id_map.get(&id) {
+                        if let Some(window) = self.windows.get_mut(&id) {
+                            window.handle_ime_preedit(&mut self.shared, text, cursor_range);
+                        }
+                    }
+                }
+                ProxyAction::ImeCommit(id, text) => {
+                    if let Some(id) = self.id_map.get(&id) {
+                        if let Some(window) = self.windows.get_mut(&id) {
+                            window.handle_ime_commit(&mut self.shared, text);
+                        }
+                    }
+                }
             },
 
             NewEvents(cause) => {
@@ -181,9 +195,12 @@ where
 
                 self.resumes.sort_by_key(|item| item.0);
 
+                // Redraw continuously only while animating
+                let animating = self.windows.values().any(|w| w.is_animating());
+
                 *control_flow = if *control_flow == ControlFlow::Exit || self.windows.is_empty() {
                     ControlFlow::Exit
-                } else if *control_flow == ControlFlow::Poll {
+                } else if *control_flow == ControlFlow::Poll || animating {
                     ControlFlow::Poll
                 } else if let Some((instant, _)) = self.resumes.first() {
                     trace!("Requesting resume at {:?}", *instant);
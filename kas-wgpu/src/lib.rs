@@ -14,15 +14,20 @@
 
 #![cfg_attr(feature = "gat", feature(generic_associated_types))]
 
+mod clipboard;
 pub mod draw;
 mod event_loop;
 pub mod options;
 mod shared;
 mod window;
 
+use std::ops::Range;
 use std::{error, fmt};
 
+use kas::draw::{DrawHandle, DrawShared};
 use kas::event::UpdateHandle;
+use kas::geom::Size;
+use kas::text::fonts::FontId;
 use kas::WindowId;
 use kas_theme::Theme;
 use winit::error::OsError;
@@ -159,6 +164,40 @@ where
         Ok(self)
     }
 
+    /// Set the swap chain present mode
+    ///
+    /// This controls vertical synchronisation; the default is
+    /// [`PresentMode::Fifo`] (see [`Options::present_mode`]). The swap chain of
+    /// each existing window is recreated as required; windows added later use
+    /// the new mode.
+    ///
+    /// [`PresentMode::Fifo`]: options::PresentMode::Fifo
+    pub fn set_present_mode(&mut self, mode: options::PresentMode) {
+        self.shared.present_mode = mode;
+        for window in &mut self.windows {
+            window.set_present_mode(&self.shared, mode);
+        }
+    }
+
+    /// Set a closure to draw an overlay over each window
+    ///
+    /// The closure is called at the end of each window's draw pass, after the
+    /// widget tree (including pop-ups) has been drawn, with the window's size
+    /// in physical pixels. It does not take part in layout or event handling.
+    /// This may be used for debug visualisations and HUD-like overlays.
+    pub fn set_overlay_draw(&mut self, f: Box<dyn FnMut(&mut dyn DrawHandle, Size)>) {
+        self.shared.overlay_draw = Some(f);
+    }
+
+    /// Pre-rasterise glyphs
+    ///
+    /// This should be called after loading fonts but before adding windows,
+    /// to avoid a hitch when text is first drawn.
+    /// See [`DrawShared::preload_glyphs`](kas::draw::DrawShared::preload_glyphs).
+    pub fn preload_glyphs(&mut self, font: FontId, dpem: f32, chars: &str) {
+        self.shared.draw.preload_glyphs(font, dpem, chars);
+    }
+
     /// Create a proxy which can be used to update the UI from another thread
     pub fn create_proxy(&self) -> ToolkitProxy {
         ToolkitProxy {
@@ -217,6 +256,35 @@ impl ToolkitProxy {
             .send_event(ProxyAction::Update(handle, payload))
             .map_err(|_| ClosedError)
     }
+
+    /// Report an input method (IME) preedit update
+    ///
+    /// winit does not report input-method composition: composed text is
+    /// received as `ReceivedCharacter` events and no preedit text is shown.
+    /// Platform integrations may instead report the preedit (uncommitted)
+    /// text and its cursor range here; it is sent to the widget with character
+    /// focus as [`kas::event::Event::ImePreedit`]. An empty `text` clears the
+    /// preedit.
+    pub fn ime_preedit(
+        &self,
+        id: WindowId,
+        text: String,
+        cursor_range: Option<Range<usize>>,
+    ) -> Result<(), ClosedError> {
+        self.proxy
+            .send_event(ProxyAction::ImePreedit(id, text, cursor_range))
+            .map_err(|_| ClosedError)
+    }
+
+    /// Report an input method (IME) commit
+    ///
+    /// The committed `text` is sent to the widget with character focus as
+    /// [`kas::event::Event::ImeCommit`]. See [`ToolkitProxy::ime_preedit`].
+    pub fn ime_commit(&self, id: WindowId, text: String) -> Result<(), ClosedError> {
+        self.proxy
+            .send_event(ProxyAction::ImeCommit(id, text))
+            .map_err(|_| ClosedError)
+    }
 }
 
 #[derive(Debug)]
@@ -224,4 +292,6 @@ enum ProxyAction {
     CloseAll,
     Close(WindowId),
     Update(UpdateHandle, u64),
+    ImePreedit(WindowId, String, Option<Range<usize>>),
+    ImeCommit(WindowId, String),
 }
@@ -5,9 +5,10 @@
 
 //! Options
 
+use kas::event::EventConfig;
 use log::warn;
 use std::env::var;
-pub use wgpu::{BackendBit, PowerPreference};
+pub use wgpu::{BackendBit, PowerPreference, PresentMode};
 
 /// Toolkit options
 #[derive(Clone, PartialEq, Hash)]
@@ -16,6 +17,12 @@ pub struct Options {
     pub power_preference: PowerPreference,
     /// Adapter backend. Default value: PRIMARY (Vulkan/Metal/DX12).
     pub backends: BackendBit,
+    /// Swap chain present mode. Default value: Fifo (vsync).
+    ///
+    /// This may be changed later via [`crate::Toolkit::set_present_mode`].
+    pub present_mode: PresentMode,
+    /// Event handling configuration. Default value: [`EventConfig::default`].
+    pub event: EventConfig,
 }
 
 impl Default for Options {
@@ -23,6 +30,8 @@ impl Default for Options {
         Options {
             power_preference: PowerPreference::LowPower,
             backends: BackendBit::PRIMARY,
+            present_mode: PresentMode::Fifo,
+            event: Default::default(),
         }
     }
 }
@@ -51,6 +60,14 @@ impl Options {
     /// -   `DX12`
     /// -   `PRIMARY`: any of Vulkan, Metal or DX12
     /// -   `SECONDARY`: any of GL or DX11
+    ///
+    /// ### Present mode
+    ///
+    /// The `KAS_PRESENT_MODE` variable supports:
+    ///
+    /// -   `Fifo` or `Vsync`: wait for vertical blanking (no tearing)
+    /// -   `Immediate`: present immediately (lowest latency; may tear)
+    /// -   `Mailbox`: replace any queued frame (no tearing)
     pub fn from_env() -> Self {
         let mut options = Options::default();
 
@@ -87,6 +104,19 @@ impl Options {
             }
         }
 
+        if let Ok(mut v) = var("KAS_PRESENT_MODE") {
+            v.make_ascii_uppercase();
+            options.present_mode = match v.as_str() {
+                "FIFO" | "VSYNC" => PresentMode::Fifo,
+                "IMMEDIATE" => PresentMode::Immediate,
+                "MAILBOX" => PresentMode::Mailbox,
+                other => {
+                    warn!("Unexpected environment value: KAS_PRESENT_MODE={}", other);
+                    options.present_mode
+                }
+            }
+        }
+
         options
     }
 
@@ -5,21 +5,24 @@
 
 //! Shared state
 
-use log::{info, warn};
+use log::info;
 use std::num::NonZeroU32;
 
+use crate::clipboard::{self, Clipboard};
 use crate::draw::{CustomPipe, CustomPipeBuilder, DrawPipe, DrawWindow, ShaderManager};
 use crate::{Error, Options, WindowId};
-use kas::event::UpdateHandle;
+use kas::draw::DrawHandle;
+use kas::event::{EventConfig, UpdateHandle};
+use kas::geom::Size;
+use kas::ClipboardFormat;
 use kas_theme::Theme;
 
-#[cfg(feature = "clipboard")]
-use clipboard::{ClipboardContext, ClipboardProvider};
+/// Closure used to draw an overlay over each window
+pub type OverlayDraw = Box<dyn FnMut(&mut dyn DrawHandle, Size)>;
 
 /// State shared between windows
 pub struct SharedState<C: CustomPipe, T> {
-    #[cfg(feature = "clipboard")]
-    clipboard: Option<ClipboardContext>,
+    clipboard: Clipboard,
     pub instance: wgpu::Instance,
     pub device: wgpu::Device,
     pub queue: wgpu::Queue,
@@ -27,9 +30,14 @@ pub struct SharedState<C: CustomPipe, T> {
     pub draw: DrawPipe<C>,
     pub theme: T,
     pub pending: Vec<PendingAction>,
+    pub overlay_draw: Option<OverlayDraw>,
     /// Newly created windows need to know the scale_factor *before* they are
     /// created. This is used to estimate ideal window size.
     pub scale_factor: f64,
+    /// Present mode used by new swap chains
+    pub present_mode: wgpu::PresentMode,
+    /// Event handling configuration used by new windows
+    pub event_config: EventConfig,
     window_id: u32,
 }
 
@@ -44,14 +52,7 @@ where
         options: Options,
         scale_factor: f64,
     ) -> Result<Self, Error> {
-        #[cfg(feature = "clipboard")]
-        let clipboard = match ClipboardContext::new() {
-            Ok(cb) => Some(cb),
-            Err(e) => {
-                warn!("Unable to open clipboard: {:?}", e);
-                None
-            }
-        };
+        let clipboard = Clipboard::new(clipboard::system());
 
         let instance = wgpu::Instance::new(options.backend());
         let adapter_options = options.adapter_options();
@@ -76,7 +77,6 @@ where
         theme.init(&mut draw);
 
         Ok(SharedState {
-            #[cfg(feature = "clipboard")]
             clipboard,
             instance,
             device,
@@ -85,7 +85,10 @@ where
             draw,
             theme,
             pending: vec![],
+            overlay_draw: None,
             scale_factor,
+            present_mode: options.present_mode,
+            event_config: options.event,
             window_id: 0,
         })
     }
@@ -110,35 +113,18 @@ where
         );
     }
 
-    #[cfg(not(feature = "clipboard"))]
+    /// Get clipboard contents
+    ///
+    /// See [`Clipboard`] regarding formats other than plain text.
     #[inline]
-    pub fn get_clipboard(&mut self) -> Option<String> {
-        None
-    }
-
-    #[cfg(feature = "clipboard")]
-    pub fn get_clipboard(&mut self) -> Option<String> {
-        self.clipboard
-            .as_mut()
-            .and_then(|cb| match cb.get_contents() {
-                Ok(c) => Some(c),
-                Err(e) => {
-                    warn!("Failed to get clipboard contents: {:?}", e);
-                    None
-                }
-            })
+    pub fn get_clipboard(&mut self, format: &ClipboardFormat) -> Option<Vec<u8>> {
+        self.clipboard.get(format)
     }
 
-    #[cfg(not(feature = "clipboard"))]
+    /// Set clipboard contents
     #[inline]
-    pub fn set_clipboard<'c>(&mut self, _: std::borrow::Cow<'c, str>) {}
-
-    #[cfg(feature = "clipboard")]
-    pub fn set_clipboard<'c>(&mut self, content: std::borrow::Cow<'c, str>) {
-        self.clipboard.as_mut().map(|cb| {
-            cb.set_contents(content.into())
-                .unwrap_or_else(|e| warn!("Failed to set clipboard contents: {:?}", e))
-        });
+    pub fn set_clipboard(&mut self, format: ClipboardFormat, content: Vec<u8>) {
+        self.clipboard.set(format, content)
     }
 }
 
@@ -6,22 +6,23 @@
 //! `Window` and `WindowList` types
 
 use log::{debug, info, trace};
+use std::ops::Range;
 use std::time::Instant;
 
-use kas::draw::SizeHandle;
+use kas::draw::{ClipRegion, DrawHandle, ImageFilter, ImageHandle, SizeHandle};
 use kas::event::{CursorIcon, ManagerState, UpdateHandle};
 use kas::geom::{Coord, Rect, Size};
 use kas::layout::SolveCache;
-use kas::{ThemeAction, ThemeApi, TkAction, WindowId};
+use kas::{ClipboardFormat, ThemeAction, ThemeApi, TkAction, WindowId};
 use kas_theme::Theme;
-use winit::dpi::PhysicalSize;
+use winit::dpi::{PhysicalPosition, PhysicalSize};
 use winit::error::OsError;
 use winit::event::WindowEvent;
 use winit::event_loop::EventLoopWindowTarget;
 use winit::window::WindowBuilder;
 
 use crate::draw::{CustomPipe, CustomWindow, DrawPipe, DrawWindow, TEX_FORMAT};
-use crate::shared::{PendingAction, SharedState};
+use crate::shared::{OverlayDraw, PendingAction, SharedState};
 use crate::ProxyAction;
 
 /// Per-window data
@@ -69,13 +70,21 @@ where
         let ideal = solve_cache.ideal(true).max(Size(1, 1));
         drop(size_handle);
 
+        let layout = (solve_cache.min(true), solve_cache.ideal(true));
+        let (min, max) = size_limits(&*widget, layout, scale_factor);
+        let mut ideal = ideal;
+        if let Some(max) = max {
+            ideal = ideal.min(max);
+        }
+        if let Some(min) = min {
+            ideal = ideal.max(min);
+        }
         let mut builder = WindowBuilder::new().with_inner_size(ideal);
-        let restrict_dimensions = widget.restrict_dimensions();
-        if restrict_dimensions.0 {
-            builder = builder.with_min_inner_size(solve_cache.min(true));
+        if let Some(min) = min {
+            builder = builder.with_min_inner_size(min);
         }
-        if restrict_dimensions.1 {
-            builder = builder.with_max_inner_size(ideal);
+        if let Some(max) = max {
+            builder = builder.with_max_inner_size(max);
         }
         let window = builder.with_title(widget.title()).build(elwt)?;
 
@@ -94,11 +103,12 @@ where
             format: TEX_FORMAT,
             width: size.0,
             height: size.1,
-            present_mode: wgpu::PresentMode::Fifo,
+            present_mode: shared.present_mode,
         };
         let swap_chain = shared.device.create_swap_chain(&surface, &sc_desc);
 
         let mut mgr = ManagerState::new();
+        mgr.set_config(shared.event_config.clone());
         let mut tkw = TkWindow::new(shared, &window, &mut theme_window);
         mgr.configure(&mut tkw, &mut *widget);
 
@@ -131,6 +141,7 @@ where
             .theme
             .update_window(&mut self.theme_window, scale_factor);
         self.solve_cache.invalidate_rule_cache();
+        self.widget.invalidate_popup_rules();
         self.apply_size();
     }
 
@@ -143,6 +154,12 @@ where
         // Note: resize must be handled here to update self.swap_chain.
         match event {
             WindowEvent::Destroyed => (),
+            WindowEvent::CloseRequested => {
+                let mut tkw = TkWindow::new(shared, &self.window, &mut self.theme_window);
+                let widget = &mut *self.widget;
+                self.mgr
+                    .with(&mut tkw, |mgr| mgr.handle_close_request(widget));
+            }
             WindowEvent::Resized(size) => self.do_resize(shared, size),
             WindowEvent::ScaleFactorChanged {
                 scale_factor,
@@ -154,6 +171,7 @@ where
                     .theme
                     .update_window(&mut self.theme_window, scale_factor as f32);
                 self.solve_cache.invalidate_rule_cache();
+                self.widget.invalidate_popup_rules();
                 self.do_resize(shared, *new_inner_size);
             }
             event @ _ => {
@@ -194,6 +212,7 @@ where
             TkAction::SetSize => self.apply_size(),
             TkAction::Resize => {
                 self.solve_cache.invalidate_rule_cache();
+                self.widget.invalidate_popup_rules();
                 self.apply_size();
             }
             TkAction::Reconfigure => self.reconfigure(shared),
@@ -245,6 +264,42 @@ where
         });
     }
 
+    /// Handle an input method (IME) preedit update
+    ///
+    /// winit does not report input-method composition (composed text is
+    /// received as `ReceivedCharacter` events); platform integrations may
+    /// report it via [`crate::ToolkitProxy::ime_preedit`].
+    pub fn handle_ime_preedit<C, T>(
+        &mut self,
+        shared: &mut SharedState<C, T>,
+        text: String,
+        cursor_range: Option<Range<usize>>,
+    ) where
+        C: CustomPipe<Window = CW>,
+        T: Theme<DrawPipe<C>, Window = TW>,
+    {
+        let mut tkw = TkWindow::new(shared, &self.window, &mut self.theme_window);
+        let widget = &mut *self.widget;
+        self.mgr.with(&mut tkw, |mgr| {
+            mgr.handle_ime_preedit(widget, text, cursor_range);
+        });
+    }
+
+    /// Handle an input method (IME) commit
+    ///
+    /// See [`Window::handle_ime_preedit`].
+    pub fn handle_ime_commit<C, T>(&mut self, shared: &mut SharedState<C, T>, text: String)
+    where
+        C: CustomPipe<Window = CW>,
+        T: Theme<DrawPipe<C>, Window = TW>,
+    {
+        let mut tkw = TkWindow::new(shared, &self.window, &mut self.theme_window);
+        let widget = &mut *self.widget;
+        self.mgr.with(&mut tkw, |mgr| {
+            mgr.handle_ime_commit(widget, text);
+        });
+    }
+
     pub fn add_popup<C, T>(
         &mut self,
         shared: &mut SharedState<C, T>,
@@ -265,6 +320,11 @@ where
         self.mgr.send_action(action);
     }
 
+    /// True if any widget awaits an animation frame
+    pub fn is_animating(&self) -> bool {
+        self.mgr.is_animating()
+    }
+
     pub fn send_close<C, T>(&mut self, shared: &mut SharedState<C, T>, id: WindowId)
     where
         C: CustomPipe<Window = CW>,
@@ -300,6 +360,7 @@ where
         self.mgr.configure(&mut tkw, &mut *self.widget);
 
         self.solve_cache.invalidate_rule_cache();
+        self.widget.invalidate_popup_rules();
         self.apply_size();
         trace!("reconfigure completed in {}µs", time.elapsed().as_micros());
     }
@@ -315,20 +376,34 @@ where
             .apply_rect(self.widget.as_widget_mut(), &mut size_handle, rect, true);
         self.widget.resize_popups(&mut size_handle);
 
-        let restrict_dimensions = self.widget.restrict_dimensions();
-        if restrict_dimensions.0 {
-            self.window
-                .set_min_inner_size(Some(self.solve_cache.min(true)));
-        };
-        if restrict_dimensions.1 {
-            self.window
-                .set_max_inner_size(Some(self.solve_cache.ideal(true)));
-        };
+        let scale_factor = self.window.scale_factor() as f32;
+        let layout = (self.solve_cache.min(true), self.solve_cache.ideal(true));
+        let (min, max) = size_limits(&*self.widget, layout, scale_factor);
+        // Limits are always set since `None` clears a previous limit
+        self.window.set_min_inner_size(min);
+        self.window.set_max_inner_size(max);
 
         self.window.request_redraw();
         trace!("apply_size completed in {}µs", time.elapsed().as_micros());
     }
 
+    /// Change the present mode, recreating the swap chain if required
+    pub fn set_present_mode<C, T>(&mut self, shared: &SharedState<C, T>, mode: wgpu::PresentMode)
+    where
+        C: CustomPipe<Window = CW>,
+        T: Theme<DrawPipe<C>, Window = TW>,
+    {
+        if set_present_mode(&mut self.sc_desc, mode) {
+            debug!("Window::set_present_mode: {:?}", mode);
+            // The old swap chain is dropped on assignment, after the new one
+            // is created; no frame is held at this point.
+            self.swap_chain = shared
+                .device
+                .create_swap_chain(&self.surface, &self.sc_desc);
+            self.window.request_redraw();
+        }
+    }
+
     fn do_resize<C, T>(&mut self, shared: &mut SharedState<C, T>, size: PhysicalSize<u32>)
     where
         C: CustomPipe<Window = CW>,
@@ -365,12 +440,25 @@ where
         T: Theme<DrawPipe<C>, Window = TW>,
     {
         let time = Instant::now();
+        if self.mgr.is_animating() {
+            let mut tkw = TkWindow::new(shared, &self.window, &mut self.theme_window);
+            let widget = &mut *self.widget;
+            self.mgr.with(&mut tkw, |mgr| {
+                mgr.animation_frame(widget);
+            });
+        }
+
         let size = Size(self.sc_desc.width, self.sc_desc.height);
         let rect = Rect {
             pos: Coord::ZERO,
             size,
         };
 
+        let mut size_handle = unsafe { self.theme_window.size_handle() };
+        self.mgr
+            .update_inspector(self.widget.as_widget_mut(), &mut size_handle);
+        drop(size_handle);
+
         unsafe {
             // Safety: we must drop draw_handle after draw call (wrong lifetime)
             let mut draw_handle =
@@ -378,6 +466,18 @@ where
                     .theme
                     .draw_handle(&mut self.draw, &mut self.theme_window, rect);
             self.widget.draw(&mut draw_handle, &self.mgr, false);
+
+            if let Some(ghost) = self.mgr.drag_ghost() {
+                // The drag ghost is drawn above pop-ups
+                let class = ClipRegion::Drag;
+                draw_handle.clip_region(rect, Coord::ZERO, class, &mut |draw_handle| {
+                    draw_handle.outer_frame(ghost)
+                });
+            }
+
+            if let Some(overlay) = shared.overlay_draw.as_mut() {
+                draw_overlay(&mut draw_handle, size, overlay);
+            }
         }
 
         let time2 = Instant::now();
@@ -402,6 +502,22 @@ where
     }
 }
 
+// Draw the user overlay above everything else, including pop-ups
+fn draw_overlay<D: DrawHandle + ?Sized>(
+    draw_handle: &mut D,
+    size: Size,
+    overlay: &mut OverlayDraw,
+) {
+    let rect = Rect {
+        pos: Coord::ZERO,
+        size,
+    };
+    let class = ClipRegion::Tooltip;
+    draw_handle.clip_region(rect, Coord::ZERO, class, &mut |draw_handle| {
+        overlay(draw_handle, size)
+    });
+}
+
 fn to_wgpu_color(c: kas::draw::Colour) -> wgpu::Color {
     wgpu::Color {
         r: c.r as f64,
@@ -411,6 +527,15 @@ fn to_wgpu_color(c: kas::draw::Colour) -> wgpu::Color {
     }
 }
 
+// Set the present mode of sc_desc, returning true if this changed
+fn set_present_mode(sc_desc: &mut wgpu::SwapChainDescriptor, mode: wgpu::PresentMode) -> bool {
+    if sc_desc.present_mode == mode {
+        return false;
+    }
+    sc_desc.present_mode = mode;
+    true
+}
+
 struct TkWindow<'a, C: CustomPipe, T: Theme<DrawPipe<C>>>
 where
     T::Window: kas_theme::Window,
@@ -477,13 +602,13 @@ where
     }
 
     #[inline]
-    fn get_clipboard(&mut self) -> Option<String> {
-        self.shared.get_clipboard()
+    fn get_clipboard_data(&mut self, format: &ClipboardFormat) -> Option<Vec<u8>> {
+        self.shared.get_clipboard(format)
     }
 
     #[inline]
-    fn set_clipboard<'c>(&mut self, content: std::borrow::Cow<'c, str>) {
-        self.shared.set_clipboard(content);
+    fn set_clipboard_data(&mut self, format: ClipboardFormat, content: Vec<u8>) {
+        self.shared.set_clipboard(format, content);
     }
 
     fn adjust_theme(&mut self, f: &mut dyn FnMut(&mut dyn ThemeApi) -> ThemeAction) {
@@ -500,8 +625,148 @@ where
         f(&mut size_handle);
     }
 
+    fn load_image(&mut self, size: Size, data: &[u8], filter: ImageFilter) -> ImageHandle {
+        let shared = &mut self.shared;
+        let (device, queue) = (&shared.device, &shared.queue);
+        shared.draw.load_image(device, queue, size, data, filter)
+    }
+
     #[inline]
     fn set_cursor_icon(&mut self, icon: CursorIcon) {
         self.window.set_cursor_icon(icon);
     }
+
+    #[inline]
+    fn set_ime_position(&mut self, rect: Rect) {
+        // winit enables the input method by default; composed text is
+        // received as `ReceivedCharacter` events (see ToolkitProxy::ime_preedit)
+        let pos = rect.pos + Coord(0, rect.size.1 as i32);
+        self.window
+            .set_ime_position(PhysicalPosition::new(pos.0, pos.1));
+    }
+}
+
+/// Effective `(min, max)` window size limits
+///
+/// This combines layout-derived limits (if enabled by
+/// [`kas::Window::restrict_dimensions`]) with [`kas::Window::size_bounds`]
+/// and [`kas::Window::logical_size_bounds`].
+// Get the (min, max) window size limits, given the layout's (min, ideal) sizes
+fn size_limits(
+    widget: &dyn kas::Window,
+    layout: (Size, Size),
+    scale_factor: f32,
+) -> (Option<Size>, Option<Size>) {
+    let restrict = widget.restrict_dimensions();
+    let (bound_min, bound_max) = widget.size_bounds();
+    let (logical_min, logical_max) = widget.logical_size_bounds();
+    let bound_min = match (bound_min, logical_min.map(|s| s.to_physical(scale_factor))) {
+        (Some(a), Some(b)) => Some(a.max(b)),
+        (a, b) => a.or(b),
+    };
+    let bound_max = match (bound_max, logical_max.map(|s| s.to_physical(scale_factor))) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    };
+
+    let layout_min = Some(layout.0).filter(|_| restrict.0);
+    let min = match (layout_min, bound_min) {
+        (Some(a), Some(b)) => Some(a.max(b)),
+        (a, b) => a.or(b),
+    };
+
+    let layout_max = Some(layout.1).filter(|_| restrict.1);
+    let max = match (layout_max, bound_max) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    };
+
+    // Conflicting bounds: max may not be less than min
+    let max = match (min, max) {
+        (Some(min), Some(max)) => Some(max.max(min)),
+        (_, max) => max,
+    };
+    (min, max)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn present_mode() {
+        let mut sc_desc = wgpu::SwapChainDescriptor {
+            usage: wgpu::TextureUsage::OUTPUT_ATTACHMENT,
+            format: TEX_FORMAT,
+            width: 100,
+            height: 100,
+            present_mode: wgpu::PresentMode::Fifo,
+        };
+        // The swap chain is only recreated on change
+        assert!(!set_present_mode(&mut sc_desc, wgpu::PresentMode::Fifo));
+        assert!(set_present_mode(&mut sc_desc, wgpu::PresentMode::Immediate));
+        assert_eq!(sc_desc.present_mode, wgpu::PresentMode::Immediate);
+        assert!(!set_present_mode(
+            &mut sc_desc,
+            wgpu::PresentMode::Immediate
+        ));
+    }
+
+    #[test]
+    fn size_limits_cleared() {
+        let layout = (Size(100, 50), Size(200, 80));
+        let mut window = kas::widget::Window::new("", kas::widget::Label::new("label"));
+        window.set_restrict_dimensions(false, false);
+        window.set_size_bounds(Some(Size(300, 200)), Some(Size(400, 300)));
+        assert_eq!(
+            size_limits(&window, layout, 1.0),
+            (Some(Size(300, 200)), Some(Size(400, 300)))
+        );
+
+        // Removing bounds yields `None`, which must be applied to clear limits
+        window.set_size_bounds(None, None);
+        assert_eq!(size_limits(&window, layout, 1.0), (None, None));
+
+        window.set_restrict_dimensions(true, true);
+        assert_eq!(
+            size_limits(&window, layout, 1.0),
+            (Some(layout.0), Some(layout.1))
+        );
+    }
+
+    #[test]
+    fn overlay_draw() {
+        use kas::test_util::{DrawCommand, RecordingDrawHandle};
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        let size = Size(320, 240);
+        let rect = Rect::new(Coord::ZERO, size);
+        let mut draw_handle = RecordingDrawHandle::new(rect);
+
+        let calls = Rc::new(Cell::new(0));
+        let calls2 = calls.clone();
+        let cross = Rect::new(Coord(155, 0), Size(10, 240));
+        let mut overlay: OverlayDraw = Box::new(move |draw_handle, size| {
+            assert_eq!(size, Size(320, 240));
+            assert_eq!(draw_handle.target_rect(), Rect::new(Coord::ZERO, size));
+            calls2.set(calls2.get() + 1);
+            draw_handle.separator(cross);
+        });
+
+        draw_overlay(&mut draw_handle, size, &mut overlay);
+        assert_eq!(calls.get(), 1);
+        assert_eq!(
+            draw_handle.take_commands(),
+            vec![
+                DrawCommand::ClipStart(rect, Coord::ZERO, ClipRegion::Tooltip),
+                DrawCommand::Separator(cross),
+                DrawCommand::ClipEnd,
+            ]
+        );
+
+        // The closure is invoked again on each draw pass
+        draw_overlay(&mut draw_handle, size, &mut overlay);
+        assert_eq!(calls.get(), 2);
+    }
 }
@@ -0,0 +1,202 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Accessibility model
+//!
+//! Each widget describes itself via [`WidgetConfig::accessible`], returning an
+//! [`AccessNode`]: a [`Role`], label, value and checked state. A snapshot of
+//! the whole tree, including focus and disabled state, may be constructed via
+//! [`Manager::access_tree`]. Toolkits may use this to bridge to platform
+//! accessibility APIs (e.g. AT-SPI or UI Automation).
+//!
+//! Changes in focus are announced via an [`UpdateHandle`]; see
+//! [`Manager::set_access_handle`].
+//!
+//! [`UpdateHandle`]: crate::event::UpdateHandle
+
+#[allow(unused)]
+use crate::event::Manager; // for doc-links
+use crate::event::ManagerState;
+use crate::{WidgetConfig, WidgetId};
+
+/// The role of a widget
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Role {
+    /// A container or otherwise unclassified widget
+    Generic,
+    /// A push-button
+    Button,
+    /// A check box (or toggle)
+    CheckBox,
+    /// A radio button
+    RadioButton,
+    /// A text-entry box
+    TextBox,
+    /// Non-editable text
+    Label,
+    /// A menu (list of menu items)
+    Menu,
+    /// An entry in a menu (possibly opening a sub-menu)
+    MenuItem,
+    /// A slider
+    Slider,
+    /// A drop-down list
+    ComboBox,
+}
+
+impl Role {
+    /// True for roles which describe their children
+    ///
+    /// Widgets with these roles are presented as a single node: their label
+    /// and value cover any child widgets (for example the label of a
+    /// [`CheckBox`](crate::widget::CheckBox)). Menu items and drop-down lists
+    /// are not leaves since their pop-up menus are children.
+    pub fn is_leaf(self) -> bool {
+        match self {
+            Role::Generic | Role::Menu | Role::MenuItem | Role::ComboBox => false,
+            _ => true,
+        }
+    }
+}
+
+impl Default for Role {
+    fn default() -> Self {
+        Role::Generic
+    }
+}
+
+/// Description of a widget, as reported by [`WidgetConfig::accessible`]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AccessNode {
+    /// The widget's role
+    pub role: Role,
+    /// A label (e.g. the text of a button)
+    pub label: Option<String>,
+    /// A value (e.g. the contents of a text box)
+    pub value: Option<String>,
+    /// Checked state, for check boxes and radio buttons
+    pub checked: Option<bool>,
+}
+
+impl AccessNode {
+    /// Construct with the given role
+    pub fn new(role: Role) -> Self {
+        AccessNode {
+            role,
+            ..Default::default()
+        }
+    }
+
+    /// Set the label (chain style)
+    pub fn with_label<S: ToString>(mut self, label: S) -> Self {
+        self.label = Some(label.to_string());
+        self
+    }
+
+    /// Set the value (chain style)
+    pub fn with_value<S: ToString>(mut self, value: S) -> Self {
+        self.value = Some(value.to_string());
+        self
+    }
+
+    /// Set the checked state (chain style)
+    pub fn with_checked(mut self, checked: bool) -> Self {
+        self.checked = Some(checked);
+        self
+    }
+}
+
+/// A snapshot of the accessibility tree
+///
+/// Constructed by [`Manager::access_tree`]. Children of widgets whose role
+/// [is a leaf](Role::is_leaf) are omitted; such nodes represent all their
+/// descendants (identifiers `first_id..=id`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct AccessTree {
+    /// The widget's identifier
+    pub id: WidgetId,
+    /// The first identifier of the widget's descendants (see
+    /// [`WidgetChildren::first_id`](crate::WidgetChildren::first_id))
+    pub first_id: WidgetId,
+    /// The widget's description
+    pub node: AccessNode,
+    /// True if this widget or an ancestor is disabled
+    pub disabled: bool,
+    /// True if this widget has navigation or character focus
+    ///
+    /// For leaf nodes, this is true if any descendant has focus.
+    pub focused: bool,
+    /// Child nodes
+    pub children: Vec<AccessTree>,
+}
+
+impl AccessTree {
+    /// Construct a snapshot of `widget` and its descendants
+    pub fn new(widget: &dyn WidgetConfig, mgr: &ManagerState) -> Self {
+        Self::new_inner(widget, mgr, false)
+    }
+
+    fn new_inner(widget: &dyn WidgetConfig, mgr: &ManagerState, disabled: bool) -> Self {
+        let id = widget.id();
+        let node = widget.accessible();
+        let disabled = disabled || widget.is_disabled();
+        let has_focus = |id| mgr.nav_focus(id) || mgr.char_focus(id).0;
+        let mut focused = has_focus(id);
+        let mut children = vec![];
+        if node.role.is_leaf() {
+            widget.walk_dyn(&mut |w| focused |= has_focus(w.id()));
+        } else {
+            for i in 0..widget.len() {
+                if let Some(w) = widget.get(i) {
+                    children.push(Self::new_inner(w, mgr, disabled));
+                }
+            }
+        }
+        AccessTree {
+            id,
+            first_id: widget.first_id(),
+            node,
+            disabled,
+            focused,
+            children,
+        }
+    }
+
+    /// Find the node representing widget `id`, if present
+    ///
+    /// This is either the node for `id` or a leaf node representing `id`.
+    pub fn find(&self, id: WidgetId) -> Option<&AccessTree> {
+        if self.id == id || (self.node.role.is_leaf() && self.first_id <= id && id <= self.id) {
+            return Some(self);
+        }
+        self.children.iter().find_map(|child| child.find(id))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::event::VoidMsg;
+    use crate::widget::{CheckBox, Column, Label};
+
+    #[test]
+    fn tree() {
+        let mut column: Column<Box<dyn crate::Widget<Msg = VoidMsg>>> = Column::new(vec![]);
+        let _ = column.push(Box::new(Label::new("Hello")));
+        let _ = column.push(Box::new(CheckBox::new("Check").state(true)));
+
+        let tree = AccessTree::new(&column, &ManagerState::new());
+        assert_eq!(tree.node, AccessNode::default());
+        assert_eq!(tree.children.len(), 2);
+        let label = AccessNode::new(Role::Label).with_label("Hello");
+        assert_eq!(tree.children[0].node, label);
+        let checkbox = AccessNode::new(Role::CheckBox)
+            .with_checked(true)
+            .with_label("Check");
+        assert_eq!(tree.children[1].node, checkbox);
+        // The check box's children are not listed
+        assert!(tree.children[1].children.is_empty());
+    }
+}
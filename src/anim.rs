@@ -0,0 +1,296 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Animation utilities
+//!
+//! A widget may hold a [`Tween`], [advance](Tween::advance) it on each
+//! [`Event::AnimationFrame`](crate::event::Event::AnimationFrame) and query
+//! the current [value](Tween::value) when drawing.
+
+use std::time::Duration;
+
+use crate::draw::Colour;
+use crate::geom::{Coord, Vec2};
+
+/// Types supporting linear interpolation
+pub trait Lerp: Copy {
+    /// Interpolate between `self` (`t = 0`) and `other` (`t = 1`)
+    fn lerp(self, other: Self, t: f32) -> Self;
+}
+
+impl Lerp for f32 {
+    #[inline]
+    fn lerp(self, other: Self, t: f32) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Lerp for Vec2 {
+    #[inline]
+    fn lerp(self, other: Self, t: f32) -> Self {
+        Vec2(
+            Lerp::lerp(self.0, other.0, t),
+            Lerp::lerp(self.1, other.1, t),
+        )
+    }
+}
+
+impl Lerp for Coord {
+    /// Interpolate, rounding to the nearest pixel
+    #[inline]
+    fn lerp(self, other: Self, t: f32) -> Self {
+        let lerp = |a: i32, b: i32| Lerp::lerp(a as f32, b as f32, t).round() as i32;
+        Coord(lerp(self.0, other.0), lerp(self.1, other.1))
+    }
+}
+
+impl Lerp for Colour {
+    /// Interpolate each component (including alpha) independently
+    #[inline]
+    fn lerp(self, other: Self, t: f32) -> Self {
+        Colour {
+            r: Lerp::lerp(self.r, other.r, t),
+            g: Lerp::lerp(self.g, other.g, t),
+            b: Lerp::lerp(self.b, other.b, t),
+            a: Lerp::lerp(self.a, other.a, t),
+        }
+    }
+}
+
+/// Easing curves
+///
+/// Each maps progress `t` in the range `0..=1` to an interpolation factor,
+/// mapping 0 to 0 and 1 to 1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Easing {
+    /// Constant speed
+    Linear,
+    /// Smooth acceleration and deceleration (smoothstep)
+    EaseInOut,
+    /// Cubic acceleration from rest
+    CubicIn,
+    /// Cubic deceleration to rest
+    CubicOut,
+    /// Cubic acceleration then deceleration
+    CubicInOut,
+}
+
+impl Default for Easing {
+    fn default() -> Self {
+        Easing::Linear
+    }
+}
+
+impl Easing {
+    /// Apply the curve to progress `t`
+    ///
+    /// Input is clamped to the range `0..=1`.
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.max(0.0).min(1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseInOut => t * t * (3.0 - 2.0 * t),
+            Easing::CubicIn => t * t * t,
+            Easing::CubicOut => {
+                let u = 1.0 - t;
+                1.0 - u * u * u
+            }
+            Easing::CubicInOut if t < 0.5 => 4.0 * t * t * t,
+            Easing::CubicInOut => {
+                let u = 2.0 - 2.0 * t;
+                1.0 - 0.5 * u * u * u
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Segment<T> {
+    start: T,
+    end: T,
+    duration: Duration,
+    easing: Easing,
+}
+
+/// An interpolated value
+///
+/// A tween interpolates from a start value to an end value over a duration,
+/// following an [`Easing`] curve. Tweens may be sequenced: [`Tween::then`]
+/// continues from the current end value and [`Tween::chain`] appends another
+/// tween.
+///
+/// Time does not advance automatically; call [`Tween::advance`] (typically
+/// with the `delta` of each animation frame).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tween<T: Lerp> {
+    segments: Vec<Segment<T>>,
+    elapsed: Duration,
+}
+
+impl<T: Lerp> Tween<T> {
+    /// Construct, interpolating linearly from `start` to `end`
+    pub fn new(start: T, end: T, duration: Duration) -> Self {
+        Tween {
+            segments: vec![Segment {
+                start,
+                end,
+                duration,
+                easing: Easing::Linear,
+            }],
+            elapsed: Duration::default(),
+        }
+    }
+
+    /// Set the easing curve of the last step (chain style)
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.segments.last_mut().unwrap().easing = easing;
+        self
+    }
+
+    /// Continue from the current end value to `end` (chain style)
+    ///
+    /// The new step interpolates linearly; use [`Tween::with_easing`] to
+    /// change this.
+    pub fn then(mut self, end: T, duration: Duration) -> Self {
+        let start = self.end();
+        self.segments.push(Segment {
+            start,
+            end,
+            duration,
+            easing: Easing::Linear,
+        });
+        self
+    }
+
+    /// Append another tween (chain style)
+    ///
+    /// The steps of `next` follow those of `self`; its elapsed time is
+    /// ignored.
+    pub fn chain(mut self, next: Tween<T>) -> Self {
+        self.segments.extend(next.segments);
+        self
+    }
+
+    /// Advance time by `delta`
+    ///
+    /// Returns true while the tween has not finished.
+    pub fn advance(&mut self, delta: Duration) -> bool {
+        self.elapsed = (self.elapsed + delta).min(self.duration());
+        !self.is_finished()
+    }
+
+    /// Restart from the beginning
+    pub fn reset(&mut self) {
+        self.elapsed = Duration::default();
+    }
+
+    /// True when the total duration has elapsed
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.duration()
+    }
+
+    /// The total duration of all steps
+    pub fn duration(&self) -> Duration {
+        self.segments.iter().map(|seg| seg.duration).sum()
+    }
+
+    /// The elapsed time
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// The final value
+    pub fn end(&self) -> T {
+        self.segments.last().unwrap().end
+    }
+
+    /// The current value
+    pub fn value(&self) -> T {
+        let mut time = self.elapsed;
+        for seg in &self.segments {
+            if time < seg.duration {
+                let t = time.as_secs_f32() / seg.duration.as_secs_f32();
+                return seg.start.lerp(seg.end, seg.easing.apply(t));
+            }
+            time -= seg.duration;
+        }
+        self.end()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EASINGS: [Easing; 5] = [
+        Easing::Linear,
+        Easing::EaseInOut,
+        Easing::CubicIn,
+        Easing::CubicOut,
+        Easing::CubicInOut,
+    ];
+
+    #[test]
+    fn easing_bounds() {
+        for easing in EASINGS.iter().cloned() {
+            assert_eq!(easing.apply(0.0), 0.0, "{:?}", easing);
+            assert_eq!(easing.apply(1.0), 1.0, "{:?}", easing);
+            assert_eq!(easing.apply(-1.0), 0.0, "{:?}", easing);
+            assert_eq!(easing.apply(2.0), 1.0, "{:?}", easing);
+            if easing != Easing::CubicIn && easing != Easing::CubicOut {
+                assert!((easing.apply(0.5) - 0.5).abs() < 1e-6, "{:?}", easing);
+            }
+        }
+        assert!(Easing::CubicIn.apply(0.5) < 0.5);
+        assert!(Easing::CubicOut.apply(0.5) > 0.5);
+    }
+
+    #[test]
+    fn tween_bounds() {
+        let sec = Duration::from_secs(1);
+        let red = Colour::new(1.0, 0.0, 0.0);
+        let blue = Colour::new(0.0, 0.0, 1.0);
+        for easing in EASINGS.iter().cloned() {
+            let mut tween = Tween::new(red, blue, sec).with_easing(easing);
+            assert_eq!(tween.value(), red);
+            assert!(!tween.advance(sec));
+            assert_eq!(tween.value(), blue);
+
+            let mut tween = Tween::new(Coord(0, 10), Coord(100, -10), sec).with_easing(easing);
+            assert_eq!(tween.value(), Coord(0, 10));
+            tween.advance(sec * 2);
+            assert_eq!(tween.value(), Coord(100, -10));
+        }
+    }
+
+    #[test]
+    fn sequence() {
+        let ms = Duration::from_millis;
+        let mut tween = Tween::new(0.0, 1.0, ms(100))
+            .then(3.0, ms(200))
+            .chain(Tween::new(10.0, 20.0, ms(100)));
+        assert_eq!(tween.duration(), ms(400));
+        assert_eq!(tween.value(), 0.0);
+
+        assert!(tween.advance(ms(50)));
+        assert_eq!(tween.value(), 0.5);
+        assert!(tween.advance(ms(150)));
+        assert_eq!(tween.value(), 2.0);
+        assert!(tween.advance(ms(150)));
+        assert_eq!(tween.value(), 15.0);
+        assert!(!tween.advance(ms(100)));
+        assert_eq!(tween.elapsed(), ms(400));
+        assert_eq!(tween.value(), 20.0);
+
+        tween.reset();
+        assert!(!tween.is_finished());
+        assert_eq!(tween.value(), 0.0);
+
+        // A zero-duration step jumps to its end value
+        let tween = Tween::new(0.0, 1.0, ms(0)).then(5.0, ms(0));
+        assert!(tween.is_finished());
+        assert_eq!(tween.value(), 5.0);
+    }
+}
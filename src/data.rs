@@ -67,8 +67,14 @@ impl<T: 'static> Future<T> {
 /// `Option<WidgetId>` is a free extension (requires no extra memory).
 ///
 /// Identifiers are assigned when configured and when re-configured
-/// (via [`kas::TkAction::Reconfigure`]). Since user-code is not notified of a
-/// re-configure, user-code should not store a `WidgetId`.
+/// (via [`kas::TkAction::Reconfigure`]), in depth-first order such that each
+/// widget's identifier is greater than those of its descendants.
+///
+/// A re-configure assigns the same identifiers as before if the structure of
+/// the widget tree is unchanged; other changes (e.g. to the disabled state of
+/// a widget) do not affect identifiers. When widgets are added or removed,
+/// identifiers of subsequent widgets (and their ancestors) change; a stored
+/// `WidgetId` may be updated via [`kas::event::Manager::remap_id`].
 #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct WidgetId(NonZeroU32);
 
@@ -179,6 +185,17 @@ impl AlignHints {
         (self.horiz.unwrap_or(horiz), self.vert.unwrap_or(vert))
     }
 
+    /// Combine with another set of hints
+    ///
+    /// Hints from `self` take priority; any missing from `self` are taken from
+    /// `other`. This may be used to apply a widget-local override.
+    pub fn or(self, other: AlignHints) -> AlignHints {
+        AlignHints {
+            horiz: self.horiz.or(other.horiz),
+            vert: self.vert.or(other.vert),
+        }
+    }
+
     /// Complete via defaults and ideal size information
     pub fn complete(&self, horiz: Align, vert: Align, ideal: Size) -> CompleteAlignment {
         CompleteAlignment {
@@ -0,0 +1,143 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Font information
+
+/// Information about a loaded font
+///
+/// See [`DrawShared::fonts`](super::DrawShared::fonts). Vertical metrics are
+/// read from the font's `hhea` table (or `OS/2`, if the font requests use of
+/// its typographic metrics) and are scaled by `dpem`, the font size
+/// in pixels per em (i.e. the font size in points multiplied by
+/// `scale_factor * 96 / 72`).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FontInfo {
+    family: Option<String>,
+    // Metrics in units of the em size
+    ascent: f32,
+    descent: f32,
+    line_gap: f32,
+}
+
+impl FontInfo {
+    /// Read font information from font data
+    ///
+    /// Supports OpenType and TrueType fonts and collections; `index` selects
+    /// the font within a collection (use 0 otherwise). Returns `None` if the
+    /// data cannot be parsed or lacks required tables.
+    pub fn parse(data: &[u8], index: u32) -> Option<Self> {
+        let face = ttf_parser::Face::from_slice(data, index).ok()?;
+        let units_per_em = f32::from(face.units_per_em()?);
+        let metric = |v: i16| f32::from(v) / units_per_em;
+        Some(FontInfo {
+            family: face.family_name(),
+            ascent: metric(face.ascender()),
+            descent: metric(face.descender()),
+            line_gap: metric(face.line_gap()),
+        })
+    }
+
+    /// The font family name, if available
+    pub fn family(&self) -> Option<&str> {
+        self.family.as_deref()
+    }
+
+    /// Distance from the baseline to the top of the line, in pixels
+    pub fn ascent(&self, dpem: f32) -> f32 {
+        self.ascent * dpem
+    }
+
+    /// Distance from the baseline to the bottom of the line, in pixels
+    ///
+    /// This is usually negative (below the baseline).
+    pub fn descent(&self, dpem: f32) -> f32 {
+        self.descent * dpem
+    }
+
+    /// Recommended gap between lines, in pixels
+    pub fn line_gap(&self, dpem: f32) -> f32 {
+        self.line_gap * dpem
+    }
+
+    /// Line height (ascent minus descent plus line gap), in pixels
+    pub fn line_height(&self, dpem: f32) -> f32 {
+        (self.ascent - self.descent + self.line_gap) * dpem
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::convert::TryInto;
+
+    // Construct a minimal font with `head`, `hhea`, `maxp` and `name` tables
+    fn font(family: &str, units_per_em: u16, metrics: [i16; 3]) -> Vec<u8> {
+        let mut head = vec![0; 54];
+        head[18..20].copy_from_slice(&units_per_em.to_be_bytes());
+        let mut hhea = vec![0; 36];
+        for (i, m) in metrics.iter().enumerate() {
+            hhea[4 + 2 * i..6 + 2 * i].copy_from_slice(&m.to_be_bytes());
+        }
+        let string: Vec<u8> = family
+            .encode_utf16()
+            .flat_map(|u| u.to_be_bytes().to_vec())
+            .collect();
+        let mut name = vec![];
+        for v in &[0u16, 1, 18, 3, 1, 0x409, 1, string.len() as u16, 0] {
+            name.extend_from_slice(&v.to_be_bytes());
+        }
+        name.extend_from_slice(&string);
+        // Version 0.5 with one glyph
+        let maxp = vec![0, 0, 0x50, 0, 0, 1];
+
+        let tables: [(&[u8; 4], Vec<u8>); 4] = [
+            (b"head", head),
+            (b"hhea", hhea),
+            (b"maxp", maxp),
+            (b"name", name),
+        ];
+        let mut data = vec![0, 1, 0, 0, 0, tables.len() as u8, 0, 0, 0, 0, 0, 0];
+        let mut offset = 12 + 16 * tables.len();
+        for (tag, table) in &tables {
+            data.extend_from_slice(&tag[..]);
+            data.extend_from_slice(&[0; 4]);
+            data.extend_from_slice(&(offset as u32).to_be_bytes());
+            data.extend_from_slice(&(table.len() as u32).to_be_bytes());
+            offset += table.len();
+        }
+        for (_, table) in &tables {
+            data.extend_from_slice(table);
+        }
+        data
+    }
+
+    #[test]
+    fn parse_fonts() {
+        let a = FontInfo::parse(&font("Sans", 1024, [768, -256, 128]), 0).unwrap();
+        assert_eq!(a.family(), Some("Sans"));
+        assert_eq!(a.ascent(16.0), 12.0);
+        assert_eq!(a.descent(16.0), -4.0);
+        assert_eq!(a.line_gap(16.0), 2.0);
+        assert_eq!(a.line_height(16.0), 18.0);
+
+        // A font collection; table offsets are relative to the file start
+        let mut data = vec![];
+        data.extend_from_slice(b"ttcf");
+        data.extend_from_slice(&[0, 1, 0, 0, 0, 0, 0, 1, 0, 0, 0, 16]);
+        let mut b = font("Serif Ünicode", 2048, [1536, -512, 0]);
+        for i in 0..4 {
+            let pos = 12 + 16 * i + 8;
+            let offset = u32::from_be_bytes(b[pos..pos + 4].try_into().unwrap());
+            b[pos..pos + 4].copy_from_slice(&(offset + 16).to_be_bytes());
+        }
+        data.extend_from_slice(&b);
+        let b = FontInfo::parse(&data, 0).unwrap();
+        assert_eq!(b.family(), Some("Serif Ünicode"));
+        assert_eq!(b.ascent(16.0), 12.0);
+        assert_eq!(b.line_height(16.0), 16.0);
+        assert_eq!(FontInfo::parse(&data, 1), None);
+        assert_eq!(FontInfo::parse(&data[..20], 0), None);
+    }
+}
@@ -7,21 +7,56 @@
 
 use std::convert::AsRef;
 use std::ops::{Bound, Deref, DerefMut, Range, RangeBounds};
+use std::time::Duration;
 
-use kas::draw::{Draw, Pass};
+use kas::draw::{Colour, Draw, ImageId, Pass};
 use kas::geom::{Coord, Rect, Size, Vec2};
 use kas::layout::{AxisInfo, Margins, SizeRules};
-use kas::text::{format::FormattableText, AccelString, Text, TextApi, TextDisplay};
-use kas::Direction;
+use kas::text::{format::FormattableText, AccelString, RichText, Text, TextApi, TextDisplay};
+use kas::{Direction, Directional};
 
 // for doc use
 #[allow(unused)]
 use kas::text::TextApiExt;
 
 /// Classification of a clip region
+///
+/// Besides clipping, the class determines the depth of the new region
+/// relative to its parent. From lowest to highest, the layers are: normal
+/// content (including [`ClipRegion::Scroll`] regions), [`ClipRegion::Popup`],
+/// [`ClipRegion::Drag`] and [`ClipRegion::Tooltip`]. Anything drawn in a
+/// higher layer appears above anything drawn in a lower one, regardless of
+/// draw order (including text).
+///
+/// Depth is relative to the parent region, thus layers are only strictly
+/// ordered when each is started from the window's top-level draw handle (as
+/// the toolkit does for pop-ups).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ClipRegion {
+    /// A pop-up (menu or similar), above normal content
     Popup,
+    /// A scrollable region, at the same layer as its parent
     Scroll,
+    /// Drag-and-drop feedback, above pop-ups
+    Drag,
+    /// Tooltips and other overlays, above everything else
+    Tooltip,
+}
+
+impl ClipRegion {
+    /// Get the clip rect of a new region of this class
+    ///
+    /// Given the region's `rect` and the clip rect of its `parent` (in the
+    /// same coordinate space), this returns the rect to which the new region
+    /// is clipped. [`ClipRegion::Scroll`] regions are clipped to their parent.
+    /// Other (overlay) layers are not, thus a pop-up, drag feedback or tooltip
+    /// started within a scroll region may extend beyond that region.
+    pub fn clip_rect(self, rect: Rect, parent: Rect) -> Rect {
+        match self {
+            ClipRegion::Scroll => rect.intersection(parent),
+            ClipRegion::Popup | ClipRegion::Drag | ClipRegion::Tooltip => rect,
+        }
+    }
 }
 
 /// Input and highlighting state of a widget
@@ -45,6 +80,10 @@ pub struct InputState {
     /// graphics. The `hover` state should be ignored when depressed.
     pub depress: bool,
     /// Keyboard navigation of UIs moves a "focus" from widget to widget.
+    ///
+    /// Themes draw a focus ring for this state. It is only set while the
+    /// focus was last moved via the keyboard (see
+    /// [`ManagerState::focus_is_keyboard`](crate::event::ManagerState::focus_is_keyboard)).
     pub nav_focus: bool,
     /// "Character focus" implies this widget is ready to receive text input
     /// (e.g. typing into an input field).
@@ -101,6 +140,24 @@ impl Default for TextClass {
     }
 }
 
+/// Shape of a text-edit caret (marker)
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CaretShape {
+    /// A thin vertical line before the character
+    Bar,
+    /// A filled box covering the character
+    Block,
+    /// A line under the character
+    Underline,
+}
+
+/// Default shape: Bar
+impl Default for CaretShape {
+    fn default() -> Self {
+        CaretShape::Bar
+    }
+}
+
 /// Handle passed to objects during draw and sizing operations
 ///
 /// This handle is provided by the toolkit (usually via a theme implementation)
@@ -150,14 +207,17 @@ pub trait SizeHandle {
     /// Update a [`Text`] and get a size bound
     ///
     /// First, this method updates the text's [`Environment`]: `bounds`, `dpp`
-    /// and `pt_size` are set. Second, the text is prepared (which is necessary
-    /// to calculate size requirements). Finally, this converts the requirements
-    /// to a [`SizeRules`] value and returns it.
+    /// and `pt_size` are set. The font (`font_id`) is not changed, allowing
+    /// widgets to select a font (see e.g. [`Label::with_font`]). Second, the
+    /// text is prepared (which is necessary to calculate size requirements).
+    /// Finally, this converts the requirements to a [`SizeRules`] value and
+    /// returns it.
     ///
     /// Usually this method is used in [`Layout::size_rules`], then
     /// [`TextApiExt::update_env`] is used in [`Layout::set_rect`].
     ///
     /// [`Environment`]: kas::text::Environment
+    /// [`Label::with_font`]: kas::widget::Label::with_font
     /// [`Layout::set_rect`]: kas::Layout::set_rect
     /// [`Layout::size_rules`]: kas::Layout::size_rules
     fn text_bound(&mut self, text: &mut dyn TextApi, class: TextClass, axis: AxisInfo)
@@ -166,6 +226,12 @@ pub trait SizeHandle {
     /// Width of an edit marker
     fn edit_marker_width(&self) -> f32;
 
+    /// Interval at which an edit marker blinks
+    ///
+    /// The marker is alternately shown and hidden for this duration. If
+    /// `None`, the marker does not blink.
+    fn edit_marker_blink(&self) -> Option<Duration>;
+
     /// Size of the sides of a button.
     ///
     /// Returns `(top_left, bottom_right)` dimensions as two `Size`s.
@@ -178,6 +244,15 @@ pub trait SizeHandle {
     /// Excludes size of content area.
     fn edit_surround(&self) -> (Size, Size);
 
+    /// Inset of the content area within a framed element
+    ///
+    /// Returns the distance from each edge of an element drawn for the given
+    /// `class` (e.g. [`DrawHandle::edit_box`] for [`TextClass::Edit`]) to the
+    /// area within which content may be drawn without overlapping the frame,
+    /// including its rounded corners. Text classes without a frame return
+    /// [`Margins::ZERO`].
+    fn content_inset(&self, class: TextClass) -> Margins;
+
     /// Size of the element drawn by [`DrawHandle::checkbox`].
     fn checkbox(&self) -> Size;
 
@@ -248,6 +323,10 @@ pub trait DrawHandle {
     ///
     /// All content drawn by the new region is clipped to the given `rect`
     /// (in the current coordinate space, i.e. not translated by `offset`).
+    /// Regions may be nested; a nested [`ClipRegion::Scroll`] region is
+    /// additionally clipped to its parent region (i.e. the clip rect is the
+    /// intersection of the two), while overlay layers are not (see
+    /// [`ClipRegion::clip_rect`]). Only rectangular clip regions are supported.
     fn clip_region(
         &mut self,
         rect: Rect,
@@ -260,7 +339,8 @@ pub trait DrawHandle {
     ///
     /// If this instance of [`DrawHandle`] was created via
     /// [`DrawHandle::clip_region`], then this returns the `rect` passed to
-    /// that method; otherwise this returns the window's `rect`.
+    /// that method (clipped as by [`ClipRegion::clip_rect`]); otherwise this
+    /// returns the window's `rect`.
     fn target_rect(&self) -> Rect;
 
     /// Draw a frame inside the given `rect`
@@ -268,6 +348,14 @@ pub trait DrawHandle {
     /// The frame dimensions equal those of [`SizeHandle::frame`] on each side.
     fn outer_frame(&mut self, rect: Rect);
 
+    /// Draw a frame inside the given `rect`, with a gap for a title
+    ///
+    /// This is like [`DrawHandle::outer_frame`], except that the frame's top
+    /// edge is left clear around `title` (plus [`SizeHandle::inner_margin`]
+    /// on each side). `title` may overlap the frame's top edge. The title
+    /// text itself should be drawn separately.
+    fn titled_frame(&mut self, rect: Rect, title: Rect);
+
     /// Draw a menu frame and background inside the given `rect`
     ///
     /// The frame dimensions equal those of [`SizeHandle::frame`] on each side.
@@ -276,11 +364,42 @@ pub trait DrawHandle {
     /// Draw a separator in the given `rect`
     fn separator(&mut self, rect: Rect);
 
+    /// Fill the given `rect` as the background of an alternate table row
+    ///
+    /// Used for zebra-striping of grids.
+    fn grid_stripe(&mut self, rect: Rect);
+
+    /// Draw a line between grid cells, filling the given `rect`
+    fn grid_line(&mut self, rect: Rect);
+
+    /// Fill the given `rect` with a linear colour gradient
+    ///
+    /// The colour is `cols.0` on the starting edge and `cols.1` on the final
+    /// edge, where `dir` is the direction from start to end.
+    /// See [`Draw::gradient_rect`].
+    fn gradient(&mut self, rect: Rect, cols: (Colour, Colour), dir: Direction);
+
+    /// Draw a colour swatch, filling the given `rect`
+    ///
+    /// This shows a user-chosen `colour`, e.g. in a colour picker. Themes
+    /// draw the colour opaque (alpha is not blended).
+    fn swatch(&mut self, rect: Rect, colour: Colour);
+
+    /// Draw an image, scaled to fill the given `rect`
+    ///
+    /// See [`kas::event::Manager::load_image`].
+    fn image(&mut self, id: ImageId, rect: Rect);
+
     /// Draw some text using the standard font
     ///
     /// The `text` is drawn within the rect from `pos` to `text.env().bounds`,
     /// but offset by subtracting `offset` (allowing scrolling).
     ///
+    /// If `disabled`, the text is drawn in the disabled (dimmed) style. This
+    /// applies to all text-drawing methods; widgets should usually pass the
+    /// `disabled` state given to [`Layout::draw`](crate::Layout::draw) (or
+    /// [`InputState::disabled`]).
+    ///
     /// The dimensions required for this text may be queried with [`SizeHandle::text_bound`].
     fn text_offset(
         &mut self,
@@ -289,6 +408,7 @@ pub trait DrawHandle {
         offset: Coord,
         text: &TextDisplay,
         class: TextClass,
+        disabled: bool,
     );
 
     /// Draw text with effects
@@ -296,14 +416,51 @@ pub trait DrawHandle {
     /// [`DrawHandle::text_offset`] already supports *font* effects: bold,
     /// emphasis, text size. In addition, this method supports underline and
     /// strikethrough effects.
-    fn text_effects(&mut self, pos: Coord, offset: Coord, text: &dyn TextApi, class: TextClass);
+    fn text_effects(
+        &mut self,
+        pos: Coord,
+        offset: Coord,
+        text: &dyn TextApi,
+        class: TextClass,
+        disabled: bool,
+    );
 
     /// Draw an `AccelString` text
     ///
     /// The `text` is drawn within the rect from `pos` to `text.env().bounds`.
+    /// Accelerator keys are underlined if `state`.
     ///
     /// The dimensions required for this text may be queried with [`SizeHandle::text_bound`].
-    fn text_accel(&mut self, pos: Coord, text: &Text<AccelString>, state: bool, class: TextClass);
+    fn text_accel(
+        &mut self,
+        pos: Coord,
+        text: &Text<AccelString>,
+        state: bool,
+        class: TextClass,
+        disabled: bool,
+    );
+
+    /// Draw a [`RichText`] text
+    ///
+    /// The `text` is drawn within the rect from `pos` to `text.env().bounds`.
+    /// Runs without a colour use the colour for `class`.
+    ///
+    /// The dimensions required for this text may be queried with [`SizeHandle::text_bound`].
+    fn text_rich(&mut self, pos: Coord, text: &Text<RichText>, class: TextClass, disabled: bool);
+
+    /// Draw a [`RichText`] text with links
+    ///
+    /// This is like [`DrawHandle::text_rich`], except that each range of
+    /// `links` (byte indices into the text) is drawn as a link, in a style
+    /// depending on the given state (hover, nav focus, etc.).
+    fn text_links(
+        &mut self,
+        pos: Coord,
+        text: &Text<RichText>,
+        links: &[(Range<usize>, InputState)],
+        class: TextClass,
+        disabled: bool,
+    );
 
     /// Method used to implement [`DrawHandleExt::text_selected`]
     #[cfg_attr(not(feature = "internal_doc"), doc(hidden))]
@@ -315,9 +472,15 @@ pub trait DrawHandle {
         text: &TextDisplay,
         range: Range<usize>,
         class: TextClass,
+        disabled: bool,
     );
 
-    /// Draw an edit marker at the given `byte` index on this `text`
+    /// Draw an edit marker (caret) on this `text`
+    ///
+    /// The marker is placed at byte index `range.start`. The `range` should
+    /// cover the character following the marker (or be empty at the end of
+    /// the text); the [`CaretShape::Block`] and [`CaretShape::Underline`]
+    /// shapes use its width.
     fn edit_marker(
         &mut self,
         pos: Coord,
@@ -325,12 +488,33 @@ pub trait DrawHandle {
         offset: Coord,
         text: &TextDisplay,
         class: TextClass,
-        byte: usize,
+        range: Range<usize>,
+        shape: CaretShape,
     );
 
     /// Draw the background of a menu entry
     fn menu_entry(&mut self, rect: Rect, state: InputState);
 
+    /// Draw UI element: the mark of an active menu entry
+    ///
+    /// This is a check mark, or a bullet if `radio`, drawn over the
+    /// [`SizeHandle::checkbox`]-sized `rect` to the left of a menu entry's
+    /// label. It is only drawn for active (checked) entries.
+    fn menu_mark(&mut self, rect: Rect, radio: bool, state: InputState);
+
+    /// Draw the bubble of a badge (e.g. a notification count)
+    ///
+    /// This is a small, filled, pill-shaped element. Text is drawn over it
+    /// separately using [`TextClass::Button`].
+    fn badge(&mut self, rect: Rect, state: InputState);
+
+    /// Draw UI element: a chevron (arrow-head)
+    ///
+    /// This is a small triangular mark pointing in direction `dir`, drawn
+    /// centred within `rect` (usually sized via [`SizeHandle::checkbox`]). It
+    /// is used to indicate the state of an expandable section.
+    fn chevron(&mut self, rect: Rect, dir: Direction, state: InputState);
+
     /// Draw button sides, background and margin-area highlight
     fn button(&mut self, rect: Rect, state: InputState);
 
@@ -390,9 +574,15 @@ pub trait DrawHandleExt: DrawHandle {
     /// The `text` is drawn within the rect from `pos` to `text.env().bounds`.
     ///
     /// The dimensions required for this text may be queried with [`SizeHandle::text_bound`].
-    fn text<T: FormattableText>(&mut self, pos: Coord, text: &Text<T>, class: TextClass) {
+    fn text<T: FormattableText>(
+        &mut self,
+        pos: Coord,
+        text: &Text<T>,
+        class: TextClass,
+        disabled: bool,
+    ) {
         let bounds = text.env().bounds.into();
-        self.text_offset(pos, bounds, Coord::ZERO, text.as_ref(), class);
+        self.text_offset(pos, bounds, Coord::ZERO, text.as_ref(), class, disabled);
     }
 
     /// Draw some text using the standard font, with a subset selected
@@ -408,6 +598,7 @@ pub trait DrawHandleExt: DrawHandle {
         text: T,
         range: R,
         class: TextClass,
+        disabled: bool,
     ) {
         let start = match range.start_bound() {
             Bound::Included(n) => *n,
@@ -420,12 +611,74 @@ pub trait DrawHandleExt: DrawHandle {
             Bound::Unbounded => usize::MAX,
         };
         let range = Range { start, end };
-        self.text_selected_range(pos, bounds, offset, text.as_ref(), range, class);
+        let text = text.as_ref();
+        self.text_selected_range(pos, bounds, offset, text, range, class, disabled);
+    }
+
+    /// Fill the given `rect` with a multi-stop linear colour gradient
+    ///
+    /// Each stop is a `(position, colour)` pair, where position is in the
+    /// range `0.0..=1.0` along the direction `dir`. Stops must be sorted by
+    /// position. Before the first stop and after the last, the colour is
+    /// constant. See also [`DrawHandle::gradient`].
+    fn gradient_stops(&mut self, rect: Rect, stops: &[(f32, Colour)], dir: Direction) {
+        for (band, cols) in gradient_bands(rect, stops, dir) {
+            self.gradient(band, cols, dir);
+        }
     }
 }
 
 impl<D: DrawHandle + ?Sized> DrawHandleExt for D {}
 
+// Split rect into bands of two-colour gradients
+fn gradient_bands(
+    rect: Rect,
+    stops: &[(f32, Colour)],
+    dir: Direction,
+) -> Vec<(Rect, (Colour, Colour))> {
+    debug_assert!(
+        stops.windows(2).all(|w| w[0].0 <= w[1].0),
+        "gradient stops must be sorted"
+    );
+    let len = if dir.is_horizontal() {
+        rect.size.0
+    } else {
+        rect.size.1
+    };
+    let offset = |pos: f32| (pos.max(0.0).min(1.0) * len as f32).round() as u32;
+
+    let mut bands = Vec::with_capacity(stops.len() + 1);
+    let (first, last) = match (stops.first(), stops.last()) {
+        (Some(first), Some(last)) => (*first, *last),
+        _ => return bands,
+    };
+    let mut push = |a: u32, b: u32, cols: (Colour, Colour)| {
+        if a >= b {
+            return;
+        }
+        let start = if dir.is_reversed() { len - b } else { a };
+        let band = if dir.is_horizontal() {
+            Rect::new(
+                Coord(rect.pos.0 + start as i32, rect.pos.1),
+                Size(b - a, rect.size.1),
+            )
+        } else {
+            Rect::new(
+                Coord(rect.pos.0, rect.pos.1 + start as i32),
+                Size(rect.size.0, b - a),
+            )
+        };
+        bands.push((band, cols));
+    };
+
+    push(0, offset(first.0), (first.1, first.1));
+    for w in stops.windows(2) {
+        push(offset(w[0].0), offset(w[1].0), (w[0].1, w[1].1));
+    }
+    push(offset(last.0), len, (last.1, last.1));
+    bands
+}
+
 impl<S: SizeHandle> SizeHandle for Box<S> {
     fn scale_factor(&self) -> f32 {
         self.deref().scale_factor()
@@ -458,6 +711,9 @@ impl<S: SizeHandle> SizeHandle for Box<S> {
     fn edit_marker_width(&self) -> f32 {
         self.deref().edit_marker_width()
     }
+    fn edit_marker_blink(&self) -> Option<Duration> {
+        self.deref().edit_marker_blink()
+    }
 
     fn button_surround(&self) -> (Size, Size) {
         self.deref().button_surround()
@@ -465,6 +721,9 @@ impl<S: SizeHandle> SizeHandle for Box<S> {
     fn edit_surround(&self) -> (Size, Size) {
         self.deref().edit_surround()
     }
+    fn content_inset(&self, class: TextClass) -> Margins {
+        self.deref().content_inset(class)
+    }
 
     fn checkbox(&self) -> Size {
         self.deref().checkbox()
@@ -516,6 +775,9 @@ where
     fn edit_marker_width(&self) -> f32 {
         self.deref().edit_marker_width()
     }
+    fn edit_marker_blink(&self) -> Option<Duration> {
+        self.deref().edit_marker_blink()
+    }
 
     fn button_surround(&self) -> (Size, Size) {
         self.deref().button_surround()
@@ -523,6 +785,9 @@ where
     fn edit_surround(&self) -> (Size, Size) {
         self.deref().edit_surround()
     }
+    fn content_inset(&self, class: TextClass) -> Margins {
+        self.deref().content_inset(class)
+    }
 
     fn checkbox(&self) -> Size {
         self.deref().checkbox()
@@ -560,12 +825,30 @@ impl<H: DrawHandle> DrawHandle for Box<H> {
     fn outer_frame(&mut self, rect: Rect) {
         self.deref_mut().outer_frame(rect);
     }
+    fn titled_frame(&mut self, rect: Rect, title: Rect) {
+        self.deref_mut().titled_frame(rect, title);
+    }
     fn menu_frame(&mut self, rect: Rect) {
         self.deref_mut().menu_frame(rect);
     }
     fn separator(&mut self, rect: Rect) {
         self.deref_mut().separator(rect);
     }
+    fn grid_stripe(&mut self, rect: Rect) {
+        self.deref_mut().grid_stripe(rect);
+    }
+    fn grid_line(&mut self, rect: Rect) {
+        self.deref_mut().grid_line(rect);
+    }
+    fn gradient(&mut self, rect: Rect, cols: (Colour, Colour), dir: Direction) {
+        self.deref_mut().gradient(rect, cols, dir);
+    }
+    fn swatch(&mut self, rect: Rect, colour: Colour) {
+        self.deref_mut().swatch(rect, colour);
+    }
+    fn image(&mut self, id: ImageId, rect: Rect) {
+        self.deref_mut().image(id, rect);
+    }
     fn text_offset(
         &mut self,
         pos: Coord,
@@ -573,15 +856,46 @@ impl<H: DrawHandle> DrawHandle for Box<H> {
         offset: Coord,
         text: &TextDisplay,
         class: TextClass,
+        disabled: bool,
     ) {
         self.deref_mut()
-            .text_offset(pos, bounds, offset, text, class)
+            .text_offset(pos, bounds, offset, text, class, disabled)
     }
-    fn text_effects(&mut self, pos: Coord, offset: Coord, text: &dyn TextApi, class: TextClass) {
-        self.deref_mut().text_effects(pos, offset, text, class);
+    fn text_effects(
+        &mut self,
+        pos: Coord,
+        offset: Coord,
+        text: &dyn TextApi,
+        class: TextClass,
+        disabled: bool,
+    ) {
+        self.deref_mut()
+            .text_effects(pos, offset, text, class, disabled);
+    }
+    fn text_accel(
+        &mut self,
+        pos: Coord,
+        text: &Text<AccelString>,
+        state: bool,
+        class: TextClass,
+        disabled: bool,
+    ) {
+        self.deref_mut()
+            .text_accel(pos, text, state, class, disabled);
+    }
+    fn text_rich(&mut self, pos: Coord, text: &Text<RichText>, class: TextClass, disabled: bool) {
+        self.deref_mut().text_rich(pos, text, class, disabled);
     }
-    fn text_accel(&mut self, pos: Coord, text: &Text<AccelString>, state: bool, class: TextClass) {
-        self.deref_mut().text_accel(pos, text, state, class);
+    fn text_links(
+        &mut self,
+        pos: Coord,
+        text: &Text<RichText>,
+        links: &[(Range<usize>, InputState)],
+        class: TextClass,
+        disabled: bool,
+    ) {
+        self.deref_mut()
+            .text_links(pos, text, links, class, disabled);
     }
     fn text_selected_range(
         &mut self,
@@ -591,9 +905,10 @@ impl<H: DrawHandle> DrawHandle for Box<H> {
         text: &TextDisplay,
         range: Range<usize>,
         class: TextClass,
+        disabled: bool,
     ) {
         self.deref_mut()
-            .text_selected_range(pos, bounds, offset, text, range, class);
+            .text_selected_range(pos, bounds, offset, text, range, class, disabled);
     }
     fn edit_marker(
         &mut self,
@@ -602,14 +917,24 @@ impl<H: DrawHandle> DrawHandle for Box<H> {
         offset: Coord,
         text: &TextDisplay,
         class: TextClass,
-        byte: usize,
+        range: Range<usize>,
+        shape: CaretShape,
     ) {
         self.deref_mut()
-            .edit_marker(pos, bounds, offset, text, class, byte)
+            .edit_marker(pos, bounds, offset, text, class, range, shape)
     }
     fn menu_entry(&mut self, rect: Rect, state: InputState) {
         self.deref_mut().menu_entry(rect, state)
     }
+    fn menu_mark(&mut self, rect: Rect, radio: bool, state: InputState) {
+        self.deref_mut().menu_mark(rect, radio, state)
+    }
+    fn badge(&mut self, rect: Rect, state: InputState) {
+        self.deref_mut().badge(rect, state)
+    }
+    fn chevron(&mut self, rect: Rect, dir: Direction, state: InputState) {
+        self.deref_mut().chevron(rect, dir, state)
+    }
     fn button(&mut self, rect: Rect, state: InputState) {
         self.deref_mut().button(rect, state)
     }
@@ -656,12 +981,30 @@ where
     fn outer_frame(&mut self, rect: Rect) {
         self.deref_mut().outer_frame(rect);
     }
+    fn titled_frame(&mut self, rect: Rect, title: Rect) {
+        self.deref_mut().titled_frame(rect, title);
+    }
     fn menu_frame(&mut self, rect: Rect) {
         self.deref_mut().menu_frame(rect);
     }
     fn separator(&mut self, rect: Rect) {
         self.deref_mut().separator(rect);
     }
+    fn grid_stripe(&mut self, rect: Rect) {
+        self.deref_mut().grid_stripe(rect);
+    }
+    fn grid_line(&mut self, rect: Rect) {
+        self.deref_mut().grid_line(rect);
+    }
+    fn gradient(&mut self, rect: Rect, cols: (Colour, Colour), dir: Direction) {
+        self.deref_mut().gradient(rect, cols, dir);
+    }
+    fn swatch(&mut self, rect: Rect, colour: Colour) {
+        self.deref_mut().swatch(rect, colour);
+    }
+    fn image(&mut self, id: ImageId, rect: Rect) {
+        self.deref_mut().image(id, rect);
+    }
     fn text_offset(
         &mut self,
         pos: Coord,
@@ -669,15 +1012,46 @@ where
         offset: Coord,
         text: &TextDisplay,
         class: TextClass,
+        disabled: bool,
     ) {
         self.deref_mut()
-            .text_offset(pos, bounds, offset, text, class)
+            .text_offset(pos, bounds, offset, text, class, disabled)
     }
-    fn text_effects(&mut self, pos: Coord, offset: Coord, text: &dyn TextApi, class: TextClass) {
-        self.deref_mut().text_effects(pos, offset, text, class);
+    fn text_effects(
+        &mut self,
+        pos: Coord,
+        offset: Coord,
+        text: &dyn TextApi,
+        class: TextClass,
+        disabled: bool,
+    ) {
+        self.deref_mut()
+            .text_effects(pos, offset, text, class, disabled);
     }
-    fn text_accel(&mut self, pos: Coord, text: &Text<AccelString>, state: bool, class: TextClass) {
-        self.deref_mut().text_accel(pos, text, state, class);
+    fn text_accel(
+        &mut self,
+        pos: Coord,
+        text: &Text<AccelString>,
+        state: bool,
+        class: TextClass,
+        disabled: bool,
+    ) {
+        self.deref_mut()
+            .text_accel(pos, text, state, class, disabled);
+    }
+    fn text_rich(&mut self, pos: Coord, text: &Text<RichText>, class: TextClass, disabled: bool) {
+        self.deref_mut().text_rich(pos, text, class, disabled);
+    }
+    fn text_links(
+        &mut self,
+        pos: Coord,
+        text: &Text<RichText>,
+        links: &[(Range<usize>, InputState)],
+        class: TextClass,
+        disabled: bool,
+    ) {
+        self.deref_mut()
+            .text_links(pos, text, links, class, disabled);
     }
     fn text_selected_range(
         &mut self,
@@ -687,9 +1061,10 @@ where
         text: &TextDisplay,
         range: Range<usize>,
         class: TextClass,
+        disabled: bool,
     ) {
         self.deref_mut()
-            .text_selected_range(pos, bounds, offset, text, range, class);
+            .text_selected_range(pos, bounds, offset, text, range, class, disabled);
     }
     fn edit_marker(
         &mut self,
@@ -698,14 +1073,24 @@ where
         offset: Coord,
         text: &TextDisplay,
         class: TextClass,
-        byte: usize,
+        range: Range<usize>,
+        shape: CaretShape,
     ) {
         self.deref_mut()
-            .edit_marker(pos, bounds, offset, text, class, byte)
+            .edit_marker(pos, bounds, offset, text, class, range, shape)
     }
     fn menu_entry(&mut self, rect: Rect, state: InputState) {
         self.deref_mut().menu_entry(rect, state)
     }
+    fn menu_mark(&mut self, rect: Rect, radio: bool, state: InputState) {
+        self.deref_mut().menu_mark(rect, radio, state)
+    }
+    fn badge(&mut self, rect: Rect, state: InputState) {
+        self.deref_mut().badge(rect, state)
+    }
+    fn chevron(&mut self, rect: Rect, dir: Direction, state: InputState) {
+        self.deref_mut().chevron(rect, dir, state)
+    }
     fn button(&mut self, rect: Rect, state: InputState) {
         self.deref_mut().button(rect, state)
     }
@@ -739,6 +1124,31 @@ mod test {
         let zero = Coord::ZERO;
         let bounds = Vec2(100.0, 30.0);
         let text = kas::text::Text::new_single("sample");
-        draw_handle.text_selected(zero, bounds, zero, &text, .., TextClass::Label)
+        draw_handle.text_selected(zero, bounds, zero, &text, .., TextClass::Label, false)
+    }
+
+    #[test]
+    fn gradient_three_stops() {
+        let (red, green, blue) = (
+            Colour::new(1.0, 0.0, 0.0),
+            Colour::new(0.0, 1.0, 0.0),
+            Colour::new(0.0, 0.0, 1.0),
+        );
+        let stops = [(0.0, red), (0.5, green), (1.0, blue)];
+        let rect = Rect::new(Coord(10, 0), Size(100, 20));
+
+        let bands = gradient_bands(rect, &stops, Direction::Right);
+        assert_eq!(bands.len(), 2);
+        assert_eq!(bands[0].0, Rect::new(Coord(10, 0), Size(50, 20)));
+        assert_eq!(bands[0].1, (red, green));
+        assert_eq!(bands[1].0, Rect::new(Coord(60, 0), Size(50, 20)));
+        assert_eq!(bands[1].1, (green, blue));
+
+        let bands = gradient_bands(rect, &stops[1..], Direction::Left);
+        assert_eq!(bands.len(), 2);
+        assert_eq!(bands[0].0, Rect::new(Coord(60, 0), Size(50, 20)));
+        assert_eq!(bands[0].1, (green, green));
+        assert_eq!(bands[1].0, Rect::new(Coord(10, 0), Size(50, 20)));
+        assert_eq!(bands[1].1, (green, blue));
     }
 }
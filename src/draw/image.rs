@@ -0,0 +1,91 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Image resources
+
+use std::sync::mpsc::Sender;
+
+use crate::geom::Size;
+
+/// Identifier for an image loaded by the toolkit
+///
+/// Images are loaded via [`kas::event::Manager::load_image`], which returns
+/// an [`ImageHandle`] owning the image.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ImageId(u32);
+
+impl ImageId {
+    /// Construct
+    ///
+    /// Only for use by toolkits.
+    #[cfg_attr(not(feature = "internal_doc"), doc(hidden))]
+    #[inline]
+    pub const fn new(n: u32) -> Self {
+        ImageId(n)
+    }
+
+    /// Get the identifier number
+    #[inline]
+    pub fn get(self) -> u32 {
+        self.0
+    }
+}
+
+/// Filtering used when an image is drawn at a size other than its own
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ImageFilter {
+    /// Use the nearest pixel (suitable for pixel art)
+    Nearest,
+    /// Interpolate linearly between pixels (suitable for photographs)
+    Linear,
+}
+
+/// Default filter: Linear
+impl Default for ImageFilter {
+    fn default() -> Self {
+        ImageFilter::Linear
+    }
+}
+
+/// Owning handle to an image loaded by the toolkit
+///
+/// The image resource is freed when this handle is dropped. For this reason
+/// the handle does not support `Clone`; load the image again if needed.
+#[derive(Debug)]
+pub struct ImageHandle {
+    id: ImageId,
+    size: Size,
+    release: Sender<ImageId>,
+}
+
+impl ImageHandle {
+    /// Construct
+    ///
+    /// Only for use by toolkits. When dropped, `id` is sent to `release`; the
+    /// toolkit should then free the image resource.
+    #[cfg_attr(not(feature = "internal_doc"), doc(hidden))]
+    pub fn new(id: ImageId, size: Size, release: Sender<ImageId>) -> Self {
+        ImageHandle { id, size, release }
+    }
+
+    /// Get the image's identifier, for use with [`super::DrawHandle::image`]
+    #[inline]
+    pub fn id(&self) -> ImageId {
+        self.id
+    }
+
+    /// Get the image's size in pixels
+    #[inline]
+    pub fn size(&self) -> Size {
+        self.size
+    }
+}
+
+impl Drop for ImageHandle {
+    fn drop(&mut self) {
+        // Failure implies the toolkit has already shut down
+        let _ = self.release.send(self.id);
+    }
+}
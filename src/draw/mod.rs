@@ -26,9 +26,9 @@
 //! and may provide their own extension traits. For this reason, themes are
 //! parameterised over an object `D: Draw + ...` (with specified trait bounds).
 //!
-//! The medium-level API will be extended in the future to support texturing
-//! (not yet supported) and potentially a more comprehensive path-based API
-//! (e.g. Lyon).
+//! Images may be drawn via [`Draw::image`], once loaded by the toolkit (see
+//! [`ImageHandle`]). The medium-level API may be extended in the future to
+//! support a more comprehensive path-based API (e.g. Lyon).
 //!
 //! ### Low-level interface
 //!
@@ -38,15 +38,21 @@
 //! [`kas-wgpu::draw::CustomPipe`](https://docs.rs/kas-wgpu/*/kas_wgpu/draw/trait.CustomPipe.html).
 
 mod colour;
+mod font;
 mod handle;
+mod image;
 
 use std::any::Any;
 
 use crate::geom::{Quad, Rect, Vec2};
+use crate::text::fonts::FontId;
 use crate::text::{Effect, TextDisplay};
+use crate::Direction;
 
 pub use colour::Colour;
+pub use font::FontInfo;
 pub use handle::*;
+pub use image::{ImageFilter, ImageHandle, ImageId};
 
 /// Pass identifier
 ///
@@ -82,6 +88,34 @@ impl Pass {
 /// Bounds on type shared across [`Draw`] implementations
 pub trait DrawShared {
     type Draw: Draw;
+
+    /// List loaded fonts
+    ///
+    /// Fonts are indexed by [`FontId`] and include all fonts loaded (via
+    /// [`crate::text::fonts`]) before this call. Fonts which cannot be parsed
+    /// are listed with default (empty) information.
+    fn fonts(&mut self) -> &[FontInfo];
+
+    /// Get information on a font
+    ///
+    /// Returns `None` if `id` is not listed by [`DrawShared::fonts`].
+    fn font_info(&mut self, id: FontId) -> Option<&FontInfo> {
+        self.fonts().get(id.get())
+    }
+
+    /// Pre-rasterise glyphs
+    ///
+    /// Glyphs are usually rasterised when first drawn, which may cause a
+    /// noticeable hitch when much new text appears at once (e.g. a large
+    /// menu). This requests that the glyph of each `char` in `chars` be cached
+    /// in advance, for the font `font` at size `dpem` (pixels per Em).
+    ///
+    /// This is best-effort: glyphs not present in the font (and fonts not
+    /// loaded) are ignored. It should be called after fonts are loaded (see
+    /// [`crate::text::fonts`]) but before the first window is shown.
+    ///
+    /// The default implementation does nothing.
+    fn preload_glyphs(&mut self, _font: FontId, _dpem: f32, _chars: &str) {}
 }
 
 /// Base abstraction over drawing
@@ -118,10 +152,27 @@ pub trait Draw: Any {
     /// Draw a rectangle of uniform colour
     fn rect(&mut self, pass: Pass, rect: Quad, col: Colour);
 
+    /// Draw a rectangle with a linear colour gradient
+    ///
+    /// The colour is `cols.0` on the starting edge and `cols.1` on the final
+    /// edge of `rect`, where `dir` is the direction from start to end. For
+    /// example, `Direction::Down` fades from `cols.0` at the top to `cols.1`
+    /// at the bottom.
+    ///
+    /// [`Colour`] values are linear RGB, and interpolation is linear in this
+    /// space (avoiding the dark mid-tones of interpolation in sRGB space).
+    fn gradient_rect(&mut self, pass: Pass, rect: Quad, cols: (Colour, Colour), dir: Direction);
+
     /// Draw a frame of uniform colour
     ///
     /// The frame is defined by the area inside `outer` and not inside `inner`.
     fn frame(&mut self, pass: Pass, outer: Quad, inner: Quad, col: Colour);
+
+    /// Draw an image
+    ///
+    /// The image is scaled to fill `rect`. If `id` does not refer to a loaded
+    /// image, nothing is drawn.
+    fn image(&mut self, pass: Pass, id: ImageId, rect: Quad);
 }
 
 /// Drawing commands for rounded shapes
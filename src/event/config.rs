@@ -0,0 +1,42 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Event handling: configuration
+
+use std::time::Duration;
+
+/// Event handling configuration
+///
+/// This is provided by the toolkit (see [`super::ManagerState::set_config`]),
+/// for example from system settings.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct EventConfig {
+    /// Time a press must be held (without significant movement) to count as
+    /// a long-press (see [`super::Event::LongPress`])
+    ///
+    /// Default value: 800ms.
+    pub long_press_time: Duration,
+    /// Inactivity period after which [`super::Event::Idle`] is sent to the
+    /// root widget
+    ///
+    /// Default value: `None` (disabled). Widgets may request idle events
+    /// independently via [`super::Manager::update_on_idle`].
+    pub idle_timeout: Option<Duration>,
+    /// Maximum delay between characters continuing a type-ahead search (see
+    /// [`super::TypeAhead`])
+    ///
+    /// Default value: 1s.
+    pub type_ahead_timeout: Duration,
+}
+
+impl Default for EventConfig {
+    fn default() -> Self {
+        EventConfig {
+            long_press_time: Duration::from_millis(800),
+            idle_timeout: None,
+            type_ahead_timeout: Duration::from_secs(1),
+        }
+    }
+}
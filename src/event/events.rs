@@ -5,13 +5,67 @@
 
 //! Event handling: events
 
+use std::any::{Any, TypeId};
+use std::fmt;
+use std::ops::Range;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::time::Duration;
+
 #[allow(unused)]
-use super::{GrabMode, Manager, Response}; // for doc-links
+use super::{EventConfig, GrabMode, Manager, ManagerState, Response}; // for doc-links
 use super::{MouseButton, UpdateHandle, VirtualKeyCode};
 
 use crate::geom::{Coord, DVec2};
 use crate::{WidgetId, WindowId};
 
+/// Payload of an in-application drag
+///
+/// This wraps a value of any type; see [`Manager::start_drag`]. Drop targets
+/// may query the type via [`DragPayload::is`] and access the value via
+/// [`DragPayload::downcast_ref`].
+///
+/// Cloning is cheap (reference counted). Comparison tests identity.
+#[derive(Clone)]
+pub struct DragPayload(Rc<dyn Any>);
+
+impl DragPayload {
+    /// Construct
+    pub fn new<T: Any>(value: T) -> Self {
+        DragPayload(Rc::new(value))
+    }
+
+    /// True if the payload has type `T`
+    #[inline]
+    pub fn is<T: Any>(&self) -> bool {
+        self.0.is::<T>()
+    }
+
+    /// Get the type identifier of the payload
+    #[inline]
+    pub fn payload_type_id(&self) -> TypeId {
+        (*self.0).type_id()
+    }
+
+    /// Access the payload, if of type `T`
+    #[inline]
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        self.0.downcast_ref()
+    }
+}
+
+impl fmt::Debug for DragPayload {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "DragPayload({:?})", self.payload_type_id())
+    }
+}
+
+impl PartialEq for DragPayload {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
 /// Events addressed to a widget
 #[non_exhaustive]
 #[derive(Clone, Debug, PartialEq)]
@@ -48,8 +102,42 @@ pub enum Event {
     /// widget has already received [`Event::LostCharFocus`].
     LostSelFocus,
     /// Widget receives a character of text input
+    ///
+    /// This is sent to the widget with character focus. Without character
+    /// focus, it is sent to the parent of the top-most pop-up (if any), for
+    /// example to match menu mnemonics, otherwise to each ancestor of the
+    /// widget with navigation focus until handled, for example for
+    /// [type-ahead search](super::TypeAhead).
     ReceivedCharacter(char),
+    /// Input method (IME) composition in progress
+    ///
+    /// This is sent to the widget with character focus while composing text
+    /// (for example CJK characters or accented letters). The `text` is the
+    /// uncommitted *preedit* string; it replaces any previous preedit string
+    /// and should be displayed (typically underlined) at the edit position
+    /// without being added to the widget's content. An empty `text` clears the
+    /// preedit string.
+    ///
+    /// `cursor_range` is a byte range within `text` for the composition
+    /// cursor, if any.
+    ImePreedit {
+        text: String,
+        cursor_range: Option<Range<usize>>,
+    },
+    /// Input method (IME) text committed
+    ///
+    /// This is sent to the widget with character focus. The widget should
+    /// discard any preedit string (see [`Event::ImePreedit`]) and insert
+    /// `text` as if typed.
+    ImeCommit { text: String },
     /// A mouse or touchpad scroll event
+    ///
+    /// This is sent to the widget under the mouse cursor. Widgets which do not
+    /// scroll (or cannot scroll further) should return the event via
+    /// [`Response::Unhandled`], allowing an ancestor (e.g. a
+    /// [`kas::widget::ScrollRegion`]) to handle it instead.
+    ///
+    /// See [`ScrollDelta`] for the sign convention.
     Scroll(ScrollDelta),
     /// A mouse or touch-screen move/zoom/rotate event
     ///
@@ -131,11 +219,84 @@ pub enum Event {
         end_id: Option<WidgetId>,
         coord: Coord,
     },
+    /// A press was held stationary for a while
+    ///
+    /// The duration is configured by [`EventConfig::long_press_time`].
+    ///
+    /// Received only given a [press grab](Manager::request_grab) with
+    /// [`GrabMode::Grab`], when the press is held without significant
+    /// movement. If this event is handled, the following [`Event::PressEnd`]
+    /// has `end_id == None`, thus a long-press does not also cause activation
+    /// (a click); if not, the press ends as usual.
+    ///
+    /// This may be used e.g. to open a context menu on touch screens.
+    LongPress { source: PressSource, coord: Coord },
+    /// A file is dragged over the window
+    ///
+    /// This is sent to the widget under the mouse cursor. Widgets which do not
+    /// accept dropped files should return the event via
+    /// [`Response::Unhandled`]; it is then offered to each ancestor in turn.
+    /// The widget which handles this event receives
+    /// [`Event::FileHoverCancelled`] if the file is not dropped.
+    ///
+    /// When multiple files are dragged, one event is sent per file.
+    FileHovered(PathBuf),
+    /// A file is dropped on the window
+    ///
+    /// This is sent to the widget under the mouse cursor and offered to
+    /// ancestors in the same way as [`Event::FileHovered`].
+    FileDropped(PathBuf),
+    /// Files dragged over the window were not dropped
+    ///
+    /// This is sent to the widget which handled [`Event::FileHovered`], if
+    /// any, when files leave the window or the drag is cancelled. It is also
+    /// sent when the cursor moves to another widget, which is then sent
+    /// [`Event::FileHovered`] for each hovered file.
+    FileHoverCancelled,
+    /// An in-application drag entered the widget
+    ///
+    /// This is sent to the widget under the mouse cursor during a drag
+    /// started via [`Manager::start_drag`]. A widget accepting the drop (e.g.
+    /// after checking the payload's type via [`DragPayload::is`]) should return
+    /// [`Response::None`]; it then becomes the drop target and may highlight
+    /// itself (see [`ManagerState::is_drag_target`]). Otherwise it should
+    /// return [`Response::Unhandled`], and the event is offered to each
+    /// ancestor in turn.
+    DragEnter {
+        source: WidgetId,
+        payload: DragPayload,
+    },
+    /// The cursor moved over the drop target
+    DragOver { coord: Coord },
+    /// The drag left the drop target or was cancelled
+    DragLeave,
+    /// The payload was dropped on the drop target
+    Drop {
+        source: WidgetId,
+        payload: DragPayload,
+        coord: Coord,
+    },
     /// Update from a timer
     ///
     /// This event is received after requesting timed wake-up(s)
     /// (see [`Manager::update_on_timer`]).
     TimerUpdate,
+    /// Animation frame
+    ///
+    /// This event is received on the next redraw after requesting it via
+    /// [`Manager::request_animation_frame`]. `delta` is the time elapsed since
+    /// the previous frame (or, for the first frame, since the request). It is
+    /// clamped to at most 100ms, thus an animation does not jump after the
+    /// event loop stalls.
+    ///
+    /// To continue animating, request another frame when handling this event.
+    AnimationFrame { delta: Duration },
+    /// Notification of inactivity
+    ///
+    /// This event is received when no user input has occurred for some time,
+    /// after requesting it via [`Manager::update_on_idle`]. It is received
+    /// at most once per period of inactivity.
+    Idle,
     /// Update triggerred via an [`UpdateHandle`]
     ///
     /// This event may be received after registering an [`UpdateHandle`] via
@@ -158,7 +319,10 @@ pub enum Event {
     PopupRemoved(WindowId),
     /// Sent when a widget receives keyboard navigation focus
     ///
-    /// The widget should reply with [`Response::Focus`].
+    /// The widget should reply with [`Response::Focus`]; if unhandled, the
+    /// widget's rect is used. This is sent both when navigating via the Tab
+    /// key and following [`Manager::set_nav_focus`] or
+    /// [`Manager::set_key_focus`].
     NavFocus,
 }
 
@@ -184,6 +348,15 @@ pub enum ControlKey {
     /// Each press of this key should somehow relax control. It is expected that
     /// widgets receiving this key repeatedly eventually (soon) have no more
     /// use for this themselves and return it via [`Response::Unhandled`].
+    ///
+    /// This key is sent to the widget with character focus or, failing that,
+    /// the widget with navigation focus, and is thus seen by each ancestor
+    /// until handled. When unhandled, the top-most pop-up (if any) is closed;
+    /// otherwise navigation focus is cleared. For example, an [`EditBox`]
+    /// first clears its selection, while a sub-menu closes itself when the
+    /// key is received from within its (open) menu, leaving parent menus open.
+    ///
+    /// [`EditBox`]: crate::widget::EditBox
     Escape,
     /// Line break (return / enter key)
     ///
@@ -313,6 +486,18 @@ impl PressSource {
         }
     }
 
+    /// Returns true if this represents the right mouse button
+    ///
+    /// By convention, this opens a context menu (see
+    /// [`ContextMenu`](crate::widget::ContextMenu)).
+    #[inline]
+    pub fn is_secondary(self) -> bool {
+        match self {
+            PressSource::Mouse(button, _) => button == MouseButton::Right,
+            PressSource::Touch(_) => false,
+        }
+    }
+
     /// Returns true if this represents a touch event
     #[inline]
     pub fn is_touch(self) -> bool {
@@ -336,10 +521,25 @@ impl PressSource {
 }
 
 /// Type used by [`Event::Scroll`]
+///
+/// Both variants have horizontal and vertical components; horizontal scrolling
+/// is reported by some mice and most touchpads.
+///
+/// Sign convention: positive values indicate that content should move left
+/// and up, revealing more content to the right and bottom. For example,
+/// rolling a mouse wheel towards the user yields a positive `y` component,
+/// scrolling the view towards the end of a document. (This is the opposite
+/// of `winit`'s convention; the toolkit negates its deltas.)
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum ScrollDelta {
     /// Scroll a given number of lines
+    ///
+    /// Usually reported by mouse wheels. The widget chooses how many pixels
+    /// a "line" corresponds to.
     LineDelta(f32, f32),
     /// Scroll a given number of pixels
+    ///
+    /// Usually reported by touchpads. The value is in physical pixels (i.e.
+    /// already adjusted for the window's scale factor).
     PixelDelta(Coord),
 }
@@ -116,7 +116,32 @@ impl<'a> Manager<'a> {
     ///
     /// This is a free function often called from [`SendEvent::send`] to
     /// simplify certain events and then invoke [`Handler::handle`].
+    ///
+    /// With feature `trace_events`, the result is logged.
     pub fn handle_generic<W>(
+        widget: &mut W,
+        mgr: &mut Manager,
+        event: Event,
+    ) -> Response<<W as Handler>::Msg>
+    where
+        W: Handler + ?Sized,
+    {
+        #[cfg(feature = "trace_events")]
+        let traced = event.clone();
+        let response = Self::handle_generic_impl(widget, mgr, event);
+        #[cfg(feature = "trace_events")]
+        log::trace!(
+            target: ROUTE_TARGET,
+            "handle: widget={} id={} event={:?} disposition={}",
+            widget.widget_name(),
+            widget.id(),
+            traced,
+            response.disposition(),
+        );
+        response
+    }
+
+    fn handle_generic_impl<W>(
         widget: &mut W,
         mgr: &mut Manager,
         mut event: Event,
@@ -144,9 +169,11 @@ impl<'a> Manager<'a> {
             };
         }
         match event {
-            Event::NavFocus => return Response::Focus(widget.rect()),
-            _ => (),
+            Event::NavFocus => match widget.handle(mgr, event) {
+                Response::Unhandled(_) => Response::Focus(widget.rect()),
+                r => r,
+            },
+            event => widget.handle(mgr, event),
         }
-        widget.handle(mgr, event)
     }
 }
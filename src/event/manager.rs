@@ -8,16 +8,18 @@
 // Without winit, several things go unused
 #![cfg_attr(not(feature = "winit"), allow(unused))]
 
-use log::trace;
+use log::{trace, warn};
 use smallvec::SmallVec;
 use std::collections::HashMap;
-use std::time::Instant;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 use std::u16;
 
 use super::*;
-use crate::geom::Coord;
-#[allow(unused)]
-use crate::WidgetConfig; // for doc-links
+use crate::geom::{Coord, Rect};
+use crate::layout::SizeRules;
+use crate::text::Text;
+use crate::WidgetConfig;
 use crate::{TkAction, TkWindow, Widget, WidgetId, WindowId};
 
 mod mgr_pub;
@@ -38,6 +40,23 @@ pub enum GrabMode {
     PanOnly,
 }
 
+/// Layout details of the widget under inspection
+///
+/// See [`Manager::set_inspector`].
+#[derive(Clone, Debug)]
+pub struct Inspection {
+    /// The inspected widget
+    pub id: WidgetId,
+    /// The widget's assigned rect
+    pub rect: Rect,
+    /// The widget's horizontal and vertical size rules
+    ///
+    /// Vertical rules are computed given the width of `rect`.
+    pub rules: (SizeRules, SizeRules),
+    /// A summary of the above, prepared for drawing
+    pub text: Text<String>,
+}
+
 #[derive(Clone, Debug)]
 struct MouseGrab {
     button: MouseButton,
@@ -59,6 +78,19 @@ struct TouchGrab {
     pan_grab: (u16, u16),
 }
 
+// An in-application drag
+#[derive(Clone, Debug)]
+struct Drag {
+    source: WidgetId,
+    payload: DragPayload,
+    // Rect drawn at the cursor, relative to `start`
+    ghost: Rect,
+    start: Coord,
+    // Widget under the cursor, and the widget accepting the drag (if any)
+    hover: Option<WidgetId>,
+    target: Option<WidgetId>,
+}
+
 const MAX_PAN_GRABS: usize = 2;
 
 #[derive(Clone, Debug)]
@@ -70,8 +102,73 @@ struct PanGrab {
     coords: [(Coord, Coord); MAX_PAN_GRABS],
 }
 
+#[derive(Clone, Debug)]
+struct LongPress {
+    source: PressSource,
+    start_id: WidgetId,
+    coord: Coord,
+    time: Instant,
+    fired: bool,
+    handled: bool,
+}
+
+// Maximum distance a press may move while remaining a long-press candidate
+const LONG_PRESS_SLOP: i32 = 8;
+
+// Defaults; see ManagerState::set_double_click
+const DOUBLE_CLICK_TIMEOUT: Duration = Duration::from_secs(1);
+const DOUBLE_CLICK_DISTANCE: u32 = 4;
+
+/// Counts repeated mouse clicks (double-click, triple-click, ...)
+///
+/// A press is a repetition of the previous press if it uses the same button,
+/// occurs within `timeout` of the previous press and is within `distance` of
+/// the first press of the sequence (on each axis).
+#[derive(Clone, Debug)]
+struct ClickCounter {
+    button: MouseButton,
+    coord: Coord,
+    time: Instant,
+    repetitions: u32,
+    timeout: Duration,
+    distance: u32,
+}
+
+impl ClickCounter {
+    fn new() -> Self {
+        ClickCounter {
+            button: MouseButton::Left,
+            coord: Coord::ZERO,
+            time: Instant::now(), // unimportant value
+            repetitions: 0,
+            timeout: DOUBLE_CLICK_TIMEOUT,
+            distance: DOUBLE_CLICK_DISTANCE,
+        }
+    }
+
+    // The next press will be counted as a single click
+    fn reset(&mut self) {
+        self.repetitions = 0;
+    }
+
+    // Register a press, returning the number of repetitions (1 for a single click)
+    fn press(&mut self, button: MouseButton, coord: Coord, now: Instant) -> u32 {
+        let d = coord - self.coord;
+        let far = d.0.abs().max(d.1.abs()) as u32 > self.distance;
+        if self.repetitions == 0 || button != self.button || now > self.time + self.timeout || far {
+            self.button = button;
+            self.coord = coord;
+            self.repetitions = 0;
+        }
+        self.repetitions += 1;
+        self.time = now;
+        self.repetitions
+    }
+}
+
 #[derive(Clone, Debug)]
 enum Pending {
+    NavFocus(WidgetId),
     LostCharFocus(WidgetId),
     LostSelFocus(WidgetId),
 }
@@ -92,41 +189,114 @@ enum Pending {
 // `SmallVec` is used to keep contents in local memory.
 #[derive(Debug)]
 pub struct ManagerState {
+    config: EventConfig,
     end_id: WidgetId,
     modifiers: ModifiersState,
     /// char focus is on same widget as sel_focus; otherwise its value is ignored
     char_focus: bool,
     sel_focus: Option<WidgetId>,
     nav_focus: Option<WidgetId>,
+    /// True unless a pointer press occurred since focus last moved via keyboard
+    focus_keyboard: bool,
     nav_fallback: Option<WidgetId>,
     nav_stack: SmallVec<[u32; 16]>,
+    /// Requested focus target (applied in `update`, `update_timer` and
+    /// `update_handle`) and whether this includes char focus
+    pending_focus: Option<(WidgetId, bool)>,
     hover: Option<WidgetId>,
     hover_icon: CursorIcon,
+    /// Files hovered over the window, if any
+    hovered_files: Vec<PathBuf>,
+    /// Widget accepting a hovered file (see [`Event::FileHovered`])
+    file_hover: Option<WidgetId>,
+    drag: Option<Drag>,
     key_depress: SmallVec<[(u32, WidgetId); 10]>,
     last_mouse_coord: Coord,
-    last_click_button: MouseButton,
-    last_click_repetitions: u32,
-    last_click_timeout: Instant,
+    click: ClickCounter,
     mouse_grab: Option<MouseGrab>,
     touch_grab: SmallVec<[TouchGrab; 10]>,
     pan_grab: SmallVec<[PanGrab; 4]>,
     accel_stack: Vec<(bool, HashMap<VirtualKeyCode, WidgetId>)>,
     accel_layers: HashMap<WidgetId, (bool, HashMap<VirtualKeyCode, WidgetId>)>,
+    hotkeys: HashMap<(ModifiersState, VirtualKeyCode), WidgetId>,
     popups: SmallVec<[(WindowId, kas::Popup); 16]>,
     new_popups: SmallVec<[WidgetId; 16]>,
     popup_removed: SmallVec<[(WidgetId, WindowId); 16]>,
+    inspector: bool,
+    inspection: Option<Inspection>,
+    /// Handle triggered on focus change, and the last focus announced
+    access_handle: Option<UpdateHandle>,
+    access_focus: Option<WidgetId>,
+    /// IME position: widget and rect in its coordinate space (see `update`)
+    ime_position: Option<(WidgetId, Rect)>,
 
     time_start: Instant,
     time_updates: Vec<(Instant, WidgetId)>,
+    last_input: Instant,
+    long_press: Option<LongPress>,
+    idle_updates: SmallVec<[(Duration, WidgetId, bool); 4]>,
+    /// Timers triggering update handles: deadline, handle and repeat period
+    handle_timers: SmallVec<[(Instant, UpdateHandle, Option<Duration>); 4]>,
+    /// Widgets awaiting an animation frame
+    anim_frames: SmallVec<[WidgetId; 4]>,
+    /// Time of the last animation frame (or first request), while animating
+    anim_time: Option<Instant>,
     // TODO(opt): consider other containers, e.g. C++ multimap
     // or sorted Vec with binary search yielding a range
     handle_updates: HashMap<UpdateHandle, Vec<WidgetId>>,
     pending: SmallVec<[Pending; 8]>,
     action: TkAction,
+    /// Map of old to new identifiers from the last configure
+    id_map: HashMap<WidgetId, WidgetId>,
 }
 
 /// internals
 impl ManagerState {
+    // A mouse or touch press hides the focus ring until the next keyboard
+    // navigation
+    fn pointer_press(&mut self) {
+        if self.focus_keyboard {
+            self.focus_keyboard = false;
+            if self.nav_focus.is_some() {
+                self.send_action(TkAction::Redraw);
+            }
+        }
+    }
+
+    fn dump_widget(&self, out: &mut String, widget: &dyn WidgetConfig, depth: usize) {
+        use std::fmt::Write;
+        let id = widget.id();
+        let indent = "  ".repeat(depth);
+        let _ = write!(out, "{}{} ", indent, widget.widget_name());
+        // Unconfigured widgets have the default id
+        if id == WidgetId::default() {
+            out.push_str("(unset)");
+        } else {
+            let _ = write!(out, "{}", id);
+        }
+        let rect = widget.rect();
+        let _ = write!(out, " pos {:?} size {:?}", rect.pos, rect.size);
+
+        let states = [
+            (widget.is_disabled(), "disabled"),
+            (widget.key_nav(), "key-nav"),
+            (self.nav_focus == Some(id), "nav-focus"),
+            (self.char_focus(id).0, "char-focus"),
+            (self.is_hovered(id), "hover"),
+            (self.popups.iter().any(|(_, p)| p.id == id), "popup"),
+        ];
+        for (_, state) in states.iter().filter(|s| s.0) {
+            let _ = write!(out, " [{}]", state);
+        }
+        out.push('\n');
+
+        for i in 0..widget.len() {
+            if let Some(w) = widget.get(i) {
+                self.dump_widget(out, w, depth + 1);
+            }
+        }
+    }
+
     fn set_pan_on(
         &mut self,
         id: WidgetId,
@@ -165,6 +335,86 @@ impl ManagerState {
         (gj, 0)
     }
 
+    // Get the translation from the coordinate space of widget `id` to that of
+    // the window: the sum of the translations of its ancestors (below the
+    // pop-up containing it, if any, since pop-ups are drawn untranslated)
+    fn translation_of(&self, widget: &dyn WidgetConfig, id: WidgetId) -> Option<Coord> {
+        if id == widget.id() {
+            return Some(Coord::ZERO);
+        }
+        let index = crate::find_child_index(widget, id)?;
+        let child = widget.get(index).unwrap();
+        let offset = self.translation_of(child, id)?;
+        if self.popups.iter().any(|(_, popup)| popup.id == child.id()) {
+            Some(offset)
+        } else {
+            Some(offset + widget.translation(index))
+        }
+    }
+
+    // Note user input, resetting idle timers
+    fn input_activity(&mut self) {
+        self.last_input = Instant::now();
+        for update in &mut self.idle_updates {
+            update.2 = false;
+        }
+    }
+
+    // Note movement of a press, cancelling a long-press if moved too far
+    fn long_press_moved(&mut self, source: PressSource, coord: Coord) {
+        if let Some(lp) = self.long_press.as_ref() {
+            let d = coord - lp.coord;
+            let far = d.0.abs().max(d.1.abs()) > LONG_PRESS_SLOP;
+            if same_source(lp.source, source) && !lp.fired && far {
+                self.long_press = None;
+            }
+        }
+    }
+
+    // End any long-press by this source, returning true if it was handled
+    fn long_press_end(&mut self, source: PressSource) -> bool {
+        match self.long_press.as_ref() {
+            Some(lp) if same_source(lp.source, source) => {
+                let handled = lp.handled;
+                self.long_press = None;
+                handled
+            }
+            _ => false,
+        }
+    }
+
+    // Get the time of the next (unsent) idle event, if any
+    fn next_idle(&self) -> Option<Instant> {
+        self.idle_updates
+            .iter()
+            .filter(|update| !update.2)
+            .map(|update| self.last_input + update.0)
+            .min()
+    }
+
+    fn next_handle_timer(&self) -> Option<Instant> {
+        self.handle_timers.iter().map(|timer| timer.0).min()
+    }
+
+    // Remove due handle timers (re-arming repeating timers), returning handles
+    fn take_due_handle_timers(&mut self, now: Instant) -> SmallVec<[UpdateHandle; 4]> {
+        let mut due = SmallVec::new();
+        for timer in &mut self.handle_timers {
+            if timer.0 <= now {
+                due.push(timer.1);
+                if let Some(period) = timer.2 {
+                    // Skip missed periods instead of queueing them
+                    timer.0 += period;
+                    if timer.0 <= now {
+                        timer.0 = now + period;
+                    }
+                }
+            }
+        }
+        self.handle_timers.retain(|timer| timer.0 > now);
+        due
+    }
+
     fn remove_pan(&mut self, index: usize) {
         trace!("Manager: end pan grab {}", index);
         self.pan_grab.remove(index);
@@ -270,38 +520,115 @@ impl<'a> Manager<'a> {
         })
     }
 
-    fn start_key_event<W>(&mut self, widget: &mut W, vkey: VirtualKeyCode, scancode: u32)
+    pub(crate) fn received_char<W>(&mut self, widget: &mut W, c: char)
+    where
+        W: Widget<Msg = VoidMsg> + ?Sized,
+    {
+        // Filter out control codes (Unicode 5.11). These may be
+        // generated from combinations such as Ctrl+C by some other
+        // layer. We use our own shortcut system instead.
+        if c >= '\u{20}' && (c < '\u{7f}' || c > '\u{9f}') {
+            let event = Event::ReceivedCharacter(c);
+            if self.mgr.char_focus {
+                if let Some(id) = self.mgr.sel_focus {
+                    self.send_event(widget, id, event);
+                }
+            } else {
+                // Without char focus, the parent of the top pop-up
+                // receives text (e.g. for menu mnemonics), else
+                // ancestors of the nav focus (e.g. for type-ahead
+                // search), but not key combinations handled as
+                // accelerators/hotkeys
+                let m = self.mgr.modifiers;
+                if !(m.ctrl() || m.alt() || m.logo()) {
+                    if let Some(popup) = self.mgr.popups.last() {
+                        let id = popup.1.parent;
+                        self.send_event(widget, id, event);
+                    } else if let Some(id) = self.mgr.nav_focus {
+                        self.send_to_ancestors(widget, id, event);
+                    }
+                }
+            }
+        }
+        self.apply_key_focus(widget.as_widget());
+    }
+
+    pub(crate) fn start_key_event<W>(&mut self, widget: &mut W, vkey: VirtualKeyCode, scancode: u32)
+    where
+        W: Widget<Msg = VoidMsg> + ?Sized,
+    {
+        self.key_event(widget, vkey, scancode);
+        self.apply_key_focus(widget.as_widget());
+    }
+
+    fn key_event<W>(&mut self, widget: &mut W, vkey: VirtualKeyCode, scancode: u32)
     where
         W: Widget<Msg = VoidMsg> + ?Sized,
     {
         use VirtualKeyCode as VK;
+        if vkey == VK::Escape && self.mgr.drag.is_some() {
+            self.cancel_drag(widget);
+            return;
+        }
+
         let opt_control = self.match_shortcuts(vkey);
+        let modifiers = self.mgr.modifiers;
+        let hotkey = self.mgr.hotkeys.get(&(modifiers, vkey)).cloned();
+        let hotkey = hotkey.filter(|id| !self.blocked_by_modal(widget.as_widget(), *id));
 
         if self.mgr.char_focus {
             if let Some(id) = self.mgr.sel_focus {
                 if let Some(key) = opt_control {
                     let event = Event::Control(key);
-                    trace!("Send to {}: {:?}", id, event);
-                    match widget.send(self, id, event) {
+                    match self.send_to(widget, id, event) {
                         Response::Unhandled(Event::Control(key)) => match key {
-                            ControlKey::Escape => self.set_char_focus(None),
+                            ControlKey::Escape => {
+                                self.set_char_focus(None);
+                                // Close the top pop-up only if it contains the focus
+                                let last = self.mgr.popups.last().map(|(wid, p)| (*wid, p.id));
+                                if let Some((wid, popup)) = last {
+                                    let in_popup = widget.find(popup).map(|w| w.is_ancestor_of(id));
+                                    if in_popup == Some(true) {
+                                        self.close_window(wid);
+                                    }
+                                }
+                            }
+                            ControlKey::Tab => {
+                                // The widget does not insert tabs: move focus
+                                self.set_char_focus(None);
+                                if self.mgr.nav_focus != Some(id) {
+                                    self.mgr.nav_focus = Some(id);
+                                    self.mgr.nav_stack.clear();
+                                }
+                                self.tab_nav_focus(widget);
+                            }
                             _ => (),
                         },
                         _ => (),
                     }
+                } else if let Some(id) = hotkey {
+                    // Without a command modifier, the key is probably text input
+                    if modifiers.ctrl() || modifiers.alt() || modifiers.logo() {
+                        self.send_event(widget, id, Event::Activate);
+                    }
                 }
                 return;
             }
         }
 
-        if vkey == VK::Tab {
-            if !self.next_nav_focus(widget.as_widget(), self.mgr.modifiers.shift()) {
-                self.clear_nav_focus();
-            }
+        if let Some(id) = hotkey {
+            self.send_event(widget, id, Event::Activate);
+        } else if vkey == VK::Tab {
+            self.tab_nav_focus(widget);
+        } else if vkey == VK::Escape {
+            // First offer Escape to the nav focus (and its ancestors)
             if let Some(id) = self.mgr.nav_focus {
-                self.send_event(widget, id, Event::NavFocus);
+                let event = Event::Control(ControlKey::Escape);
+                match self.send_to(widget, id, event) {
+                    Response::Unhandled(_) => (),
+                    _ => return,
+                }
             }
-        } else if vkey == VK::Escape {
             if let Some(id) = self.mgr.popups.last().map(|(id, _)| *id) {
                 self.close_window(id);
             } else {
@@ -338,10 +665,17 @@ impl<'a> Manager<'a> {
 
             if id_action.is_none() {
                 // Next priority goes to accelerator keys when Alt is held or alt_bypass is true
+                // Layers beneath a modal pop-up are excluded
+                let modal = self.mgr.popups.iter().rev().position(|(_, p)| p.modal);
+                let root = match modal {
+                    Some(_) => None,
+                    None => Some(widget.id()),
+                };
                 let mut n = 0;
                 for (i, id) in (self.mgr.popups.iter().rev())
+                    .take(modal.map(|i| i + 1).unwrap_or(usize::MAX))
                     .map(|(_, popup)| popup.parent)
-                    .chain(std::iter::once(widget.id()))
+                    .chain(root)
                     .enumerate()
                 {
                     if let Some(layer) = self.mgr.accel_layers.get(&id) {
@@ -385,7 +719,7 @@ impl<'a> Manager<'a> {
         }
     }
 
-    fn end_key_event(&mut self, scancode: u32) {
+    pub(crate) fn end_key_event(&mut self, scancode: u32) {
         // We must match scancode not vkey since the latter may have changed due to modifiers
 
         // TODO: it would be nice to replace key_depress with a set
@@ -428,6 +762,18 @@ impl<'a> Manager<'a> {
         }
     }
 
+    fn start_long_press(&mut self, source: PressSource, start_id: WidgetId, coord: Coord) {
+        let time = Instant::now() + self.mgr.config.long_press_time;
+        self.mgr.long_press = Some(LongPress {
+            source,
+            start_id,
+            coord,
+            time,
+            fired: false,
+            handled: false,
+        });
+    }
+
     #[inline]
     fn get_touch(&mut self, touch_id: u64) -> Option<&mut TouchGrab> {
         self.mgr.touch_grab.iter_mut().find_map(|grab| {
@@ -453,7 +799,7 @@ impl<'a> Manager<'a> {
         None
     }
 
-    fn set_char_focus(&mut self, wid: Option<WidgetId>) {
+    pub(crate) fn set_char_focus(&mut self, wid: Option<WidgetId>) {
         trace!(
             "Manager::set_char_focus: char_focus={:?}, new={:?}",
             self.mgr.char_focus,
@@ -490,15 +836,279 @@ impl<'a> Manager<'a> {
         }
     }
 
+    /// Apply a focus request from [`Manager::set_nav_focus`] or [`Manager::set_key_focus`]
+    fn apply_pending_focus(&mut self, widget: &dyn WidgetConfig) {
+        if let Some((id, char_focus)) = self.mgr.pending_focus.take() {
+            self.set_focus_checked(widget, id, char_focus);
+        }
+    }
+
+    // Apply a focus request made while handling keyboard input (e.g. arrow
+    // keys or type-ahead search); this shows the focus ring
+    fn apply_key_focus(&mut self, widget: &dyn WidgetConfig) {
+        if let Some((id, _)) = self.mgr.pending_focus {
+            self.apply_pending_focus(widget);
+            if self.mgr.nav_focus == Some(id) {
+                self.mgr.focus_keyboard = true;
+            }
+        }
+    }
+
+    fn set_focus_checked(&mut self, widget: &dyn WidgetConfig, id: WidgetId, char_focus: bool) {
+        match widget.find(id) {
+            Some(w) if w.key_nav() && !w.is_disabled() => (),
+            Some(_) => {
+                warn!("Manager: unable to focus widget {}: not focusable", id);
+                return;
+            }
+            None => {
+                warn!("Manager: unable to focus widget {}: not found", id);
+                return;
+            }
+        }
+        if let Some(popup_id) = self.mgr.popups.last().map(|(_, p)| p.id) {
+            let in_popup = widget.find(popup_id).map(|w| w.is_ancestor_of(id));
+            if in_popup != Some(true) {
+                warn!("Manager: unable to focus widget {}: not in open pop-up", id);
+                return;
+            }
+        }
+
+        if let Some(old_id) = self.mgr.nav_focus {
+            self.redraw(old_id);
+        }
+        self.redraw(id);
+        self.mgr.nav_focus = Some(id);
+        self.mgr.nav_stack.clear();
+        trace!("Manager: nav_focus = {:?}", self.mgr.nav_focus);
+
+        if char_focus {
+            self.set_char_focus(Some(id));
+        }
+        self.mgr.pending.push(Pending::NavFocus(id));
+    }
+
+    // Move nav focus for Tab (reversed with Shift), wrapping at either end
+    fn tab_nav_focus<W: Widget + ?Sized>(&mut self, widget: &mut W) {
+        let reverse = self.mgr.modifiers.shift();
+        if !self.next_nav_focus(widget.as_widget(), reverse) {
+            // Restart from the first (or last) widget
+            self.clear_nav_focus();
+            self.next_nav_focus(widget.as_widget(), reverse);
+        }
+        if let Some(id) = self.mgr.nav_focus {
+            self.send_event(widget, id, Event::NavFocus);
+        }
+    }
+
     fn send_event<W: Widget + ?Sized>(&mut self, widget: &mut W, id: WidgetId, event: Event) {
+        let _ = self.send_to(widget, id, event);
+    }
+
+    // Send an event to widget id; with feature trace_events, log the result
+    fn send_to<W: Widget + ?Sized>(
+        &mut self,
+        widget: &mut W,
+        id: WidgetId,
+        event: Event,
+    ) -> Response<W::Msg> {
         trace!("Send to {}: {:?}", id, event);
-        let _ = widget.send(self, id, event);
+        #[cfg(feature = "trace_events")]
+        let traced = event.clone();
+        let response = widget.send(self, id, event);
+        #[cfg(feature = "trace_events")]
+        trace!(
+            target: ROUTE_TARGET,
+            "send: target={} event={:?} disposition={}",
+            id,
+            traced,
+            response.disposition(),
+        );
+        response
+    }
+
+    // Update an active drag after the cursor moves
+    fn drag_move<W: Widget + ?Sized>(
+        &mut self,
+        widget: &mut W,
+        cur_id: Option<WidgetId>,
+        coord: Coord,
+    ) {
+        let drag = match self.mgr.drag.as_mut() {
+            Some(drag) => drag,
+            None => return,
+        };
+        let (source, payload, target) = (drag.source, drag.payload.clone(), drag.target);
+        let hover = std::mem::replace(&mut drag.hover, cur_id);
+        // The ghost follows the cursor
+        self.send_action(TkAction::Redraw);
+
+        if let Some(id) = target {
+            let within = cur_id.and_then(|cur| widget.find(id).map(|w| w.is_ancestor_of(cur)));
+            if within == Some(true) {
+                self.send_event(widget, id, Event::DragOver { coord });
+                return;
+            }
+            self.mgr.drag.as_mut().unwrap().target = None;
+            self.redraw(id);
+            self.send_event(widget, id, Event::DragLeave);
+        } else if hover == cur_id {
+            // The widget under the cursor already rejected this drag
+            return;
+        }
+
+        if let Some(id) = cur_id {
+            let event = Event::DragEnter { source, payload };
+            if let Some(target) = self.send_bubbling(widget, id, event) {
+                if let Some(drag) = self.mgr.drag.as_mut() {
+                    drag.target = Some(target);
+                }
+                self.redraw(target);
+                self.send_event(widget, target, Event::DragOver { coord });
+            }
+        }
+    }
+
+    // End an active drag, dropping the payload on the target if `drop`
+    fn end_drag<W: Widget + ?Sized>(&mut self, widget: &mut W, drop: bool) {
+        let drag = match self.mgr.drag.take() {
+            Some(drag) => drag,
+            None => return,
+        };
+        trace!("Manager: end drag by {} (drop: {})", drag.source, drop);
+        self.send_action(TkAction::Redraw);
+        if let Some(id) = drag.target {
+            let event = match drop {
+                true => Event::Drop {
+                    source: drag.source,
+                    payload: drag.payload,
+                    coord: self.mgr.last_mouse_coord,
+                },
+                false => Event::DragLeave,
+            };
+            self.send_event(widget, id, event);
+        }
+    }
+
+    // Cancel an active drag. The source's press is ended (without an end
+    // target, thus without activation) and its grab released.
+    fn cancel_drag<W: Widget + ?Sized>(&mut self, widget: &mut W) {
+        self.end_drag(widget, false);
+        if let Some(grab) = self.mgr.mouse_grab.clone() {
+            let source = PressSource::Mouse(grab.button, grab.repetitions);
+            self.mgr.long_press_end(source);
+            let event = Event::PressEnd {
+                source,
+                end_id: None,
+                coord: self.mgr.last_mouse_coord,
+            };
+            self.send_event(widget, grab.start_id, event);
+            self.end_mouse_grab(grab.button);
+        }
+    }
+
+    // Re-send hovered files to the widget under the cursor, `id`
+    //
+    // Toolkits may report a file hover before the cursor position, thus the
+    // first target may be stale.
+    fn route_hovered_files<W: Widget + ?Sized>(&mut self, widget: &mut W, id: Option<WidgetId>) {
+        if let Some(old_id) = self.mgr.file_hover.take() {
+            self.send_event(widget, old_id, Event::FileHoverCancelled);
+        }
+        if let Some(id) = id {
+            for path in self.mgr.hovered_files.clone() {
+                if let Some(id) = self.send_bubbling(widget, id, Event::FileHovered(path)) {
+                    self.mgr.file_hover = Some(id);
+                }
+            }
+        }
+    }
+
+    /// Send an event to `id`, then to each ancestor until handled
+    ///
+    /// Returns the identifier of the widget handling the event, if any.
+    fn send_bubbling<W: Widget + ?Sized>(
+        &mut self,
+        widget: &mut W,
+        id: WidgetId,
+        event: Event,
+    ) -> Option<WidgetId> {
+        let path = Self::path_to(widget.as_widget(), id);
+        self.send_along(widget, path, event)
+    }
+
+    /// Send an event to each ancestor of `id` (excluding `id`) until handled
+    fn send_to_ancestors<W: Widget + ?Sized>(
+        &mut self,
+        widget: &mut W,
+        id: WidgetId,
+        event: Event,
+    ) -> Option<WidgetId> {
+        let mut path = Self::path_to(widget.as_widget(), id);
+        if path.last() == Some(&id) {
+            path.pop();
+        }
+        self.send_along(widget, path, event)
+    }
+
+    // Path from the root to id
+    fn path_to(widget: &dyn WidgetConfig, id: WidgetId) -> Vec<WidgetId> {
+        let mut path = vec![widget.id()];
+        let mut w = widget;
+        while w.id() != id {
+            let mut children = (0..w.len()).filter_map(|i| w.get(i));
+            match children.find(|c| c.is_ancestor_of(id)) {
+                Some(child) => {
+                    path.push(child.id());
+                    w = child;
+                }
+                None => break,
+            }
+        }
+        path
+    }
+
+    // Send an event along path (in reverse) until handled
+    fn send_along<W: Widget + ?Sized>(
+        &mut self,
+        widget: &mut W,
+        path: Vec<WidgetId>,
+        mut event: Event,
+    ) -> Option<WidgetId> {
+        for id in path.into_iter().rev() {
+            match self.send_to(widget, id, event) {
+                Response::Unhandled(ev) => event = ev,
+                _ => return Some(id),
+            }
+        }
+        None
+    }
+
+    /// True if `id` is beneath a modal pop-up (and not within a pop-up above)
+    fn blocked_by_modal(&self, widget: &dyn WidgetConfig, id: WidgetId) -> bool {
+        for (_, popup) in self.mgr.popups.iter().rev() {
+            if widget.find(popup.id).map(|w| w.is_ancestor_of(id)) == Some(true) {
+                return false;
+            }
+            if popup.modal {
+                return true;
+            }
+        }
+        false
     }
 
     fn send_popup_first<W: Widget + ?Sized>(&mut self, widget: &mut W, id: WidgetId, event: Event) {
-        while let Some((wid, parent)) = self.mgr.popups.last().map(|(wid, p)| (*wid, p.parent)) {
-            trace!("Send to popup parent: {}: {:?}", parent, event);
-            match widget.send(self, parent, event.clone()) {
+        while let Some((wid, popup)) = self.mgr.popups.last().map(|(wid, p)| (*wid, p.clone())) {
+            if popup.modal {
+                // Modal pop-ups are not closed by clicking elsewhere
+                if self.blocked_by_modal(widget.as_widget(), id) {
+                    trace!("Blocked by modal pop-up {}: {:?}", popup.id, event);
+                    return;
+                }
+                break;
+            }
+            let parent = popup.parent;
+            match self.send_to(widget, parent, event.clone()) {
                 Response::Unhandled(_) => (),
                 _ => return,
             }
@@ -548,3 +1158,1016 @@ impl<'a: 'b, 'b> ConfigureManager<'a, 'b> {
         self.mgr
     }
 }
+
+// Mouse presses match regardless of button or repetitions
+fn same_source(a: PressSource, b: PressSource) -> bool {
+    match (a, b) {
+        (PressSource::Mouse(..), PressSource::Mouse(..)) => true,
+        (PressSource::Touch(a), PressSource::Touch(b)) => a == b,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::geom::Size;
+    use crate::test_util::{TestManager, TestWindow};
+    use crate::widget::{Column, ContextMenu, EditBox, Hidden, Menu, MenuBar, MenuEntry};
+    use crate::widget::{StrLabel, SubMenu};
+    use crate::{Direction, Right, WidgetCore};
+
+    #[test]
+    fn escape_from_char_focus_outside_popup() {
+        let column = Column::new(vec![EditBox::new("outside"), EditBox::new("inside")]);
+        let mut test = TestManager::new(column);
+        let outside = test.widget[0].id();
+        let inside = test.widget[1].id();
+        let parent = test.widget.id();
+
+        let popup = kas::Popup {
+            id: inside,
+            parent,
+            direction: Direction::Down,
+            modal: false,
+            anchor: None,
+        };
+        test.with(|mgr, _| {
+            mgr.add_popup(popup);
+            mgr.request_char_focus(outside);
+        });
+
+        // The pop-up does not contain the char focus, so stays open
+        test.key(VirtualKeyCode::Escape);
+        assert!(!test.state.char_focus);
+        assert_eq!(test.state.popups.len(), 1);
+
+        test.with(|mgr, _| mgr.request_char_focus(inside));
+        test.key(VirtualKeyCode::Escape);
+        assert!(!test.state.char_focus);
+        assert!(test.state.popups.is_empty());
+    }
+
+    type TestMenu = SubMenu<Right, SubMenu<Right, SubMenu<Right, MenuEntry<VoidMsg>>>>;
+
+    #[test]
+    fn escape_closes_innermost_menu() {
+        let c = SubMenu::right("C", Vec::<MenuEntry<VoidMsg>>::new());
+        let b = SubMenu::right("B", vec![c]);
+        let mut menu: TestMenu = SubMenu::right("A", vec![b]);
+
+        let mut tkw = TestWindow::default();
+        let mut state = ManagerState::new();
+        state.configure(&mut tkw, &mut menu);
+        let a_id = menu.id();
+        let b_id = menu.list.inner[0].id();
+        let c_id = menu.list.inner[0].list.inner[0].id();
+
+        for id in [a_id, b_id].iter().cloned() {
+            state.with(&mut tkw, |mgr| {
+                mgr.send_event(&mut menu, id, Event::Activate)
+            });
+            state.update(&mut tkw, &mut menu);
+        }
+        assert_eq!(state.popups.len(), 2);
+        assert_eq!(state.nav_focus, Some(c_id));
+
+        let escape = |state: &mut ManagerState, tkw: &mut TestWindow, menu: &mut TestMenu| {
+            state.with(tkw, |mgr| {
+                mgr.start_key_event(&mut *menu, VirtualKeyCode::Escape, 0)
+            });
+            state.update(tkw, menu);
+        };
+
+        // Only the innermost open menu is closed
+        escape(&mut state, &mut tkw, &mut menu);
+        assert_eq!(state.popups.len(), 1);
+        assert_eq!(state.nav_focus, Some(b_id));
+        assert!(!menu.list.inner[0].menu_is_open());
+        assert!(menu.menu_is_open());
+
+        escape(&mut state, &mut tkw, &mut menu);
+        assert!(state.popups.is_empty());
+        assert_eq!(state.nav_focus, Some(a_id));
+        assert!(!menu.menu_is_open());
+
+        // With nothing open, Escape clears navigation focus
+        escape(&mut state, &mut tkw, &mut menu);
+        assert_eq!(state.nav_focus, None);
+    }
+
+    #[test]
+    fn menu_mnemonics() {
+        let entry = |label: &'static str| SubMenu::right(label, vec![]);
+        let list = vec![
+            entry("&Copy"),
+            entry("&Cut"),
+            entry("&Paste"),
+            entry("&Clear"),
+        ];
+        let mut menu: TestMenu = SubMenu::right("Edit", list);
+        menu.list.inner[3].core_data_mut().disabled = true;
+
+        let mut tkw = TestWindow::default();
+        let mut state = ManagerState::new();
+        state.configure(&mut tkw, &mut menu);
+        let ids: Vec<_> = (0..4).map(|i| menu.list.inner[i].id()).collect();
+
+        let menu_id = menu.id();
+        state.with(&mut tkw, |mgr| {
+            mgr.send_event(&mut menu, menu_id, Event::Activate)
+        });
+        state.update(&mut tkw, &mut menu);
+        assert_eq!(state.nav_focus, Some(ids[0]));
+
+        let send = |state: &mut ManagerState,
+                    tkw: &mut TestWindow,
+                    menu: &mut TestMenu,
+                    ev: Result<VirtualKeyCode, char>| {
+            state.with(tkw, |mgr| match ev {
+                Ok(vkey) => mgr.start_key_event(&mut *menu, vkey, 0),
+                Err(c) => mgr.send_event(&mut *menu, menu_id, Event::ReceivedCharacter(c)),
+            });
+            state.update(tkw, menu);
+        };
+
+        // Duplicate mnemonics cycle focus, skipping disabled entries
+        send(&mut state, &mut tkw, &mut menu, Err('c'));
+        assert_eq!(state.nav_focus, Some(ids[1]));
+        send(&mut state, &mut tkw, &mut menu, Err('C'));
+        assert_eq!(state.nav_focus, Some(ids[0]));
+        assert_eq!(state.popups.len(), 1);
+
+        // Characters without a match are ignored, as is the key without Alt
+        send(&mut state, &mut tkw, &mut menu, Err('x'));
+        send(&mut state, &mut tkw, &mut menu, Ok(VirtualKeyCode::P));
+        assert_eq!(state.nav_focus, Some(ids[0]));
+        assert_eq!(state.popups.len(), 1);
+
+        // A unique mnemonic activates its entry
+        send(&mut state, &mut tkw, &mut menu, Err('p'));
+        assert!(menu.list.inner[2].menu_is_open());
+        assert_eq!(state.popups.len(), 2);
+    }
+
+    type TestList = Column<SubMenu<Right, MenuEntry<VoidMsg>>>;
+
+    #[test]
+    fn hotkeys() {
+        let column = Column::new(vec![EditBox::new(""), EditBox::new("")]);
+        let mut test = TestManager::new(column);
+        let (a, b) = (test.widget[0].id(), test.widget[1].id());
+        let ctrl = ModifiersState::CTRL;
+        test.with(|mgr, _| {
+            mgr.add_hotkey(ctrl, VirtualKeyCode::S, b);
+            mgr.add_hotkey(ctrl, VirtualKeyCode::C, b);
+            mgr.add_hotkey(ModifiersState::empty(), VirtualKeyCode::F, b);
+        });
+
+        // A hotkey activates its target without keyboard focus
+        test.state.modifiers = ctrl;
+        test.key(VirtualKeyCode::S);
+        assert_eq!(test.state.char_focus(b), (true, true));
+
+        // Modifiers must match exactly
+        test.with(|mgr, _| mgr.set_char_focus(None));
+        test.state.modifiers = ctrl | ModifiersState::SHIFT;
+        test.key(VirtualKeyCode::S);
+        assert!(!test.state.char_focus);
+
+        // With character focus, control keys go to the focussed widget and
+        // hotkeys without a command modifier are ignored
+        test.with(|mgr, _| mgr.request_char_focus(a));
+        test.state.modifiers = ctrl;
+        test.key(VirtualKeyCode::C);
+        assert_eq!(test.state.char_focus(a), (true, true));
+        test.state.modifiers = ModifiersState::empty();
+        test.key(VirtualKeyCode::F);
+        assert_eq!(test.state.char_focus(a), (true, true));
+
+        // Other hotkeys take precedence
+        test.state.modifiers = ctrl;
+        test.key(VirtualKeyCode::S);
+        assert_eq!(test.state.char_focus(b), (true, true));
+
+        // Hotkeys may be removed, and are cleared on reconfigure
+        test.with(|mgr, _| {
+            mgr.remove_hotkeys(b);
+            mgr.set_char_focus(None);
+        });
+        test.key(VirtualKeyCode::S);
+        assert!(!test.state.char_focus);
+        test.with(|mgr, _| mgr.add_hotkey(ctrl, VirtualKeyCode::S, b));
+        test.configure();
+        test.key(VirtualKeyCode::S);
+        assert!(!test.state.char_focus);
+    }
+
+    #[cfg(feature = "winit")]
+    #[test]
+    fn focus_visible() {
+        use crate::widget::CheckBox;
+        let entries = ["Apple", "Banana"]
+            .iter()
+            .map(|label| CheckBox::new(*label));
+        let mut test = TestManager::new(Column::new(entries.collect()).with_type_ahead());
+        test.set_rect(Rect::new(Coord::ZERO, Size(80, 40)));
+        // Each CheckBox's first child is its (focussable) box
+        let boxes = |test: &TestManager<Column<CheckBox<VoidMsg>>>, i: usize| {
+            test.widget[i].get(0).unwrap().id()
+        };
+        let ids = [boxes(&test, 0), boxes(&test, 1)];
+        let visible = |test: &TestManager<Column<CheckBox<VoidMsg>>>, i: usize| {
+            let w = test.widget[i].get(0).unwrap();
+            w.input_state(&test.state, false).nav_focus
+        };
+
+        test.key(VirtualKeyCode::Tab);
+        assert_eq!(test.state.nav_focus, Some(ids[0]));
+        assert!(test.state.focus_is_keyboard());
+        assert!(visible(&test, 0));
+
+        // A click (even on the focussed widget) hides the ring but keeps focus
+        let rect = test.widget[0].get(0).unwrap().rect();
+        test.mouse_move(rect.pos + Coord(1, 1));
+        test.mouse_input(MouseButton::Left, true);
+        test.mouse_input(MouseButton::Left, false);
+        assert_eq!(test.state.nav_focus, Some(ids[0]));
+        assert!(!test.state.focus_is_keyboard());
+        assert!(!visible(&test, 0));
+
+        // Focus set programmatically does not show the ring
+        test.with(|mgr, _| mgr.set_nav_focus(ids[1]));
+        assert_eq!(test.state.nav_focus, Some(ids[1]));
+        assert!(!visible(&test, 1));
+
+        // Focus moved by typing (type-ahead search) shows it
+        test.with(|mgr, w| mgr.received_char(w, 'a'));
+        assert_eq!(test.state.nav_focus, Some(ids[0]));
+        assert!(test.state.focus_is_keyboard());
+        assert!(visible(&test, 0));
+
+        // As does Tab (here: Shift+Tab) after another click
+        test.mouse_input(MouseButton::Left, true);
+        test.mouse_input(MouseButton::Left, false);
+        assert!(!test.state.focus_is_keyboard());
+        test.state.modifiers = ModifiersState::SHIFT;
+        test.key(VirtualKeyCode::Tab);
+        assert_eq!(test.state.nav_focus, Some(ids[1]));
+        assert!(test.state.focus_is_keyboard());
+        assert!(visible(&test, 1));
+    }
+
+    #[test]
+    fn pending_focus_on_timer_and_handle() {
+        let entries = ["A", "B"]
+            .iter()
+            .map(|label| SubMenu::right(*label, vec![]));
+        let mut list: TestList = Column::new(entries.collect());
+
+        let mut tkw = TestWindow::default();
+        let mut state = ManagerState::new();
+        state.configure(&mut tkw, &mut list);
+        let ids: Vec<_> = list.iter().map(|w| w.id()).collect();
+
+        // Focus requested outside of event handling is applied by the timer
+        // and update-handle paths, without a call to update
+        state.with(&mut tkw, |mgr| mgr.set_nav_focus(ids[0]));
+        assert_eq!(state.nav_focus, None);
+        state.with(&mut tkw, |mgr| mgr.update_timer(&mut list));
+        assert_eq!(state.nav_focus, Some(ids[0]));
+
+        let handle = UpdateHandle::new();
+        state.with(&mut tkw, |mgr| mgr.set_nav_focus(ids[1]));
+        state.with(&mut tkw, |mgr| mgr.update_handle(&mut list, handle, 0));
+        assert_eq!(state.nav_focus, Some(ids[1]));
+    }
+
+    #[test]
+    fn stable_ids() {
+        let entries = ["A", "B", "C"]
+            .iter()
+            .map(|label| SubMenu::right(*label, vec![]));
+        let mut list: TestList = Column::new(entries.collect());
+
+        let mut tkw = TestWindow::default();
+        let mut state = ManagerState::new();
+        state.configure(&mut tkw, &mut list);
+        let ids: Vec<_> = list.iter().map(|w| w.id()).collect();
+        let list_id = list.id();
+
+        // Re-configuring an unchanged tree assigns the same identifiers
+        let _ = list[1].set_disabled(true);
+        state.configure(&mut tkw, &mut list);
+        assert_eq!(list.iter().map(|w| w.id()).collect::<Vec<_>>(), ids);
+        assert_eq!(list.id(), list_id);
+
+        // Inserting a widget changes identifiers; old ones may be remapped
+        let _ = list.insert(0, SubMenu::right("Z", vec![]));
+        state.configure(&mut tkw, &mut list);
+        state.with(&mut tkw, |mgr| {
+            for (i, id) in ids.iter().enumerate() {
+                assert_eq!(mgr.remap_id(*id), Some(list[i + 1].id()));
+            }
+            assert_eq!(mgr.remap_id(list_id), Some(list.id()));
+        });
+    }
+
+    type TestContextMenu = ContextMenu<StrLabel, SubMenu<Right, MenuEntry<VoidMsg>>>;
+
+    #[test]
+    fn context_menu() {
+        let sub = SubMenu::right("Sub", vec![]);
+        let mut widget: TestContextMenu = ContextMenu::new(StrLabel::new("text"), vec![sub]);
+
+        let mut tkw = TestWindow::default();
+        let mut state = ManagerState::new();
+        state.configure(&mut tkw, &mut widget);
+        let inner_id = widget.inner.id();
+        let sub_id = widget.menu.inner[0].id();
+
+        let send =
+            |state: &mut ManagerState, tkw: &mut TestWindow, w: &mut TestContextMenu, event| {
+                state.with(tkw, |mgr| match event {
+                    Event::PressStart { start_id, .. } => {
+                        mgr.send_popup_first(&mut *w, start_id, event)
+                    }
+                    event => mgr.send_event(&mut *w, sub_id, event),
+                });
+                state.update(tkw, w);
+            };
+        let press = |button| Event::PressStart {
+            source: PressSource::Mouse(button, 1),
+            start_id: inner_id,
+            coord: Coord(5, 7),
+        };
+
+        // Only the secondary button opens the menu, at the press coordinate
+        send(&mut state, &mut tkw, &mut widget, press(MouseButton::Left));
+        assert!(state.popups.is_empty());
+        send(&mut state, &mut tkw, &mut widget, press(MouseButton::Right));
+        assert_eq!(state.popups.len(), 1);
+        assert_eq!(state.popups[0].1.anchor, Some(Coord(5, 7)));
+        assert!(widget.is_open());
+
+        // Sub-menus open as usual; a click elsewhere closes all
+        send(&mut state, &mut tkw, &mut widget, Event::Activate);
+        assert_eq!(state.popups.len(), 2);
+        send(&mut state, &mut tkw, &mut widget, press(MouseButton::Left));
+        assert!(state.popups.is_empty());
+        assert!(!widget.is_open());
+    }
+
+    type TestMenuBar = MenuBar<Right, SubMenu<Right, MenuEntry<VoidMsg>>>;
+
+    #[test]
+    fn menubar_navigation() {
+        let entries = || vec![SubMenu::right("Item", vec![])];
+        let file = SubMenu::down("&File", entries());
+        let edit = SubMenu::down("&Edit", entries());
+        let mut bar: TestMenuBar = MenuBar::new(vec![file, edit]);
+
+        let mut tkw = TestWindow::default();
+        let mut state = ManagerState::new();
+        state.configure(&mut tkw, &mut bar);
+        let (file, edit) = (bar.bar[0].id(), bar.bar[1].id());
+
+        let send = |state: &mut ManagerState,
+                    tkw: &mut TestWindow,
+                    bar: &mut TestMenuBar,
+                    ev: Result<VirtualKeyCode, (WidgetId, Event)>| {
+            state.with(tkw, |mgr| match ev {
+                Ok(vkey) => mgr.start_key_event(&mut *bar, vkey, 0),
+                Err((id, event)) => mgr.send_event(&mut *bar, id, event),
+            });
+            state.update(tkw, bar);
+        };
+        let is_open = |bar: &TestMenuBar| (bar.bar[0].menu_is_open(), bar.bar[1].menu_is_open());
+
+        // Without an open menu, arrow keys move along the bar
+        state.nav_focus = Some(file);
+        send(&mut state, &mut tkw, &mut bar, Ok(VirtualKeyCode::Right));
+        assert_eq!(state.nav_focus, Some(edit));
+        assert_eq!(is_open(&bar), (false, false));
+
+        // Down opens the focussed menu; Left then switches menus
+        send(&mut state, &mut tkw, &mut bar, Ok(VirtualKeyCode::Down));
+        assert_eq!(is_open(&bar), (false, true));
+        send(&mut state, &mut tkw, &mut bar, Ok(VirtualKeyCode::Left));
+        assert_eq!(is_open(&bar), (true, false));
+        assert_eq!(state.popups.len(), 1);
+
+        // While open, hovering another menu button opens it immediately
+        let hover = Event::PressMove {
+            source: PressSource::Mouse(MouseButton::Other(0), 0),
+            cur_id: Some(edit),
+            coord: Coord::ZERO,
+            delta: Coord::ZERO,
+        };
+        send(&mut state, &mut tkw, &mut bar, Err((file, hover)));
+        assert_eq!(is_open(&bar), (false, true));
+        assert_eq!(state.popups.len(), 1);
+
+        // Accelerator keys work across the bar while a menu is open
+        state.modifiers = ModifiersState::ALT;
+        send(&mut state, &mut tkw, &mut bar, Ok(VirtualKeyCode::F));
+        assert_eq!(is_open(&bar), (true, false));
+        assert_eq!(state.popups.len(), 1);
+    }
+
+    #[test]
+    fn dump_tree() {
+        let b = SubMenu::right("B", Vec::<MenuEntry<VoidMsg>>::new());
+        let mut menu = SubMenu::right("A", vec![b]);
+
+        let mut tkw = TestWindow::default();
+        let mut state = ManagerState::new();
+        let mut dump = String::new();
+        state.with(&mut tkw, |mgr| dump = mgr.dump_tree(&menu));
+        assert!(dump.starts_with("SubMenu (unset) pos Coord(0, 0)"));
+
+        state.configure(&mut tkw, &mut menu);
+        let a_id = menu.id();
+        state.with(&mut tkw, |mgr| {
+            mgr.send_event(&mut menu, a_id, Event::Activate)
+        });
+        state.update(&mut tkw, &mut menu);
+        state.with(&mut tkw, |mgr| dump = mgr.dump_tree(&menu));
+        let lines: Vec<&str> = dump.lines().collect();
+        assert_eq!(lines.len(), menu_len(&menu));
+        assert!(lines[0].starts_with(&format!("SubMenu {} ", a_id)));
+        assert!(lines[0].ends_with(" [key-nav]"));
+        assert!(lines.iter().any(|line| line.ends_with(" [popup]")));
+        assert!(lines
+            .iter()
+            .any(|line| line.contains(" [key-nav] [nav-focus]")));
+    }
+
+    fn menu_len(widget: &dyn WidgetConfig) -> usize {
+        (0..widget.len()).fold(1, |n, i| n + widget.get(i).map(menu_len).unwrap_or(0))
+    }
+
+    #[test]
+    fn handle_timers() {
+        let mut menu = SubMenu::right("A", Vec::<MenuEntry<VoidMsg>>::new());
+        let mut tkw = TestWindow::default();
+        let mut state = ManagerState::new();
+        state.configure(&mut tkw, &mut menu);
+
+        let a = UpdateHandle::new();
+        let b = UpdateHandle::new();
+        let c = UpdateHandle::new();
+        let hour = Duration::from_secs(3600);
+        state.with(&mut tkw, |mgr| {
+            mgr.request_timer(Duration::from_secs(0), a);
+            mgr.request_timer(hour, b);
+            // Requests for the same handle coalesce to the soonest deadline
+            mgr.request_timer(Duration::from_secs(0), b);
+            mgr.request_timer(hour, b);
+            mgr.request_repeating_timer(hour, c);
+        });
+        assert_eq!(state.handle_timers.len(), 3);
+        assert!(state.next_resume().unwrap() <= Instant::now());
+
+        state.with(&mut tkw, |mgr| mgr.update_timer(&mut menu));
+        assert_eq!(tkw.updates, vec![a, b]);
+        // Repeating timers remain until cancelled
+        assert_eq!(state.handle_timers.len(), 1);
+        assert!(state.next_resume().unwrap() > Instant::now());
+
+        let now = Instant::now();
+        assert_eq!(state.take_due_handle_timers(now + hour).as_slice(), &[c]);
+        assert!(state.next_handle_timer().unwrap() > now + hour);
+
+        let mut cancelled = (false, false);
+        state.with(&mut tkw, |mgr| {
+            cancelled = (mgr.cancel_timer(c), mgr.cancel_timer(a));
+        });
+        assert_eq!(cancelled, (true, false));
+        assert_eq!(state.next_handle_timer(), None);
+    }
+
+    #[test]
+    fn animation_frames() {
+        let mut menu = SubMenu::right("A", Vec::<MenuEntry<VoidMsg>>::new());
+        let mut tkw = TestWindow::default();
+        let mut state = ManagerState::new();
+        state.configure(&mut tkw, &mut menu);
+        assert!(!state.is_animating());
+
+        let id = menu.id();
+        let removed = id.next();
+        state.with(&mut tkw, |mgr| {
+            mgr.request_animation_frame(id);
+            mgr.request_animation_frame(id);
+            mgr.request_animation_frame(removed);
+        });
+        assert_eq!(state.anim_frames.as_slice(), &[id, removed]);
+        assert!(state.is_animating());
+
+        // Requests for widgets no longer present are dropped on configure
+        state.configure(&mut tkw, &mut menu);
+        assert_eq!(state.anim_frames.as_slice(), &[id]);
+
+        // The menu does not request another frame, thus animation stops
+        state.with(&mut tkw, |mgr| mgr.animation_frame(&mut menu));
+        assert!(!state.is_animating());
+        assert_eq!(state.anim_time, None);
+    }
+
+    #[test]
+    fn click_repetitions() {
+        let mut click = ClickCounter::new();
+        let t0 = Instant::now();
+        let ms = |n| t0 + Duration::from_millis(n);
+        let left = MouseButton::Left;
+        let c = Coord(10, 10);
+
+        assert_eq!(click.press(left, c, ms(0)), 1);
+        assert_eq!(click.press(left, Coord(12, 7), ms(200)), 2);
+        assert_eq!(click.press(left, c, ms(400)), 3);
+        assert_eq!(click.press(left, c, ms(600)), 4);
+
+        // Different button
+        assert_eq!(click.press(MouseButton::Right, c, ms(700)), 1);
+        assert_eq!(click.press(left, c, ms(800)), 1);
+
+        // Timeout, measured from the previous press
+        assert_eq!(click.press(left, c, ms(1700)), 2);
+        assert_eq!(click.press(left, c, ms(2701)), 1);
+
+        // Distance, measured from the first press of the sequence
+        assert_eq!(click.press(left, Coord(14, 6), ms(2800)), 2);
+        assert_eq!(click.press(left, Coord(15, 10), ms(2900)), 1);
+        assert_eq!(click.press(left, Coord(15, 10), ms(3000)), 2);
+
+        click.reset();
+        assert_eq!(click.press(left, Coord(15, 10), ms(3100)), 1);
+    }
+
+    #[test]
+    fn tab_order() {
+        type Entry = Hidden<SubMenu<Right, MenuEntry<VoidMsg>>>;
+        let entry = |label: &str| -> Entry { Hidden::new(SubMenu::right(label, vec![])) };
+        let mut list = Column::new(vec![
+            entry("A"),
+            entry("B").with_disabled(true),
+            entry("C").with_visible(false),
+            entry("D"),
+        ]);
+
+        let mut tkw = TestWindow::default();
+        let mut state = ManagerState::new();
+        state.configure(&mut tkw, &mut list);
+        let ids: Vec<_> = list.iter().map(|w| w.id()).collect();
+        let tab = |state: &mut ManagerState, tkw: &mut TestWindow, list: &mut Column<Entry>| {
+            state.with(tkw, |mgr| {
+                mgr.start_key_event(&mut *list, VirtualKeyCode::Tab, 0)
+            });
+            state.update(tkw, list);
+            state.nav_focus
+        };
+
+        // Disabled and hidden widgets are skipped; focus wraps at the end
+        let order: Vec<_> = (0..3)
+            .map(|_| tab(&mut state, &mut tkw, &mut list))
+            .collect();
+        assert_eq!(order, [Some(ids[0]), Some(ids[3]), Some(ids[0])]);
+
+        // Shift+Tab traverses in reverse, also wrapping
+        state.modifiers = ModifiersState::SHIFT;
+        let order: Vec<_> = (0..2)
+            .map(|_| tab(&mut state, &mut tkw, &mut list))
+            .collect();
+        assert_eq!(order, [Some(ids[3]), Some(ids[0])]);
+    }
+
+    #[test]
+    fn spatial_navigation() {
+        // A 3x3 grid of cells, in row-major order
+        let entries = (0..9).map(|i| SubMenu::right(i.to_string(), vec![]));
+        let mut list: TestList = Column::new(entries.collect());
+
+        let mut tkw = TestWindow::default();
+        let mut state = ManagerState::new();
+        state.configure(&mut tkw, &mut list);
+        for i in 0..9 {
+            let pos = Coord(20 * (i % 3) as i32, 10 * (i / 3) as i32);
+            list[i].core_data_mut().rect = Rect::new(pos, Size(20, 10));
+        }
+        let ids: Vec<_> = list.iter().map(|w| w.id()).collect();
+
+        let mut step = |from: usize, dir| {
+            state.nav_focus = Some(ids[from]);
+            let mut moved = false;
+            state.with(&mut tkw, |mgr| {
+                moved = mgr.nav_focus_spatial(list.as_widget(), dir);
+            });
+            let focus = state.nav_focus.unwrap();
+            assert_eq!(moved, focus != ids[from]);
+            ids.iter().position(|id| *id == focus).unwrap()
+        };
+
+        // From the centre, each direction lands on the adjacent cell
+        assert_eq!(step(4, Direction::Right), 5);
+        assert_eq!(step(4, Direction::Down), 7);
+        assert_eq!(step(4, Direction::Left), 3);
+        assert_eq!(step(4, Direction::Up), 1);
+        assert_eq!(step(0, Direction::Down), 3);
+        assert_eq!(step(8, Direction::Up), 5);
+
+        // At an edge, focus does not move
+        assert_eq!(step(5, Direction::Right), 5);
+        assert_eq!(step(1, Direction::Up), 1);
+
+        // The new focus receives Event::NavFocus (on update)
+        assert_eq!(step(4, Direction::Down), 7);
+        let sent = match state.pending.last() {
+            Some(Pending::NavFocus(id)) => Some(*id),
+            _ => None,
+        };
+        assert_eq!(sent, Some(ids[7]));
+    }
+
+    #[cfg(feature = "winit")]
+    mod drag {
+        use super::*;
+        use crate::test_util::{EventLog, EventRecorder, TestManager};
+
+        // Starts a drag when moved while pressed and accepts drags of `()`
+        // from other widgets
+        fn drag(mgr: &mut Manager, w: &EventRecorder, event: Event) -> Response<VoidMsg> {
+            let name = match event {
+                Event::PressStart { source, coord, .. } => {
+                    mgr.request_grab(w.id(), source, coord, GrabMode::Grab, None);
+                    "press"
+                }
+                Event::PressMove { .. } => {
+                    mgr.start_drag(w.id(), w.rect(), ());
+                    return Response::None;
+                }
+                Event::PressEnd {
+                    end_id: Some(_), ..
+                } => "activate",
+                Event::PressEnd { end_id: None, .. } => "end",
+                Event::DragEnter { source, payload } if source != w.id() && payload.is::<()>() => {
+                    "enter"
+                }
+                Event::DragLeave => "leave",
+                Event::Drop { .. } => "drop",
+                event => return Response::Unhandled(event),
+            };
+            w.record(name);
+            Response::None
+        }
+
+        // Accepts drags of `u32` only
+        fn drop_u32(_: &mut Manager, w: &EventRecorder, event: Event) -> Response<VoidMsg> {
+            let name = match event {
+                Event::DragEnter { payload, .. } if payload.is::<u32>() => "enter",
+                Event::DragLeave => "leave",
+                Event::Drop { .. } => "drop",
+                event => return Response::Unhandled(event),
+            };
+            w.record(name);
+            Response::None
+        }
+
+        #[test]
+        fn drag_and_drop() {
+            let log = EventLog::default();
+            let new = || EventRecorder::new(&log, drag);
+            let mut test = TestManager::new(crate::widget::Row::new(vec![new(), new()]));
+            test.set_rect(Rect::new(Coord::ZERO, Size(20, 10)));
+            let (a, b) = (test.widget[0].id(), test.widget[1].id());
+            let take = || std::mem::take(&mut *log.borrow_mut());
+            let left = MouseButton::Left;
+
+            // Drag from a to b and drop
+            test.mouse_move(Coord(5, 5));
+            test.mouse_input(left, true);
+            test.mouse_move(Coord(6, 5));
+            assert!(test.state.drag.is_some());
+            test.mouse_move(Coord(15, 5));
+            assert!(test.state.is_drag_target(b));
+            test.mouse_input(left, false);
+            assert!(test.state.drag.is_none());
+            let events = [(a, "press"), (b, "enter"), (b, "drop"), (a, "end")];
+            assert_eq!(take(), events);
+
+            // Cancel a drag with Escape: the press ends without activation and
+            // the grab is released
+            test.mouse_move(Coord(5, 5));
+            test.mouse_input(left, true);
+            test.mouse_move(Coord(15, 5));
+            test.key(VirtualKeyCode::Escape);
+            assert!(test.state.drag.is_none());
+            assert!(test.state.mouse_grab.is_none());
+            test.mouse_input(left, false);
+            let events = [(a, "press"), (b, "enter"), (b, "leave"), (a, "end")];
+            assert_eq!(take(), events);
+
+            // Without a drag, release over the source activates it
+            test.mouse_input(left, true);
+            test.mouse_input(left, false);
+            assert_eq!(take(), [(b, "press"), (b, "activate")]);
+        }
+
+        #[test]
+        fn payload_type_mismatch() {
+            let log = EventLog::default();
+            let widgets = vec![
+                EventRecorder::new(&log, drag),
+                EventRecorder::new(&log, drop_u32),
+            ];
+            let mut test = TestManager::new(crate::widget::Row::new(widgets));
+            test.set_rect(Rect::new(Coord::ZERO, Size(20, 10)));
+            let (a, b) = (test.widget[0].id(), test.widget[1].id());
+            let take = || std::mem::take(&mut *log.borrow_mut());
+            let left = MouseButton::Left;
+
+            // b rejects the `()` payload, thus is not highlighted as a target
+            // and receives no drop
+            test.mouse_move(Coord(5, 5));
+            test.mouse_input(left, true);
+            test.mouse_move(Coord(6, 5));
+            test.mouse_move(Coord(15, 5));
+            assert!(test.state.drag.is_some());
+            assert!(!test.state.is_drag_target(b));
+            test.mouse_move(Coord(16, 5));
+            assert!(!test.state.is_drag_target(b));
+            test.mouse_input(left, false);
+            assert!(test.state.drag.is_none());
+            assert_eq!(take(), [(a, "press"), (a, "end")]);
+        }
+    }
+
+    #[cfg(feature = "winit")]
+    mod idle {
+        use super::*;
+        use crate::test_util::{EventLog, EventRecorder, TestManager};
+
+        fn record_idle(_: &mut Manager, w: &EventRecorder, event: Event) -> Response<VoidMsg> {
+            match event {
+                Event::Idle => w.record("idle"),
+                event => return Response::Unhandled(event),
+            }
+            Response::None
+        }
+
+        #[test]
+        fn idle_timeout() {
+            let timeout = Duration::from_millis(100);
+            let log = EventLog::default();
+            let mut test = TestManager::new(EventRecorder::new(&log, record_idle));
+            assert_eq!(test.state.next_resume(), None);
+            test.state.set_config(EventConfig {
+                idle_timeout: Some(timeout),
+                ..Default::default()
+            });
+            test.configure();
+            test.set_rect(Rect::new(Coord::ZERO, Size(10, 10)));
+            let idle = |test: &mut TestManager<EventRecorder>, time: Instant| {
+                test.with(|mgr, widget| mgr.update_timer_at(widget, time));
+                log.borrow().len()
+            };
+
+            // Input restarts the timeout
+            test.mouse_move(Coord(1, 1));
+            let input = test.state.last_input;
+            assert_eq!(test.state.next_resume(), Some(input + timeout));
+            assert_eq!(idle(&mut test, input + timeout / 2), 0);
+
+            // Without input, the root receives one idle event
+            assert_eq!(idle(&mut test, input + timeout), 1);
+            assert_eq!(idle(&mut test, input + timeout * 2), 1);
+            assert_eq!(test.state.next_resume(), None);
+
+            // Further input allows another idle event
+            test.mouse_move(Coord(2, 2));
+            let input = test.state.last_input;
+            assert_eq!(test.state.next_resume(), Some(input + timeout));
+            assert_eq!(idle(&mut test, input + timeout), 2);
+        }
+    }
+
+    #[cfg(feature = "winit")]
+    mod long_press {
+        use super::*;
+        use crate::test_util::{EventLog, EventRecorder, TestManager};
+
+        // Records presses, long-presses and activations (clicks)
+        fn record_press(mgr: &mut Manager, w: &EventRecorder, event: Event) -> Response<VoidMsg> {
+            let name = match event {
+                Event::PressStart { source, coord, .. } => {
+                    mgr.request_grab(w.id(), source, coord, GrabMode::Grab, None);
+                    "press"
+                }
+                Event::LongPress { .. } => "long",
+                Event::PressEnd {
+                    end_id: Some(_), ..
+                } => "activate",
+                Event::PressEnd { end_id: None, .. } => "end",
+                event => return Response::Unhandled(event),
+            };
+            w.record(name);
+            Response::None
+        }
+
+        #[test]
+        fn long_press_time() {
+            let time = Duration::from_millis(50);
+            let log = EventLog::default();
+            let mut test = TestManager::new(EventRecorder::new(&log, record_press));
+            test.state.set_config(EventConfig {
+                long_press_time: time,
+                ..Default::default()
+            });
+            test.set_rect(Rect::new(Coord::ZERO, Size(10, 10)));
+            let id = test.widget.id();
+            let take = || std::mem::take(&mut *log.borrow_mut());
+            let left = MouseButton::Left;
+            let timer = |test: &mut TestManager<EventRecorder>, time: Instant| {
+                test.with(|mgr, widget| mgr.update_timer_at(widget, time));
+            };
+
+            // A short press is a click
+            test.mouse_move(Coord(5, 5));
+            test.mouse_input(left, true);
+            let due = test.state.next_resume().unwrap();
+            timer(&mut test, due - time / 2);
+            test.mouse_input(left, false);
+            assert_eq!(take(), [(id, "press"), (id, "activate")]);
+
+            // A press held beyond the configured time is a long-press, and
+            // its release does not also activate
+            test.mouse_input(left, true);
+            let due = test.state.next_resume().unwrap();
+            timer(&mut test, due);
+            test.mouse_input(left, false);
+            assert_eq!(take(), [(id, "press"), (id, "long"), (id, "end")]);
+        }
+    }
+
+    #[cfg(feature = "winit")]
+    mod files {
+        use super::*;
+        use crate::test_util::{EventLog, EventRecorder, TestManager};
+        use winit::event::WindowEvent;
+
+        fn file_event(event: &Event) -> Option<&'static str> {
+            match event {
+                Event::FileHovered(_) => Some("hover"),
+                Event::FileDropped(_) => Some("drop"),
+                Event::FileHoverCancelled => Some("cancel"),
+                _ => None,
+            }
+        }
+
+        fn accept(_: &mut Manager, w: &EventRecorder, event: Event) -> Response<VoidMsg> {
+            match file_event(&event) {
+                Some(name) => w.record(name),
+                None => return Response::Unhandled(event),
+            }
+            Response::None
+        }
+
+        fn reject(_: &mut Manager, _: &EventRecorder, event: Event) -> Response<VoidMsg> {
+            Response::Unhandled(event)
+        }
+
+        // Accepts files not accepted by its children
+        #[layout(row)]
+        #[handler(handle=noauto)]
+        #[derive(Debug, crate::macros::Widget)]
+        struct Parent {
+            #[widget_core]
+            core: crate::CoreData,
+            #[layout_data]
+            layout_data: <Self as crate::LayoutData>::Data,
+            #[widget]
+            a: EventRecorder,
+            #[widget]
+            b: EventRecorder,
+            log: EventLog,
+        }
+
+        impl Handler for Parent {
+            type Msg = VoidMsg;
+
+            fn handle(&mut self, _: &mut Manager, event: Event) -> Response<VoidMsg> {
+                match file_event(&event) {
+                    Some(name) => self.log.borrow_mut().push((self.id(), name)),
+                    None => return Response::Unhandled(event),
+                }
+                Response::None
+            }
+        }
+
+        #[test]
+        fn file_hover_and_drop() {
+            let log = EventLog::default();
+            let mut test = TestManager::new(Parent {
+                core: Default::default(),
+                layout_data: Default::default(),
+                a: EventRecorder::new(&log, accept),
+                b: EventRecorder::new(&log, reject),
+                log: log.clone(),
+            });
+            test.set_rect(Rect::new(Coord::ZERO, Size(20, 10)));
+            let (a, parent) = (test.widget.a.id(), test.widget.id());
+            let take = || std::mem::take(&mut *log.borrow_mut());
+            let path = || PathBuf::from("file.txt");
+
+            // The hover is first sent using the last known cursor position
+            test.mouse_move(Coord(5, 5));
+            test.winit(WindowEvent::HoveredFile(path()));
+            assert_eq!(take(), [(a, "hover")]);
+
+            // When the cursor position is reported, files are re-sent to the
+            // widget under the cursor; b does not accept files, thus the
+            // events bubble to the parent
+            test.mouse_move(Coord(15, 5));
+            assert_eq!(take(), [(a, "cancel"), (parent, "hover")]);
+            test.mouse_move(Coord(16, 5));
+            assert!(take().is_empty());
+            test.winit(WindowEvent::DroppedFile(path()));
+            assert_eq!(take(), [(parent, "drop")]);
+
+            // After a drop, moving the cursor has no effect
+            test.mouse_move(Coord(5, 5));
+            assert!(take().is_empty());
+
+            // A cancelled hover notifies the accepting widget
+            test.winit(WindowEvent::HoveredFile(path()));
+            test.winit(WindowEvent::HoveredFileCancelled);
+            assert_eq!(take(), [(a, "hover"), (a, "cancel")]);
+        }
+    }
+
+    #[cfg(feature = "winit")]
+    mod modal {
+        use super::*;
+        use crate::test_util::{EventLog, EventRecorder, TestManager};
+
+        // Records presses and activations
+        fn record_press(_: &mut Manager, w: &EventRecorder, event: Event) -> Response<VoidMsg> {
+            let name = match event {
+                Event::PressStart { .. } => "press",
+                Event::Activate => "activate",
+                event => return Response::Unhandled(event),
+            };
+            w.record(name);
+            Response::None
+        }
+
+        #[test]
+        fn input_blocked_by_modal() {
+            let log = EventLog::default();
+            let new = || EventRecorder::new(&log, record_press);
+            let mut test = TestManager::new(Column::new(vec![new(), new()]));
+            test.set_rect(Rect::new(Coord::ZERO, Size(10, 20)));
+            let (a, b) = (test.widget[0].id(), test.widget[1].id());
+            let parent = test.widget.id();
+            let take = || std::mem::take(&mut *log.borrow_mut());
+            let left = MouseButton::Left;
+            let press = |test: &mut TestManager<_>, coord| {
+                test.mouse_move(coord);
+                test.mouse_input(left, true);
+                test.mouse_input(left, false);
+            };
+
+            test.with(|mgr, _| {
+                mgr.add_hotkey(ModifiersState::CTRL, VirtualKeyCode::A, a);
+                mgr.add_hotkey(ModifiersState::CTRL, VirtualKeyCode::B, b);
+            });
+            test.state.modifiers = ModifiersState::CTRL;
+
+            // Show b as a modal pop-up
+            let popup = crate::Popup {
+                id: b,
+                parent,
+                direction: Direction::Down,
+                modal: true,
+                anchor: None,
+            };
+            let window_id = test.with(|mgr, _| mgr.add_popup(popup));
+
+            // Presses and hotkeys beneath the modal pop-up are dropped
+            press(&mut test, Coord(5, 5));
+            test.key(VirtualKeyCode::A);
+            assert!(take().is_empty());
+
+            // Input within the modal pop-up is delivered
+            press(&mut test, Coord(5, 15));
+            test.key(VirtualKeyCode::B);
+            assert_eq!(take(), [(b, "press"), (b, "activate")]);
+
+            // Closing the modal pop-up unblocks input
+            test.with(|mgr, _| mgr.close_window(window_id));
+            press(&mut test, Coord(5, 5));
+            test.key(VirtualKeyCode::A);
+            assert_eq!(take(), [(a, "press"), (a, "activate")]);
+        }
+    }
+}
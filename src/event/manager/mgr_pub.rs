@@ -6,15 +6,17 @@
 //! Event manager — public API
 
 use log::{debug, trace, warn};
+use std::any::Any;
 use std::time::{Duration, Instant};
 use std::u16;
 
 use super::*;
-use crate::draw::SizeHandle;
-use crate::geom::Coord;
+use crate::access::AccessTree;
+use crate::draw::{ImageFilter, ImageHandle, SizeHandle};
+use crate::geom::{Coord, Rect, Size};
 #[allow(unused)]
 use crate::WidgetConfig; // for doc-links
-use crate::{ThemeAction, ThemeApi, TkAction, WidgetId, WindowId};
+use crate::{ClipboardFormat, Direction, ThemeAction, ThemeApi, TkAction, WidgetId, WindowId};
 
 impl<'a> std::ops::AddAssign<TkAction> for Manager<'a> {
     #[inline]
@@ -57,6 +59,48 @@ impl ManagerState {
         self.nav_focus == Some(w_id)
     }
 
+    /// Get whether navigation focus should be visibly indicated
+    ///
+    /// This follows the "focus-visible" convention: a focus ring is drawn
+    /// only when focus was moved via the keyboard. It becomes true when
+    /// [`Manager::next_nav_focus`] moves the focus (e.g. via the Tab key) or
+    /// when focus is moved while handling keyboard input (e.g. arrow keys or
+    /// type-ahead search via [`Manager::set_nav_focus`]), and false on any
+    /// mouse or touch press (including on the widget which already has
+    /// focus). Other focus changes do not affect this. Initially true.
+    #[inline]
+    pub fn focus_is_keyboard(&self) -> bool {
+        self.focus_keyboard
+    }
+
+    /// Get the layout inspector's details of the nav-focus widget
+    ///
+    /// This is `None` unless the inspector is enabled (see
+    /// [`Manager::set_inspector`]) and some widget has navigation focus.
+    #[inline]
+    pub fn inspection(&self) -> Option<&Inspection> {
+        self.inspection.as_ref()
+    }
+
+    /// Get whether the widget is the target of an in-application drag
+    ///
+    /// This is true after the widget accepts [`Event::DragEnter`] until it
+    /// receives [`Event::DragLeave`] or [`Event::Drop`]. Drop targets should
+    /// highlight themselves while this is true.
+    #[inline]
+    pub fn is_drag_target(&self, w_id: WidgetId) -> bool {
+        self.drag.as_ref().and_then(|drag| drag.target) == Some(w_id)
+    }
+
+    /// Get the rect of the drag "ghost", if an in-application drag is active
+    ///
+    /// This is the rect passed to [`Manager::start_drag`], translated with the
+    /// mouse cursor. Toolkits should draw this above other content.
+    pub fn drag_ghost(&self) -> Option<Rect> {
+        let drag = self.drag.as_ref()?;
+        Some(drag.ghost + (self.last_mouse_coord - drag.start))
+    }
+
     /// Get whether the widget is under the mouse cursor
     #[inline]
     pub fn is_hovered(&self, w_id: WidgetId) -> bool {
@@ -91,6 +135,25 @@ impl<'a> Manager<'a> {
         self.mgr.modifiers
     }
 
+    /// Enable or disable the layout inspector
+    ///
+    /// This is a debugging aid. When enabled, the widget with navigation focus
+    /// is outlined and annotated with its rect and the [`SizeRules`] for each
+    /// axis (see [`ManagerState::inspection`]). Details are computed only
+    /// when drawn (see [`ManagerState::update_inspector`]).
+    ///
+    /// Drawing is done by [`kas::widget::Window`]. The inspector is disabled
+    /// by default and has negligible cost while disabled.
+    ///
+    /// [`SizeRules`]: crate::layout::SizeRules
+    pub fn set_inspector(&mut self, enable: bool) {
+        self.mgr.inspector = enable;
+        if !enable {
+            self.mgr.inspection = None;
+        }
+        self.send_action(TkAction::Redraw);
+    }
+
     /// Schedule an update
     ///
     /// Widgets requiring animation should schedule an update; as a result,
@@ -125,6 +188,25 @@ impl<'a> Manager<'a> {
         self.mgr.time_updates.sort_by(|a, b| b.cmp(a)); // reverse sort
     }
 
+    /// Request notification of inactivity
+    ///
+    /// After no user input (keyboard, mouse or touch) has been received for
+    /// `timeout`, [`Event::Idle`] will be sent to `w_id`. Further input resets
+    /// the timer, after which the event may be sent again. This may be used
+    /// e.g. to auto-save or to dim the UI.
+    ///
+    /// Calling this again for the same widget replaces the previous timeout.
+    /// Like timer updates, requests are cleared when widgets are reconfigured.
+    pub fn update_on_idle(&mut self, timeout: Duration, w_id: WidgetId) {
+        for row in &mut self.mgr.idle_updates {
+            if row.1 == w_id {
+                row.0 = timeout;
+                return;
+            }
+        }
+        self.mgr.idle_updates.push((timeout, w_id, false));
+    }
+
     /// Subscribe to an update handle
     ///
     /// All widgets subscribed to an update handle will be sent
@@ -140,10 +222,74 @@ impl<'a> Manager<'a> {
             .push(w_id);
     }
 
+    /// Schedule an update-handle trigger
+    ///
+    /// After `duration`, `handle` is triggered as by [`Manager::trigger_update`]
+    /// (with payload 0), thus all widgets subscribed via
+    /// [`Manager::update_on_handle`] are sent [`Event::HandleUpdate`]. Timings
+    /// are approximate, as for [`Manager::update_on_timer`].
+    ///
+    /// At most one timer is pending per handle: if one is already pending, the
+    /// sooner deadline is kept. Unlike [`Manager::update_on_timer`], these
+    /// timers are not cleared when widgets are reconfigured (but are when the
+    /// window is closed).
+    pub fn request_timer(&mut self, duration: Duration, handle: UpdateHandle) {
+        let time = Instant::now() + duration;
+        for timer in &mut self.mgr.handle_timers {
+            if timer.1 == handle {
+                timer.0 = timer.0.min(time);
+                return;
+            }
+        }
+        self.mgr.handle_timers.push((time, handle, None));
+    }
+
+    /// Schedule a repeating update-handle trigger
+    ///
+    /// As [`Manager::request_timer`], except that `handle` is triggered every
+    /// `period` until cancelled via [`Manager::cancel_timer`]. Triggers missed
+    /// (e.g. while the event loop was busy) are skipped, not queued. This
+    /// replaces any timer pending for `handle`.
+    ///
+    /// Panics if `period` is zero.
+    pub fn request_repeating_timer(&mut self, period: Duration, handle: UpdateHandle) {
+        assert!(period > Duration::from_secs(0), "zero timer period");
+        self.cancel_timer(handle);
+        let time = Instant::now() + period;
+        self.mgr.handle_timers.push((time, handle, Some(period)));
+    }
+
+    /// Cancel a timer scheduled for `handle`
+    ///
+    /// Returns true if a timer was pending.
+    pub fn cancel_timer(&mut self, handle: UpdateHandle) -> bool {
+        let len = self.mgr.handle_timers.len();
+        self.mgr.handle_timers.retain(|timer| timer.1 != handle);
+        self.mgr.handle_timers.len() != len
+    }
+
+    /// Request an animation frame
+    ///
+    /// On the next redraw, [`Event::AnimationFrame`] is sent to `w_id`. To
+    /// continue animating, the widget should request another frame when
+    /// handling this event; the animation stops when it does not.
+    ///
+    /// While any widget awaits a frame, the window is redrawn continuously
+    /// (limited by vsync). Requests are dropped if the widget is removed.
+    pub fn request_animation_frame(&mut self, w_id: WidgetId) {
+        if self.mgr.anim_time.is_none() {
+            self.mgr.anim_time = Some(Instant::now());
+        }
+        if !self.mgr.anim_frames.contains(&w_id) {
+            self.mgr.anim_frames.push(w_id);
+        }
+        self.send_action(TkAction::Redraw);
+    }
+
     /// Notify that a widget must be redrawn
     ///
     /// Currently the entire window is redrawn on any redraw request and the
-    /// [`WidgetId`] is ignored. In the future partial redraws may be used.
+    /// [`WidgetId`] is ignored.
     #[inline]
     pub fn redraw(&mut self, _id: WidgetId) {
         // Theoretically, notifying by WidgetId allows selective redrawing
@@ -195,6 +341,25 @@ impl<'a> Manager<'a> {
         id
     }
 
+    /// Add a pop-up placed at a point
+    ///
+    /// This is a convenience wrapper around [`Manager::add_popup`] for context
+    /// menus: the pop-up widget `id` is placed at `coord` (in the coordinate
+    /// space of events received by `parent`) instead of adjacent to `parent`.
+    /// As with other pop-ups, `id` must be a descendant of `parent` and the
+    /// pop-up is closed by a click elsewhere or the Escape key.
+    ///
+    /// See also [`ContextMenu`](crate::widget::ContextMenu).
+    pub fn open_context_menu(&mut self, id: WidgetId, parent: WidgetId, coord: Coord) -> WindowId {
+        self.add_popup(kas::Popup {
+            id,
+            parent,
+            direction: kas::Direction::Down,
+            modal: false,
+            anchor: Some(coord),
+        })
+    }
+
     /// Add a window
     ///
     /// Typically an application adds at least one window before the event-loop
@@ -263,12 +428,61 @@ impl<'a> Manager<'a> {
         self.tkw.set_clipboard(content)
     }
 
+    /// Attempt to get clipboard contents in the given `format`
+    ///
+    /// Returns `None` if content is not available in this format.
+    #[inline]
+    pub fn get_clipboard_data(&mut self, format: &ClipboardFormat) -> Option<Vec<u8>> {
+        self.tkw.get_clipboard_data(format)
+    }
+
+    /// Attempt to set clipboard contents in the given `format`
+    ///
+    /// This replaces existing clipboard contents.
+    #[inline]
+    pub fn set_clipboard_data(&mut self, format: ClipboardFormat, content: Vec<u8>) {
+        self.tkw.set_clipboard_data(format, content)
+    }
+
+    /// Set the position of the input method (IME) candidate window
+    ///
+    /// Widgets accepting text input should call this with the rect of the
+    /// edit marker while they have character focus, allowing the toolkit to
+    /// place the candidate window next to it. The `rect` is in the coordinate
+    /// space of widget `id` (that of its own rect); it is translated to window
+    /// coordinates (see [`Layout::translation`]) and passed to the toolkit on
+    /// the next update.
+    ///
+    /// [`Layout::translation`]: crate::Layout::translation
+    #[inline]
+    pub fn set_ime_position(&mut self, id: WidgetId, rect: Rect) {
+        self.mgr.ime_position = Some((id, rect));
+    }
+
     /// Adjust the theme
     #[inline]
     pub fn adjust_theme<F: FnMut(&mut dyn ThemeApi) -> ThemeAction>(&mut self, mut f: F) {
         self.tkw.adjust_theme(&mut f);
     }
 
+    /// Load an image
+    ///
+    /// The image is given as `data` in RGBA format (8 bits per channel,
+    /// sRGB colour space, un-premultiplied alpha), row by row from the top.
+    /// The `filter` is used when the image is drawn at other than its natural
+    /// size; use [`ImageFilter::Nearest`] for pixel art.
+    ///
+    /// The returned handle owns the image: when dropped, the toolkit frees
+    /// the image's resources. Draw the image via
+    /// [`kas::draw::DrawHandle::image`].
+    ///
+    /// Panics if `data.len() != 4 * size.0 * size.1`.
+    #[inline]
+    pub fn load_image(&mut self, size: Size, data: &[u8], filter: ImageFilter) -> ImageHandle {
+        assert_eq!(data.len(), 4 * (size.0 * size.1) as usize);
+        self.tkw.load_image(size, data, filter)
+    }
+
     /// Access a [`SizeHandle`]
     pub fn size_handle<F: FnMut(&mut dyn SizeHandle) -> T, T>(&mut self, mut f: F) -> T {
         let mut result = None;
@@ -371,6 +585,35 @@ impl<'a> Manager<'a> {
         }
     }
 
+    /// Add a hotkey (keyboard shortcut)
+    ///
+    /// When `key` is pressed with exactly the given `modifiers` held, widget
+    /// `id` is sent [`Event::Activate`], regardless of keyboard focus. Unlike
+    /// accelerator keys (see [`Manager::add_accel_keys`]), hotkeys need not
+    /// correspond to a visible label and do not belong to an accelerator
+    /// layer. Adding an existing key combination replaces its target.
+    ///
+    /// Hotkeys take precedence over other key handling except when a widget
+    /// has character focus (e.g. a [`kas::widget::EditBox`] being edited).
+    /// In that case [`ControlKey`]s (e.g. Ctrl+C) are sent to that widget, and
+    /// only other combinations using Ctrl, Alt or Logo match hotkeys.
+    ///
+    /// Hotkeys are cleared when widgets are (re)configured, for example when
+    /// a widget is removed; thus this should be called from
+    /// [`WidgetConfig::configure`].
+    pub fn add_hotkey(&mut self, modifiers: ModifiersState, key: VirtualKeyCode, id: WidgetId) {
+        if !self.read_only {
+            self.mgr.hotkeys.insert((modifiers, key), id);
+        }
+    }
+
+    /// Remove all hotkeys targetting widget `id`
+    pub fn remove_hotkeys(&mut self, id: WidgetId) {
+        if !self.read_only {
+            self.mgr.hotkeys.retain(|_, target| *target != id);
+        }
+    }
+
     /// Request character-input focus
     ///
     /// If successful, [`Event::ReceivedCharacter`] events are sent to this
@@ -383,6 +626,49 @@ impl<'a> Manager<'a> {
         }
     }
 
+    /// Start an in-application drag-and-drop operation
+    ///
+    /// This may be called by the `source` widget while it holds a mouse
+    /// [grab](Manager::request_grab) (typically while handling
+    /// [`Event::PressMove`]). It fails, returning false, otherwise or if a
+    /// drag is already active. Touch input is not currently supported.
+    ///
+    /// While the drag is active, the widget under the cursor receives
+    /// [`Event::DragEnter`] and may accept or reject the drag (e.g. depending
+    /// on the type of `payload`). The accepting widget receives
+    /// [`Event::DragOver`] as the cursor moves, [`Event::DragLeave`] if the
+    /// cursor leaves it and [`Event::Drop`] when the mouse button is released.
+    /// Pressing <kbd>Escape</kbd> cancels the drag (the accepting widget
+    /// receives [`Event::DragLeave`]) and releases the grab.
+    ///
+    /// When the drag ends (by drop or cancellation), `source` receives
+    /// [`Event::PressEnd`] with `end_id == None`, thus the press does not also
+    /// cause activation.
+    ///
+    /// The toolkit draws a "ghost" of the `ghost` rect (usually the source
+    /// widget's rect) following the cursor.
+    pub fn start_drag<T: Any>(&mut self, source: WidgetId, ghost: Rect, payload: T) -> bool {
+        if self.read_only || self.mgr.drag.is_some() {
+            return false;
+        }
+        match self.mgr.mouse_grab.as_ref() {
+            Some(grab) if grab.start_id == source => (),
+            _ => return false,
+        }
+
+        trace!("Manager: start drag by {}", source);
+        self.mgr.drag = Some(Drag {
+            source,
+            payload: DragPayload::new(payload),
+            ghost,
+            start: self.mgr.last_mouse_coord,
+            hover: None,
+            target: None,
+        });
+        self.send_action(TkAction::Redraw);
+        true
+    }
+
     /// Request a grab on the given input `source`
     ///
     /// On success, this method returns true and corresponding mouse/touch
@@ -515,6 +801,65 @@ impl<'a> Manager<'a> {
         self.mgr.nav_focus
     }
 
+    /// Get whether the widget has character focus
+    ///
+    /// See [`Manager::request_char_focus`].
+    pub fn has_char_focus(&self, id: WidgetId) -> bool {
+        self.mgr.char_focus(id).0
+    }
+
+    /// Translate an identifier from before the last (re)configure
+    ///
+    /// Returns the widget's new identifier, or `None` if the widget was not
+    /// present both before and after the last configure (see
+    /// [`TkAction::Reconfigure`]). Since identifiers only change when the
+    /// structure of the widget tree changes, this is only needed to update
+    /// identifiers stored across such changes.
+    pub fn remap_id(&self, id: WidgetId) -> Option<WidgetId> {
+        self.mgr.id_map.get(&id).cloned()
+    }
+
+    /// Get the type-ahead search timeout
+    ///
+    /// See [`EventConfig::type_ahead_timeout`].
+    #[inline]
+    pub fn type_ahead_timeout(&self) -> Duration {
+        self.mgr.config.type_ahead_timeout
+    }
+
+    /// Construct an accessibility snapshot of the widget tree
+    ///
+    /// See [`kas::access`].
+    pub fn access_tree(&self, root: &dyn WidgetConfig) -> AccessTree {
+        AccessTree::new(root, self.mgr)
+    }
+
+    /// Set a handle to announce focus changes for accessibility
+    ///
+    /// When set, `handle` is triggered (see [`Manager::trigger_update`])
+    /// whenever navigation focus changes, with payload the newly focussed
+    /// widget's [`WidgetId`] (as `u64`) or 0 if no widget has focus.
+    /// Use `None` to stop announcements.
+    pub fn set_access_handle(&mut self, handle: Option<UpdateHandle>) {
+        self.mgr.access_handle = handle;
+        self.mgr.access_focus = None;
+    }
+
+    /// Describe the widget tree as text
+    ///
+    /// Each line describes one widget: its name, [`WidgetId`] (or `(unset)`
+    /// if not yet configured), rect and state (disabled, keyboard-navigable,
+    /// focus, hover and open pop-up), indented according to depth below
+    /// `root`. Pop-up widgets are part of their parent's subtree, thus a
+    /// window's pop-ups are included.
+    ///
+    /// This is intended for debugging; the format is not stable.
+    pub fn dump_tree(&self, root: &dyn WidgetConfig) -> String {
+        let mut out = String::new();
+        self.mgr.dump_widget(&mut out, root, 0);
+        out
+    }
+
     /// Clear keyboard navigation focus
     pub fn clear_nav_focus(&mut self) {
         if let Some(id) = self.mgr.nav_focus {
@@ -525,13 +870,39 @@ impl<'a> Manager<'a> {
         trace!("Manager: nav_focus = None");
     }
 
+    /// Clear character-input focus
+    ///
+    /// The widget with character focus, if any, is sent
+    /// [`Event::LostCharFocus`] but retains selection focus.
+    pub fn clear_char_focus(&mut self) {
+        if !self.read_only {
+            self.set_char_focus(None);
+        }
+    }
+
     /// Set the keyboard navigation focus directly
     ///
-    /// [`WidgetConfig::key_nav`] *should* return true for the given widget,
-    /// otherwise navigation behaviour may not be correct.
+    /// This request is applied after the current event (or timer or update
+    /// handle notification) has been handled or after configuration, thus a
+    /// pop-up opened by the same handler may be targetted. The request is
+    /// ignored (with a warning) unless the widget exists, is not disabled and
+    /// [`WidgetConfig::key_nav`] returns true. If a pop-up is open, the widget
+    /// must be within the top-most pop-up.
+    ///
+    /// When successful, both the new and the previous focus are redrawn.
     pub fn set_nav_focus(&mut self, id: WidgetId) {
-        self.mgr.nav_focus = Some(id);
-        self.mgr.nav_stack.clear();
+        self.mgr.pending_focus = Some((id, false));
+    }
+
+    /// Set the keyboard focus directly
+    ///
+    /// This is like [`Manager::set_nav_focus`], but additionally requests
+    /// character-input focus for the widget (see
+    /// [`Manager::request_char_focus`]), for example to give focus to a search
+    /// box when a window is opened. Any widget losing character focus
+    /// receives [`Event::LostCharFocus`].
+    pub fn set_key_focus(&mut self, id: WidgetId) {
+        self.mgr.pending_focus = Some((id, true));
     }
 
     /// Advance the keyboard navigation focus
@@ -544,6 +915,19 @@ impl<'a> Manager<'a> {
     /// This method returns true when the navigation focus has been updated,
     /// otherwise leaves the focus unchanged. The caller may (optionally) choose
     /// to call [`Manager::clear_nav_focus`] when this method returns false.
+    ///
+    /// Traversal is depth-first in the order given by
+    /// [`Layout::spatial_range`], skipping disabled widgets and any subtree
+    /// excluded by `spatial_range` (e.g. hidden or collapsed content). While a
+    /// pop-up is open, traversal is confined to the top-most pop-up.
+    ///
+    /// The toolkit calls this on Tab (reversed with Shift+Tab) with the
+    /// window as `widget`; at the end (start) focus wraps to the first (last)
+    /// widget. If a widget with character focus does not handle Tab (see
+    /// [`Event::Control`]), focus moves from that widget and it loses
+    /// character focus.
+    ///
+    /// [`Layout::spatial_range`]: crate::Layout::spatial_range
     pub fn next_nav_focus(&mut self, mut widget: &dyn WidgetConfig, reverse: bool) -> bool {
         type WidgetStack<'b> = SmallVec<[&'b dyn WidgetConfig; 16]>;
         let mut widget_stack = WidgetStack::new();
@@ -672,6 +1056,7 @@ impl<'a> Manager<'a> {
             ($self:ident, $widget:ident) => {
                 if $widget.key_nav() && !$widget.is_disabled() {
                     $self.mgr.nav_focus = Some($widget.id());
+                    $self.mgr.focus_keyboard = true;
                     trace!("Manager: nav_focus = {:?}", $self.mgr.nav_focus);
                     return true;
                 }
@@ -715,4 +1100,103 @@ impl<'a> Manager<'a> {
 
         false
     }
+
+    /// Move the keyboard navigation focus spatially
+    ///
+    /// This gives focus to the nearest widget in direction `dir` from the
+    /// widget with navigation focus, by rect geometry, for example to the
+    /// widget visually below on [`Direction::Down`]. It complements logical
+    /// traversal via [`Manager::next_nav_focus`] and considers the same
+    /// widgets (those under `widget`, or under the top-most pop-up if any).
+    ///
+    /// Candidates are compared by the distance between rect centres, with
+    /// offset along the secondary axis weighted double; only widgets whose
+    /// centre lies strictly beyond the current focus's centre in direction
+    /// `dir` are considered (thus overlapping widgets with the same centre are
+    /// never chosen). Between equal candidates, the first in logical order
+    /// wins.
+    ///
+    /// This method returns true when the navigation focus has been updated; as
+    /// with [`Manager::set_nav_focus`], the newly focussed widget then receives
+    /// [`Event::NavFocus`]. If nothing has focus or no widget lies in direction
+    /// `dir`, focus is unchanged (there is no wrapping).
+    ///
+    /// The toolkit does not call this method itself. Containers with a
+    /// two-dimensional layout may call this on unhandled arrow keys
+    /// ([`Event::Control`]), as does [`Grid`](crate::widget::Grid).
+    pub fn nav_focus_spatial(&mut self, mut widget: &dyn WidgetConfig, dir: Direction) -> bool {
+        if let Some(id) = self.mgr.popups.last().map(|(_, p)| p.id) {
+            match widget.find(id) {
+                Some(w) => widget = w,
+                None => return false,
+            }
+        }
+        let focus = match self.mgr.nav_focus {
+            Some(id) => id,
+            None => return false,
+        };
+
+        let mut candidates = vec![];
+        nav_rects(widget, Coord::ZERO, &mut candidates);
+        let from = match candidates.iter().find(|(id, _)| *id == focus) {
+            Some((_, rect)) => centre(*rect),
+            None => return false,
+        };
+
+        let mut best: Option<(i64, WidgetId)> = None;
+        for (id, rect) in candidates {
+            let d = centre(rect) - from;
+            let (primary, secondary) = match dir {
+                Direction::Right => (d.0, d.1),
+                Direction::Down => (d.1, d.0),
+                Direction::Left => (-d.0, d.1),
+                Direction::Up => (-d.1, d.0),
+            };
+            if primary <= 0 {
+                continue;
+            }
+            let score = i64::from(primary) + 2 * i64::from(secondary).abs();
+            if best.map(|(s, _)| score < s).unwrap_or(true) {
+                best = Some((score, id));
+            }
+        }
+
+        match best {
+            Some((_, id)) => {
+                self.mgr.nav_focus = Some(id);
+                self.mgr.nav_stack.clear();
+                self.mgr.focus_keyboard = true;
+                self.mgr.send_action(TkAction::Redraw);
+                trace!("Manager: nav_focus = {:?}", self.mgr.nav_focus);
+                self.mgr.pending.push(Pending::NavFocus(id));
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+// Collect enabled key_nav widgets under w with rects in w's coordinate space
+// (offset by `offset`), in logical order. Subtrees excluded from navigation
+// via Layout::spatial_range are skipped.
+fn nav_rects(w: &dyn WidgetConfig, offset: Coord, out: &mut Vec<(WidgetId, Rect)>) {
+    if w.is_disabled() {
+        return;
+    }
+    if w.key_nav() {
+        out.push((w.id(), w.rect() - offset));
+    }
+    if w.spatial_range().1 == std::usize::MAX {
+        return;
+    }
+    for index in 0..w.len() {
+        if let Some(child) = w.get(index) {
+            nav_rects(child, offset + w.translation(index), out);
+        }
+    }
+}
+
+fn centre(rect: Rect) -> Coord {
+    let half = Coord(rect.size.0 as i32 / 2, rect.size.1 as i32 / 2);
+    rect.pos + half
 }
@@ -8,18 +8,18 @@
 use log::*;
 use smallvec::SmallVec;
 use std::collections::HashMap;
+use std::ops::Range;
 use std::time::{Duration, Instant};
 
 use super::*;
-use crate::geom::{Coord, DVec2};
-#[allow(unused)]
-use crate::WidgetConfig; // for doc-links
-use crate::{TkAction, TkWindow, Widget, WidgetId};
-
-// TODO: this should be configurable or derived from the system
-const DOUBLE_CLICK_TIMEOUT: Duration = Duration::from_secs(1);
+use crate::draw::{SizeHandle, TextClass};
+use crate::geom::{Coord, DVec2, Size};
+use crate::layout::{AxisInfo, SizeRules};
+use crate::text::{Text, TextApiExt};
+use crate::{TkAction, TkWindow, Widget, WidgetConfig, WidgetId};
 
 const FAKE_MOUSE_BUTTON: MouseButton = MouseButton::Other(0);
+const MAX_FRAME_DELTA: Duration = Duration::from_millis(100);
 
 /// Toolkit API
 #[cfg_attr(not(feature = "internal_doc"), doc(hidden))]
@@ -28,37 +28,75 @@ impl ManagerState {
     #[inline]
     pub fn new() -> Self {
         ManagerState {
+            config: Default::default(),
             end_id: Default::default(),
             modifiers: ModifiersState::empty(),
             char_focus: false,
             sel_focus: None,
             nav_focus: None,
+            focus_keyboard: true,
             nav_fallback: None,
             nav_stack: SmallVec::new(),
+            pending_focus: None,
             hover: None,
             hover_icon: CursorIcon::Default,
+            hovered_files: vec![],
+            file_hover: None,
+            drag: None,
             key_depress: Default::default(),
             last_mouse_coord: Coord::ZERO,
-            last_click_button: FAKE_MOUSE_BUTTON,
-            last_click_repetitions: 0,
-            last_click_timeout: Instant::now(), // unimportant value
+            click: ClickCounter::new(),
             mouse_grab: None,
             touch_grab: Default::default(),
             pan_grab: SmallVec::new(),
             accel_stack: vec![],
             accel_layers: HashMap::new(),
+            hotkeys: HashMap::new(),
             popups: Default::default(),
             new_popups: Default::default(),
             popup_removed: Default::default(),
+            inspector: false,
+            inspection: None,
+            access_handle: None,
+            access_focus: None,
+            ime_position: None,
 
             time_start: Instant::now(),
             time_updates: vec![],
+            last_input: Instant::now(),
+            long_press: None,
+            idle_updates: Default::default(),
+            handle_timers: Default::default(),
+            anim_frames: SmallVec::new(),
+            anim_time: None,
             handle_updates: HashMap::new(),
             pending: SmallVec::new(),
             action: TkAction::None,
+            id_map: HashMap::new(),
         }
     }
 
+    /// Set double-click detection parameters
+    ///
+    /// A mouse press counts as a repetition of the previous press (see
+    /// [`PressSource::repetitions`]) if made with the same button, within
+    /// `timeout` of the previous press and within `distance` pixels (on each
+    /// axis) of the first press of the sequence. Defaults are 1 second and
+    /// 4 pixels; toolkits may override these, e.g. from system settings.
+    pub fn set_double_click(&mut self, timeout: Duration, distance: u32) {
+        self.click.timeout = timeout;
+        self.click.distance = distance;
+    }
+
+    /// Set the event handling configuration
+    ///
+    /// The default configuration is [`EventConfig::default`]. Changes to
+    /// [`EventConfig::idle_timeout`] take effect when the widget tree is next
+    /// configured.
+    pub fn set_config(&mut self, config: EventConfig) {
+        self.config = config;
+    }
+
     /// Configure event manager for a widget tree.
     ///
     /// This should be called by the toolkit on the widget tree when the window
@@ -78,10 +116,14 @@ impl ManagerState {
         // We re-set these instead of remapping:
         self.accel_stack.clear();
         self.accel_layers.clear();
+        self.hotkeys.clear();
         self.time_updates.clear();
+        self.idle_updates.clear();
+        self.long_press = None;
         self.handle_updates.clear();
         self.pending.clear();
         self.nav_fallback = None;
+        self.pending_focus = None;
 
         // Enumerate and configure all widgets:
         let coord = self.last_mouse_coord;
@@ -95,6 +137,14 @@ impl ManagerState {
             mgr.pop_accel_layer(widget.id());
             debug_assert!(mgr.mgr.accel_stack.is_empty());
 
+            // The root receives idle events unless it requested its own timeout
+            if let Some(timeout) = mgr.mgr.config.idle_timeout {
+                let id = widget.id();
+                if !mgr.mgr.idle_updates.iter().any(|update| update.1 == id) {
+                    mgr.update_on_idle(timeout, id);
+                }
+            }
+
             let hover = widget.find_id(coord);
             mgr.set_hover(widget, hover);
         });
@@ -110,6 +160,13 @@ impl ManagerState {
 
         self.sel_focus = self.sel_focus.and_then(|id| map.get(&id).cloned());
         self.nav_focus = self.nav_focus.and_then(|id| map.get(&id).cloned());
+        self.file_hover = self.file_hover.and_then(|id| map.get(&id).cloned());
+        self.drag = self.drag.take().and_then(|mut drag| {
+            drag.source = *map.get(&drag.source)?;
+            drag.hover = drag.hover.and_then(|id| map.get(&id).cloned());
+            drag.target = drag.target.and_then(|id| map.get(&id).cloned());
+            Some(drag)
+        });
         self.mouse_grab = self.mouse_grab.as_ref().and_then(|grab| {
             map.get(&grab.start_id).map(|id| MouseGrab {
                 button: grab.button,
@@ -163,6 +220,16 @@ impl ManagerState {
         do_map!(self.key_depress, |elt: (u32, WidgetId)| map
             .get(&elt.1)
             .map(|id| (elt.0, *id)));
+
+        // Animations of removed widgets stop here
+        do_map!(self.anim_frames, |id: WidgetId| map.get(&id).cloned());
+        if self.anim_frames.is_empty() {
+            self.anim_time = None;
+        }
+
+        // Unconfigured widgets have the default id; this is not a valid key
+        map.remove(&WidgetId::default());
+        self.id_map = map;
     }
 
     /// Update the widgets under the cursor and touch events
@@ -197,7 +264,26 @@ impl ManagerState {
 
     /// Get the next resume time
     pub fn next_resume(&self) -> Option<Instant> {
-        self.time_updates.last().map(|time| time.0)
+        let timer = self.time_updates.last().map(|time| time.0);
+        let long_press = self
+            .long_press
+            .as_ref()
+            .filter(|lp| !lp.fired)
+            .map(|lp| lp.time);
+        let handle_timer = self.next_handle_timer();
+        [timer, self.next_idle(), long_press, handle_timer]
+            .iter()
+            .flatten()
+            .min()
+            .cloned()
+    }
+
+    /// True if any widget awaits an animation frame
+    ///
+    /// While this is true, the toolkit should redraw continuously.
+    #[inline]
+    pub fn is_animating(&self) -> bool {
+        !self.anim_frames.is_empty()
     }
 
     /// Set an action
@@ -228,6 +314,63 @@ impl ManagerState {
         self.send_action(action);
     }
 
+    /// Update the inspection of the nav-focus widget
+    ///
+    /// This does nothing unless the inspector is enabled (see
+    /// [`Manager::set_inspector`]). Details are computed lazily: only if the
+    /// inspection is stale, i.e. after [`ManagerState::update`] or when the
+    /// nav-focus widget has moved. Toolkits should call this before drawing.
+    pub fn update_inspector(
+        &mut self,
+        widget: &mut dyn WidgetConfig,
+        size_handle: &mut dyn SizeHandle,
+    ) {
+        if !self.inspector {
+            return;
+        }
+        let target = self.nav_focus.and_then(|id| widget.find_mut(id));
+        if let (Some(insp), Some(w)) = (self.inspection.as_ref(), target.as_ref()) {
+            if insp.id == w.id() && insp.rect == w.rect() {
+                return;
+            }
+        }
+        self.inspection = target.map(|w| {
+            let id = w.id();
+            let rect = w.rect();
+            // NOTE: this may update cached state, but only from the same inputs
+            // as used by the last solve (unless the widget has since changed).
+            let horiz = w.size_rules(size_handle, AxisInfo::new(false, None));
+            let vert = w.size_rules(size_handle, AxisInfo::new(true, Some(rect.size.0)));
+            let fmt = |r: SizeRules| {
+                let (min, ideal) = (r.min_size(), r.ideal_size());
+                format!("min {}, ideal {}, {:?}", min, ideal, r.stretch())
+            };
+            let s = format!(
+                "{}: pos {:?}, size {:?}\nhoriz: {}\nvert: {}",
+                id,
+                rect.pos,
+                rect.size,
+                fmt(horiz),
+                fmt(vert),
+            );
+
+            let mut text = Text::new_multi(s);
+            let class = TextClass::Label;
+            let width = size_handle.text_bound(&mut text, class, AxisInfo::new(false, None));
+            let width = width.ideal_size();
+            let height = size_handle.text_bound(&mut text, class, AxisInfo::new(true, Some(width)));
+            let bounds = Size(width, height.ideal_size());
+            text.update_env(|env| env.set_bounds(bounds.into()));
+
+            Inspection {
+                id,
+                rect,
+                rules: (horiz, vert),
+                text,
+            }
+        });
+    }
+
     /// Update, after receiving all events
     #[inline]
     pub fn update<W>(&mut self, tkw: &mut dyn TkWindow, widget: &mut W) -> TkAction
@@ -256,6 +399,25 @@ impl ManagerState {
             }
         }
 
+        mgr.apply_pending_focus(widget.as_widget());
+
+        if let Some((id, rect)) = mgr.mgr.ime_position.take() {
+            if let Some(offset) = mgr.mgr.translation_of(widget.as_widget(), id) {
+                mgr.tkw.set_ime_position(rect - offset);
+            }
+        }
+
+        if let Some(handle) = mgr.mgr.access_handle {
+            let focus = mgr.mgr.nav_focus;
+            if focus != mgr.mgr.access_focus {
+                mgr.mgr.access_focus = focus;
+                mgr.trigger_update(handle, focus.map(u64::from).unwrap_or(0));
+            }
+        }
+
+        // The inspection is recomputed when next drawn
+        mgr.mgr.inspection = None;
+
         for gi in 0..mgr.mgr.pan_grab.len() {
             let grab = &mut mgr.mgr.pan_grab[gi];
             debug_assert!(grab.mode != GrabMode::Grab);
@@ -304,8 +466,10 @@ impl ManagerState {
         // make mgr const, but merely pretend it is in the public API.
         mgr.read_only = true;
 
-        for item in mgr.mgr.pending.pop() {
+        // Deliver all pending events, in order
+        for item in std::mem::take(&mut mgr.mgr.pending) {
             let (id, event) = match item {
+                Pending::NavFocus(id) => (id, Event::NavFocus),
                 Pending::LostCharFocus(id) => (id, Event::LostCharFocus),
                 Pending::LostSelFocus(id) => (id, Event::LostSelFocus),
             };
@@ -324,8 +488,15 @@ impl ManagerState {
 impl<'a> Manager<'a> {
     /// Update widgets due to timer
     pub fn update_timer<W: Widget + ?Sized>(&mut self, widget: &mut W) {
-        let now = Instant::now();
+        self.update_timer_at(widget, Instant::now());
+    }
 
+    /// Update widgets due to timer, as of time `now`
+    ///
+    /// This is [`Manager::update_timer`] with an explicit time, allowing
+    /// timers to be tested without waiting.
+    #[doc(hidden)]
+    pub fn update_timer_at<W: Widget + ?Sized>(&mut self, widget: &mut W, now: Instant) {
         // assumption: time_updates are sorted in reverse order
         while !self.mgr.time_updates.is_empty() {
             if self.mgr.time_updates.last().unwrap().0 > now {
@@ -337,6 +508,64 @@ impl<'a> Manager<'a> {
         }
 
         self.mgr.time_updates.sort_by(|a, b| b.cmp(a)); // reverse sort
+
+        let last_input = self.mgr.last_input;
+        let mut idle = SmallVec::<[WidgetId; 4]>::new();
+        for update in &mut self.mgr.idle_updates {
+            if !update.2 && last_input + update.0 <= now {
+                update.2 = true;
+                idle.push(update.1);
+            }
+        }
+        for id in idle {
+            self.send_event(widget, id, Event::Idle);
+        }
+
+        for handle in self.mgr.take_due_handle_timers(now) {
+            self.trigger_update(handle, 0);
+        }
+
+        if let Some(lp) = self.mgr.long_press.as_mut() {
+            if !lp.fired && lp.time <= now {
+                lp.fired = true;
+                let (id, source, coord) = (lp.start_id, lp.source, lp.coord);
+                let event = Event::LongPress { source, coord };
+                // If not handled, the press may still end in a click
+                let response = self.send_to(widget, id, event);
+                let handled = !matches!(response, Response::Unhandled(_));
+                if let Some(lp) = self.mgr.long_press.as_mut() {
+                    lp.handled = handled;
+                }
+            }
+        }
+
+        self.apply_pending_focus(widget.as_widget());
+    }
+
+    /// Send animation frames
+    ///
+    /// This should be called by the toolkit before drawing each frame. It sends
+    /// [`Event::AnimationFrame`] to all widgets which requested a frame.
+    pub fn animation_frame<W: Widget + ?Sized>(&mut self, widget: &mut W) {
+        if self.mgr.anim_frames.is_empty() {
+            return;
+        }
+
+        let now = Instant::now();
+        let last = self.mgr.anim_time.unwrap_or(now);
+        let delta = now.duration_since(last).min(MAX_FRAME_DELTA);
+        self.mgr.anim_time = Some(now);
+
+        let ids = std::mem::take(&mut self.mgr.anim_frames);
+        for id in ids {
+            if widget.find(id).is_some() {
+                self.send_event(widget, id, Event::AnimationFrame { delta });
+            }
+        }
+
+        if self.mgr.anim_frames.is_empty() {
+            self.mgr.anim_time = None;
+        }
     }
 
     /// Update widgets due to handle
@@ -353,6 +582,49 @@ impl<'a> Manager<'a> {
                 self.send_event(widget, w_id, event);
             }
         }
+
+        self.apply_pending_focus(widget.as_widget());
+    }
+
+    /// Handle a request to close the window
+    ///
+    /// The toolkit should call this when the user requests closure of the
+    /// window (e.g. winit's `CloseRequested`). This closes the window (via
+    /// [`TkAction::Close`]) unless vetoed by
+    /// [`kas::Window::handle_close_request`].
+    pub fn handle_close_request(&mut self, window: &mut dyn kas::Window) {
+        if window.handle_close_request(self) {
+            self.send_action(TkAction::Close);
+        }
+    }
+
+    /// Handle an input method (IME) preedit update
+    ///
+    /// Sends [`Event::ImePreedit`] to the widget with character focus, if any.
+    pub fn handle_ime_preedit<W: Widget + ?Sized>(
+        &mut self,
+        widget: &mut W,
+        text: String,
+        cursor_range: Option<Range<usize>>,
+    ) {
+        let event = Event::ImePreedit { text, cursor_range };
+        self.send_ime_event(widget, event);
+    }
+
+    /// Handle an input method (IME) commit
+    ///
+    /// Sends [`Event::ImeCommit`] to the widget with character focus, if any.
+    pub fn handle_ime_commit<W: Widget + ?Sized>(&mut self, widget: &mut W, text: String) {
+        self.send_ime_event(widget, Event::ImeCommit { text });
+    }
+
+    fn send_ime_event<W: Widget + ?Sized>(&mut self, widget: &mut W, event: Event) {
+        self.mgr.input_activity();
+        if let Some(id) = self.mgr.sel_focus {
+            if self.mgr.char_focus {
+                self.send_event(widget, id, event);
+            }
+        }
     }
 
     /// Handle a winit `WindowEvent`.
@@ -360,6 +632,7 @@ impl<'a> Manager<'a> {
     /// Note that some event types are not *does not* handled, since for these
     /// events the toolkit must take direct action anyway:
     /// `Resized(size)`, `RedrawRequested`, `HiDpiFactorChanged(factor)`.
+    /// `CloseRequested` is not handled; see [`Manager::handle_close_request`].
     #[cfg(feature = "winit")]
     pub fn handle_winit<W>(&mut self, widget: &mut W, event: winit::event::WindowEvent)
     where
@@ -372,25 +645,41 @@ impl<'a> Manager<'a> {
         // Unhandled events here, so we can freely ignore all responses.
 
         match event {
-            CloseRequested => self.send_action(TkAction::Close),
-            /* Not yet supported: see #98
-            DroppedFile(path) => ,
-            HoveredFile(path) => ,
-            HoveredFileCancelled => ,
-            */
-            ReceivedCharacter(c) => {
-                if let Some(id) = self.mgr.sel_focus {
-                    if self.mgr.char_focus {
-                        // Filter out control codes (Unicode 5.11). These may be
-                        // generated from combinations such as Ctrl+C by some other
-                        // layer. We use our own shortcut system instead.
-                        if c >= '\u{20}' && (c < '\u{7f}' || c > '\u{9f}') {
-                            let event = Event::ReceivedCharacter(c);
-                            self.send_event(widget, id, event);
-                        }
+            ReceivedCharacter(_)
+            | KeyboardInput { .. }
+            | CursorMoved { .. }
+            | MouseWheel { .. }
+            | MouseInput { .. }
+            | Touch(_) => self.mgr.input_activity(),
+            _ => (),
+        }
+
+        match event {
+            HoveredFile(path) => {
+                self.mgr.hovered_files.push(path.clone());
+                let coord = self.mgr.last_mouse_coord;
+                if let Some(id) = widget.find_id(coord) {
+                    let event = Event::FileHovered(path);
+                    if let Some(id) = self.send_bubbling(widget, id, event) {
+                        self.mgr.file_hover = Some(id);
                     }
                 }
             }
+            DroppedFile(path) => {
+                self.mgr.hovered_files.clear();
+                self.mgr.file_hover = None;
+                let coord = self.mgr.last_mouse_coord;
+                if let Some(id) = widget.find_id(coord) {
+                    self.send_bubbling(widget, id, Event::FileDropped(path));
+                }
+            }
+            HoveredFileCancelled => {
+                self.mgr.hovered_files.clear();
+                if let Some(id) = self.mgr.file_hover.take() {
+                    self.send_event(widget, id, Event::FileHoverCancelled);
+                }
+            }
+            ReceivedCharacter(c) => self.received_char(widget, c),
             KeyboardInput {
                 input,
                 is_synthetic,
@@ -412,17 +701,20 @@ impl<'a> Manager<'a> {
                 self.mgr.modifiers = state;
             }
             CursorMoved { position, .. } => {
-                self.mgr.last_click_button = FAKE_MOUSE_BUTTON;
                 let coord = position.into();
 
                 // Update hovered widget
                 let cur_id = widget.find_id(coord);
                 let delta = coord - self.mgr.last_mouse_coord;
+                if !self.mgr.hovered_files.is_empty() && cur_id != self.mgr.hover {
+                    self.route_hovered_files(widget, cur_id);
+                }
                 self.set_hover(widget, cur_id);
 
                 if let Some(grab) = self.mouse_grab() {
                     if grab.mode == GrabMode::Grab {
                         let source = PressSource::Mouse(grab.button, grab.repetitions);
+                        self.mgr.long_press_moved(source, coord);
                         let event = Event::PressMove {
                             source,
                             cur_id,
@@ -447,10 +739,11 @@ impl<'a> Manager<'a> {
                 }
 
                 self.mgr.last_mouse_coord = coord;
+                self.drag_move(widget, cur_id, coord);
             }
             // CursorEntered { .. },
             CursorLeft { .. } => {
-                self.mgr.last_click_button = FAKE_MOUSE_BUTTON;
+                self.mgr.click.reset();
 
                 if self.mouse_grab().is_none() {
                     // If there's a mouse grab, we will continue to receive
@@ -460,11 +753,14 @@ impl<'a> Manager<'a> {
                 }
             }
             MouseWheel { delta, .. } => {
-                self.mgr.last_click_button = FAKE_MOUSE_BUTTON;
+                self.mgr.click.reset();
 
+                // Our sign convention is the opposite of winit's
                 let event = Event::Scroll(match delta {
-                    MouseScrollDelta::LineDelta(x, y) => ScrollDelta::LineDelta(x, y),
-                    MouseScrollDelta::PixelDelta(pos) => ScrollDelta::PixelDelta(pos.into()),
+                    MouseScrollDelta::LineDelta(x, y) => ScrollDelta::LineDelta(-x, -y),
+                    MouseScrollDelta::PixelDelta(pos) => {
+                        ScrollDelta::PixelDelta(Coord::ZERO - Coord::from(pos))
+                    }
                 });
                 if let Some(id) = self.mgr.hover {
                     self.send_event(widget, id, event);
@@ -474,14 +770,12 @@ impl<'a> Manager<'a> {
                 let coord = self.mgr.last_mouse_coord;
 
                 if state == ElementState::Pressed {
-                    let now = Instant::now();
-                    if button != self.mgr.last_click_button || self.mgr.last_click_timeout < now {
-                        self.mgr.last_click_button = button;
-                        self.mgr.last_click_repetitions = 0;
-                    }
-                    self.mgr.last_click_repetitions += 1;
-                    self.mgr.last_click_timeout = now + DOUBLE_CLICK_TIMEOUT;
+                    self.mgr.pointer_press();
                 }
+                let repetitions = match state {
+                    ElementState::Pressed => self.mgr.click.press(button, coord, Instant::now()),
+                    ElementState::Released => self.mgr.click.repetitions,
+                };
 
                 if let Some(grab) = self.mouse_grab() {
                     match grab.mode {
@@ -489,9 +783,15 @@ impl<'a> Manager<'a> {
                             // Mouse grab active: send events there
                             debug_assert_eq!(state, ElementState::Released);
                             let source = PressSource::Mouse(button, grab.repetitions);
+                            let dragged = self.mgr.drag.is_some();
+                            self.end_drag(widget, true);
+                            let end_id = match self.mgr.long_press_end(source) || dragged {
+                                true => None,
+                                false => self.mgr.hover,
+                            };
                             let event = Event::PressEnd {
                                 source,
-                                end_id: self.mgr.hover,
+                                end_id,
                                 coord,
                             };
                             self.send_event(widget, grab.start_id, event);
@@ -506,13 +806,19 @@ impl<'a> Manager<'a> {
                 } else if let Some(start_id) = self.mgr.hover {
                     // No mouse grab but have a hover target
                     if state == ElementState::Pressed {
-                        let source = PressSource::Mouse(button, self.mgr.last_click_repetitions);
+                        let source = PressSource::Mouse(button, repetitions);
                         let event = Event::PressStart {
                             source,
                             start_id,
                             coord,
                         };
                         self.send_popup_first(widget, start_id, event);
+                        if let Some(grab) = self.mgr.mouse_grab.as_ref() {
+                            if grab.mode == GrabMode::Grab {
+                                let start_id = grab.start_id;
+                                self.start_long_press(source, start_id, coord);
+                            }
+                        }
                     }
                 }
             }
@@ -523,6 +829,7 @@ impl<'a> Manager<'a> {
                 let coord = touch.location.into();
                 match touch.phase {
                     TouchPhase::Started => {
+                        self.mgr.pointer_press();
                         if let Some(start_id) = widget.find_id(coord) {
                             let event = Event::PressStart {
                                 source,
@@ -530,10 +837,17 @@ impl<'a> Manager<'a> {
                                 coord,
                             };
                             self.send_popup_first(widget, start_id, event);
+                            if let Some(grab) = self.get_touch(touch.id) {
+                                if grab.mode == GrabMode::Grab {
+                                    let start_id = grab.start_id;
+                                    self.start_long_press(source, start_id, coord);
+                                }
+                            }
                         }
                     }
                     TouchPhase::Moved => {
                         let cur_id = widget.find_id(coord);
+                        self.mgr.long_press_moved(source, coord);
 
                         let mut r = None;
                         let mut pan_grab = None;
@@ -574,11 +888,12 @@ impl<'a> Manager<'a> {
                         }
                     }
                     TouchPhase::Ended => {
+                        let handled = self.mgr.long_press_end(source);
                         if let Some(grab) = self.remove_touch(touch.id) {
                             if grab.mode == GrabMode::Grab {
                                 let event = Event::PressEnd {
                                     source,
-                                    end_id: grab.cur_id,
+                                    end_id: if handled { None } else { grab.cur_id },
                                     coord,
                                 };
                                 if let Some(cur_id) = grab.cur_id {
@@ -591,6 +906,7 @@ impl<'a> Manager<'a> {
                         }
                     }
                     TouchPhase::Cancelled => {
+                        self.mgr.long_press_end(source);
                         if let Some(grab) = self.remove_touch(touch.id) {
                             let event = Event::PressEnd {
                                 source,
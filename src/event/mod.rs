@@ -65,16 +65,31 @@
 //! with a reference to the [`ManagerState`] (which is passed to
 //! [`Layout::draw`] calls) in order to obtain an [`InputState`] instance.
 //!
+//! ## Debugging
+//!
+//! With feature `trace_events`, event routing is logged at trace level with
+//! target `kas::event::route`. Each event sent by the [`Manager`] is logged
+//! with its target and disposition (`none`, `unhandled`, `focus` or `msg`),
+//! preceded by one record for each widget which handled the event via
+//! [`Manager::handle_generic`], innermost first. For example, a
+//! button which does not respond may be seen to return `unhandled`, or an
+//! ancestor to consume the event before it reaches the button.
+//!
 //! [`WidgetId`]: crate::WidgetId
 
+mod config;
 #[cfg(not(feature = "winit"))]
 mod enums;
 mod events;
 mod handler;
 mod manager;
 mod response;
+mod type_ahead;
 mod update;
 
+#[cfg(feature = "trace_events")]
+const ROUTE_TARGET: &str = "kas::event::route";
+
 use smallvec::SmallVec;
 use std::fmt::Debug;
 
@@ -87,12 +102,14 @@ pub use winit::event::{ModifiersState, MouseButton, VirtualKeyCode};
 #[cfg(feature = "winit")]
 pub use winit::window::CursorIcon;
 
+pub use config::EventConfig;
 #[cfg(not(feature = "winit"))]
 pub use enums::{CursorIcon, ModifiersState, MouseButton, VirtualKeyCode};
 pub use events::*;
 pub use handler::{Handler, SendEvent};
-pub use manager::{ConfigureManager, GrabMode, Manager, ManagerState};
+pub use manager::{ConfigureManager, GrabMode, Inspection, Manager, ManagerState};
 pub use response::Response;
+pub use type_ahead::TypeAhead;
 pub use update::UpdateHandle;
 
 /// A type supporting a small number of key bindings
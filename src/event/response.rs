@@ -31,6 +31,17 @@ pub enum Response<M> {
 // Unfortunately we cannot write generic `From` / `TryFrom` impls
 // due to trait coherence rules, so we impl `from` etc. directly.
 impl<M> Response<M> {
+    // Name of the variant, for logging
+    #[cfg(feature = "trace_events")]
+    pub(crate) fn disposition(&self) -> &'static str {
+        match self {
+            Response::None => "none",
+            Response::Unhandled(_) => "unhandled",
+            Response::Focus(_) => "focus",
+            Response::Msg(_) => "msg",
+        }
+    }
+
     /// True if variant is `None`
     #[inline]
     pub fn is_none(&self) -> bool {
@@ -0,0 +1,171 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Event handling: type-ahead search
+
+use std::time::{Duration, Instant};
+
+use super::Manager;
+use crate::{WidgetConfig, WidgetId};
+
+/// A type-ahead search buffer
+///
+/// Lists and menus use this to jump to an item by typing the start of its
+/// label. Characters typed within the timeout of the previous character
+/// (see [`Manager::type_ahead_timeout`]) are accumulated; after the timeout
+/// the search restarts.
+///
+/// The owning widget should call [`TypeAhead::push`] on each
+/// [`Event::ReceivedCharacter`](super::Event::ReceivedCharacter) and
+/// [`TypeAhead::on_timer`] on [`Event::TimerUpdate`](super::Event::TimerUpdate),
+/// then use [`TypeAhead::find_child`] to find the item to focus.
+/// Matching is case-insensitive, against the label reported by
+/// [`WidgetConfig::accessible`].
+#[derive(Clone, Debug, Default)]
+pub struct TypeAhead {
+    buf: String,
+    deadline: Option<Instant>,
+}
+
+impl TypeAhead {
+    /// Construct, with an empty buffer
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The current search string (lower case)
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.buf
+    }
+
+    /// True while a search is in progress (the timeout has not elapsed)
+    pub fn is_active(&self) -> bool {
+        self.is_active_at(Instant::now())
+    }
+
+    /// Clear the search
+    pub fn clear(&mut self) {
+        self.buf.clear();
+        self.deadline = None;
+    }
+
+    /// Append a character to the search, returning the search string
+    ///
+    /// If the timeout has elapsed since the last character, the search is
+    /// first cleared. Each character restarts the timeout; a timer update is
+    /// scheduled for widget `id` (see [`TypeAhead::on_timer`]).
+    pub fn push(&mut self, mgr: &mut Manager, id: WidgetId, c: char) -> &str {
+        let timeout = mgr.type_ahead_timeout();
+        self.push_at(c, Instant::now(), timeout);
+        mgr.update_on_timer(timeout, id);
+        &self.buf
+    }
+
+    /// Handle a timer update for widget `id`
+    ///
+    /// This clears the search once the timeout has elapsed, otherwise
+    /// schedules another update.
+    pub fn on_timer(&mut self, mgr: &mut Manager, id: WidgetId) {
+        let now = Instant::now();
+        if let Some(deadline) = self.deadline {
+            if now >= deadline {
+                self.clear();
+            } else {
+                mgr.update_on_timer(deadline - now, id);
+            }
+        }
+    }
+
+    /// True if `label` starts with the search string (ignoring case)
+    pub fn matches(&self, label: &str) -> bool {
+        !self.buf.is_empty() && label.to_lowercase().starts_with(&self.buf)
+    }
+
+    /// Find the first child of `widget` matching the search
+    ///
+    /// Children are searched in order; disabled children are skipped. The
+    /// result is the matching child or, if this does not support keyboard
+    /// navigation, its first descendant which does.
+    pub fn find_child(&self, widget: &dyn WidgetConfig) -> Option<WidgetId> {
+        Self::find(widget, &self.buf)
+    }
+
+    /// Find the first child of `widget` whose label starts with `prefix`
+    ///
+    /// This is like [`TypeAhead::find_child`], but using the given `prefix`
+    /// instead of the current search. This may be used to test whether a
+    /// character would continue a search.
+    pub fn find(widget: &dyn WidgetConfig, prefix: &str) -> Option<WidgetId> {
+        if prefix.is_empty() {
+            return None;
+        }
+        let prefix = prefix.to_lowercase();
+        (0..widget.len())
+            .filter_map(|i| widget.get(i))
+            .filter(|w| !w.is_disabled())
+            .filter(|w| match w.accessible().label {
+                Some(label) => label.to_lowercase().starts_with(&prefix),
+                None => false,
+            })
+            .find_map(nav_target)
+    }
+
+    fn is_active_at(&self, now: Instant) -> bool {
+        self.deadline.map(|d| now < d).unwrap_or(false)
+    }
+
+    fn push_at(&mut self, c: char, now: Instant, timeout: Duration) {
+        if !self.is_active_at(now) {
+            self.buf.clear();
+        }
+        self.buf.extend(c.to_lowercase());
+        self.deadline = Some(now + timeout);
+    }
+}
+
+// The first enabled widget under (and including) w supporting key navigation
+fn nav_target(w: &dyn WidgetConfig) -> Option<WidgetId> {
+    if w.is_disabled() {
+        None
+    } else if w.key_nav() {
+        Some(w.id())
+    } else {
+        (0..w.len()).filter_map(|i| w.get(i)).find_map(nav_target)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn timeout() {
+        let t0 = Instant::now();
+        let ms = |n| t0 + Duration::from_millis(n);
+        let timeout = Duration::from_millis(500);
+        let mut search = TypeAhead::new();
+        assert!(!search.is_active_at(t0));
+        assert!(!search.matches("anything"));
+
+        search.push_at('S', ms(0), timeout);
+        search.push_at('a', ms(400), timeout);
+        assert_eq!(search.as_str(), "sa");
+        assert!(search.matches("Save"));
+        assert!(!search.matches("Open"));
+
+        // Each character restarts the timeout
+        search.push_at('v', ms(800), timeout);
+        assert_eq!(search.as_str(), "sav");
+        assert!(search.is_active_at(ms(1200)));
+        assert!(!search.is_active_at(ms(1300)));
+
+        // After the timeout, a new search starts
+        search.push_at('o', ms(1300), timeout);
+        assert_eq!(search.as_str(), "o");
+        assert!(search.matches("Open"));
+    }
+}
@@ -58,6 +58,12 @@ impl Coord {
         let pos: (i32, i32) = pos.into();
         Coord(pos.0, pos.1)
     }
+
+    /// Convert to logical pixels, given the scale factor
+    #[inline]
+    pub fn to_logical(self, scale_factor: f32) -> LogicalCoord {
+        LogicalCoord(self.0 as f32 / scale_factor, self.1 as f32 / scale_factor)
+    }
 }
 
 impl From<(i32, i32)> for Coord {
@@ -172,6 +178,12 @@ impl Size {
     pub fn transpose(self) -> Self {
         Size(self.1, self.0)
     }
+
+    /// Convert to logical pixels, given the scale factor
+    #[inline]
+    pub fn to_logical(self, scale_factor: f32) -> LogicalSize {
+        LogicalSize(self.0 as f32 / scale_factor, self.1 as f32 / scale_factor)
+    }
 }
 
 impl From<(u32, u32)> for Size {
@@ -317,6 +329,16 @@ impl Rect {
         let size = Size(w, h);
         Rect { pos, size }
     }
+
+    /// Get the largest rect contained by both `self` and `other`
+    ///
+    /// If the two do not overlap, the result is empty (zero width or height).
+    #[inline]
+    pub fn intersection(&self, other: Rect) -> Rect {
+        let pos = self.pos.max(other.pos);
+        let end = self.pos_end().min(other.pos_end()).max(pos);
+        Rect::new(pos, Size::from(end - pos))
+    }
 }
 
 impl std::ops::Add<Coord> for Rect {
@@ -344,3 +366,128 @@ impl std::ops::Sub<Coord> for Rect {
         }
     }
 }
+
+// Round to an integer, treating values within rounding error of an integer as
+// that integer; otherwise round up.
+fn round_up(x: f32) -> f32 {
+    let r = x.round();
+    if (x - r).abs() < 1e-3 {
+        r
+    } else {
+        x.ceil()
+    }
+}
+
+/// An `(x, y)` coordinate in logical (DPI-independent) pixels
+///
+/// Most of KAS, including the layout engine, uses physical pixels ([`Coord`]).
+/// Logical coordinates allow application code to specify positions
+/// independent of the display's scale factor (see
+/// [`SizeHandle::scale_factor`](crate::draw::SizeHandle::scale_factor)).
+/// Use [`LogicalCoord::to_physical`] and [`Coord::to_logical`] to convert.
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct LogicalCoord(pub f32, pub f32);
+
+impl LogicalCoord {
+    /// Convert to physical pixels, given the scale factor
+    ///
+    /// Components are rounded to the nearest pixel.
+    #[inline]
+    pub fn to_physical(self, scale_factor: f32) -> Coord {
+        let x = (self.0 * scale_factor).round() as i32;
+        let y = (self.1 * scale_factor).round() as i32;
+        Coord(x, y)
+    }
+}
+
+impl From<(f32, f32)> for LogicalCoord {
+    fn from(pos: (f32, f32)) -> LogicalCoord {
+        LogicalCoord(pos.0, pos.1)
+    }
+}
+
+#[cfg(feature = "winit")]
+impl<X: Pixel> From<LogicalCoord> for LogicalPosition<X> {
+    #[inline]
+    fn from(pos: LogicalCoord) -> LogicalPosition<X> {
+        let pos: LogicalPosition<f32> = (pos.0, pos.1).into();
+        pos.cast()
+    }
+}
+
+/// A `(w, h)` size in logical (DPI-independent) pixels
+///
+/// Most of KAS, including the layout engine, uses physical pixels ([`Size`]).
+/// Logical sizes allow application code to specify sizes independent of the
+/// display's scale factor (see
+/// [`SizeHandle::scale_factor`](crate::draw::SizeHandle::scale_factor)).
+/// Use [`LogicalSize::to_physical`] and [`Size::to_logical`] to convert.
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct LogicalSize(pub f32, pub f32);
+
+impl LogicalSize {
+    /// Convert to physical pixels, given the scale factor
+    ///
+    /// Components are rounded up to the next whole pixel, thus the result is
+    /// never smaller than the logical size. (Values within rounding error of a
+    /// whole pixel are not rounded up, thus converting a physical size to
+    /// logical and back again yields the original size.) Negative components
+    /// are clamped to zero.
+    #[inline]
+    pub fn to_physical(self, scale_factor: f32) -> Size {
+        let w = round_up(self.0 * scale_factor).max(0.0) as u32;
+        let h = round_up(self.1 * scale_factor).max(0.0) as u32;
+        Size(w, h)
+    }
+}
+
+impl From<(f32, f32)> for LogicalSize {
+    fn from(size: (f32, f32)) -> LogicalSize {
+        LogicalSize(size.0, size.1)
+    }
+}
+
+#[cfg(feature = "winit")]
+impl From<LogicalSize> for winit::dpi::Size {
+    #[inline]
+    fn from(size: LogicalSize) -> winit::dpi::Size {
+        winit::dpi::Size::Logical((size.0 as f64, size.1 as f64).into())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn logical_round_trip() {
+        for &factor in &[1.0, 1.25, 1.5, 1.75, 2.0, 2.5, 3.0] {
+            for &n in &[0, 1, 7, 100, 333, 1920] {
+                let size = Size(n, n + 1);
+                assert_eq!(size.to_logical(factor).to_physical(factor), size);
+                let coord = Coord(n as i32, -(n as i32));
+                assert_eq!(coord.to_logical(factor).to_physical(factor), coord);
+            }
+        }
+    }
+
+    #[test]
+    fn rect_intersection() {
+        let a = Rect::new(Coord(0, 10), Size(20, 5));
+        let b = Rect::new(Coord(5, 0), Size(5, 12));
+        assert_eq!(a.intersection(b), Rect::new(Coord(5, 10), Size(5, 2)));
+        assert_eq!(a.intersection(b), b.intersection(a));
+        assert_eq!(a.intersection(a), a);
+        let c = Rect::new(Coord(30, 0), Size(5, 5));
+        assert_eq!(a.intersection(c).size, Size::ZERO);
+    }
+
+    #[test]
+    fn logical_rounding() {
+        // Sizes round up; positions to nearest
+        assert_eq!(LogicalSize(10.2, 10.0).to_physical(1.0), Size(11, 10));
+        assert_eq!(LogicalSize(100.0, 0.5).to_physical(1.5), Size(150, 1));
+        assert_eq!(LogicalCoord(10.2, 10.7).to_physical(1.0), Coord(10, 11));
+        assert_eq!(LogicalCoord(-0.7, 0.3).to_physical(2.0), Coord(-1, 1));
+    }
+}
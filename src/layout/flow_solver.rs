@@ -0,0 +1,283 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Flow solver (wrapping rows)
+
+use std::ops::Range;
+
+use super::{AxisInfo, FlowStorage, RulesSetter, RulesSolver, SizeRules};
+use crate::geom::{Coord, Rect, Size};
+use crate::{Align, AlignHints};
+
+/// Find line breaks
+///
+/// Each line holds as many children as fit within `width` at their ideal size
+/// (including margins between children), but always at least one child.
+/// Outputs the index of the first child on each line to `lines`.
+fn break_lines(rules: &[SizeRules], width: u32, lines: &mut Vec<usize>) {
+    lines.clear();
+    let mut x = 0;
+    for (i, r) in rules.iter().enumerate() {
+        if i == 0 {
+            lines.push(0);
+            x = r.ideal_size();
+            continue;
+        }
+        let margin = rules[i - 1].margins().1.max(r.margins().0) as u32;
+        let end = x.saturating_add(margin + r.ideal_size());
+        if end > width {
+            lines.push(i);
+            x = r.ideal_size();
+        } else {
+            x = end;
+        }
+    }
+}
+
+fn line_range(storage: &FlowStorage, line: usize) -> Range<usize> {
+    let end = match storage.lines.get(line + 1) {
+        Some(end) => *end,
+        None => storage.width_rules.len(),
+    };
+    storage.lines[line]..end
+}
+
+// Vertical rules of each line
+fn line_rules(storage: &FlowStorage) -> Vec<SizeRules> {
+    (0..storage.lines.len())
+        .map(|line| {
+            let rules = &storage.height_rules[line_range(storage, line)];
+            rules.iter().fold(SizeRules::EMPTY, |a, b| a.max(*b))
+        })
+        .collect()
+}
+
+/// A [`RulesSolver`] for flow layouts
+///
+/// Children are set out left-to-right at their ideal width, wrapping onto a
+/// new line whenever the next child would not fit within the available width.
+///
+/// The horizontal minimum size is that of the widest child, while the ideal
+/// size places all children on a single line. Vertical size requirements
+/// depend on the width (see [`AxisInfo::other`]), thus the vertical axis must
+/// be solved with a fixed width (as done by [`super::SolveCache`]).
+///
+/// Margins between children and between lines are the maximum of adjacent
+/// children's margins, as for rows and columns.
+pub struct FlowSolver {
+    axis: AxisInfo,
+    width: u32,
+    min: u32,
+    rules: Option<SizeRules>,
+}
+
+impl FlowSolver {
+    /// Construct.
+    ///
+    /// Argument order is consistent with other [`RulesSolver`]s.
+    ///
+    /// - `axis`: `AxisInfo` instance passed into `size_rules`
+    /// - `len`: number of children
+    /// - `storage`: reference to persistent storage
+    pub fn new(axis: AxisInfo, len: usize, storage: &mut FlowStorage) -> Self {
+        storage.set_len(len);
+
+        let width = axis.other().unwrap_or(u32::MAX);
+        if axis.is_vertical() {
+            break_lines(&storage.width_rules, width, &mut storage.lines);
+        }
+
+        FlowSolver {
+            axis,
+            width,
+            min: 0,
+            rules: None,
+        }
+    }
+}
+
+impl RulesSolver for FlowSolver {
+    type Storage = FlowStorage;
+    type ChildInfo = usize;
+
+    fn for_child<CR: FnOnce(AxisInfo) -> SizeRules>(
+        &mut self,
+        storage: &mut Self::Storage,
+        index: Self::ChildInfo,
+        child_rules: CR,
+    ) {
+        if self.axis.is_horizontal() {
+            let rules = child_rules(self.axis);
+            storage.width_rules[index] = rules;
+            self.min = self.min.max(rules.min_size());
+            self.rules = Some(match self.rules {
+                Some(sum) => sum.appended(rules),
+                None => rules,
+            });
+        } else {
+            let width = storage.width_rules[index].ideal_size().min(self.width);
+            storage.height_rules[index] = child_rules(AxisInfo::new(true, Some(width)));
+        }
+    }
+
+    fn finish(self, storage: &mut Self::Storage) -> SizeRules {
+        if self.axis.is_horizontal() {
+            let sum = self.rules.unwrap_or(SizeRules::EMPTY);
+            let ideal = sum.ideal_size().max(self.min);
+            SizeRules::new(self.min, ideal, sum.margins(), sum.stretch())
+        } else {
+            line_rules(storage).iter().sum()
+        }
+    }
+}
+
+/// A [`RulesSetter`] for flow layouts
+///
+/// See [`FlowSolver`]. Children are given their ideal width (or the full
+/// width, if less). A child wider than the available width is placed on its
+/// own line and clipped to that width. Each line is aligned horizontally
+/// according to alignment hints ([`Align::Stretch`] is treated as
+/// [`Align::TL`]), while children receive the full height of their line.
+pub struct FlowSetter {
+    rect: Rect,
+    child_rects: Vec<Rect>,
+    line_rects: Vec<Rect>,
+}
+
+impl FlowSetter {
+    /// Construct
+    ///
+    /// Argument order is consistent with other [`RulesSetter`]s.
+    ///
+    /// -   `rect`: the [`Rect`] within which to position children
+    /// -   `len`: number of children
+    /// -   `align`: alignment hints
+    /// -   `storage`: access to the solver's storage
+    pub fn new(rect: Rect, len: usize, align: AlignHints, storage: &mut FlowStorage) -> Self {
+        storage.set_len(len);
+        let width = rect.size.0;
+        break_lines(&storage.width_rules, width, &mut storage.lines);
+
+        let rules = line_rules(storage);
+        let mut heights = vec![0; rules.len()];
+        SizeRules::solve_seq(&mut heights, &rules, rect.size.1);
+
+        let mut child_rects = Vec::with_capacity(len);
+        let mut line_rects = Vec::with_capacity(rules.len());
+        let mut y = rect.pos.1;
+        for line in 0..rules.len() {
+            if line > 0 {
+                let margin = rules[line - 1].margins().1.max(rules[line].margins().0);
+                y += heights[line - 1] as i32 + margin as i32;
+            }
+            let height = heights[line];
+            line_rects.push(Rect::new(Coord(rect.pos.0, y), Size(width, height)));
+
+            let range = line_range(storage, line);
+            let children = &storage.width_rules[range];
+            let mut used = 0;
+            for (i, r) in children.iter().enumerate() {
+                if i > 0 {
+                    used += children[i - 1].margins().1.max(r.margins().0) as u32;
+                }
+                used += r.ideal_size().min(width);
+            }
+            let extra = width.saturating_sub(used);
+            let mut x = rect.pos.0
+                + match align.horiz.unwrap_or(Align::TL) {
+                    Align::Default | Align::TL | Align::Stretch => 0,
+                    Align::Centre => extra / 2,
+                    Align::BR => extra,
+                } as i32;
+            for (i, r) in children.iter().enumerate() {
+                if i > 0 {
+                    x += children[i - 1].margins().1.max(r.margins().0) as i32;
+                }
+                let w = r.ideal_size().min(width);
+                child_rects.push(Rect::new(Coord(x, y), Size(w, height)));
+                x += w as i32;
+            }
+        }
+
+        FlowSetter {
+            rect,
+            child_rects,
+            line_rects,
+        }
+    }
+}
+
+impl RulesSetter for FlowSetter {
+    type Storage = FlowStorage;
+    type ChildInfo = usize;
+
+    fn child_rect(&mut self, _: &mut Self::Storage, index: Self::ChildInfo) -> Rect {
+        self.child_rects[index]
+    }
+
+    /// The maximal rect of a child is the whole of its line
+    fn maximal_rect_of(&mut self, storage: &mut Self::Storage, index: Self::ChildInfo) -> Rect {
+        let line = match storage.lines.binary_search(&index) {
+            Ok(line) => line,
+            Err(line) => line - 1,
+        };
+        self.line_rects.get(line).cloned().unwrap_or(self.rect)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::layout::StretchPolicy;
+
+    fn solve(width: u32) -> (SizeRules, Vec<Rect>) {
+        let widths = [30, 40, 50];
+        let mut storage = FlowStorage::default();
+
+        let axis = AxisInfo::horizontal();
+        let mut solver = FlowSolver::new(axis, widths.len(), &mut storage);
+        for (i, w) in widths.iter().enumerate() {
+            let rules = SizeRules::new(*w / 2, *w, (2, 2), StretchPolicy::Fixed);
+            solver.for_child(&mut storage, i, |_| rules);
+        }
+        let rules = solver.finish(&mut storage);
+        assert_eq!((rules.min_size(), rules.ideal_size()), (25, 124));
+
+        let axis = AxisInfo::vertical().with_fixed(width);
+        let mut solver = FlowSolver::new(axis, widths.len(), &mut storage);
+        for i in 0..widths.len() {
+            solver.for_child(&mut storage, i, |axis| {
+                assert_eq!(axis.other(), Some(widths[i].min(width)));
+                SizeRules::fixed(10, (2, 2))
+            });
+        }
+        let rules = solver.finish(&mut storage);
+
+        let rect = Rect::new(Coord(5, 5), Size(width, rules.ideal_size()));
+        let mut setter = FlowSetter::new(rect, widths.len(), AlignHints::NONE, &mut storage);
+        let rects = (0..widths.len())
+            .map(|i| setter.child_rect(&mut storage, i))
+            .collect();
+        (rules, rects)
+    }
+
+    #[test]
+    fn wrapping() {
+        let (rules, rects) = solve(200);
+        assert_eq!(rules.ideal_size(), 10);
+        assert_eq!(rects[2], Rect::new(Coord(79, 5), Size(50, 10)));
+
+        let (rules, rects) = solve(80);
+        assert_eq!(rules.ideal_size(), 22);
+        assert_eq!(rects[0], Rect::new(Coord(5, 5), Size(30, 10)));
+        assert_eq!(rects[1], Rect::new(Coord(37, 5), Size(40, 10)));
+        assert_eq!(rects[2], Rect::new(Coord(5, 17), Size(50, 10)));
+
+        // A child wider than the line is clipped
+        let (rules, rects) = solve(45);
+        assert_eq!(rules.ideal_size(), 34);
+        assert_eq!(rects[2], Rect::new(Coord(5, 29), Size(45, 10)));
+    }
+}
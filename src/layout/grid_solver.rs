@@ -7,11 +7,12 @@
 
 use std::marker::PhantomData;
 
-use super::{AxisInfo, GridStorage, RowTemp, RulesSetter, RulesSolver, SizeRules};
+use super::{AxisInfo, GridStorage, Margins, RowTemp, RulesSetter, RulesSolver, SizeRules};
 use crate::geom::{Coord, Rect, Size};
 use kas::{Align, AlignHints};
 
 /// Per-child information
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct GridChildInfo {
     /// Column index (first column when in a span)
     pub col: u32,
@@ -23,6 +24,56 @@ pub struct GridChildInfo {
     pub row_end: u32,
 }
 
+impl GridChildInfo {
+    /// Construct for a single cell
+    #[inline]
+    pub fn single(col: u32, row: u32) -> Self {
+        GridChildInfo {
+            col,
+            col_end: col + 1,
+            row,
+            row_end: row + 1,
+        }
+    }
+
+    /// Construct for a span of cells
+    ///
+    /// Ends are one-past-the-last index: `span(0, 2, 1, 2)` covers columns 0
+    /// and 1 of row 1. Panics unless `col_end > col` and `row_end > row`.
+    #[inline]
+    pub fn span(col: u32, col_end: u32, row: u32, row_end: u32) -> Self {
+        assert!(col_end > col, "GridChildInfo::span: expected col_end > col");
+        assert!(row_end > row, "GridChildInfo::span: expected row_end > row");
+        GridChildInfo {
+            col,
+            col_end,
+            row,
+            row_end,
+        }
+    }
+
+    /// Number of columns covered
+    #[inline]
+    pub fn col_span(&self) -> u32 {
+        self.col_end - self.col
+    }
+
+    /// Number of rows covered
+    #[inline]
+    pub fn row_span(&self) -> u32 {
+        self.row_end - self.row
+    }
+
+    /// True if any cell is covered by both `self` and `other`
+    #[inline]
+    pub fn overlaps(&self, other: &GridChildInfo) -> bool {
+        self.col < other.col_end
+            && other.col < self.col_end
+            && self.row < other.row_end
+            && other.row < self.row_end
+    }
+}
+
 /// A [`RulesSolver`] for grids supporting cell-spans
 ///
 /// This implementation relies on the caller to provide storage for solver data.
@@ -43,10 +94,26 @@ impl<CSR: Default, RSR: Default, S: GridStorage> GridSolver<CSR, RSR, S> {
     /// - `axis`: `AxisInfo` instance passed into `size_rules`
     /// - `(cols, rows)`: number of columns and rows
     /// - `storage`: reference to persistent storage
-    pub fn new(axis: AxisInfo, (cols, rows): (usize, usize), storage: &mut S) -> Self {
-        let col_spans = CSR::default();
-        let row_spans = RSR::default();
+    pub fn new(axis: AxisInfo, dim: (usize, usize), storage: &mut S) -> Self {
+        let spans = (CSR::default(), RSR::default());
+        Self::new_with_spans(axis, dim, spans, storage)
+    }
+}
 
+impl<CSR, RSR, S: GridStorage> GridSolver<CSR, RSR, S> {
+    /// Construct, with span storage
+    ///
+    /// This is like [`GridSolver::new`] except that storage for column and row
+    /// spans is passed in, which is useful where the number of spans is only
+    /// known at run-time. Each must have length equal to the number of
+    /// column / row spans respectively, with each entry initialised to
+    /// `Default::default()`.
+    pub fn new_with_spans(
+        axis: AxisInfo,
+        (cols, rows): (usize, usize),
+        (col_spans, row_spans): (CSR, RSR),
+        storage: &mut S,
+    ) -> Self {
         storage.set_dims(cols, rows);
 
         let mut solver = GridSolver {
@@ -224,6 +291,13 @@ where
 }
 
 /// A [`RulesSetter`] for grids supporting cell-spans
+///
+/// After children are positioned, the rect of any cell may be queried via
+/// [`GridStorage::cell_rect`].
+///
+/// As with [`super::RowSetter`], adjacent margins are merged: adjacent
+/// columns (rows) are separated by the larger of their facing margins, where
+/// each column's margins are the maximum over its cells.
 pub struct GridSetter<RT: RowTemp, CT: RowTemp, S: GridStorage> {
     w_offsets: RT,
     h_offsets: CT,
@@ -303,6 +377,10 @@ impl<RT: RowTemp, CT: RowTemp, S: GridStorage> GridSetter<RT, CT, S> {
             }
         }
 
+        let x = w_offsets.as_mut().first().cloned().unwrap_or(0);
+        let y = h_offsets.as_mut().first().cloned().unwrap_or(0);
+        *storage.origin() = rect.pos + Coord(x as i32, y as i32);
+
         GridSetter {
             w_offsets,
             h_offsets,
@@ -310,6 +388,27 @@ impl<RT: RowTemp, CT: RowTemp, S: GridStorage> GridSetter<RT, CT, S> {
             _s: Default::default(),
         }
     }
+
+    /// Get the margins of the cell (or span) described by `info`
+    ///
+    /// These are the margins of the column and row rules at the cell's edges,
+    /// i.e. the maximum over all cells in that column (row) of the margins
+    /// reported by their [`SizeRules`]. Where all cells use the theme's
+    /// default margins, this is the value of
+    /// [`SizeHandle::outer_margins`](crate::draw::SizeHandle::outer_margins).
+    pub fn cell_margins(&self, storage: &mut S, info: GridChildInfo) -> Margins {
+        let horiz = (
+            storage.width_rules()[info.col as usize].margins().0,
+            storage.width_rules()[info.col_end as usize - 1].margins().1,
+        );
+        let vert = (
+            storage.height_rules()[info.row as usize].margins().0,
+            storage.height_rules()[info.row_end as usize - 1]
+                .margins()
+                .1,
+        );
+        Margins { horiz, vert }
+    }
 }
 
 impl<RT: RowTemp, CT: RowTemp, S: GridStorage> RulesSetter for GridSetter<RT, CT, S> {
@@ -336,3 +435,116 @@ impl<RT: RowTemp, CT: RowTemp, S: GridStorage> RulesSetter for GridSetter<RT, CT
         unimplemented!()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn child_info() {
+        let a = GridChildInfo::single(1, 2);
+        assert_eq!(a, GridChildInfo::span(1, 2, 2, 3));
+        assert_eq!((a.col_span(), a.row_span()), (1, 1));
+
+        let b = GridChildInfo::span(0, 3, 2, 4);
+        assert_eq!((b.col_span(), b.row_span()), (3, 2));
+        assert!(a.overlaps(&b));
+        assert!(!a.overlaps(&GridChildInfo::single(1, 3)));
+    }
+
+    #[test]
+    fn cell_rects() {
+        use crate::layout::{DynGridStorage, StretchPolicy};
+
+        type Solver =
+            GridSolver<[(SizeRules, u32, u32); 0], [(SizeRules, u32, u32); 0], DynGridStorage>;
+        let mut storage = DynGridStorage::default();
+        for vertical in [false, true].iter().cloned() {
+            let axis = AxisInfo::new(vertical, None);
+            let mut solver = Solver::new(axis, (3, 3), &mut storage);
+            for col in 0..3 {
+                for row in 0..3 {
+                    let info = GridChildInfo::single(col, row);
+                    solver.for_child(&mut storage, info, |_| {
+                        SizeRules::new(10, 20, (2, 2), StretchPolicy::LowUtility)
+                    });
+                }
+            }
+            // Three cells of ideal size 20 plus two internal margins
+            assert_eq!(solver.finish(&mut storage).ideal_size(), 64);
+        }
+
+        let rect = Rect::new(Coord(5, 7), Size(94, 64));
+        let mut setter =
+            GridSetter::<Vec<u32>, Vec<u32>, _>::new(rect, (3, 3), AlignHints::NONE, &mut storage);
+        let mid = GridChildInfo::single(1, 1);
+        assert_eq!(
+            setter.child_rect(&mut storage, mid),
+            storage.cell_rect(1, 1)
+        );
+
+        // Cells tile the grid area, separated only by margins
+        assert_eq!(storage.cell_rect(0, 0).pos, rect.pos);
+        assert_eq!(storage.cell_rect(2, 2).pos_end(), rect.pos_end());
+        for i in 0..3 {
+            for j in 0..2 {
+                let (a, b) = (storage.cell_rect(j, i), storage.cell_rect(j + 1, i));
+                assert_eq!((a.pos.1, a.size.1), (b.pos.1, b.size.1));
+                assert_eq!(b.pos.0, a.pos_end().0 + 2);
+                let (a, b) = (storage.cell_rect(i, j), storage.cell_rect(i, j + 1));
+                assert_eq!((a.pos.0, a.size.0), (b.pos.0, b.size.0));
+                assert_eq!(b.pos.1, a.pos_end().1 + 2);
+            }
+        }
+        let widths: u32 = (0..3).map(|col| storage.cell_rect(col, 0).size.0).sum();
+        assert_eq!(widths + 2 * 2, rect.size.0);
+
+        // A span covers the union of its cells
+        let span = storage.span_rect(GridChildInfo::span(0, 2, 1, 3));
+        assert_eq!(span.pos, storage.cell_rect(0, 1).pos);
+        assert_eq!(span.pos_end(), storage.cell_rect(1, 2).pos_end());
+    }
+
+    #[test]
+    fn cell_margins() {
+        use crate::draw::SizeHandle;
+        use crate::layout::DynGridStorage;
+        use crate::test_util::TestSizeHandle;
+
+        type Solver =
+            GridSolver<[(SizeRules, u32, u32); 0], [(SizeRules, u32, u32); 0], DynGridStorage>;
+        let size_handle = TestSizeHandle::new();
+        let margins = size_handle.outer_margins();
+        let mut storage = DynGridStorage::default();
+        for vertical in [false, true].iter().cloned() {
+            let axis = AxisInfo::new(vertical, None);
+            let mut solver = Solver::new(axis, (2, 2), &mut storage);
+            for col in 0..2 {
+                for row in 0..2 {
+                    let info = GridChildInfo::single(col, row);
+                    solver.for_child(&mut storage, info, |axis| {
+                        SizeRules::extract_fixed(axis.is_vertical(), Size(10, 10), margins)
+                    });
+                }
+            }
+            solver.finish(&mut storage);
+        }
+
+        let rect = Rect::new(Coord::ZERO, Size(40, 40));
+        let setter =
+            GridSetter::<Vec<u32>, Vec<u32>, _>::new(rect, (2, 2), AlignHints::NONE, &mut storage);
+        for info in &[
+            GridChildInfo::single(0, 0),
+            GridChildInfo::single(1, 0),
+            GridChildInfo::span(0, 2, 0, 2),
+        ] {
+            assert_eq!(setter.cell_margins(&mut storage, *info), margins);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn child_info_empty_span() {
+        GridChildInfo::span(1, 1, 0, 1);
+    }
+}
@@ -6,6 +6,9 @@
 //! Layout solver
 //!
 //! Size units are physical (real) pixels. This applies to most of KAS.
+//! Application code may specify some sizes in logical (DPI-independent) pixels
+//! using [`LogicalSize`](crate::geom::LogicalSize); these are converted to
+//! physical pixels by the toolkit.
 //!
 //! ## Data types
 //!
@@ -29,10 +32,13 @@
 //! -   [`GridSolver`] and [`GridSetter`] set out children assigned to grid
 //!     cells with optional cell-spans. This is the most powerful and flexible
 //!     layout engine.
+//! -   [`FlowSolver`] and [`FlowSetter`] set out children left-to-right,
+//!     wrapping onto new lines as required by the available width.
 //!
 //! [`RowPositionSolver`] may be used with widgets set out by [`RowSetter`]
 //! to quickly locate children from a `coord` or `rect`.
 
+mod flow_solver;
 mod grid_solver;
 mod row_solver;
 mod single_solver;
@@ -42,14 +48,15 @@ mod storage;
 
 use crate::geom::Size;
 
+pub use flow_solver::{FlowSetter, FlowSolver};
 pub use grid_solver::{GridChildInfo, GridSetter, GridSolver};
 pub use row_solver::{RowPositionSolver, RowSetter, RowSolver};
 pub use single_solver::{SingleSetter, SingleSolver};
 pub use size_rules::{Margins, SizeRules, StretchPolicy};
 pub use sizer::{RulesSetter, RulesSolver, SolveCache};
 pub use storage::{
-    DynGridStorage, DynRowStorage, FixedGridStorage, FixedRowStorage, GridStorage, RowStorage,
-    RowTemp, Storage,
+    DynGridStorage, DynRowStorage, FixedGridStorage, FixedRowStorage, FlowStorage, GridStorage,
+    RowStorage, RowTemp, Storage,
 };
 
 /// Information on which axis is being resized
@@ -75,6 +82,46 @@ impl AxisInfo {
         }
     }
 
+    /// Construct for the horizontal axis, with no fixed height
+    ///
+    /// Size requirements are usually measured in two passes: first the
+    /// horizontal axis, then the vertical axis given a fixed width (which is
+    /// necessary to calculate the height of wrapped text):
+    /// ```
+    /// # use kas::layout::AxisInfo;
+    /// let axis = AxisInfo::horizontal();
+    /// assert_eq!(axis.other(), None);
+    /// // let w = widget.size_rules(size_handle, axis);
+    /// # let width = 200;
+    ///
+    /// let axis = AxisInfo::vertical().with_fixed(width);
+    /// assert_eq!(axis.size_other_if_fixed(true), Some(width));
+    /// // let h = widget.size_rules(size_handle, axis);
+    /// ```
+    #[inline]
+    pub fn horizontal() -> Self {
+        AxisInfo::new(false, None)
+    }
+
+    /// Construct for the vertical axis, with no fixed width
+    ///
+    /// See also [`AxisInfo::horizontal`] and [`AxisInfo::with_fixed`].
+    #[inline]
+    pub fn vertical() -> Self {
+        AxisInfo::new(true, None)
+    }
+
+    /// Set the size of the other axis
+    ///
+    /// For a vertical axis, `size` is the (fixed) width; for a horizontal
+    /// axis, it is the height.
+    #[inline]
+    pub fn with_fixed(mut self, size: u32) -> Self {
+        self.has_fixed = true;
+        self.other_axis = size;
+        self
+    }
+
     /// True if the current axis is vertical
     #[inline]
     pub fn is_vertical(&self) -> bool {
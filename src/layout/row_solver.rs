@@ -106,6 +106,12 @@ impl<S: RowStorage> RulesSolver for RowSolver<S> {
 /// -   `D:` [`Directional`] — whether this represents a row or a column
 /// -   `T:` [`RowTemp`] — temporary storage type
 /// -   `S:` [`RowStorage`] — persistent storage type
+///
+/// Adjacent margins are merged: the gap between two children is the larger
+/// of the first child's post-margin and the second child's pre-margin, not
+/// their sum (compare CSS margin collapsing). For example, children with
+/// margins of 6px and 2px are separated by 6px, not 8px. This matches the
+/// rules calculated by [`RowSolver`] (see [`SizeRules::append`]).
 pub struct RowSetter<D, T: RowTemp, S: RowStorage> {
     rect: Rect,
     offsets: T,
@@ -123,11 +129,37 @@ impl<D: Directional, T: RowTemp, S: RowStorage> RowSetter<D, T, S> {
     /// -   `align`: alignment hints
     /// -   `storage`: access to the solver's storage
     pub fn new(
+        rect: Rect,
+        (direction, len): (D, usize),
+        align: AlignHints,
+        storage: &mut S,
+    ) -> Self {
+        Self::new_weighted(rect, (direction, len), align, storage, &[])
+    }
+
+    /// Construct, with stretch weights
+    ///
+    /// This is the same as [`RowSetter::new`], except that space in excess of
+    /// children's ideal sizes is divided in proportion to `weights` (one per
+    /// child, in index order) instead of evenly. For example, children with
+    /// weights `[1, 3]` receive one and three quarters of the excess space.
+    ///
+    /// Weights only apply among children with the highest [`StretchPolicy`]
+    /// in the row: as usual, other children are not stretched beyond their
+    /// ideal size. Space below ideal sizes is distributed without weights.
+    /// See [`SizeRules::solve_seq_weighted`].
+    ///
+    /// `weights` must be empty (no weighting) or have length `len`.
+    ///
+    /// [`StretchPolicy`]: super::StretchPolicy
+    pub fn new_weighted(
         mut rect: Rect,
         (direction, len): (D, usize),
         align: AlignHints,
         storage: &mut S,
+        weights: &[u32],
     ) -> Self {
+        assert!(weights.is_empty() || weights.len() == len);
         let mut offsets = T::default();
         offsets.set_len(len);
         storage.set_dim(len);
@@ -153,7 +185,7 @@ impl<D: Directional, T: RowTemp, S: RowStorage> RowSetter<D, T, S> {
                     rect.pos.1 += offset;
                 }
             }
-            SizeRules::solve_seq_total(widths, rules, width);
+            SizeRules::solve_seq_total_weighted(widths, rules, width, weights);
         }
 
         let _s = Default::default();
@@ -202,21 +234,31 @@ impl<D: Directional, T: RowTemp, S: RowStorage> RowSetter<D, T, S> {
             self.rect.pos.1
         };
 
+        // Margin preceding each item, skipping collapsed items
+        let (rules, widths) = storage.rules_and_widths();
+        let mut prev: Option<u16> = None;
+        let mut margin = |r: SizeRules| -> u32 {
+            if r.is_collapsed() {
+                return 0;
+            }
+            let m = prev.map(|m| m.max(r.margins().0) as u32);
+            prev = Some(r.margins().1);
+            m.unwrap_or(0)
+        };
+
         if self.direction.is_reversed() {
+            margin(rules[len - 1]);
             offsets[len - 1] = pos as u32;
             for i in (0..(len - 1)).rev() {
                 let i1 = i + 1;
-                let m1 = storage.rules()[i1].margins().1;
-                let m0 = storage.rules()[i].margins().0;
-                offsets[i] = offsets[i1] + storage.widths()[i1] + m1.max(m0) as u32;
+                offsets[i] = offsets[i1] + widths[i1] + margin(rules[i]);
             }
         } else {
+            margin(rules[0]);
             offsets[0] = pos as u32;
             for i in 1..len {
                 let i1 = i - 1;
-                let m1 = storage.rules()[i1].margins().1;
-                let m0 = storage.rules()[i].margins().0;
-                offsets[i] = offsets[i1] + storage.widths()[i1] + m1.max(m0) as u32;
+                offsets[i] = offsets[i1] + widths[i1] + margin(rules[i]);
             }
         }
     }
@@ -227,6 +269,19 @@ impl<D: Directional, T: RowTemp, S: RowStorage> RowSetter<D, T, S> {
         let (rules, widths) = storage.rules_and_widths();
         SizeRules::solve_seq(&mut widths[range.clone()], &rules[range], width);
     }
+
+    /// Get the margins of child `index` along the row's axis
+    ///
+    /// These are the margins of the [`SizeRules`] reported by the child, thus
+    /// the values returned by the [`SizeHandle`](crate::draw::SizeHandle)
+    /// unless the widget adjusted them. The row only stores rules along its
+    /// own axis; margins on the other axis are not available here.
+    ///
+    /// Note that the space between adjacent children is the larger of their
+    /// facing margins, and that a collapsed child has no margins in effect.
+    pub fn child_margins(&self, storage: &mut S, index: usize) -> (u16, u16) {
+        storage.rules()[index].margins()
+    }
 }
 
 impl<D: Directional, T: RowTemp, S: RowStorage> RulesSetter for RowSetter<D, T, S> {
@@ -246,10 +301,20 @@ impl<D: Directional, T: RowTemp, S: RowStorage> RulesSetter for RowSetter<D, T,
     }
 
     fn maximal_rect_of(&mut self, storage: &mut Self::Storage, index: Self::ChildInfo) -> Rect {
-        let pre_rules = SizeRules::min_sum(&storage.rules()[0..index]);
-        let m = storage.rules()[index].margins();
         let len = storage.widths().len();
-        let post_rules = SizeRules::min_sum(&storage.rules()[(index + 1)..len]);
+        let rules = storage.rules();
+        let m = rules[index].margins();
+        // pre_rules / post_rules are the items positioned before / after this
+        let (pre_rules, post_rules) = match self.direction.is_reversed() {
+            false => (
+                SizeRules::min_sum(&rules[0..index]),
+                SizeRules::min_sum(&rules[(index + 1)..len]),
+            ),
+            true => (
+                min_sum_rev(&rules[(index + 1)..len]),
+                min_sum_rev(&rules[0..index]),
+            ),
+        };
 
         let size1 = pre_rules.min_size() as i32 + pre_rules.margins().1.max(m.0) as i32;
         let size2 = size1 as u32 + post_rules.min_size() + post_rules.margins().0.max(m.1) as u32;
@@ -266,6 +331,15 @@ impl<D: Directional, T: RowTemp, S: RowStorage> RulesSetter for RowSetter<D, T,
     }
 }
 
+// Like SizeRules::min_sum, but for items in reverse order
+fn min_sum_rev(range: &[SizeRules]) -> SizeRules {
+    let mut iter = range.iter().rev();
+    match iter.next() {
+        None => SizeRules::EMPTY,
+        Some(first) => iter.fold(*first, |rules, r| rules.appended(*r)),
+    }
+}
+
 /// Allows efficient implementations of `draw` / event handlers based on the
 /// layout representation.
 ///
@@ -316,37 +390,227 @@ impl<D: Directional> RowPositionSolver<D> {
         Some(&widgets[index])
     }
 
+    /// Find the range of children intersecting the given `clip` rect
+    ///
+    /// Children are assumed to be positioned in order along the row (as by
+    /// [`RowSetter`]), thus the result is found by binary search. Children
+    /// partially within `clip` are included. Only the row's own axis is
+    /// considered. The result is empty when `clip` is empty or lies entirely
+    /// before or after all children.
+    ///
+    /// This may be used to skip drawing children outside of a scroll region.
+    pub fn solve_range<W: Widget>(self, widgets: &[W], clip: Rect) -> Range<usize> {
+        if clip.size.0 == 0 || clip.size.1 == 0 {
+            return 0..0;
+        }
+        let is_vertical = self.direction.is_vertical();
+        let axis = |coord: Coord| match is_vertical {
+            false => coord.0,
+            true => coord.1,
+        };
+        let (clip_start, clip_end) = (axis(clip.pos), axis(clip.pos_end()));
+        let start = |w: &W| axis(w.rect().pos);
+        let end = |w: &W| axis(w.rect().pos_end());
+
+        let range = if !self.direction.is_reversed() {
+            let a = partition_point(widgets, |w| end(w) <= clip_start);
+            let b = partition_point(widgets, |w| start(w) < clip_end);
+            a..b
+        } else {
+            let a = partition_point(widgets, |w| start(w) >= clip_end);
+            let b = partition_point(widgets, |w| end(w) > clip_start);
+            a..b
+        };
+        range.start..range.end.max(range.start)
+    }
+
     /// Call `f` on each child intersecting the given `rect`
-    pub fn for_children<W: Widget, F: FnMut(&W)>(self, widgets: &[W], rect: Rect, mut f: F) {
-        let (pos, end) = match self.direction.is_reversed() {
-            false => (rect.pos, rect.pos + rect.size),
-            true => (rect.pos + rect.size, rect.pos),
+    ///
+    /// See [`RowPositionSolver::solve_range`].
+    pub fn for_children<W: Widget, F: FnMut(&W)>(self, widgets: &[W], rect: Rect, f: F) {
+        widgets[self.solve_range(widgets, rect)].iter().for_each(f);
+    }
+}
+
+// The number of leading elements of `slice` satisfying `pred`, which must be
+// true for some prefix of `slice` and false for the remainder
+fn partition_point<T, P: Fn(&T) -> bool>(slice: &[T], pred: P) -> usize {
+    let (mut lo, mut hi) = (0, slice.len());
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if pred(&slice[mid]) {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    lo
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::geom::Size;
+    use crate::layout::{DynRowStorage, StretchPolicy};
+    use crate::widget::Filler;
+    use crate::{Layout, WidgetCore};
+
+    fn set_rects(dir: Direction, rect: Rect) -> (Vec<Filler>, Vec<Rect>) {
+        let rules = [
+            SizeRules::new(10, 10, (2, 2), StretchPolicy::Fixed),
+            SizeRules::new(20, 20, (2, 2), StretchPolicy::Fixed),
+            SizeRules::new(30, 30, (2, 2), StretchPolicy::Fixed),
+        ];
+        let mut storage = DynRowStorage::default();
+        let mut solver = RowSolver::new(AxisInfo::new(false, None), (dir, 3), &mut storage);
+        for (i, r) in rules.iter().enumerate() {
+            solver.for_child(&mut storage, i, |_| *r);
+        }
+        assert_eq!(solver.finish(&mut storage).min_size(), 64);
+
+        let mut setter =
+            RowSetter::<_, Vec<u32>, _>::new(rect, (dir, 3), AlignHints::NONE, &mut storage);
+        let mut widgets = vec![Filler::new(), Filler::new(), Filler::new()];
+        let mut maximal = vec![];
+        for (i, w) in widgets.iter_mut().enumerate() {
+            w.set_rect(setter.child_rect(&mut storage, i), AlignHints::NONE);
+            maximal.push(setter.maximal_rect_of(&mut storage, i));
+        }
+        (widgets, maximal)
+    }
+
+    #[test]
+    fn reversed_row() {
+        let rect = Rect::new(Coord(100, 0), Size(64, 10));
+        let (fwd, fwd_max) = set_rects(Direction::Right, rect);
+        let (rev, rev_max) = set_rects(Direction::Left, rect);
+        let xs = |widgets: &[Filler]| -> Vec<(i32, u32)> {
+            widgets
+                .iter()
+                .map(|w| (w.rect().pos.0, w.rect().size.0))
+                .collect()
         };
-        let start = match self.binary_search(widgets, pos) {
-            Ok(i) => i,
-            Err(i) if i > 0 => {
-                let j = i - 1;
-                if widgets[j].rect().contains(pos) {
-                    j
-                } else {
-                    i
-                }
-            }
-            Err(_) => 0,
+        assert_eq!(xs(&fwd), vec![(100, 10), (112, 20), (134, 30)]);
+        assert_eq!(xs(&rev), vec![(154, 10), (132, 20), (100, 30)]);
+
+        // Reversed positions mirror forward positions
+        let mirror = |f: Rect, r: Rect| {
+            assert_eq!(r.pos.0 - rect.pos.0, rect.pos_end().0 - f.pos_end().0);
+            assert_eq!(r.size, f.size);
         };
+        for i in 0..3 {
+            mirror(fwd[i].rect(), rev[i].rect());
+            mirror(fwd_max[i], rev_max[i]);
+        }
 
-        for i in start..widgets.len() {
-            let child = &widgets[i];
-            let do_break = match self.direction.as_direction() {
-                Direction::Right => child.rect().pos.0 >= end.0,
-                Direction::Down => child.rect().pos.1 >= end.1,
-                Direction::Left => child.rect().pos_end().0 < end.0,
-                Direction::Up => child.rect().pos_end().1 < end.1,
-            };
-            if do_break {
-                break;
-            }
-            f(child);
+        let solver = RowPositionSolver::new(Direction::Left);
+        for (x, index) in &[(100, Some(2)), (131, None), (140, Some(1)), (163, Some(0))] {
+            let child = solver.find_child(&rev, Coord(*x, 5)).map(|w| w.rect());
+            assert_eq!(child, index.map(|i| rev[i].rect()));
         }
     }
+
+    #[test]
+    fn solve_range() {
+        let rect = Rect::new(Coord(100, 0), Size(64, 10));
+        let clip = |x, w| Rect::new(Coord(x, 0), Size(w, 10));
+
+        // Children span x = 100..110, 112..132 and 134..164
+        let (fwd, _) = set_rects(Direction::Right, rect);
+        let solver = RowPositionSolver::new(Direction::Right);
+        assert_eq!(solver.solve_range(&fwd, rect), 0..3);
+        assert_eq!(solver.solve_range(&fwd, clip(105, 10)), 0..2);
+        assert_eq!(solver.solve_range(&fwd, clip(131, 4)), 1..3);
+        // Within the margin between children
+        assert!(solver.solve_range(&fwd, clip(110, 2)).is_empty());
+        assert!(solver.solve_range(&fwd, clip(120, 0)).is_empty());
+        assert!(solver.solve_range(&fwd, clip(0, 100)).is_empty());
+        assert!(solver.solve_range(&fwd, clip(164, 50)).is_empty());
+
+        // Children span x = 154..164, 132..152 and 100..130
+        let (rev, _) = set_rects(Direction::Left, rect);
+        let solver = RowPositionSolver::new(Direction::Left);
+        assert_eq!(solver.solve_range(&rev, rect), 0..3);
+        assert_eq!(solver.solve_range(&rev, clip(105, 35)), 1..3);
+        assert_eq!(solver.solve_range(&rev, clip(160, 10)), 0..1);
+        assert!(solver.solve_range(&rev, clip(152, 2)).is_empty());
+        assert!(solver.solve_range(&rev, clip(0, 100)).is_empty());
+        assert!(solver.solve_range(&rev, clip(164, 50)).is_empty());
+    }
+
+    #[test]
+    fn merged_margins() {
+        // Asymmetric margins: gaps are max(6, 4) and max(1, 3), not 10 and 4
+        let rules = [
+            SizeRules::new(10, 10, (2, 6), StretchPolicy::Fixed),
+            SizeRules::new(20, 20, (4, 1), StretchPolicy::Fixed),
+            SizeRules::new(30, 30, (3, 7), StretchPolicy::Fixed),
+        ];
+        let dir = Direction::Right;
+        let mut storage = DynRowStorage::default();
+        let mut solver = RowSolver::new(AxisInfo::new(false, None), (dir, 3), &mut storage);
+        for (i, r) in rules.iter().enumerate() {
+            solver.for_child(&mut storage, i, |_| *r);
+        }
+        let total = solver.finish(&mut storage);
+        let summed = 60 + (6 + 4) + (1 + 3);
+        assert_eq!(total.min_size(), 60 + 6 + 3);
+        assert!(total.min_size() < summed);
+        // Outer margins are those of the first and last children
+        assert_eq!(total.margins(), (2, 7));
+
+        let rect = Rect::new(Coord::ZERO, Size(69, 10));
+        let mut setter =
+            RowSetter::<_, Vec<u32>, _>::new(rect, (dir, 3), AlignHints::NONE, &mut storage);
+        let xs: Vec<i32> = (0..3)
+            .map(|i| setter.child_rect(&mut storage, i).pos.0)
+            .collect();
+        assert_eq!(xs, vec![0, 16, 39]);
+
+        // The margins reported by each child are retained
+        for (i, r) in rules.iter().enumerate() {
+            assert_eq!(setter.child_margins(&mut storage, i), r.margins());
+        }
+    }
+
+    #[test]
+    fn stretch_weights() {
+        // A spacer, a search box and a button
+        let rules = [
+            SizeRules::new(0, 0, (0, 0), StretchPolicy::HighUtility),
+            SizeRules::new(20, 50, (0, 0), StretchPolicy::HighUtility),
+            SizeRules::new(20, 20, (0, 0), StretchPolicy::Fixed),
+        ];
+        let dir = Direction::Right;
+        let mut storage = DynRowStorage::default();
+        let mut solver = RowSolver::new(AxisInfo::new(false, None), (dir, 3), &mut storage);
+        for (i, r) in rules.iter().enumerate() {
+            solver.for_child(&mut storage, i, |_| *r);
+        }
+        solver.finish(&mut storage);
+        let mut storage2 = storage.clone();
+
+        let rect = Rect::new(Coord::ZERO, Size(150, 10));
+        let weights = [1, 3, 1];
+        let mut setter = RowSetter::<_, Vec<u32>, _>::new_weighted(
+            rect,
+            (dir, 3),
+            AlignHints::NONE,
+            &mut storage,
+            &weights,
+        );
+        let widths: Vec<u32> = (0..3)
+            .map(|i| setter.child_rect(&mut storage, i).size.0)
+            .collect();
+        // 80 pixels of excess space are split 1:3; the button does not stretch
+        assert_eq!(widths, vec![20, 110, 20]);
+
+        // Without weights, excess space is split evenly
+        let mut setter =
+            RowSetter::<_, Vec<u32>, _>::new(rect, (dir, 3), AlignHints::NONE, &mut storage2);
+        let widths: Vec<u32> = (0..3)
+            .map(|i| setter.child_rect(&mut storage2, i).size.0)
+            .collect();
+        assert_eq!(widths, vec![40, 90, 20]);
+    }
 }
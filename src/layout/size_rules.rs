@@ -19,7 +19,7 @@ use kas::draw::SizeHandle;
 ///
 /// Used by the layout system for margins around child widgets. Margins may be
 /// drawn in and handle events like any other widget area.
-#[derive(Copy, Clone, Debug, Default)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
 pub struct Margins {
     /// Size of horizontal margins
     pub horiz: (u16, u16),
@@ -115,9 +115,10 @@ impl Default for StretchPolicy {
 /// "pre" (left/top) and "post" (right/bottom). These are stored as `u16` values
 /// on the assumption that no margin need exceed 65536.
 ///
-/// When widgets are placed next to each other, their margins may be combined;
+/// When widgets are placed next to each other, their margins are combined;
 /// e.g. if a widget with margin of 6px is followed by another with margin 2px,
-/// the required margin between the two is the maximum, 6px.
+/// the required margin between the two is the maximum, 6px (not the sum, 8px).
+/// This applies to all layout solvers and setters.
 ///
 /// Only the layout engine and parent widgets need consider margins (beyond
 /// their specification). For these cases, one needs to be aware that due to
@@ -140,6 +141,14 @@ impl Default for StretchPolicy {
 /// up to the widget to align itself within this space: see
 /// [`kas::Layout::set_rect`] and [`kas::AlignHints`].
 ///
+/// ### Collapsed rules
+///
+/// [`SizeRules::COLLAPSED`] describes a widget which is not part of the
+/// layout at all (e.g. a hidden widget; see [`kas::widget::Hidden`]). Unlike
+/// [`SizeRules::EMPTY`], collapsed rules are skipped when combining rules,
+/// thus the margins of their neighbours merge as if the collapsed widget were
+/// not present, and collapsed widgets are never allocated any space.
+///
 /// [`Rect`]: kas::geom::Rect
 #[derive(Copy, Clone, Default, PartialEq, Eq)]
 pub struct SizeRules {
@@ -150,10 +159,15 @@ pub struct SizeRules {
     // (pre, post) margins
     m: (u16, u16),
     stretch: StretchPolicy,
+    // excluded from layout
+    collapsed: bool,
 }
 
 impl fmt::Debug for SizeRules {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.collapsed {
+            return write!(f, "SizeRules::COLLAPSED");
+        }
         write!(
             f,
             "SizeRules {{ a: {}, b: {}, m: ({}, {}), stretch: {:?} }}",
@@ -171,6 +185,20 @@ impl SizeRules {
     /// non-empty `SizeRules`.
     pub const EMPTY: Self = SizeRules::empty(StretchPolicy::Fixed);
 
+    /// Collapsed (excluded from layout)
+    ///
+    /// Like [`SizeRules::EMPTY`], this has zero size and margins. Unlike
+    /// `EMPTY`, combining these rules with others (e.g. via
+    /// [`SizeRules::appended`] or [`SizeRules::max`]) has no effect, and no
+    /// margins are included for the collapsed element.
+    pub const COLLAPSED: Self = SizeRules {
+        a: 0,
+        b: 0,
+        m: (0, 0),
+        stretch: StretchPolicy::Fixed,
+        collapsed: true,
+    };
+
     /// Empty space with the given stretch policy
     ///
     /// See warning on [`SizeRules::EMPTY`].
@@ -181,6 +209,7 @@ impl SizeRules {
             b: 0,
             m: (0, 0),
             stretch,
+            collapsed: false,
         }
     }
 
@@ -192,6 +221,7 @@ impl SizeRules {
             b: size,
             m: margins,
             stretch: StretchPolicy::Fixed,
+            collapsed: false,
         }
     }
 
@@ -204,6 +234,7 @@ impl SizeRules {
                 b: size.0,
                 m: margin.horiz,
                 stretch: StretchPolicy::Fixed,
+                collapsed: false,
             }
         } else {
             SizeRules {
@@ -211,6 +242,7 @@ impl SizeRules {
                 b: size.1,
                 m: margin.vert,
                 stretch: StretchPolicy::Fixed,
+                collapsed: false,
             }
         }
     }
@@ -228,6 +260,7 @@ impl SizeRules {
             b: ideal.max(min),
             m: margins,
             stretch,
+            collapsed: false,
         }
     }
 
@@ -243,6 +276,22 @@ impl SizeRules {
         self.b
     }
 
+    /// Get the minimum size
+    ///
+    /// This is equivalent to [`SizeRules::min_size`].
+    #[inline]
+    pub fn min(self) -> u32 {
+        self.a
+    }
+
+    /// Get the ideal size
+    ///
+    /// This is equivalent to [`SizeRules::ideal_size`].
+    #[inline]
+    pub fn ideal(self) -> u32 {
+        self.b
+    }
+
     /// Get the `(pre, post)` margin sizes
     #[inline]
     pub fn margins(self) -> (u16, u16) {
@@ -255,6 +304,38 @@ impl SizeRules {
         self.stretch
     }
 
+    /// True if the size is zero
+    ///
+    /// This is true when both the minimum and ideal sizes are zero; margins
+    /// and stretch policy are not considered. This is true of
+    /// [`SizeRules::EMPTY`] and [`SizeRules::COLLAPSED`].
+    #[inline]
+    pub fn is_empty(self) -> bool {
+        self.b == 0
+    }
+
+    /// True if these are [`SizeRules::COLLAPSED`]
+    #[inline]
+    pub fn is_collapsed(self) -> bool {
+        self.collapsed
+    }
+
+    /// True if the size is fixed
+    ///
+    /// This is true when the minimum and ideal sizes are equal and the
+    /// stretch policy is [`StretchPolicy::Fixed`]: such an element is never
+    /// resized (except when a parent cannot meet the minimum size).
+    #[inline]
+    pub fn is_fixed(self) -> bool {
+        self.a == self.b && self.stretch == StretchPolicy::Fixed
+    }
+
+    /// True if these rules are [collapsed](SizeRules::COLLAPSED)
+    #[inline]
+    pub fn is_collapsed(self) -> bool {
+        self.collapsed
+    }
+
     /// Set margins to max of own margins and given margins
     pub fn include_margins(&mut self, margins: (u16, u16)) {
         self.m.0 = self.m.0.max(margins.0);
@@ -262,13 +343,25 @@ impl SizeRules {
     }
 
     /// Use the maximum size of `self` and `rhs`.
+    ///
+    /// This is used to combine the rules of widgets sharing space along this
+    /// axis, e.g. the heights of widgets within a row. Each margin is also
+    /// the maximum of the two.
+    ///
+    /// If either is collapsed, the other is returned.
     #[inline]
     pub fn max(self, rhs: Self) -> SizeRules {
+        if self.collapsed {
+            return rhs;
+        } else if rhs.collapsed {
+            return self;
+        }
         SizeRules {
             a: self.a.max(rhs.a),
             b: self.b.max(rhs.b),
             m: (self.m.0.max(rhs.m.0), self.m.1.max(rhs.m.1)),
             stretch: self.stretch.max(rhs.stretch),
+            collapsed: false,
         }
     }
 
@@ -281,11 +374,22 @@ impl SizeRules {
     /// Append the rules for `rhs` to self
     ///
     /// This implies that `rhs` rules concern an element to the right of or
-    /// below self. Note that order matters since margins may be combined.
+    /// below self. Note that order matters since margins may be combined:
+    /// adjacent margins are merged, thus the margin between the two elements
+    /// is the maximum of `self`'s post-margin and `rhs`'s pre-margin (not
+    /// their sum). The result has the pre-margin of `self` and the post-margin
+    /// of `rhs`.
     ///
     /// Note also that appending [`SizeRules::EMPTY`] does include interior
     /// margins (those between `EMPTY` and the other rules) within the result.
+    /// Appending [`SizeRules::COLLAPSED`] has no effect.
     pub fn append(&mut self, rhs: SizeRules) {
+        if rhs.collapsed {
+            return;
+        } else if self.collapsed {
+            *self = rhs;
+            return;
+        }
         let c = self.m.1.max(rhs.m.0) as u32;
         self.a += rhs.a + c;
         self.b += rhs.b + c;
@@ -295,28 +399,46 @@ impl SizeRules {
 
     /// Return the rules for self appended by `rhs`
     ///
-    ///
     /// This implies that `rhs` rules concern an element to the right of or
-    /// below self. Note that order matters since margins may be combined.
+    /// below self. Note that order matters since margins may be combined
+    /// (see [`SizeRules::append`]).
     ///
     /// Note also that appending [`SizeRules::EMPTY`] does include interior
     /// margins (those between `EMPTY` and the other rules) within the result.
+    /// Appending [`SizeRules::COLLAPSED`] has no effect.
     #[inline]
     pub fn appended(self, rhs: SizeRules) -> Self {
+        if rhs.collapsed {
+            return self;
+        } else if self.collapsed {
+            return rhs;
+        }
         let c = self.m.1.max(rhs.m.0) as u32;
         SizeRules {
             a: self.a + rhs.a + c,
             b: self.b + rhs.b + c,
             m: (self.m.0, rhs.m.1),
             stretch: self.stretch.max(rhs.stretch),
+            collapsed: false,
         }
     }
 
+    /// Prepend the rules for `lhs` to self
+    ///
+    /// This implies that `lhs` rules concern an element to the left of or
+    /// above self; it is equivalent to `*self = lhs.appended(*self)`. This is
+    /// useful when building a sequence in reverse order. Margins are merged
+    /// as in [`SizeRules::append`].
+    #[inline]
+    pub fn prepend(&mut self, lhs: SizeRules) {
+        *self = lhs.appended(*self);
+    }
+
     /// Return the rules for self surrounded by `frame`
     ///
     /// If `internal_margins` are true, then space is allocated for `self`'s
     /// margins inside the frame; if not, then `self`'s margins are merged with
-    /// the frame's margins.
+    /// the frame's margins. Collapsed contents are treated as empty.
     pub fn surrounded_by(self, frame: SizeRules, internal_margins: bool) -> Self {
         let (c, m) = if internal_margins {
             ((self.m.0 + self.m.1) as u32, frame.m)
@@ -328,31 +450,66 @@ impl SizeRules {
             b: self.b + frame.b + c,
             m,
             stretch: self.stretch.max(frame.stretch),
+            collapsed: false,
         }
     }
 
-    /// Return the result of appending all given ranges
-    pub fn sum(range: &[SizeRules]) -> SizeRules {
-        range.iter().sum()
+    /// Return the result of appending all given rules
+    ///
+    /// This calculates the rules of a sequence of widgets along this axis
+    /// (e.g. the widths of widgets within a row), in left-to-right or
+    /// top-to-bottom order. Adjacent margins are merged as in
+    /// [`SizeRules::append`]. The result is [`SizeRules::EMPTY`] if there are
+    /// no rules.
+    pub fn sum<'a, I: IntoIterator<Item = &'a SizeRules>>(rules: I) -> SizeRules {
+        rules.into_iter().sum()
+    }
+
+    /// Return the maximum of all given rules
+    ///
+    /// This calculates the rules of widgets sharing space along this axis
+    /// (e.g. the heights of widgets within a row) using [`SizeRules::max`].
+    /// The result is [`SizeRules::EMPTY`] if there are no rules.
+    pub fn max_of<'a, I: IntoIterator<Item = &'a SizeRules>>(rules: I) -> SizeRules {
+        let mut iter = rules.into_iter();
+        match iter.next() {
+            Some(first) => iter.fold(*first, |x, y| x.max(*y)),
+            None => SizeRules::EMPTY,
+        }
     }
 
     /// Return the result of appending all given ranges (min only)
     ///
     /// This is a specialised version of sum: only the minimum is calculated
     pub fn min_sum(range: &[SizeRules]) -> SizeRules {
-        if range.is_empty() {
-            return SizeRules::EMPTY;
-        }
-
-        let mut rules = range[0];
-        for r in &range[1..] {
+        let mut iter = range.iter().filter(|r| !r.collapsed);
+        let mut rules = match iter.next() {
+            Some(r) => *r,
+            None => return SizeRules::EMPTY,
+        };
+        for r in iter {
             rules.a += rules.m.1.max(r.m.0) as u32 + r.a;
+            rules.m.1 = r.m.1;
         }
         rules.b = rules.a;
-        rules.m.1 = range[range.len() - 1].m.1;
         rules
     }
 
+    /// Return the sum of interior margins between all given ranges
+    ///
+    /// Collapsed rules are skipped, thus do not contribute margins.
+    pub(crate) fn margin_sum(range: &[SizeRules]) -> u32 {
+        let mut sum = 0;
+        let mut prev: Option<u16> = None;
+        for r in range.iter().filter(|r| !r.collapsed) {
+            if let Some(m) = prev {
+                sum += m.max(r.m.0) as u32;
+            }
+            prev = Some(r.m.1);
+        }
+        sum
+    }
+
     /// Set self to `self - x + y`, in this order
     ///
     /// This is a specialised operation to join two spans, subtracing the
@@ -387,7 +544,25 @@ impl SizeRules {
             total,
             "solve_seq_total: invalid input (missing configure or invalid usage?)"
         );
-        Self::solve_seq_(out, rules, total, target);
+        Self::solve_seq_(out, rules, total, target, &[]);
+    }
+
+    /// Solve a sequence of rules, with stretch weights
+    ///
+    /// This is the same as [`SizeRules::solve_seq_total`] except that excess
+    /// space is divided among members according to `weights` (see
+    /// [`SizeRules::solve_seq_weighted`]).
+    #[cfg_attr(not(feature = "internal_doc"), doc(hidden))]
+    pub fn solve_seq_total_weighted(out: &mut [u32], rules: &[Self], target: u32, weights: &[u32]) {
+        let len = rules.len() - 1;
+        let total = rules[len];
+        let rules = &rules[0..len];
+        debug_assert_eq!(
+            SizeRules::sum(rules),
+            total,
+            "solve_seq_total_weighted: invalid input (missing configure or invalid usage?)"
+        );
+        Self::solve_seq_(out, rules, total, target, weights);
     }
 
     /// Solve a sequence of rules
@@ -416,28 +591,58 @@ impl SizeRules {
     #[cfg_attr(not(feature = "internal_doc"), doc(hidden))]
     pub fn solve_seq(out: &mut [u32], rules: &[Self], target: u32) {
         let total = SizeRules::sum(rules);
-        Self::solve_seq_(out, rules, total, target);
+        Self::solve_seq_(out, rules, total, target, &[]);
     }
 
-    fn solve_seq_(out: &mut [u32], rules: &[Self], total: Self, target: u32) {
+    /// Solve a sequence of rules, with stretch weights
+    ///
+    /// This is the same as [`SizeRules::solve_seq`] except that space in
+    /// excess of ideal sizes is divided among members in proportion to
+    /// `weights` instead of evenly. As usual, only members with the highest
+    /// stretch policy receive excess space; weights do not allow members with
+    /// a lower stretch policy to stretch. Space up to the ideal size is not
+    /// affected by weights.
+    ///
+    /// `weights` should be empty (equivalent to [`SizeRules::solve_seq`]) or
+    /// have the same length as `rules`. Weights of zero are treated as one.
+    #[cfg_attr(not(feature = "internal_doc"), doc(hidden))]
+    pub fn solve_seq_weighted(out: &mut [u32], rules: &[Self], target: u32, weights: &[u32]) {
+        let total = SizeRules::sum(rules);
+        Self::solve_seq_(out, rules, total, target, weights);
+    }
+
+    fn solve_seq_(out: &mut [u32], rules: &[Self], total: Self, target: u32, weights: &[u32]) {
         type Targets = SmallVec<[u32; 16]>;
         #[allow(non_snake_case)]
         let N = out.len();
         assert_eq!(rules.len(), N);
         if N == 0 {
             return;
+        } else if total.collapsed {
+            // All members are collapsed
+            for w in out.iter_mut() {
+                *w = 0;
+            }
+            return;
         }
+        debug_assert!(weights.is_empty() || weights.len() == N);
+        let weight = |i: usize| weights.get(i).map(|w| (*w).max(1)).unwrap_or(1);
+        let unweighted = |_: usize| 1;
 
         if target > total.a {
-            // All minimum sizes can be met.
+            // All minimum sizes can be met. Collapsed members get no space.
+            for i in 0..N {
+                if rules[i].collapsed {
+                    out[i] = 0;
+                }
+            }
             out[0] = out[0].max(rules[0].a);
-            let mut margin_sum = 0;
+            let margin_sum = SizeRules::margin_sum(rules);
             let mut sum = out[0];
             let mut dist_under_b = rules[0].b.saturating_sub(out[0]);
             let mut dist_over_b = out[0].saturating_sub(rules[0].b);
             for i in 1..N {
                 out[i] = out[i].max(rules[i].a);
-                margin_sum += (rules[i - 1].m.1).max(rules[i].m.0) as u32;
                 sum += out[i];
                 dist_under_b += rules[i].b.saturating_sub(out[i]);
                 dist_over_b += out[i].saturating_sub(rules[i].b);
@@ -447,21 +652,22 @@ impl SizeRules {
             if sum == target {
                 return;
             } else if sum < target {
-                fn increase_targets<F: Fn(usize) -> u32>(
+                fn increase_targets<F: Fn(usize) -> u32, W: Fn(usize) -> u32>(
                     out: &mut [u32],
                     targets: &mut Targets,
                     base: F,
+                    weight: W,
                     mut avail: u32,
                 ) {
                     // Calculate ceiling above which sizes will not be increased
                     let mut any_removed = true;
                     while any_removed {
                         any_removed = false;
-                        let count = targets.len() as u32;
-                        let ceil = (avail + count - 1) / count; // round up
+                        let total_weight = weight_sum(targets, &weight);
                         let mut t = 0;
                         while t < targets.len() {
                             let i = targets[t] as usize;
+                            let ceil = share_ceil(avail, weight(i), total_weight);
                             if out[i] >= base(i) + ceil {
                                 avail -= out[i] - base(i);
                                 targets.remove(t);
@@ -476,19 +682,20 @@ impl SizeRules {
                     }
 
                     // Since no more are removed by a ceiling, all remaining
-                    // targets will be (approx) equal. Arbitrarily distribute
-                    // rounding errors to the first ones.
-                    let count = targets.len() as u32;
-                    let per_elt = avail / count;
-                    let extra = (avail - per_elt * count) as usize;
-                    assert!(extra < targets.len());
-                    for t in 0..extra {
+                    // targets will be (approx) proportional to their weight.
+                    // Arbitrarily distribute rounding errors to the first ones.
+                    let total_weight = weight_sum(targets, &weight);
+                    let mut extra = avail;
+                    for t in 0..targets.len() {
                         let i = targets[t] as usize;
-                        out[i] = base(i) + per_elt + 1;
+                        let share = share_floor(avail, weight(i), total_weight);
+                        out[i] = base(i) + share;
+                        extra -= share;
                     }
-                    for t in extra..targets.len() {
-                        let i = targets[t] as usize;
-                        out[i] = base(i) + per_elt;
+                    let extra = extra as usize;
+                    assert!(extra < targets.len());
+                    for t in 0..extra {
+                        out[targets[t] as usize] += 1;
                     }
                 }
 
@@ -503,14 +710,14 @@ impl SizeRules {
                     for i in 0..N {
                         out[i] = out[i].max(rules[i].b);
                         sum += out[i];
-                        if rules[i].stretch == highest_stretch {
+                        if rules[i].stretch == highest_stretch && !rules[i].collapsed {
                             over += out[i] - rules[i].b;
                             targets.push(i as u32);
                         }
                     }
 
                     let avail = target - sum + over;
-                    increase_targets(out, &mut targets, |i| rules[i].b, avail);
+                    increase_targets(out, &mut targets, |i| rules[i].b, weight, avail);
                     debug_assert_eq!(target, (0..N).fold(0, |x, i| x + out[i]));
                 } else {
                     // We cannot increase sizes as far as their ideal: instead
@@ -525,25 +732,27 @@ impl SizeRules {
                     }
 
                     let avail = target - sum + over;
-                    increase_targets(out, &mut targets, |i| rules[i].a, avail);
+                    increase_targets(out, &mut targets, |i| rules[i].a, unweighted, avail);
                     debug_assert_eq!(target, (0..N).fold(0, |x, i| x + out[i]));
                 }
             } else {
                 // sum > target: we need to decrease some sizes
-                fn reduce_targets<F: Fn(usize) -> u32>(
+                fn reduce_targets<F: Fn(usize) -> u32, W: Fn(usize) -> u32>(
                     out: &mut [u32],
                     targets: &mut Targets,
                     base: F,
+                    weight: W,
                     mut avail: u32,
                 ) {
                     // We can ignore everything below the floor
                     let mut any_removed = true;
                     while any_removed {
                         any_removed = false;
-                        let floor = avail / targets.len() as u32;
+                        let total_weight = weight_sum(targets, &weight);
                         let mut t = 0;
                         while t < targets.len() {
                             let i = targets[t] as usize;
+                            let floor = share_floor(avail, weight(i), total_weight);
                             if out[i] <= base(i) + floor {
                                 avail -= out[i] - base(i);
                                 targets.remove(t);
@@ -555,16 +764,18 @@ impl SizeRules {
                     }
 
                     // All targets remaining must be reduced to floor, bar rounding errors
-                    let floor = avail / targets.len() as u32;
-                    let extra = avail as usize - floor as usize * targets.len();
-                    assert!(extra < targets.len());
-                    for t in 0..extra {
-                        let i = targets[t] as usize;
-                        out[i] = base(i) + floor + 1;
-                    }
-                    for t in extra..targets.len() {
+                    let total_weight = weight_sum(targets, &weight);
+                    let mut extra = avail;
+                    for t in 0..targets.len() {
                         let i = targets[t] as usize;
+                        let floor = share_floor(avail, weight(i), total_weight);
                         out[i] = base(i) + floor;
+                        extra -= floor;
+                    }
+                    let extra = extra as usize;
+                    assert!(extra < targets.len());
+                    for t in 0..extra {
+                        out[targets[t] as usize] += 1;
                     }
                 }
 
@@ -603,7 +814,7 @@ impl SizeRules {
                     }
                     if sum > target {
                         avail = avail + target - sum;
-                        reduce_targets(out, &mut targets, |i| rules[i].b, avail);
+                        reduce_targets(out, &mut targets, |i| rules[i].b, weight, avail);
                     }
                     debug_assert_eq!(target, (0..N).fold(0, |x, i| x + out[i]));
                 } else {
@@ -620,7 +831,7 @@ impl SizeRules {
                     }
                     if sum > target {
                         let avail = target + margin_sum - total.a;
-                        reduce_targets(out, &mut targets, |i| rules[i].a, avail);
+                        reduce_targets(out, &mut targets, |i| rules[i].a, unweighted, avail);
                     }
                     debug_assert_eq!(target, (0..N).fold(0, |x, i| x + out[i]));
                 }
@@ -747,6 +958,21 @@ impl SizeRules {
     }
 }
 
+// Sum of weights over targets (used by SizeRules::solve_seq_)
+fn weight_sum<W: Fn(usize) -> u32>(targets: &[u32], weight: &W) -> u64 {
+    targets.iter().map(|i| weight(*i as usize) as u64).sum()
+}
+
+// Share of avail in proportion weight / total, rounded down
+fn share_floor(avail: u32, weight: u32, total: u64) -> u32 {
+    (avail as u64 * weight as u64 / total) as u32
+}
+
+// Share of avail in proportion weight / total, rounded up
+fn share_ceil(avail: u32, weight: u32, total: u64) -> u32 {
+    ((avail as u64 * weight as u64 + total - 1) / total) as u32
+}
+
 /// Return the sum over a sequence of rules, assuming these are ordered
 ///
 /// Uses [`SizeRules::appended`] on all rules in sequence.
@@ -772,3 +998,75 @@ impl<'a> Sum<&'a Self> for SizeRules {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn predicates() {
+        let empty = SizeRules::EMPTY;
+        assert_eq!((empty.min(), empty.ideal()), (0, 0));
+        assert_eq!(empty.stretch(), StretchPolicy::Fixed);
+        assert!(empty.is_empty());
+        assert!(empty.is_fixed());
+        assert!(SizeRules::empty(StretchPolicy::Filler).is_empty());
+        assert!(!SizeRules::empty(StretchPolicy::Filler).is_fixed());
+        assert!(SizeRules::COLLAPSED.is_empty());
+
+        let fixed = SizeRules::fixed(10, (2, 2));
+        assert_eq!((fixed.min(), fixed.ideal()), (10, 10));
+        assert!(!fixed.is_empty());
+        assert!(fixed.is_fixed());
+        // Margins do not affect emptiness
+        assert!(SizeRules::fixed(0, (4, 4)).is_empty());
+
+        let variable = SizeRules::new(10, 20, (0, 0), StretchPolicy::Fixed);
+        assert_eq!((variable.min(), variable.ideal()), (10, 20));
+        assert!(!variable.is_empty());
+        assert!(!variable.is_fixed());
+        let variable = SizeRules::new(10, 10, (0, 0), StretchPolicy::LowUtility);
+        assert_eq!(variable.stretch(), StretchPolicy::LowUtility);
+        assert!(!variable.is_fixed());
+        // Ideal is clamped to min
+        let clamped = SizeRules::new(10, 5, (0, 0), StretchPolicy::Fixed);
+        assert_eq!(clamped.ideal(), 10);
+    }
+
+    #[test]
+    fn sum_and_max() {
+        let rules = [
+            SizeRules::new(10, 20, (2, 6), StretchPolicy::Fixed),
+            SizeRules::new(30, 30, (4, 1), StretchPolicy::HighUtility),
+            SizeRules::new(5, 8, (3, 7), StretchPolicy::LowUtility),
+        ];
+
+        // Adjacent margins merge: max(6, 4) + max(1, 3) between elements
+        let sum = SizeRules::sum(&rules);
+        assert_eq!(sum.min_size(), 10 + 6 + 30 + 3 + 5);
+        assert_eq!(sum.ideal_size(), 20 + 6 + 30 + 3 + 8);
+        assert_eq!(sum.margins(), (2, 7));
+        assert_eq!(sum.stretch(), StretchPolicy::HighUtility);
+
+        // Incremental building gives the same result, in either direction
+        let mut acc = rules[0];
+        acc.append(rules[1]);
+        acc.append(rules[2]);
+        assert_eq!(acc, sum);
+        let mut acc = rules[2];
+        acc.prepend(rules[1]);
+        acc.prepend(rules[0]);
+        assert_eq!(acc, sum);
+
+        let max = SizeRules::max_of(&rules);
+        assert_eq!(max.min_size(), 30);
+        assert_eq!(max.ideal_size(), 30);
+        assert_eq!(max.margins(), (4, 7));
+        assert_eq!(max.stretch(), StretchPolicy::HighUtility);
+        assert_eq!(max, rules[0].max(rules[1]).max(rules[2]));
+
+        let none: &[SizeRules] = &[];
+        assert_eq!(SizeRules::sum(none), SizeRules::EMPTY);
+        assert_eq!(SizeRules::max_of(none), SizeRules::EMPTY);
+    }
+}
@@ -69,6 +69,7 @@ pub trait RulesSetter {
 ///
 /// [`SolveCache::apply_rect`] accepts a [`Rect`], updates constraints as
 /// necessary and sets widget positions within this `rect`.
+#[derive(Clone, Debug)]
 pub struct SolveCache {
     // Technically we don't need to store min and ideal here, but it simplifies
     // the API for very little real cost.
@@ -112,8 +113,8 @@ impl SolveCache {
         widget: &mut dyn WidgetConfig,
         size_handle: &mut dyn SizeHandle,
     ) -> Self {
-        let w = widget.size_rules(size_handle, AxisInfo::new(false, None));
-        let h = widget.size_rules(size_handle, AxisInfo::new(true, Some(w.ideal_size())));
+        let w = widget.size_rules(size_handle, AxisInfo::horizontal());
+        let h = widget.size_rules(size_handle, AxisInfo::vertical().with_fixed(w.ideal_size()));
 
         let min = Size(w.min_size(), h.min_size());
         let ideal = Size(w.ideal_size(), h.ideal_size());
@@ -164,7 +165,7 @@ impl SolveCache {
         // We call size_rules not because we want the result, but because our
         // spec requires that we do so before calling set_rect.
         if self.refresh_rules {
-            let w = widget.size_rules(size_handle, AxisInfo::new(false, None));
+            let w = widget.size_rules(size_handle, AxisInfo::horizontal());
             self.min.0 = w.min_size();
             self.ideal.0 = w.ideal_size();
             self.margins.horiz = w.margins();
@@ -175,7 +176,7 @@ impl SolveCache {
         }
 
         if self.refresh_rules || width != self.last_width {
-            let h = widget.size_rules(size_handle, AxisInfo::new(true, Some(width)));
+            let h = widget.size_rules(size_handle, AxisInfo::vertical().with_fixed(width));
             self.min.1 = h.min_size();
             self.ideal.1 = h.ideal_size();
             self.margins.vert = h.margins();
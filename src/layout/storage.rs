@@ -5,7 +5,8 @@
 
 //! Layout solver — storage
 
-use super::SizeRules;
+use super::{GridChildInfo, SizeRules};
+use crate::geom::{Coord, Rect, Size};
 
 /// Master trait over storage types
 pub trait Storage {}
@@ -155,6 +156,53 @@ pub trait GridStorage: sealed::Sealed + Clone {
     fn rules_and_widths(&mut self) -> (&mut [SizeRules], &mut [u32]);
     #[doc(hidden)]
     fn rules_and_heights(&mut self) -> (&mut [SizeRules], &mut [u32]);
+
+    /// Position of the first cell, as set by [`super::GridSetter`]
+    #[doc(hidden)]
+    fn origin(&mut self) -> &mut Coord;
+
+    /// Get the rect of the cell at `(col, row)`
+    ///
+    /// This is the rect which a child occupying only this cell would be given
+    /// by the most recent [`super::GridSetter`] (i.e. the last call to
+    /// `set_rect`), thus may be used e.g. to draw cell decorations.
+    ///
+    /// Panics if the cell is out of range.
+    fn cell_rect(&mut self, col: u32, row: u32) -> Rect {
+        self.span_rect(GridChildInfo::single(col, row))
+    }
+
+    /// Get the rect of a span of cells
+    ///
+    /// This is the union of the rects of all cells covered, including the
+    /// margins between them, as given to a child with this span.
+    ///
+    /// Panics if the span is out of range.
+    fn span_rect(&mut self, info: GridChildInfo) -> Rect {
+        let origin = *self.origin();
+        let (rules, widths) = self.rules_and_widths();
+        let (x, w) = span_of(rules, widths, info.col, info.col_end);
+        let (rules, heights) = self.rules_and_heights();
+        let (y, h) = span_of(rules, heights, info.row, info.row_end);
+        Rect::new(origin + Coord(x as i32, y as i32), Size(w, h))
+    }
+}
+
+// Offset and length of a span of cells over `begin..end`
+fn span_of(rules: &[SizeRules], sizes: &[u32], begin: u32, end: u32) -> (u32, u32) {
+    let (begin, end) = (begin as usize, end as usize);
+    let mut offset = 0;
+    let mut start = 0;
+    for i in 0..end {
+        if i > 0 {
+            let margin = rules[i - 1].margins().1.max(rules[i].margins().0);
+            offset += sizes[i - 1] + margin as u32;
+        }
+        if i == begin {
+            start = offset;
+        }
+    }
+    (start, offset + sizes[end - 1] - start)
 }
 
 /// Fixed-length grid storage
@@ -171,6 +219,7 @@ pub struct FixedGridStorage<WR: Clone, HR: Clone, W: Clone, H: Clone> {
     height_rules: HR,
     widths: W,
     heights: H,
+    origin: Coord,
 }
 
 impl<WR: Clone, HR: Clone, W: Clone, H: Clone> Storage for FixedGridStorage<WR, HR, W, H> {}
@@ -193,6 +242,10 @@ where
     fn rules_and_heights(&mut self) -> (&mut [SizeRules], &mut [u32]) {
         (self.height_rules.as_mut(), self.heights.as_mut())
     }
+
+    fn origin(&mut self) -> &mut Coord {
+        &mut self.origin
+    }
 }
 
 /// Variable-length grid storage
@@ -202,6 +255,7 @@ pub struct DynGridStorage {
     height_rules: Vec<SizeRules>,
     widths: Vec<u32>,
     heights: Vec<u32>,
+    origin: Coord,
 }
 
 impl Storage for DynGridStorage {}
@@ -210,6 +264,8 @@ impl GridStorage for DynGridStorage {
     fn set_dims(&mut self, cols: usize, rows: usize) {
         self.width_rules.resize(cols + 1, SizeRules::EMPTY);
         self.height_rules.resize(rows + 1, SizeRules::EMPTY);
+        self.widths.resize(cols, 0);
+        self.heights.resize(rows, 0);
     }
 
     fn rules_and_widths(&mut self) -> (&mut [SizeRules], &mut [u32]) {
@@ -218,6 +274,30 @@ impl GridStorage for DynGridStorage {
     fn rules_and_heights(&mut self) -> (&mut [SizeRules], &mut [u32]) {
         (&mut self.height_rules, &mut self.heights)
     }
+
+    fn origin(&mut self) -> &mut Coord {
+        &mut self.origin
+    }
+}
+
+/// Storage for [`super::FlowSolver`] and [`super::FlowSetter`]
+#[derive(Clone, Debug, Default)]
+pub struct FlowStorage {
+    /// Horizontal rules of each child
+    pub(super) width_rules: Vec<SizeRules>,
+    /// Vertical rules of each child
+    pub(super) height_rules: Vec<SizeRules>,
+    /// Index of the first child on each line
+    pub(super) lines: Vec<usize>,
+}
+
+impl Storage for FlowStorage {}
+
+impl FlowStorage {
+    pub(super) fn set_len(&mut self, len: usize) {
+        self.width_rules.resize(len, SizeRules::EMPTY);
+        self.height_rules.resize(len, SizeRules::EMPTY);
+    }
 }
 
 mod sealed {
@@ -37,6 +37,8 @@ mod toolkit;
 mod traits;
 
 // public implementations:
+pub mod access;
+pub mod anim;
 pub mod class;
 pub mod draw;
 pub mod event;
@@ -46,6 +48,9 @@ pub mod prelude;
 pub mod text;
 pub mod widget;
 
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
+
 // macro re-exports
 pub mod macros;
 
@@ -0,0 +1,912 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Utilities for testing widgets
+//!
+//! This module is available in the crate's own tests and when the `test-util`
+//! feature is enabled.
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::fmt;
+use std::num::NonZeroU32;
+use std::ops::Range;
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::draw::{CaretShape, ClipRegion, Colour, Draw, DrawHandle, ImageId, InputState, Pass};
+use crate::draw::{ImageFilter, ImageHandle, SizeHandle, TextClass};
+use crate::event::{CursorIcon, Event, Manager, ManagerState, Response, SendEvent};
+use crate::event::{UpdateHandle, VirtualKeyCode, VoidMsg};
+use crate::geom::{Coord, Quad, Rect, Size, Vec2};
+use crate::layout::{AxisInfo, Margins, SizeRules, SolveCache, StretchPolicy};
+use crate::text::fonts::FontId;
+use crate::text::{AccelString, RichText, Text, TextApi, TextDisplay};
+use crate::{ClipboardFormat, Direction, Popup, ThemeAction, ThemeApi, TkAction, TkWindow};
+use crate::{CoreData, Widget, WidgetCore, WidgetId, WindowId};
+
+/// A headless [`SizeHandle`] with fixed metrics
+///
+/// This allows widget layout ([`Layout::size_rules`] and [`Layout::set_rect`])
+/// to be tested without a theme or graphics context. All metrics are public
+/// fields; the [`Default`] values are small, round numbers.
+///
+/// Text is measured from its string alone: each `char` has width
+/// `glyph_advance` (or as given by `font_glyph_advance` for the text's font)
+/// and each line has height `line_height`. Text of classes
+/// which wrap (see [`TextClass::line_wrap`]) wraps at any character when the
+/// width is fixed. Other details follow the default themes.
+///
+/// Note that [`SizeHandle::text_bound`] does not prepare the text. Widgets
+/// which prepare text in `set_rect` (e.g. labels) still require fonts to be
+/// loaded (see [`kas::text::fonts`]); text metrics do not affect the result.
+///
+/// [`Layout::size_rules`]: crate::Layout::size_rules
+/// [`Layout::set_rect`]: crate::Layout::set_rect
+#[derive(Clone, Debug, PartialEq)]
+pub struct TestSizeHandle {
+    /// Value of [`SizeHandle::scale_factor`]
+    pub scale_factor: f32,
+    /// Width of each `char` of text
+    pub glyph_advance: u32,
+    /// Width of each `char` of text for specific fonts
+    ///
+    /// Fonts not listed use `glyph_advance`.
+    pub font_glyph_advance: Vec<(FontId, u32)>,
+    /// Height of each line of text
+    pub line_height: u32,
+    /// Frame size (on each side)
+    pub frame: u32,
+    /// Button frame size (on each side)
+    pub button_frame: u32,
+    /// Margin inside a frame before contents
+    pub inner_margin: u32,
+    /// Space between elements
+    pub outer_margin: u16,
+    /// Width of an edit marker
+    pub edit_marker_width: f32,
+    /// Blink interval of an edit marker
+    pub edit_marker_blink: Option<Duration>,
+    /// Size of check boxes and radio boxes
+    pub checkbox: u32,
+    /// Minimum scrollbar handle size (horizontal orientation)
+    pub scrollbar: Size,
+    /// Minimum slider handle size (horizontal orientation)
+    pub slider: Size,
+    /// Number of calls to [`SizeHandle::text_bound`] (for testing caching)
+    pub text_bound_calls: u32,
+}
+
+impl Default for TestSizeHandle {
+    fn default() -> Self {
+        TestSizeHandle {
+            scale_factor: 1.0,
+            glyph_advance: 8,
+            font_glyph_advance: vec![],
+            line_height: 16,
+            frame: 4,
+            button_frame: 4,
+            inner_margin: 2,
+            outer_margin: 4,
+            edit_marker_width: 2.0,
+            edit_marker_blink: Some(Duration::from_millis(500)),
+            checkbox: 16,
+            scrollbar: Size(16, 8),
+            slider: Size(12, 24),
+            text_bound_calls: 0,
+        }
+    }
+}
+
+impl TestSizeHandle {
+    /// Construct with default metrics
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl SizeHandle for TestSizeHandle {
+    fn scale_factor(&self) -> f32 {
+        self.scale_factor
+    }
+
+    fn frame(&self) -> Size {
+        Size::uniform(self.frame)
+    }
+    fn menu_frame(&self) -> Size {
+        Size(self.frame, self.frame / 2)
+    }
+
+    fn inner_margin(&self) -> Size {
+        Size::uniform(self.inner_margin)
+    }
+
+    fn outer_margins(&self) -> Margins {
+        Margins::uniform(self.outer_margin)
+    }
+
+    fn line_height(&self, _: TextClass) -> u32 {
+        self.line_height
+    }
+
+    fn text_bound(
+        &mut self,
+        text: &mut dyn TextApi,
+        class: TextClass,
+        axis: AxisInfo,
+    ) -> SizeRules {
+        self.text_bound_calls += 1;
+        let font_id = text.env().font_id;
+        let glyph_advance = self
+            .font_glyph_advance
+            .iter()
+            .find(|(id, _)| *id == font_id)
+            .map(|(_, advance)| *advance)
+            .unwrap_or(self.glyph_advance);
+        let lines = text
+            .as_str()
+            .lines()
+            .map(|line| line.chars().count() as u32);
+
+        let margin = match class {
+            TextClass::Label | TextClass::LabelSingle => self.outer_margin,
+            TextClass::Button | TextClass::Edit | TextClass::EditMulti => self.inner_margin as u16,
+        };
+        let margins = (margin, margin);
+
+        if axis.is_horizontal() {
+            let width = lines.max().unwrap_or(0) * glyph_advance;
+            let stretch = match class {
+                TextClass::Edit | TextClass::EditMulti => StretchPolicy::HighUtility,
+                _ => StretchPolicy::LowUtility,
+            };
+            SizeRules::new(width, width, margins, stretch)
+        } else {
+            let per_line = match axis.other() {
+                Some(width) if class.line_wrap() => (width / glyph_advance.max(1)).max(1),
+                _ => u32::MAX,
+            };
+            let rows: u32 = lines.map(|len| len.saturating_sub(1) / per_line + 1).sum();
+            let height = rows.max(1) * self.line_height;
+
+            let (min, stretch) = match class {
+                TextClass::Label => (height, StretchPolicy::Filler),
+                TextClass::LabelSingle | TextClass::Button | TextClass::Edit => {
+                    (self.line_height, StretchPolicy::Fixed)
+                }
+                TextClass::EditMulti => (3 * self.line_height, StretchPolicy::HighUtility),
+            };
+            SizeRules::new(min, height.max(min), margins, stretch)
+        }
+    }
+
+    fn edit_marker_width(&self) -> f32 {
+        self.edit_marker_width
+    }
+
+    fn edit_marker_blink(&self) -> Option<Duration> {
+        self.edit_marker_blink
+    }
+
+    fn button_surround(&self) -> (Size, Size) {
+        let s = Size::uniform(self.button_frame);
+        (s, s)
+    }
+
+    fn edit_surround(&self) -> (Size, Size) {
+        let s = Size::uniform(self.frame);
+        (s, s)
+    }
+
+    fn content_inset(&self, class: TextClass) -> Margins {
+        // Frames are considered to have square corners
+        let frame = match class {
+            TextClass::Label | TextClass::LabelSingle => return Margins::ZERO,
+            TextClass::Button => self.button_frame,
+            TextClass::Edit | TextClass::EditMulti => self.frame,
+        };
+        Margins::uniform(frame as u16)
+    }
+
+    fn checkbox(&self) -> Size {
+        Size::uniform(self.checkbox)
+    }
+
+    fn radiobox(&self) -> Size {
+        self.checkbox()
+    }
+
+    fn scrollbar(&self) -> (Size, u32) {
+        (self.scrollbar, 2 * self.scrollbar.0)
+    }
+
+    fn slider(&self) -> (Size, u32) {
+        (self.slider, 2 * self.slider.0)
+    }
+}
+
+/// A draw operation recorded by [`RecordingDrawHandle`]
+///
+/// Most variants correspond to a [`DrawHandle`] method and record its
+/// arguments, with positions as passed by the widget. Variants `Rect`,
+/// `GradientRect`, `Frame` and `DrawImage` correspond to [`Draw`] methods
+/// (via [`DrawHandle::draw_device`]) and use device coordinates.
+///
+/// Prepared text ([`TextDisplay`]) is recorded by position and class only.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DrawCommand {
+    /// Start of a [`DrawHandle::clip_region`]: `rect, offset, class`
+    ///
+    /// Commands up to the matching `ClipEnd` were drawn within this region.
+    ClipStart(Rect, Coord, ClipRegion),
+    /// End of the innermost clip region
+    ClipEnd,
+    /// [`DrawHandle::outer_frame`]
+    OuterFrame(Rect),
+    /// [`DrawHandle::titled_frame`]: `rect, title`
+    TitledFrame(Rect, Rect),
+    /// [`DrawHandle::menu_frame`]
+    MenuFrame(Rect),
+    /// [`DrawHandle::separator`]
+    Separator(Rect),
+    /// [`DrawHandle::grid_stripe`]
+    GridStripe(Rect),
+    /// [`DrawHandle::grid_line`]
+    GridLine(Rect),
+    /// [`DrawHandle::gradient`]
+    Gradient(Rect, (Colour, Colour), Direction),
+    /// [`DrawHandle::swatch`]
+    Swatch(Rect, Colour),
+    /// [`DrawHandle::image`]
+    Image(ImageId, Rect),
+    /// [`DrawHandle::text_offset`]: `pos, bounds, offset, class, disabled`
+    Text(Coord, Vec2, Coord, TextClass, bool),
+    /// [`DrawHandle::text_effects`]: `pos, offset, text, class, disabled`
+    TextEffects(Coord, Coord, String, TextClass, bool),
+    /// [`DrawHandle::text_accel`]: `pos, text, state, class, disabled`
+    TextAccel(Coord, String, bool, TextClass, bool),
+    /// [`DrawHandle::text_rich`]: `pos, text, runs, class, disabled`
+    ///
+    /// Runs are listed as `(start, colour)`.
+    TextRich(Coord, String, Vec<(u32, Option<Colour>)>, TextClass, bool),
+    /// [`DrawHandle::text_links`]: `pos, text, links, class, disabled`
+    TextLinks(
+        Coord,
+        String,
+        Vec<(Range<usize>, InputState)>,
+        TextClass,
+        bool,
+    ),
+    /// Text with a selection: `pos, bounds, offset, range, class, disabled`
+    TextSelected(Coord, Vec2, Coord, Range<usize>, TextClass, bool),
+    /// [`DrawHandle::edit_marker`]: `pos, bounds, offset, class, range, shape`
+    EditMarker(Coord, Vec2, Coord, TextClass, Range<usize>, CaretShape),
+    /// [`DrawHandle::menu_entry`]
+    MenuEntry(Rect, InputState),
+    /// [`DrawHandle::menu_mark`]: `rect, radio, state`
+    MenuMark(Rect, bool, InputState),
+    /// [`DrawHandle::badge`]
+    Badge(Rect, InputState),
+    /// [`DrawHandle::chevron`]: `rect, dir, state`
+    Chevron(Rect, Direction, InputState),
+    /// [`DrawHandle::button`]
+    Button(Rect, InputState),
+    /// [`DrawHandle::edit_box`]
+    EditBox(Rect, InputState),
+    /// [`DrawHandle::checkbox`]: `rect, checked, state`
+    Checkbox(Rect, bool, InputState),
+    /// [`DrawHandle::radiobox`]: `rect, checked, state`
+    Radiobox(Rect, bool, InputState),
+    /// [`DrawHandle::scrollbar`]: `rect, h_rect, dir, state`
+    Scrollbar(Rect, Rect, Direction, InputState),
+    /// [`DrawHandle::slider`]: `rect, h_rect, dir, state`
+    Slider(Rect, Rect, Direction, InputState),
+    /// [`Draw::rect`]
+    Rect(Quad, Colour),
+    /// [`Draw::gradient_rect`]
+    GradientRect(Quad, (Colour, Colour), Direction),
+    /// [`Draw::frame`]: `outer, inner, colour`
+    Frame(Quad, Quad, Colour),
+    /// [`Draw::image`]
+    DrawImage(ImageId, Quad),
+}
+
+/// The [`Draw`] device of a [`RecordingDrawHandle`]
+#[derive(Debug, Default)]
+pub struct RecordingDraw {
+    commands: Vec<DrawCommand>,
+    passes: u32,
+}
+
+impl Draw for RecordingDraw {
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn add_clip_region(&mut self, _: Rect, depth: f32) -> Pass {
+        self.passes += 1;
+        Pass::new_pass_with_depth(self.passes, depth)
+    }
+
+    fn rect(&mut self, _: Pass, rect: Quad, col: Colour) {
+        self.commands.push(DrawCommand::Rect(rect, col));
+    }
+
+    fn gradient_rect(&mut self, _: Pass, rect: Quad, cols: (Colour, Colour), dir: Direction) {
+        self.commands
+            .push(DrawCommand::GradientRect(rect, cols, dir));
+    }
+
+    fn frame(&mut self, _: Pass, outer: Quad, inner: Quad, col: Colour) {
+        self.commands.push(DrawCommand::Frame(outer, inner, col));
+    }
+
+    fn image(&mut self, _: Pass, id: ImageId, rect: Quad) {
+        self.commands.push(DrawCommand::DrawImage(id, rect));
+    }
+}
+
+/// A headless [`DrawHandle`] recording draw operations
+///
+/// Instead of rendering, each operation is recorded as a [`DrawCommand`] (see
+/// [`Self::commands`]), allowing tests to assert that a widget drew the
+/// expected primitives. Sizing uses the contained [`TestSizeHandle`].
+///
+/// Nested [`DrawHandle::clip_region`] calls are recorded as a
+/// [`DrawCommand::ClipStart`] and [`DrawCommand::ClipEnd`] pair around the
+/// commands drawn within the region.
+pub struct RecordingDrawHandle {
+    /// Handle used by [`DrawHandle::size_handle_dyn`]
+    pub size_handle: TestSizeHandle,
+    draw: RecordingDraw,
+    rect: Rect,
+    offset: Coord,
+    pass: Pass,
+}
+
+impl RecordingDrawHandle {
+    /// Construct, with the given window `rect`
+    pub fn new(rect: Rect) -> Self {
+        RecordingDrawHandle {
+            size_handle: TestSizeHandle::new(),
+            draw: RecordingDraw::default(),
+            rect,
+            offset: Coord::ZERO,
+            pass: Pass::new_pass_with_depth(0, 0.0),
+        }
+    }
+
+    /// Recorded commands
+    pub fn commands(&self) -> &[DrawCommand] {
+        &self.draw.commands
+    }
+
+    /// Take recorded commands, leaving the list empty
+    pub fn take_commands(&mut self) -> Vec<DrawCommand> {
+        std::mem::take(&mut self.draw.commands)
+    }
+
+    fn push(&mut self, command: DrawCommand) {
+        self.draw.commands.push(command);
+    }
+}
+
+impl DrawHandle for RecordingDrawHandle {
+    fn size_handle_dyn(&mut self, f: &mut dyn FnMut(&mut dyn SizeHandle)) {
+        f(&mut self.size_handle);
+    }
+
+    fn draw_device(&mut self) -> (Pass, Coord, &mut dyn Draw) {
+        (self.pass, self.offset, &mut self.draw)
+    }
+
+    fn clip_region(
+        &mut self,
+        rect: Rect,
+        offset: Coord,
+        class: ClipRegion,
+        f: &mut dyn FnMut(&mut dyn DrawHandle),
+    ) {
+        self.push(DrawCommand::ClipStart(rect, offset, class));
+        let outer = (self.rect, self.offset, self.pass);
+        self.rect = class.clip_rect(rect + self.offset, self.rect);
+        self.pass = self.draw.add_clip_region(self.rect, self.pass.depth());
+        self.offset = self.offset - offset;
+        f(self);
+        self.rect = outer.0;
+        self.offset = outer.1;
+        self.pass = outer.2;
+        self.push(DrawCommand::ClipEnd);
+    }
+
+    fn target_rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn outer_frame(&mut self, rect: Rect) {
+        self.push(DrawCommand::OuterFrame(rect));
+    }
+
+    fn titled_frame(&mut self, rect: Rect, title: Rect) {
+        self.push(DrawCommand::TitledFrame(rect, title));
+    }
+
+    fn menu_frame(&mut self, rect: Rect) {
+        self.push(DrawCommand::MenuFrame(rect));
+    }
+
+    fn separator(&mut self, rect: Rect) {
+        self.push(DrawCommand::Separator(rect));
+    }
+
+    fn grid_stripe(&mut self, rect: Rect) {
+        self.push(DrawCommand::GridStripe(rect));
+    }
+
+    fn grid_line(&mut self, rect: Rect) {
+        self.push(DrawCommand::GridLine(rect));
+    }
+
+    fn gradient(&mut self, rect: Rect, cols: (Colour, Colour), dir: Direction) {
+        self.push(DrawCommand::Gradient(rect, cols, dir));
+    }
+
+    fn swatch(&mut self, rect: Rect, colour: Colour) {
+        self.push(DrawCommand::Swatch(rect, colour));
+    }
+
+    fn image(&mut self, id: ImageId, rect: Rect) {
+        self.push(DrawCommand::Image(id, rect));
+    }
+
+    fn text_offset(
+        &mut self,
+        pos: Coord,
+        bounds: Vec2,
+        offset: Coord,
+        _: &TextDisplay,
+        class: TextClass,
+        disabled: bool,
+    ) {
+        self.push(DrawCommand::Text(pos, bounds, offset, class, disabled));
+    }
+
+    fn text_effects(
+        &mut self,
+        pos: Coord,
+        offset: Coord,
+        text: &dyn TextApi,
+        class: TextClass,
+        disabled: bool,
+    ) {
+        let text = text.as_str().to_string();
+        self.push(DrawCommand::TextEffects(pos, offset, text, class, disabled));
+    }
+
+    fn text_accel(
+        &mut self,
+        pos: Coord,
+        text: &Text<AccelString>,
+        state: bool,
+        class: TextClass,
+        disabled: bool,
+    ) {
+        let text = text.as_str().to_string();
+        self.push(DrawCommand::TextAccel(pos, text, state, class, disabled));
+    }
+
+    fn text_rich(&mut self, pos: Coord, text: &Text<RichText>, class: TextClass, disabled: bool) {
+        let mut runs = vec![];
+        let mut start = 0;
+        for (s, style) in text.text().runs() {
+            runs.push((start, style.colour));
+            start += s.len() as u32;
+        }
+        let text = text.as_str().to_string();
+        self.push(DrawCommand::TextRich(pos, text, runs, class, disabled));
+    }
+
+    fn text_links(
+        &mut self,
+        pos: Coord,
+        text: &Text<RichText>,
+        links: &[(Range<usize>, InputState)],
+        class: TextClass,
+        disabled: bool,
+    ) {
+        let (text, links) = (text.as_str().to_string(), links.to_vec());
+        self.push(DrawCommand::TextLinks(pos, text, links, class, disabled));
+    }
+
+    fn text_selected_range(
+        &mut self,
+        pos: Coord,
+        bounds: Vec2,
+        offset: Coord,
+        _: &TextDisplay,
+        range: Range<usize>,
+        class: TextClass,
+        disabled: bool,
+    ) {
+        let command = DrawCommand::TextSelected(pos, bounds, offset, range, class, disabled);
+        self.push(command);
+    }
+
+    fn edit_marker(
+        &mut self,
+        pos: Coord,
+        bounds: Vec2,
+        offset: Coord,
+        _: &TextDisplay,
+        class: TextClass,
+        range: Range<usize>,
+        shape: CaretShape,
+    ) {
+        let command = DrawCommand::EditMarker(pos, bounds, offset, class, range, shape);
+        self.push(command);
+    }
+
+    fn menu_entry(&mut self, rect: Rect, state: InputState) {
+        self.push(DrawCommand::MenuEntry(rect, state));
+    }
+
+    fn menu_mark(&mut self, rect: Rect, radio: bool, state: InputState) {
+        self.push(DrawCommand::MenuMark(rect, radio, state));
+    }
+
+    fn badge(&mut self, rect: Rect, state: InputState) {
+        self.push(DrawCommand::Badge(rect, state));
+    }
+
+    fn chevron(&mut self, rect: Rect, dir: Direction, state: InputState) {
+        self.push(DrawCommand::Chevron(rect, dir, state));
+    }
+
+    fn button(&mut self, rect: Rect, state: InputState) {
+        self.push(DrawCommand::Button(rect, state));
+    }
+
+    fn edit_box(&mut self, rect: Rect, state: InputState) {
+        self.push(DrawCommand::EditBox(rect, state));
+    }
+
+    fn checkbox(&mut self, rect: Rect, checked: bool, state: InputState) {
+        self.push(DrawCommand::Checkbox(rect, checked, state));
+    }
+
+    fn radiobox(&mut self, rect: Rect, checked: bool, state: InputState) {
+        self.push(DrawCommand::Radiobox(rect, checked, state));
+    }
+
+    fn scrollbar(&mut self, rect: Rect, h_rect: Rect, dir: Direction, state: InputState) {
+        self.push(DrawCommand::Scrollbar(rect, h_rect, dir, state));
+    }
+
+    fn slider(&mut self, rect: Rect, h_rect: Rect, dir: Direction, state: InputState) {
+        self.push(DrawCommand::Slider(rect, h_rect, dir, state));
+    }
+}
+
+/// A headless [`TkWindow`]
+///
+/// Pop-ups and windows are counted (and given sequential identifiers), loaded
+/// images, triggered update handles and the IME position are recorded and
+/// clipboard contents are held in-process. Sizes are given by a
+/// [`TestSizeHandle`]. Other requests are ignored.
+#[derive(Debug, Default)]
+pub struct TestWindow {
+    /// Number of pop-ups opened
+    pub popups: u32,
+    /// Number of windows added
+    pub windows: u32,
+    /// Images loaded: identifier and size
+    pub images: Vec<(ImageId, Size)>,
+    /// Update handles triggered, in order
+    pub updates: Vec<UpdateHandle>,
+    /// Clipboard contents
+    pub clipboard: Option<(ClipboardFormat, Vec<u8>)>,
+    /// The last position set via [`TkWindow::set_ime_position`]
+    pub ime_position: Option<Rect>,
+    /// The size handle
+    pub size_handle: TestSizeHandle,
+}
+
+impl TkWindow for TestWindow {
+    fn add_popup(&mut self, _: Popup) -> WindowId {
+        self.popups += 1;
+        WindowId::new(NonZeroU32::new(self.popups + self.windows).unwrap())
+    }
+    fn add_window(&mut self, _: Box<dyn crate::Window>) -> WindowId {
+        self.windows += 1;
+        WindowId::new(NonZeroU32::new(self.popups + self.windows).unwrap())
+    }
+    fn close_window(&mut self, _: WindowId) {}
+    fn trigger_update(&mut self, handle: UpdateHandle, _: u64) {
+        self.updates.push(handle);
+    }
+    fn get_clipboard_data(&mut self, format: &ClipboardFormat) -> Option<Vec<u8>> {
+        match self.clipboard.as_ref() {
+            Some((f, data)) if f == format => Some(data.clone()),
+            _ => None,
+        }
+    }
+    fn set_clipboard_data(&mut self, format: ClipboardFormat, content: Vec<u8>) {
+        self.clipboard = Some((format, content));
+    }
+    fn adjust_theme(&mut self, _: &mut dyn FnMut(&mut dyn ThemeApi) -> ThemeAction) {}
+    fn size_handle(&mut self, f: &mut dyn FnMut(&mut dyn SizeHandle)) {
+        f(&mut self.size_handle);
+    }
+    fn load_image(&mut self, size: Size, _: &[u8], _: ImageFilter) -> ImageHandle {
+        let id = ImageId::new(self.images.len() as u32 + 1);
+        self.images.push((id, size));
+        // Releases are not tracked: the receiver is dropped immediately
+        let (release, _) = std::sync::mpsc::channel();
+        ImageHandle::new(id, size, release)
+    }
+    fn set_cursor_icon(&mut self, _: CursorIcon) {}
+    fn set_ime_position(&mut self, rect: Rect) {
+        self.ime_position = Some(rect);
+    }
+}
+
+/// A configured widget with event-manager state
+///
+/// This configures `widget` as a toolkit would (see
+/// [`ManagerState::configure`]), allowing event handling to be tested without
+/// a window. Each method sending events calls [`ManagerState::update`] after.
+pub struct TestManager<W> {
+    /// The widget (root of the tree)
+    pub widget: W,
+    /// Event-manager state
+    pub state: ManagerState,
+    /// The window
+    pub tkw: TestWindow,
+}
+
+impl<W: Widget<Msg = VoidMsg>> TestManager<W> {
+    /// Construct and configure
+    pub fn new(widget: W) -> Self {
+        let mut test = TestManager {
+            widget,
+            state: ManagerState::new(),
+            tkw: TestWindow::default(),
+        };
+        test.configure();
+        test
+    }
+
+    /// Re-configure the widget tree
+    pub fn configure(&mut self) {
+        self.state.configure(&mut self.tkw, &mut self.widget);
+    }
+
+    /// Solve layout and set the widget's rect
+    pub fn set_rect(&mut self, rect: Rect) {
+        let size_handle = &mut self.tkw.size_handle;
+        let mut cache = SolveCache::find_constraints(&mut self.widget, size_handle);
+        cache.apply_rect(&mut self.widget, size_handle, rect, false);
+    }
+
+    /// Update the manager (see [`ManagerState::update`])
+    pub fn update(&mut self) -> TkAction {
+        self.state.update(&mut self.tkw, &mut self.widget)
+    }
+
+    /// Call `f` with a [`Manager`] and the widget, then update
+    pub fn with<T, F: FnOnce(&mut Manager, &mut W) -> T>(&mut self, f: F) -> T {
+        let widget = &mut self.widget;
+        let mut result = None;
+        self.state
+            .with(&mut self.tkw, |mgr| result = Some(f(mgr, widget)));
+        self.update();
+        result.unwrap()
+    }
+
+    /// Send `event` to widget `id`, then update
+    pub fn send(&mut self, id: WidgetId, event: Event) -> Response<VoidMsg> {
+        self.with(|mgr, widget| widget.send(mgr, id, event))
+    }
+
+    /// Press and release key `vkey`, then update
+    pub fn key(&mut self, vkey: VirtualKeyCode) {
+        self.with(|mgr, widget| {
+            mgr.start_key_event(widget, vkey, 0);
+            mgr.end_key_event(0);
+        });
+    }
+}
+
+#[cfg(feature = "winit")]
+impl<W: Widget<Msg = VoidMsg>> TestManager<W> {
+    /// Handle a winit `WindowEvent` (see `Manager::handle_winit`), then update
+    pub fn winit(&mut self, event: winit::event::WindowEvent) {
+        self.with(|mgr, widget| mgr.handle_winit(widget, event));
+    }
+
+    /// Move the mouse cursor to `coord`, then update
+    #[allow(deprecated)]
+    pub fn mouse_move(&mut self, coord: Coord) {
+        use winit::event::{DeviceId, ModifiersState, WindowEvent};
+        self.winit(WindowEvent::CursorMoved {
+            device_id: unsafe { DeviceId::dummy() },
+            position: winit::dpi::PhysicalPosition::new(coord.0 as f64, coord.1 as f64),
+            modifiers: ModifiersState::empty(),
+        });
+    }
+
+    /// Press or release a mouse `button`, then update
+    #[allow(deprecated)]
+    pub fn mouse_input(&mut self, button: crate::event::MouseButton, pressed: bool) {
+        use winit::event::{DeviceId, ElementState, ModifiersState, WindowEvent};
+        let state = match pressed {
+            true => ElementState::Pressed,
+            false => ElementState::Released,
+        };
+        self.winit(WindowEvent::MouseInput {
+            device_id: unsafe { DeviceId::dummy() },
+            state,
+            button,
+            modifiers: ModifiersState::empty(),
+        });
+    }
+}
+
+/// A log of events shared between [`EventRecorder`] widgets
+pub type EventLog = Rc<RefCell<Vec<(WidgetId, &'static str)>>>;
+
+/// A fixed-size widget recording events in a shared [`EventLog`]
+///
+/// Each event is passed to the handler function given on construction, which
+/// may act on it and record a name for it via [`EventRecorder::record`].
+/// The widget has size 10×10 and draws nothing.
+#[handler(handle=noauto)]
+#[derive(Debug, crate::macros::Widget)]
+pub struct EventRecorder {
+    #[widget_core]
+    core: CoreData,
+    log: EventLog,
+    handler: RecordFn,
+}
+
+#[derive(Clone, Copy)]
+struct RecordFn(fn(&mut Manager, &EventRecorder, Event) -> Response<VoidMsg>);
+
+impl fmt::Debug for RecordFn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "RecordFn")
+    }
+}
+
+impl EventRecorder {
+    /// Construct, with a `handler` for all events received
+    pub fn new(
+        log: &EventLog,
+        handler: fn(&mut Manager, &EventRecorder, Event) -> Response<VoidMsg>,
+    ) -> Self {
+        EventRecorder {
+            core: Default::default(),
+            log: log.clone(),
+            handler: RecordFn(handler),
+        }
+    }
+
+    /// Append `(self.id(), name)` to the log
+    pub fn record(&self, name: &'static str) {
+        self.log.borrow_mut().push((self.id(), name));
+    }
+}
+
+impl crate::Layout for EventRecorder {
+    fn size_rules(&mut self, _: &mut dyn SizeHandle, _: AxisInfo) -> SizeRules {
+        SizeRules::fixed(10, (0, 0))
+    }
+
+    fn draw(&self, _: &mut dyn DrawHandle, _: &ManagerState, _: bool) {}
+}
+
+impl crate::event::Handler for EventRecorder {
+    type Msg = VoidMsg;
+
+    fn handle(&mut self, mgr: &mut Manager, event: Event) -> Response<VoidMsg> {
+        let RecordFn(handler) = self.handler;
+        handler(mgr, self, event)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::event::{ManagerState, VoidMsg};
+    use crate::widget::{CheckBoxBare, Column, Filler, Label, Overlay};
+    use crate::{AlignHints, Layout, WidgetCore};
+
+    #[test]
+    fn column_of_labels() {
+        // Labels prepare their text in set_rect
+        let _ = crate::text::fonts::fonts().load_default();
+
+        let mut column = Column::new(vec![Label::new("a"), Label::new("bb"), Label::new("ccc")]);
+        let mut size_handle = TestSizeHandle::new();
+
+        let horiz = column.size_rules(&mut size_handle, AxisInfo::new(false, None));
+        assert_eq!((horiz.min_size(), horiz.ideal_size()), (24, 24));
+        let vert = column.size_rules(&mut size_handle, AxisInfo::new(true, Some(24)));
+        assert_eq!((vert.min_size(), vert.ideal_size()), (56, 56));
+
+        column.set_rect(Rect::new(Coord(10, 10), Size(24, 56)), AlignHints::NONE);
+        assert_eq!(column[0].rect(), Rect::new(Coord(10, 10), Size(24, 16)));
+        assert_eq!(column[1].rect(), Rect::new(Coord(10, 30), Size(24, 16)));
+        assert_eq!(column[2].rect(), Rect::new(Coord(10, 50), Size(24, 16)));
+    }
+
+    #[test]
+    fn record_checkbox() {
+        let mgr = ManagerState::new();
+        let rect = Rect::new(Coord(5, 5), Size(16, 16));
+        let mut draw_handle = RecordingDrawHandle::new(rect);
+
+        let mut checkbox = CheckBoxBare::<VoidMsg>::new().state(true);
+        checkbox.set_rect(rect, AlignHints::NONE);
+        let rect = checkbox.rect();
+        checkbox.draw(&mut draw_handle, &mgr, true);
+
+        let state = InputState {
+            disabled: true,
+            ..Default::default()
+        };
+        let commands = draw_handle.take_commands();
+        assert_eq!(commands, vec![DrawCommand::Checkbox(rect, true, state)]);
+    }
+
+    #[test]
+    fn nested_clip_regions() {
+        let rect = Rect::new(Coord::ZERO, Size(50, 50));
+        let mut draw_handle = RecordingDrawHandle::new(rect);
+        let outer = Rect::new(Coord(10, 10), Size(20, 20));
+        let inner = Rect::new(Coord(0, 20), Size(30, 30));
+        let class = ClipRegion::Scroll;
+        draw_handle.clip_region(outer, Coord::ZERO, class, &mut |draw_handle| {
+            assert_eq!(draw_handle.target_rect(), outer);
+            draw_handle.clip_region(inner, Coord::ZERO, class, &mut |draw_handle| {
+                let rect = Rect::new(Coord(10, 20), Size(20, 10));
+                assert_eq!(draw_handle.target_rect(), rect);
+            });
+            // Overlay layers are not clipped to their parent
+            for &layer in &[ClipRegion::Popup, ClipRegion::Drag, ClipRegion::Tooltip] {
+                draw_handle.clip_region(inner, Coord::ZERO, layer, &mut |draw_handle| {
+                    assert_eq!(draw_handle.target_rect(), inner);
+                });
+            }
+        });
+    }
+
+    #[test]
+    fn record_clip_regions() {
+        let mgr = ManagerState::new();
+        let rect = Rect::new(Coord::ZERO, Size(50, 50));
+        let mut draw_handle = RecordingDrawHandle::new(rect);
+
+        let mut overlay = Overlay::new(vec![Filler::new(), Filler::new(), Filler::new()]);
+        overlay.set_rect(rect, AlignHints::NONE);
+        overlay.draw(&mut draw_handle, &mgr, false);
+
+        let start = DrawCommand::ClipStart(rect, Coord::ZERO, ClipRegion::Popup);
+        let end = DrawCommand::ClipEnd;
+        let expected = vec![start.clone(), end.clone(), start, end];
+        assert_eq!(draw_handle.commands(), &expected[..]);
+    }
+
+    #[test]
+    fn wrapping() {
+        let mut text = crate::text::Text::new_multi("abcdefghij\nxy");
+        let mut size_handle = TestSizeHandle::new();
+        let axis = AxisInfo::new(true, Some(32));
+        let rules = size_handle.text_bound(&mut text, TextClass::Label, axis);
+        assert_eq!(rules.ideal_size(), 4 * 16);
+        let rules = size_handle.text_bound(&mut text, TextClass::LabelSingle, axis);
+        assert_eq!(rules.ideal_size(), 2 * 16);
+    }
+}
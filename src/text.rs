@@ -10,11 +10,15 @@ pub use kas_text::*;
 mod selection;
 pub use selection::SelectionHelper;
 
+mod rich;
+pub use rich::{RichText, TextStyle};
+
 mod string;
+pub(crate) use string::find_vkeys;
 pub use string::AccelString;
 
 pub mod util {
-    use super::{format, EditableTextApi, Text, TextApi};
+    use super::{format, EditableTextApi, Text, TextApi, TextApiExt};
     use kas::TkAction;
 
     /// Set the text and prepare
@@ -41,4 +45,58 @@ pub mod util {
         text.prepare();
         TkAction::Resize
     }
+
+    /// Get the position of each character boundary
+    ///
+    /// Returns one `(x, line)` pair for each `char` boundary of the text,
+    /// including the start and end, in order of byte index. `x` is the
+    /// horizontal position of the edit marker at that boundary relative to
+    /// the text's origin (thus the cumulative advance within the line), and
+    /// `line` is the index of the (wrapped) line.
+    ///
+    /// The text must be prepared. An empty text has a single position,
+    /// `(0.0, 0)`; the position after a trailing line break is at the start
+    /// of the following (empty) line.
+    ///
+    /// Positions are calculated from the text's prepared glyphs, thus are
+    /// consistent with both [`SizeHandle::text_bound`] and drawing.
+    ///
+    /// [`SizeHandle::text_bound`]: crate::draw::SizeHandle::text_bound
+    pub fn glyph_positions<T: TextApi>(text: &T) -> Vec<(f32, usize)> {
+        let string = text.as_str();
+        let boundaries = string.char_indices().map(|(i, _)| i);
+        boundaries
+            .chain(std::iter::once(string.len()))
+            .map(|index| {
+                let x = text.text_glyph_pos(index).next_back();
+                let x = x.map(|marker| marker.pos.0).unwrap_or(0.0);
+                let line = text.find_line(index).map(|r| r.0).unwrap_or(0);
+                (x, line)
+            })
+            .collect()
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        #[test]
+        fn glyph_positions() {
+            let _ = crate::text::fonts::fonts().load_default();
+            let positions = |s: &str| {
+                let mut text = Text::new_multi(s.to_string());
+                text.prepare();
+                super::glyph_positions(&text)
+            };
+
+            assert_eq!(positions(""), vec![(0.0, 0)]);
+
+            let pos = positions("ab\n");
+            assert_eq!(pos.len(), 4);
+            assert_eq!(pos[0], (0.0, 0));
+            assert!(pos[0].0 < pos[1].0 && pos[1].0 < pos[2].0);
+            assert_eq!((pos[1].1, pos[2].1), (0, 0));
+            assert_eq!(pos[3], (0.0, 1));
+        }
+    }
 }
@@ -0,0 +1,260 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Rich text: a sequence of styled runs
+
+use std::ops::Range;
+
+use kas::draw::Colour;
+use kas::text::fonts::FontId;
+use kas::text::format::{FontToken, FormattableText};
+#[cfg(not(feature = "gat"))]
+use kas::text::OwningVecIter;
+use kas::text::{Effect, EffectFlags};
+
+/// Style of a run of [`RichText`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TextStyle {
+    /// Text colour; if `None`, the theme's colour is used
+    pub colour: Option<Colour>,
+    /// Font
+    pub font_id: FontId,
+    /// Font size, relative to the theme's font size
+    pub scale: f32,
+}
+
+impl Default for TextStyle {
+    fn default() -> Self {
+        TextStyle {
+            colour: None,
+            font_id: Default::default(),
+            scale: 1.0,
+        }
+    }
+}
+
+impl TextStyle {
+    /// Construct with the default style
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the colour (chain style)
+    pub fn with_colour(mut self, colour: Colour) -> Self {
+        self.colour = Some(colour);
+        self
+    }
+
+    /// Set the font (chain style)
+    pub fn with_font(mut self, font_id: FontId) -> Self {
+        self.font_id = font_id;
+        self
+    }
+
+    /// Set the font size relative to the theme's font size (chain style)
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+}
+
+/// Rich text
+///
+/// A paragraph constructed from a sequence of `(text, style)` runs. The runs
+/// flow together: line-wrapping and alignment apply to the whole text. Runs
+/// may differ in font and size (see [`TextStyle`]); text measurement accounts
+/// for this.
+///
+/// Run colours are applied when drawn via [`DrawHandle::text_rich`]; other
+/// draw methods use the theme's colour. [`Label`] uses this automatically
+/// when constructed via [`Label::rich`].
+///
+/// [`DrawHandle::text_rich`]: crate::draw::DrawHandle::text_rich
+/// [`Label`]: crate::widget::Label
+/// [`Label::rich`]: crate::widget::Label::rich
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RichText {
+    text: String,
+    // Start (byte index) and style of each run
+    runs: Vec<(u32, TextStyle)>,
+}
+
+impl RichText {
+    /// Construct from a sequence of runs
+    pub fn new<S: AsRef<str>, I: IntoIterator<Item = (S, TextStyle)>>(runs: I) -> Self {
+        let mut rich = RichText::default();
+        for (text, style) in runs {
+            rich.push(text.as_ref(), style);
+        }
+        rich
+    }
+
+    /// Append a run
+    pub fn push(&mut self, text: &str, style: TextStyle) {
+        let start = self.text.len() as u32;
+        match self.runs.last_mut() {
+            // Replace an empty run
+            Some(run) if run.0 == start => run.1 = style,
+            _ => self.runs.push((start, style)),
+        }
+        self.text.push_str(text);
+    }
+
+    /// Iterate over runs as `(text, style)`
+    pub fn runs(&self) -> impl Iterator<Item = (&str, &TextStyle)> {
+        let ends = self.runs.iter().skip(1).map(|run| run.0 as usize);
+        let ends = ends.chain(std::iter::once(self.text.len()));
+        let text = &self.text;
+        self.runs
+            .iter()
+            .zip(ends)
+            .map(move |(run, end)| (&text[run.0 as usize..end], &run.1))
+    }
+
+    /// Get colour effects for drawing
+    ///
+    /// Runs without a colour use `default`. The result is suitable for
+    /// [`DrawText::text_effects`](crate::draw::DrawText::text_effects).
+    pub fn colour_effects(&self, default: Colour) -> Vec<Effect<Colour>> {
+        self.link_effects(default, &[])
+    }
+
+    /// Get colour effects for drawing, with links
+    ///
+    /// Like [`RichText::colour_effects`], except that each `(range, colour)`
+    /// in `links` is drawn underlined in the given colour. Ranges are byte
+    /// indices into the text and should not overlap.
+    pub fn link_effects(
+        &self,
+        default: Colour,
+        links: &[(Range<usize>, Colour)],
+    ) -> Vec<Effect<Colour>> {
+        let mut bounds: Vec<u32> = self.runs.iter().map(|run| run.0).collect();
+        for (range, _) in links {
+            bounds.push(range.start as u32);
+            bounds.push(range.end as u32);
+        }
+        bounds.sort_unstable();
+        bounds.dedup();
+
+        let mut effects: Vec<Effect<Colour>> = Vec::with_capacity(bounds.len());
+        for start in bounds {
+            if start as usize >= self.text.len() {
+                break;
+            }
+            let link = links.iter().find(|(r, _)| r.contains(&(start as usize)));
+            let (flags, aux) = match link {
+                Some((_, col)) => (EffectFlags::UNDERLINE, *col),
+                None => {
+                    let run = self.runs.iter().rev().find(|run| run.0 <= start);
+                    let col = run.and_then(|run| run.1.colour);
+                    (EffectFlags::empty(), col.unwrap_or(default))
+                }
+            };
+            let same = |e: &Effect<Colour>| e.flags == flags && e.aux == aux;
+            if !effects.last().map(same).unwrap_or(false) {
+                effects.push(Effect { start, flags, aux });
+            }
+        }
+        effects
+    }
+
+    fn font_token_vec(&self, dpp: f32, pt_size: f32) -> Vec<FontToken> {
+        self.runs
+            .iter()
+            .map(|(start, style)| FontToken {
+                start: *start,
+                font_id: style.font_id,
+                dpem: dpp * pt_size * style.scale,
+            })
+            .collect()
+    }
+}
+
+impl FormattableText for RichText {
+    #[cfg(feature = "gat")]
+    type FontTokenIter<'a> = std::vec::IntoIter<FontToken>;
+
+    #[inline]
+    fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    #[cfg(feature = "gat")]
+    fn font_tokens<'a>(&'a self, dpp: f32, pt_size: f32) -> Self::FontTokenIter<'a> {
+        self.font_token_vec(dpp, pt_size).into_iter()
+    }
+    #[cfg(not(feature = "gat"))]
+    fn font_tokens(&self, dpp: f32, pt_size: f32) -> OwningVecIter<FontToken> {
+        OwningVecIter::new(self.font_token_vec(dpp, pt_size))
+    }
+
+    fn effect_tokens(&self) -> &[Effect<()>] {
+        &[]
+    }
+}
+
+impl From<String> for RichText {
+    fn from(text: String) -> Self {
+        RichText::new(Some((text, TextStyle::default())))
+    }
+}
+
+impl From<&str> for RichText {
+    fn from(text: &str) -> Self {
+        RichText::new(Some((text, TextStyle::default())))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn runs() {
+        let red = Colour::new(1.0, 0.0, 0.0);
+        let rich = RichText::new(vec![
+            ("Hello ", TextStyle::new()),
+            ("", TextStyle::new().with_scale(2.0)),
+            ("world", TextStyle::new().with_colour(red).with_scale(1.5)),
+        ]);
+        assert_eq!(rich.as_str(), "Hello world");
+        let runs: Vec<_> = rich.runs().map(|(s, style)| (s, style.scale)).collect();
+        assert_eq!(runs, vec![("Hello ", 1.0), ("world", 1.5)]);
+
+        let tokens = rich.font_token_vec(2.0, 10.0);
+        assert_eq!(tokens.len(), 2);
+        assert_eq!((tokens[1].start, tokens[1].dpem), (6, 30.0));
+
+        let white = Colour::new(1.0, 1.0, 1.0);
+        let effects = rich.colour_effects(white);
+        let effects: Vec<_> = effects.iter().map(|e| (e.start, e.aux)).collect();
+        assert_eq!(effects, vec![(0, white), (6, red)]);
+    }
+
+    #[test]
+    fn links() {
+        let red = Colour::new(1.0, 0.0, 0.0);
+        let blue = Colour::new(0.0, 0.0, 1.0);
+        let white = Colour::new(1.0, 1.0, 1.0);
+        let rich = RichText::new(vec![
+            ("see ", TextStyle::new()),
+            ("the docs", TextStyle::new().with_colour(red)),
+            (" now", TextStyle::new()),
+        ]);
+        let effects = rich.link_effects(white, &[(8..12, blue), (16..20, blue)]);
+        let effects: Vec<_> = effects
+            .iter()
+            .map(|e| (e.start, e.flags == EffectFlags::UNDERLINE, e.aux))
+            .collect();
+        let expected = vec![
+            (0, false, white),
+            (4, false, red),
+            (8, true, blue),
+            (12, false, white),
+        ];
+        assert_eq!(effects, expected);
+    }
+}
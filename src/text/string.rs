@@ -152,7 +152,8 @@ impl From<&'static str> for AccelString {
     }
 }
 
-fn find_vkeys(c: char) -> VirtualKeyCodes {
+/// Find the keys matching a character, as used for accelerator keys
+pub(crate) fn find_vkeys(c: char) -> VirtualKeyCodes {
     // TODO: lots of keys aren't yet available in VirtualKeyCode!
     // NOTE: some of these bindings are a little inaccurate. It isn't obvious
     // whether prefer strict or more flexible bindings here.
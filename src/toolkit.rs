@@ -14,11 +14,37 @@
 //!
 //! [winit]: https://github.com/rust-windowing/winit
 
+use std::borrow::Cow;
 use std::num::NonZeroU32;
 
-use crate::draw::SizeHandle;
+use crate::draw::{ImageFilter, ImageHandle, SizeHandle};
+use crate::geom::{Rect, Size};
 use crate::{event, ThemeAction, ThemeApi};
 
+/// Format of clipboard content
+///
+/// Content is passed as bytes; text formats use UTF-8 encoding.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ClipboardFormat {
+    /// Plain text
+    PlainText,
+    /// HTML
+    Html,
+    /// Another format, identified by its MIME type
+    Custom(String),
+}
+
+impl ClipboardFormat {
+    /// Get the MIME type
+    pub fn mime_type(&self) -> &str {
+        match self {
+            ClipboardFormat::PlainText => "text/plain;charset=utf-8",
+            ClipboardFormat::Html => "text/html",
+            ClipboardFormat::Custom(mime) => mime,
+        }
+    }
+}
+
 /// Identifier for a window or pop-up
 ///
 /// Identifiers should always be unique.
@@ -68,17 +94,29 @@ pub enum TkAction {
     /// Used when a pop-up is closed or a region adjusted (e.g. scroll or switch
     /// tab) to update which widget is under the mouse cursor / touch events.
     /// Identifier is that of the parent widget/window encapsulating the region.
+    ///
+    /// This implies [`TkAction::Redraw`] but does not require resizing. Since
+    /// each pop-up is positioned relative to its parent widget, removing one
+    /// pop-up does not require repositioning of others.
     RegionMoved,
     /// A pop-up opened/closed/needs resizing
+    ///
+    /// This implies [`TkAction::RegionMoved`]; additionally all pop-ups are
+    /// resized and repositioned.
     Popup,
     /// Reset size of all widgets without recalculating requirements
     SetSize,
     /// Resize all widgets
+    ///
+    /// Size requirements are recalculated and widgets positioned by calling
+    /// [`kas::Layout::size_rules`] and [`kas::Layout::set_rect`]. Unlike
+    /// [`TkAction::Reconfigure`], widget identifiers and event state are kept.
     Resize,
     /// Whole window requires reconfiguring
     ///
     /// *Configuring* widgets assigns [`WidgetId`] identifiers and calls
-    /// [`kas::WidgetConfig::configure`].
+    /// [`kas::WidgetConfig::configure`]. Identifiers are unchanged unless
+    /// the structure of the widget tree changed (see [`WidgetId`]).
     ///
     /// [`WidgetId`]: crate::WidgetId
     /// [`event::Manager`]: crate::event::Manager
@@ -137,14 +175,36 @@ pub trait TkWindow {
     /// windows, will receive an update.
     fn trigger_update(&mut self, handle: event::UpdateHandle, payload: u64);
 
-    /// Attempt to get clipboard contents
+    /// Attempt to get clipboard contents in the given `format`
+    ///
+    /// Returns `None` if the clipboard has no content in this format (or the
+    /// format is not supported). In case of failure, paste actions will simply
+    /// fail. The implementation may wish to log an appropriate warning message.
+    fn get_clipboard_data(&mut self, format: &ClipboardFormat) -> Option<Vec<u8>>;
+
+    /// Attempt to set clipboard contents in the given `format`
     ///
-    /// In case of failure, paste actions will simply fail. The implementation
-    /// may wish to log an appropriate warning message.
-    fn get_clipboard(&mut self) -> Option<String>;
+    /// This replaces existing clipboard contents. Where the format is not
+    /// supported by the system clipboard, the implementation may hold the
+    /// content within the application.
+    fn set_clipboard_data(&mut self, format: ClipboardFormat, content: Vec<u8>);
 
-    /// Attempt to set clipboard contents
-    fn set_clipboard<'c>(&mut self, content: std::borrow::Cow<'c, str>);
+    /// Attempt to get clipboard contents as plain text
+    ///
+    /// This is a wrapper around [`TkWindow::get_clipboard_data`]; it returns
+    /// `None` if no text is available or it is not valid UTF-8.
+    fn get_clipboard(&mut self) -> Option<String> {
+        let data = self.get_clipboard_data(&ClipboardFormat::PlainText)?;
+        String::from_utf8(data).ok()
+    }
+
+    /// Attempt to set clipboard contents as plain text
+    ///
+    /// This is a wrapper around [`TkWindow::set_clipboard_data`].
+    fn set_clipboard<'c>(&mut self, content: Cow<'c, str>) {
+        let data = content.into_owned().into_bytes();
+        self.set_clipboard_data(ClipboardFormat::PlainText, data);
+    }
 
     /// Adjust the theme
     fn adjust_theme(&mut self, f: &mut dyn FnMut(&mut dyn ThemeApi) -> ThemeAction);
@@ -156,8 +216,22 @@ pub trait TkWindow {
     /// User-code *must not* depend on `f` being called for memory safety.
     fn size_handle(&mut self, f: &mut dyn FnMut(&mut dyn SizeHandle));
 
+    /// Load an image
+    ///
+    /// The image is given as `data` in RGBA format (8 bits per channel,
+    /// sRGB colour space, un-premultiplied alpha), row by row from the top,
+    /// and must have length `4 * size.0 * size.1`. The `filter` is used when
+    /// the image is drawn at other than its natural size.
+    fn load_image(&mut self, size: Size, data: &[u8], filter: ImageFilter) -> ImageHandle;
+
     /// Set the mouse cursor
     fn set_cursor_icon(&mut self, icon: event::CursorIcon);
+
+    /// Set the position of the input method (IME) candidate window
+    ///
+    /// The `rect` is that of the edit marker, in window coordinates. The
+    /// candidate window should be placed adjacent to (normally below) it.
+    fn set_ime_position(&mut self, rect: Rect);
 }
 
 #[cfg(test)]
@@ -167,7 +241,10 @@ mod test {
     #[test]
     fn action_precedence() {
         assert!(TkAction::None < TkAction::Redraw);
-        assert!(TkAction::Redraw < TkAction::Reconfigure);
+        assert!(TkAction::Redraw < TkAction::RegionMoved);
+        assert!(TkAction::RegionMoved < TkAction::Popup);
+        assert!(TkAction::Popup < TkAction::Resize);
+        assert!(TkAction::Resize < TkAction::Reconfigure);
         assert!(TkAction::Reconfigure < TkAction::Close);
         assert!(TkAction::Close < TkAction::CloseAll);
     }
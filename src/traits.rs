@@ -11,6 +11,7 @@ use std::ops::DerefMut;
 
 use crate::draw::SizeHandle;
 use crate::event::{self, Manager};
+use crate::geom::{Coord, LogicalSize, Size};
 use crate::{layout, Direction, WidgetId, WindowId};
 
 mod impls;
@@ -58,6 +59,14 @@ pub trait LayoutData {
 /// the parent should invoke [`Manager::pop_action`] and handle the action
 /// itself, where possible (using [`Manager::close_window`] to close it).
 /// Remaining actions should be added back to the [`Manager`].
+///
+/// A *modal* pop-up blocks input to everything beneath it: mouse and touch
+/// input outside the pop-up is discarded (instead of closing the pop-up),
+/// keyboard navigation and accelerator keys are restricted to the pop-up, and
+/// hotkeys targetting widgets beneath the pop-up are ignored. Pop-ups opened
+/// above a modal pop-up (e.g. a menu within a dialog) work as normal. Where
+/// several modal pop-ups are stacked, only the top-most receives input. The
+/// Escape key always closes the top-most pop-up, whether or not it is modal.
 //
 // NOTE: it's tempting to include a pointer to the widget here. There are two
 // options: (a) an unsafe aliased pointer or (b) Rc<RefCell<dyn WidgetConfig>>.
@@ -69,6 +78,17 @@ pub struct Popup {
     pub id: WidgetId,
     pub parent: WidgetId,
     pub direction: Direction,
+    /// If true, the pop-up is modal (see above)
+    pub modal: bool,
+    /// If set, the pop-up is placed at this point instead of adjacent to the
+    /// `parent` widget's rect
+    ///
+    /// The point uses the same coordinate space as events received by
+    /// `parent` (e.g. the `coord` of [`event::Event::PressStart`]). The
+    /// pop-up opens below and to the right of the point (or above and to the
+    /// left if `direction` is reversed), flipping where necessary to stay
+    /// within the window.
+    pub anchor: Option<Coord>,
 }
 
 /// Functionality required by a window
@@ -88,6 +108,30 @@ pub trait Window: Widget<Msg = event::VoidMsg> {
     /// windows.
     fn restrict_dimensions(&self) -> (bool, bool);
 
+    /// Explicit bounds on the window size
+    ///
+    /// Return value is `(min, max)`, in physical pixels (including margins).
+    /// Where given, these bounds apply in addition to those implied by
+    /// [`Window::restrict_dimensions`]: the effective minimum size is the
+    /// larger of the two minimums and the effective maximum the smaller of
+    /// the two maximums, but never less than the effective minimum.
+    ///
+    /// The default implementation returns `(None, None)`.
+    fn size_bounds(&self) -> (Option<Size>, Option<Size>) {
+        (None, None)
+    }
+
+    /// Explicit bounds on the window size, in logical pixels
+    ///
+    /// Return value is `(min, max)`. The toolkit converts these to physical
+    /// pixels using the window's scale factor and combines them with
+    /// [`Window::size_bounds`] in the same way.
+    ///
+    /// The default implementation returns `(None, None)`.
+    fn logical_size_bounds(&self) -> (Option<LogicalSize>, Option<LogicalSize>) {
+        (None, None)
+    }
+
     /// Add a pop-up as a layer in the current window
     ///
     /// Each [`Popup`] is assigned a [`WindowId`]; both are passed.
@@ -99,11 +143,38 @@ pub trait Window: Widget<Msg = event::VoidMsg> {
     /// existing pop-ups.
     fn resize_popups(&mut self, size_handle: &mut dyn SizeHandle);
 
+    /// Invalidate cached pop-up size rules
+    ///
+    /// Implementations may cache the size rules of pop-ups between calls to
+    /// [`Window::resize_popups`]. The toolkit calls this whenever size rules
+    /// may have changed, i.e. on [`crate::TkAction::Resize`] and
+    /// [`crate::TkAction::Reconfigure`], but not when the window is merely
+    /// resized.
+    ///
+    /// The default implementation does nothing.
+    fn invalidate_popup_rules(&mut self) {}
+
     /// Trigger closure of a pop-up
     ///
     /// If the given `id` refers to a pop-up, it should be closed.
     fn remove_popup(&mut self, mgr: &mut Manager, id: WindowId);
 
+    /// Handle a request to close the window
+    ///
+    /// This is called when the user asks to close the window, e.g. via the
+    /// title bar's close button. If this returns true, the window is closed;
+    /// if false, the request is ignored (e.g. to ask for confirmation first,
+    /// closing later via [`crate::TkAction::Close`] if confirmed).
+    ///
+    /// This is not called when the window is closed by the application, via
+    /// [`crate::TkAction::Close`], [`crate::TkAction::CloseAll`] or
+    /// [`Manager::close_window`]; such closure cannot be vetoed.
+    ///
+    /// The default implementation returns true.
+    fn handle_close_request(&mut self, _mgr: &mut Manager) -> bool {
+        true
+    }
+
     /// Handle closure of self
     ///
     /// This allows for actions on destruction, but doesn't need to do anything.
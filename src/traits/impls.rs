@@ -6,8 +6,9 @@
 //! Trait impls
 
 use super::*;
+use crate::access::AccessNode;
 use crate::draw::{DrawHandle, SizeHandle};
-use crate::event::{self, Event, Manager, Response};
+use crate::event::{self, ConfigureManager, Event, Manager, Response};
 use crate::geom::{Coord, Rect};
 use crate::layout::{AxisInfo, SizeRules};
 use crate::{AlignHints, CoreData, WidgetId};
@@ -75,6 +76,9 @@ impl<M: 'static> WidgetConfig for Box<dyn Widget<Msg = M>> {
     fn configure(&mut self, mgr: &mut Manager) {
         self.as_mut().configure(mgr);
     }
+    fn configure_recurse<'a, 'b>(&mut self, cmgr: ConfigureManager<'a, 'b>) {
+        self.as_mut().configure_recurse(cmgr);
+    }
 
     fn key_nav(&self) -> bool {
         self.as_ref().key_nav()
@@ -82,6 +86,9 @@ impl<M: 'static> WidgetConfig for Box<dyn Widget<Msg = M>> {
     fn cursor_icon(&self) -> event::CursorIcon {
         self.as_ref().cursor_icon()
     }
+    fn accessible(&self) -> AccessNode {
+        self.as_ref().accessible()
+    }
 }
 
 impl<M: 'static> Layout for Box<dyn Widget<Msg = M>> {
@@ -93,6 +100,14 @@ impl<M: 'static> Layout for Box<dyn Widget<Msg = M>> {
         self.as_mut().set_rect(rect, align);
     }
 
+    fn translation(&self, child_index: usize) -> Coord {
+        self.as_ref().translation(child_index)
+    }
+
+    fn spatial_range(&self) -> (usize, usize) {
+        self.as_ref().spatial_range()
+    }
+
     fn find_id(&self, coord: Coord) -> Option<WidgetId> {
         self.as_ref().find_id(coord)
     }
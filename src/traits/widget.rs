@@ -8,6 +8,7 @@
 use std::any::Any;
 use std::fmt;
 
+use crate::access::AccessNode;
 use crate::draw::{DrawHandle, InputState, SizeHandle};
 use crate::event::{self, ConfigureManager, Manager, ManagerState};
 use crate::geom::{Coord, Rect};
@@ -121,7 +122,8 @@ pub trait WidgetCore: Any + fmt::Debug {
     /// will be true if either `disabled` or `self.is_disabled()` are true.
     ///
     /// The error state defaults to `false` since most widgets don't support
-    /// this.
+    /// this. [`InputState::nav_focus`] is only set while focus is visible
+    /// (see [`ManagerState::focus_is_keyboard`]).
     fn input_state(&self, mgr: &ManagerState, disabled: bool) -> InputState {
         let id = self.core_data().id;
         let (char_focus, sel_focus) = mgr.char_focus(id);
@@ -130,7 +132,7 @@ pub trait WidgetCore: Any + fmt::Debug {
             error: false,
             hover: mgr.is_hovered(id),
             depress: mgr.is_depressed(id),
-            nav_focus: mgr.nav_focus(id),
+            nav_focus: mgr.nav_focus(id) && mgr.focus_is_keyboard(),
             char_focus,
             sel_focus,
         }
@@ -213,16 +215,8 @@ pub trait WidgetChildren: WidgetCore {
             }
         }
 
-        let (mut start, mut end) = (0, self.len());
-        while start + 1 < end {
-            let mid = start + (end - start) / 2;
-            if id <= self.get(mid - 1).unwrap().id() {
-                end = mid;
-            } else {
-                start = mid;
-            }
-        }
-        self.get(start).unwrap().find(id)
+        let index = find_child_index(self, id)?;
+        self.get(index).unwrap().find(id)
     }
 
     /// Find a child widget by identifier
@@ -238,16 +232,8 @@ pub trait WidgetChildren: WidgetCore {
             }
         }
 
-        let (mut start, mut end) = (0, self.len());
-        while start + 1 < end {
-            let mid = start + (end - start) / 2;
-            if id <= self.get(mid - 1).unwrap().id() {
-                end = mid;
-            } else {
-                start = mid;
-            }
-        }
-        self.get_mut(start).unwrap().find_mut(id)
+        let index = find_child_index(self, id)?;
+        self.get_mut(index).unwrap().find_mut(id)
     }
 
     /// Walk through all widgets, calling `f` once on each.
@@ -356,6 +342,16 @@ pub trait WidgetConfig: Layout {
     fn cursor_icon(&self) -> event::CursorIcon {
         event::CursorIcon::Default
     }
+
+    /// Describe this widget for accessibility tools
+    ///
+    /// Defaults to [`Role::Generic`] without label or value. See
+    /// [`kas::access`].
+    ///
+    /// [`Role::Generic`]: crate::access::Role::Generic
+    fn accessible(&self) -> AccessNode {
+        AccessNode::default()
+    }
 }
 
 /// Positioning and drawing routines for widgets
@@ -459,6 +455,12 @@ pub trait Layout: WidgetChildren {
     /// the coord is within the widget's rect (example: `CheckBox` contains an
     /// embedded `CheckBoxBare` and always forwards this child's id).
     ///
+    /// Since children are positioned within their parent's rect, step 1
+    /// prunes whole subtrees, thus the rect of each widget serves as the
+    /// bounding rect of its subtree. Widgets with many children may avoid
+    /// testing each child in step 2 (see
+    /// [`RowPositionSolver::find_child`](crate::layout::RowPositionSolver::find_child)).
+    ///
     /// This must not be called before [`Layout::set_rect`].
     #[inline]
     fn find_id(&self, coord: Coord) -> Option<WidgetId> {
@@ -513,6 +515,17 @@ pub trait Layout: WidgetChildren {
 /// [`derive(Widget)`]: macros/index.html#the-derivewidget-macro
 pub trait Widget: event::SendEvent {}
 
+/// A boxed widget with message type `M`
+///
+/// Containers such as [`Column`] are parametrised over a single child type;
+/// `Column<BoxedWidget<M>>` may hold children of differing types. Use
+/// [`Boxed::boxed`] to construct. Widgets with a different message type may be
+/// adapted with [`MsgInto`].
+///
+/// [`Column`]: crate::widget::Column
+/// [`MsgInto`]: crate::widget::MsgInto
+pub type BoxedWidget<M> = Box<dyn Widget<Msg = M>>;
+
 /// Provides a convenient `.boxed()` method on implementors
 pub trait Boxed<T: ?Sized> {
     /// Boxing method
@@ -524,3 +537,26 @@ impl<W: Widget + Sized> Boxed<dyn Widget<Msg = W::Msg>> for W {
         Box::new(self)
     }
 }
+
+/// Find the index of the child which may contain `id`
+///
+/// Children are ordered by identifier, hence this is the first child whose
+/// identifier is not less than `id`. Children for which
+/// [`WidgetChildren::get`] returns `None` (e.g. unconstructed pages of a
+/// [`LazyStack`](crate::widget::LazyStack)) are skipped.
+pub(crate) fn find_child_index<W>(widget: &W, id: WidgetId) -> Option<usize>
+where
+    W: WidgetChildren + ?Sized,
+{
+    let next =
+        |start: usize, end: usize| (start..end).find_map(|i| widget.get(i).map(|w| (i, w.id())));
+    let (mut start, mut end) = (0, widget.len());
+    while start < end {
+        let mid = start + (end - start) / 2;
+        match next(mid, end) {
+            Some((i, child_id)) if id > child_id => start = i + 1,
+            _ => end = mid,
+        }
+    }
+    next(start, widget.len()).map(|(i, _)| i)
+}
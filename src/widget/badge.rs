@@ -0,0 +1,268 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Badge overlay
+
+use kas::draw::TextClass;
+use kas::{event, prelude::*};
+
+/// A count badge drawn over a child widget
+///
+/// The child is drawn normally, then a small bubble containing a number is
+/// drawn over one of its corners (by default, the top-right corner).
+///
+/// The badge does not affect the size or position of the child. Towards its
+/// corner, the badge extends beyond the child's rect by at most the child's
+/// margin; a badge wider (or taller) than the child also extends beyond the
+/// opposite side. The badge is drawn after (above) the child.
+///
+/// The badge is hidden when the count is zero. Counts above 99 are shown as
+/// `99+`.
+#[handler(msg = <W as Handler>::Msg)]
+#[derive(Clone, Debug, Widget)]
+pub struct Badge<W: Widget> {
+    #[widget_core]
+    core: CoreData,
+    #[widget]
+    child: W,
+    count: u32,
+    position: (Align, Align),
+    label: Text<String>,
+    // The widest text we display, for sizing
+    reserve: Text<String>,
+    badge_size: Size,
+    badge_rect: Rect,
+    m0: Size,
+    m1: Size,
+}
+
+impl<W: Widget> Badge<W> {
+    /// Construct a badge over `child` with the given `count`
+    #[inline]
+    pub fn new(child: W, count: u32) -> Self {
+        Badge {
+            core: Default::default(),
+            child,
+            count,
+            position: (Align::BR, Align::TL),
+            label: Text::new_single(badge_text(count)),
+            reserve: Text::new_single(badge_text(100)),
+            badge_size: Size::ZERO,
+            badge_rect: Rect::default(),
+            m0: Size::ZERO,
+            m1: Size::ZERO,
+        }
+    }
+
+    /// Set the corner over which the badge is drawn (chain style)
+    ///
+    /// Positions are given as `(horizontal, vertical)` alignment: for example,
+    /// `(Align::TL, Align::BR)` is the bottom-left corner. `Align::Centre`
+    /// centres the badge on that axis; `Align::Default` and `Align::Stretch`
+    /// are treated as `Align::TL`.
+    #[inline]
+    pub fn with_position(mut self, horiz: Align, vert: Align) -> Self {
+        self.position = (horiz, vert);
+        self
+    }
+
+    /// Set the corner over which the badge is drawn
+    ///
+    /// See [`Badge::with_position`].
+    pub fn set_position(&mut self, horiz: Align, vert: Align) -> TkAction {
+        self.position = (horiz, vert);
+        self.place_badge();
+        TkAction::Redraw
+    }
+
+    /// Get the current count
+    #[inline]
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    /// Set the count
+    ///
+    /// A count of zero hides the badge.
+    ///
+    /// Note: this must not be called before fonts have been initialised
+    /// (usually done by the theme when the main loop starts).
+    pub fn set_count(&mut self, count: u32) -> TkAction {
+        if count == self.count {
+            return TkAction::None;
+        }
+        self.count = count;
+        self.label.set_text(badge_text(count));
+        self.label.prepare();
+        TkAction::Redraw
+    }
+
+    /// Access the child widget
+    #[inline]
+    pub fn inner(&self) -> &W {
+        &self.child
+    }
+
+    /// Access the child widget mutably
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut W {
+        &mut self.child
+    }
+
+    fn place_badge(&mut self) {
+        let rect = self.core.rect;
+        let h = self.badge_size.1;
+        // Pad the text by half the height, and use a circle for narrow text
+        let size = Size((self.badge_size.0 + h / 2).max(h), h);
+
+        let place = |align: Align, pos: i32, len: u32, w: u32, m0: u32, m1: u32| -> i32 {
+            match align {
+                Align::Centre => pos + (len as i32 - w as i32) / 2,
+                Align::BR => pos + len as i32 - w as i32 + (w / 2).min(m1) as i32,
+                Align::Default | Align::TL | Align::Stretch => pos - (w / 2).min(m0) as i32,
+            }
+        };
+        let x = place(
+            self.position.0,
+            rect.pos.0,
+            rect.size.0,
+            size.0,
+            self.m0.0,
+            self.m1.0,
+        );
+        let y = place(
+            self.position.1,
+            rect.pos.1,
+            rect.size.1,
+            size.1,
+            self.m0.1,
+            self.m1.1,
+        );
+        self.badge_rect = Rect::new(Coord(x, y), size);
+
+        self.label.update_env(|env| {
+            env.set_bounds(size.into());
+            env.set_align((Align::Centre, Align::Centre));
+        });
+    }
+}
+
+impl<W: Widget> Layout for Badge<W> {
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let rules = self.child.size_rules(size_handle, axis);
+        let m = rules.margins();
+
+        // Reserve enough room for the widest text we display
+        let badge_rules = size_handle.text_bound(&mut self.reserve, TextClass::Button, axis);
+        let _ = size_handle.text_bound(&mut self.label, TextClass::Button, axis);
+
+        if axis.is_horizontal() {
+            self.badge_size.0 = badge_rules.ideal_size();
+            self.m0.0 = m.0 as u32;
+            self.m1.0 = m.1 as u32;
+        } else {
+            self.badge_size.1 = badge_rules.ideal_size();
+            self.m0.1 = m.0 as u32;
+            self.m1.1 = m.1 as u32;
+        }
+
+        rules
+    }
+
+    fn set_rect(&mut self, rect: Rect, align: AlignHints) {
+        self.core.rect = rect;
+        self.child.set_rect(rect, align);
+        self.place_badge();
+    }
+
+    #[inline]
+    fn find_id(&self, coord: Coord) -> Option<WidgetId> {
+        if !self.rect().contains(coord) {
+            return None;
+        }
+        self.child.find_id(coord).or(Some(self.id()))
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, mgr: &event::ManagerState, disabled: bool) {
+        let disabled = disabled || self.is_disabled();
+        self.child.draw(draw_handle, mgr, disabled);
+        if self.count == 0 {
+            return;
+        }
+
+        let state = self.input_state(mgr, disabled);
+        let rect = self.badge_rect;
+        draw_handle.badge(rect, state);
+        draw_handle.text(rect.pos, &self.label, TextClass::Button, state.disabled);
+    }
+}
+
+fn badge_text(count: u32) -> String {
+    if count > 99 {
+        "99+".to_string()
+    } else {
+        count.to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::event::ManagerState;
+    use crate::test_util::{DrawCommand, RecordingDrawHandle, TestSizeHandle};
+    use crate::widget::CheckBoxBare;
+
+    #[test]
+    fn badge_text_limit() {
+        assert_eq!(badge_text(0), "0");
+        assert_eq!(badge_text(5), "5");
+        assert_eq!(badge_text(99), "99");
+        assert_eq!(badge_text(100), "99+");
+    }
+
+    #[test]
+    fn size_and_draw() {
+        let _ = crate::text::fonts::fonts().load_default();
+
+        let mut size_handle = TestSizeHandle::new();
+        let mut child = CheckBoxBare::<VoidMsg>::new();
+        let mut badge = Badge::new(CheckBoxBare::<VoidMsg>::new(), 3);
+
+        // The badge does not affect size
+        for vertical in [false, true].iter() {
+            let axis = AxisInfo::new(*vertical, None);
+            let rules = child.size_rules(&mut size_handle, axis);
+            assert_eq!(badge.size_rules(&mut size_handle, axis), rules);
+        }
+
+        // The badge fits the text "99+" (24×16 plus padding) and is placed
+        // over the top-right corner, extending to the right by no more than
+        // the margin; being wider than the child, it also extends to the left
+        let rect = Rect::new(Coord(10, 10), Size(16, 16));
+        badge.set_rect(rect, AlignHints::NONE);
+        let badge_rect = Rect::new(Coord(-2, 6), Size(32, 16));
+        assert_eq!(badge.badge_rect, badge_rect);
+
+        let mgr = ManagerState::new();
+        let draw_badge = |badge: &Badge<_>| {
+            let mut draw_handle = RecordingDrawHandle::new(rect);
+            badge.draw(&mut draw_handle, &mgr, false);
+            let commands = draw_handle.take_commands();
+            // The badge is drawn in the same pass as the child
+            assert!(!commands
+                .iter()
+                .any(|c| matches!(c, DrawCommand::ClipStart(..))));
+            commands.iter().find_map(|c| match c {
+                DrawCommand::Badge(rect, _) => Some(*rect),
+                _ => None,
+            })
+        };
+        assert_eq!(draw_badge(&badge), Some(badge_rect));
+
+        // A zero count hides the badge
+        assert_eq!(badge.set_count(0), TkAction::Redraw);
+        assert_eq!(draw_badge(&badge), None);
+    }
+}
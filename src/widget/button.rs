@@ -5,13 +5,129 @@
 
 //! Push-buttons
 
-use std::fmt::Debug;
+use std::convert::TryFrom;
+use std::fmt::{self, Debug};
+use std::rc::Rc;
 
-use kas::draw::TextClass;
-use kas::event::{self, VirtualKeyCode, VirtualKeyCodes};
+use kas::access::{AccessNode, Role};
+use kas::draw::{ImageId, InputState, TextClass};
+use kas::event::{self, UpdateHandle, VirtualKeyCode, VirtualKeyCodes};
 use kas::prelude::*;
 
+// Size rules for an icon: a square the height of a line of button text
+fn icon_rules(size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> (u32, SizeRules) {
+    let size = size_handle.line_height(TextClass::Button);
+    let margin = size_handle.inner_margin();
+    let margin = match axis.is_horizontal() {
+        true => margin.0,
+        false => margin.1,
+    } as u16;
+    (size, SizeRules::fixed(size, (margin, margin)))
+}
+
+// Contents of a text button: a label and optional icon, within a frame
+#[derive(Clone, Debug, Default)]
+struct ButtonContent {
+    // label_rect: Rect,
+    label: Text<AccelString>,
+    label_pos: Coord,
+    icon: Option<ImageId>,
+    icon_rect: Rect,
+    icon_size: u32,
+    // Space between icon and label, and between frame and content
+    icon_gap: u32,
+    inset: u32,
+}
+
+impl ButtonContent {
+    fn new(label: AccelString) -> Self {
+        ButtonContent {
+            label: Text::new_single(label),
+            ..Default::default()
+        }
+    }
+
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let sides = size_handle.button_surround();
+        let margins = size_handle.outer_margins();
+        let frame_rules = SizeRules::extract_fixed(axis.is_vertical(), sides.0 + sides.1, margins);
+
+        let mut content_rules = size_handle.text_bound(&mut self.label, TextClass::Button, axis);
+        if self.icon.is_some() {
+            let (size, icon_rules) = icon_rules(size_handle, axis);
+            self.icon_size = size;
+            if axis.is_horizontal() {
+                // The gap between icon and label is the larger inner margin
+                let gap = icon_rules.margins().1.max(content_rules.margins().0);
+                self.icon_gap = gap.into();
+                self.inset = sides.0 .0 + u32::from(icon_rules.margins().0);
+                content_rules = icon_rules.appended(content_rules);
+            } else {
+                content_rules.max_with(icon_rules);
+            }
+        }
+        content_rules.surrounded_by(frame_rules, true)
+    }
+
+    fn set_rect(&mut self, rect: Rect, align: AlignHints) {
+        self.label_pos = rect.pos;
+        let (horiz, vert) = align.unwrap_or(Align::Centre, Align::Centre);
+
+        if self.icon.is_none() {
+            // In theory, text rendering should be restricted as in EditBox.
+            // In practice, it sometimes overflows a tiny bit, and looks better if
+            // we let it overflow. Since the text is centred this is okay.
+            // self.label_rect = ...
+            self.label.update_env(|env| {
+                env.set_bounds(rect.size.into());
+                env.set_align((horiz, vert));
+            });
+            return;
+        }
+
+        // Icon and label are aligned together, as a group
+        let space = self.icon_size + self.icon_gap;
+        let required = self.label.update_env(|env| {
+            env.set_bounds(Size(rect.size.0.saturating_sub(space), rect.size.1).into());
+            env.set_align((Align::Default, vert));
+        });
+        let width = (space + required.0.ceil() as u32).min(rect.size.0);
+        let excess = rect.size.0 - width;
+        let x = match horiz {
+            Align::Centre | Align::Stretch => excess / 2,
+            Align::BR => excess.saturating_sub(self.inset),
+            Align::Default | Align::TL => self.inset.min(excess),
+        };
+
+        let icon_size = Size::uniform(self.icon_size.min(rect.size.1));
+        let y = (rect.size.1 - icon_size.1) / 2;
+        self.icon_rect = Rect::new(rect.pos + Size(x, y), icon_size);
+        self.label_pos = rect.pos + Size((x + space).min(rect.size.0), 0);
+        let bounds = Size(rect.size.0 - (x + space).min(rect.size.0), rect.size.1);
+        self.label.update_env(|env| env.set_bounds(bounds.into()));
+    }
+
+    fn draw(
+        &self,
+        draw_handle: &mut dyn DrawHandle,
+        mgr: &event::ManagerState,
+        rect: Rect,
+        state: InputState,
+    ) {
+        draw_handle.button(rect, state);
+        if let Some(icon) = self.icon {
+            draw_handle.image(icon, self.icon_rect);
+        }
+        let accel = mgr.show_accel_labels();
+        let class = TextClass::Button;
+        draw_handle.text_accel(self.label_pos, &self.label, accel, class, state.disabled);
+    }
+}
+
 /// A push-button with a text label
+///
+/// Optionally, an icon may be drawn before the label
+/// (see [`TextButton::with_icon`]).
 #[handler(handle=noauto)]
 #[widget(config=noauto)]
 #[derive(Clone, Debug, Default, Widget)]
@@ -19,49 +135,38 @@ pub struct TextButton<M: Clone + Debug + 'static> {
     #[widget_core]
     core: kas::CoreData,
     keys1: VirtualKeyCodes,
-    // label_rect: Rect,
-    label: Text<AccelString>,
+    content: ButtonContent,
     msg: M,
 }
 
 impl<M: Clone + Debug + 'static> WidgetConfig for TextButton<M> {
     fn configure(&mut self, mgr: &mut Manager) {
         mgr.add_accel_keys(self.id(), &self.keys1);
-        mgr.add_accel_keys(self.id(), &self.label.text().keys());
+        mgr.add_accel_keys(self.id(), &self.content.label.text().keys());
     }
 
     fn key_nav(&self) -> bool {
         true
     }
+
+    fn accessible(&self) -> AccessNode {
+        AccessNode::new(Role::Button).with_label(self.content.label.as_str())
+    }
 }
 
 impl<M: Clone + Debug + 'static> Layout for TextButton<M> {
     fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
-        let sides = size_handle.button_surround();
-        let margins = size_handle.outer_margins();
-        let frame_rules = SizeRules::extract_fixed(axis.is_vertical(), sides.0 + sides.1, margins);
-
-        let content_rules = size_handle.text_bound(&mut self.label, TextClass::Button, axis);
-        content_rules.surrounded_by(frame_rules, true)
+        self.content.size_rules(size_handle, axis)
     }
 
     fn set_rect(&mut self, rect: Rect, align: AlignHints) {
         self.core.rect = rect;
-
-        // In theory, text rendering should be restricted as in EditBox.
-        // In practice, it sometimes overflows a tiny bit, and looks better if
-        // we let it overflow. Since the text is centred this is okay.
-        // self.label_rect = ...
-        self.label.update_env(|env| {
-            env.set_bounds(rect.size.into());
-            env.set_align(align.unwrap_or(Align::Centre, Align::Centre));
-        });
+        self.content.set_rect(rect, align);
     }
 
     fn draw(&self, draw_handle: &mut dyn DrawHandle, mgr: &event::ManagerState, disabled: bool) {
-        draw_handle.button(self.core.rect, self.input_state(mgr, disabled));
-        let state = mgr.show_accel_labels();
-        draw_handle.text_accel(self.core.rect.pos, &self.label, state, TextClass::Button);
+        let state = self.input_state(mgr, disabled);
+        self.content.draw(draw_handle, mgr, self.core.rect, state);
     }
 }
 
@@ -73,17 +178,24 @@ impl<M: Clone + Debug + 'static> TextButton<M> {
     /// simple `Copy` type (e.g. an enum). Click actions must be implemented on
     /// the parent (or other ancestor).
     pub fn new<S: Into<AccelString>>(label: S, msg: M) -> Self {
-        let label = label.into();
-        let text = Text::new_single(label);
         TextButton {
             core: Default::default(),
             keys1: Default::default(),
-            // label_rect: Default::default(),
-            label: text,
+            content: ButtonContent::new(label.into()),
             msg,
         }
     }
 
+    /// Draw an icon before the label (chain style)
+    ///
+    /// The icon is drawn as a square with side equal to the height of a line
+    /// of text, scaled as necessary. Images are loaded via
+    /// [`Manager::load_image`].
+    pub fn with_icon(mut self, icon: ImageId) -> Self {
+        self.content.icon = Some(icon);
+        self
+    }
+
     /// Add accelerator keys (chain style)
     ///
     /// These keys are added to those inferred from the label via `&` marks.
@@ -101,13 +213,13 @@ impl<M: Clone + Debug + 'static> TextButton<M> {
 
 impl<M: Clone + Debug + 'static> HasStr for TextButton<M> {
     fn get_str(&self) -> &str {
-        self.label.as_str()
+        self.content.label.as_str()
     }
 }
 
 impl<M: Clone + Debug + 'static> SetAccel for TextButton<M> {
     fn set_accel_string(&mut self, string: AccelString) -> TkAction {
-        kas::text::util::set_text_and_prepare(&mut self.label, string)
+        kas::text::util::set_text_and_prepare(&mut self.content.label, string)
     }
 }
 
@@ -126,3 +238,453 @@ impl<M: Clone + Debug + 'static> event::Handler for TextButton<M> {
         }
     }
 }
+
+/// A push-button showing only an icon
+///
+/// The icon is drawn as a square with side equal to the height of a line of
+/// button text (thus the button has the same height as a [`TextButton`]),
+/// scaled as necessary. Images are loaded via [`Manager::load_image`].
+///
+/// Since no text is drawn, a label should be set via
+/// [`IconButton::with_label`] for use by assistive technology.
+#[handler(handle=noauto)]
+#[widget(config=noauto)]
+#[derive(Clone, Debug, Widget)]
+pub struct IconButton<M: Clone + Debug + 'static> {
+    #[widget_core]
+    core: kas::CoreData,
+    keys1: VirtualKeyCodes,
+    icon: ImageId,
+    icon_rect: Rect,
+    icon_size: u32,
+    label: String,
+    msg: M,
+}
+
+impl<M: Clone + Debug + 'static> WidgetConfig for IconButton<M> {
+    fn configure(&mut self, mgr: &mut Manager) {
+        mgr.add_accel_keys(self.id(), &self.keys1);
+    }
+
+    fn key_nav(&self) -> bool {
+        true
+    }
+
+    fn accessible(&self) -> AccessNode {
+        AccessNode::new(Role::Button).with_label(&self.label)
+    }
+}
+
+impl<M: Clone + Debug + 'static> Layout for IconButton<M> {
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let sides = size_handle.button_surround();
+        let margins = size_handle.outer_margins();
+        let frame_rules = SizeRules::extract_fixed(axis.is_vertical(), sides.0 + sides.1, margins);
+
+        let (size, content_rules) = icon_rules(size_handle, axis);
+        self.icon_size = size;
+        content_rules.surrounded_by(frame_rules, true)
+    }
+
+    fn set_rect(&mut self, rect: Rect, align: AlignHints) {
+        self.core.rect = rect;
+        let size = Size::uniform(self.icon_size).min(rect.size);
+        self.icon_rect = align
+            .complete(Align::Centre, Align::Centre, size)
+            .apply(rect);
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, mgr: &event::ManagerState, disabled: bool) {
+        draw_handle.button(self.core.rect, self.input_state(mgr, disabled));
+        draw_handle.image(self.icon, self.icon_rect);
+    }
+}
+
+impl<M: Clone + Debug + 'static> IconButton<M> {
+    /// Construct a button with a given `icon` and `msg`
+    ///
+    /// The message `msg` is returned to the parent widget on activation, as
+    /// for [`TextButton::new`].
+    pub fn new(icon: ImageId, msg: M) -> Self {
+        IconButton {
+            core: Default::default(),
+            keys1: Default::default(),
+            icon,
+            icon_rect: Rect::default(),
+            icon_size: 0,
+            label: String::new(),
+            msg,
+        }
+    }
+
+    /// Set the label (chain style)
+    ///
+    /// The label is not drawn but is reported to assistive technology.
+    pub fn with_label<S: Into<String>>(mut self, label: S) -> Self {
+        self.label = label.into();
+        self
+    }
+
+    /// Add accelerator keys (chain style)
+    pub fn with_keys(mut self, keys: &[VirtualKeyCode]) -> Self {
+        self.keys1.clear();
+        self.keys1.extend_from_slice(keys);
+        self
+    }
+
+    /// Replace the icon
+    pub fn set_icon(&mut self, icon: ImageId) -> TkAction {
+        self.icon = icon;
+        TkAction::Redraw
+    }
+
+    /// Replace the message value
+    pub fn set_msg(&mut self, msg: M) {
+        self.msg = msg;
+    }
+}
+
+impl<M: Clone + Debug + 'static> HasStr for IconButton<M> {
+    fn get_str(&self) -> &str {
+        &self.label
+    }
+}
+
+impl<M: Clone + Debug + 'static> event::Handler for IconButton<M> {
+    type Msg = M;
+
+    #[inline]
+    fn activation_via_press(&self) -> bool {
+        true
+    }
+
+    fn handle(&mut self, _: &mut Manager, event: Event) -> Response<M> {
+        match event {
+            Event::Activate => self.msg.clone().into(),
+            event => Response::Unhandled(event),
+        }
+    }
+}
+
+/// A button which latches in the pressed state while on
+///
+/// This behaves like a [`CheckBox`](super::CheckBox) but appears as a
+/// [`TextButton`], which is drawn depressed while on. Each toggle emits the
+/// new state via the closure set with [`ToggleButton::on_toggle`].
+///
+/// Toggle buttons may be grouped via [`ToggleButton::with_group`], in which
+/// case at most one button of the group is on at any time. As with
+/// [`RadioBox`](super::RadioBox), a grouped button is not turned off by
+/// activation, and a button turned off by activation of another group member
+/// does not emit a message.
+#[handler(handle=noauto)]
+#[widget(config=noauto)]
+#[derive(Clone, Widget)]
+pub struct ToggleButton<M: 'static> {
+    #[widget_core]
+    core: CoreData,
+    keys1: VirtualKeyCodes,
+    content: ButtonContent,
+    state: bool,
+    group: Option<UpdateHandle>,
+    on_toggle: Option<Rc<dyn Fn(bool) -> M>>,
+}
+
+impl<M: 'static> Debug for ToggleButton<M> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "ToggleButton {{ core: {:?}, content: {:?}, state: {:?}, group: {:?}, ... }}",
+            self.core, self.content, self.state, self.group,
+        )
+    }
+}
+
+impl<M: 'static> WidgetConfig for ToggleButton<M> {
+    fn configure(&mut self, mgr: &mut Manager) {
+        mgr.add_accel_keys(self.id(), &self.keys1);
+        mgr.add_accel_keys(self.id(), &self.content.label.text().keys());
+        if let Some(handle) = self.group {
+            mgr.update_on_handle(handle, self.id());
+        }
+    }
+
+    fn key_nav(&self) -> bool {
+        true
+    }
+
+    fn accessible(&self) -> AccessNode {
+        let role = match self.group {
+            None => Role::CheckBox,
+            Some(_) => Role::RadioButton,
+        };
+        AccessNode::new(role)
+            .with_label(self.content.label.as_str())
+            .with_checked(self.state)
+    }
+}
+
+impl<M: 'static> Layout for ToggleButton<M> {
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        self.content.size_rules(size_handle, axis)
+    }
+
+    fn set_rect(&mut self, rect: Rect, align: AlignHints) {
+        self.core.rect = rect;
+        self.content.set_rect(rect, align);
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, mgr: &event::ManagerState, disabled: bool) {
+        let mut state = self.input_state(mgr, disabled);
+        state.depress = state.depress || self.state;
+        self.content.draw(draw_handle, mgr, self.core.rect, state);
+    }
+}
+
+impl<M: 'static> ToggleButton<M> {
+    /// Construct a toggle button which calls `f` when toggled
+    ///
+    /// This is a shortcut for `ToggleButton::new(label).on_toggle(f)`.
+    ///
+    /// The closure `f` is called with the new state of the button when
+    /// toggled, and the result of `f` is returned from the event handler.
+    #[inline]
+    pub fn new_on<S: Into<AccelString>, F>(label: S, f: F) -> Self
+    where
+        F: Fn(bool) -> M + 'static,
+    {
+        ToggleButton {
+            core: Default::default(),
+            keys1: Default::default(),
+            content: ButtonContent::new(label.into()),
+            state: false,
+            group: None,
+            on_toggle: Some(Rc::new(f)),
+        }
+    }
+
+    /// Set the initial state (chain style)
+    #[inline]
+    pub fn state(mut self, state: bool) -> Self {
+        self.state = state;
+        self
+    }
+
+    /// Make this button part of an exclusive group (chain style)
+    ///
+    /// All toggle buttons constructed over the same `handle` are considered
+    /// part of a single group. When one is turned on, all others are turned
+    /// off. Ensure that at most one button of the group has initial state on.
+    #[inline]
+    pub fn with_group(mut self, handle: UpdateHandle) -> Self {
+        self.group = Some(handle);
+        self
+    }
+
+    /// Draw an icon before the label (chain style)
+    ///
+    /// See [`TextButton::with_icon`].
+    pub fn with_icon(mut self, icon: ImageId) -> Self {
+        self.content.icon = Some(icon);
+        self
+    }
+
+    /// Add accelerator keys (chain style)
+    ///
+    /// These keys are added to those inferred from the label via `&` marks.
+    pub fn with_keys(mut self, keys: &[VirtualKeyCode]) -> Self {
+        self.keys1.clear();
+        self.keys1.extend_from_slice(keys);
+        self
+    }
+}
+
+impl ToggleButton<VoidMsg> {
+    /// Construct a toggle button with a given `label`
+    #[inline]
+    pub fn new<S: Into<AccelString>>(label: S) -> Self {
+        ToggleButton {
+            core: Default::default(),
+            keys1: Default::default(),
+            content: ButtonContent::new(label.into()),
+            state: false,
+            group: None,
+            on_toggle: None,
+        }
+    }
+
+    /// Set the event handler to be called on toggle.
+    ///
+    /// The closure `f` is called with the new state of the button when
+    /// toggled, and the result of `f` is returned from the event handler.
+    #[inline]
+    pub fn on_toggle<M, F>(self, f: F) -> ToggleButton<M>
+    where
+        F: Fn(bool) -> M + 'static,
+    {
+        ToggleButton {
+            core: self.core,
+            keys1: self.keys1,
+            content: self.content,
+            state: self.state,
+            group: self.group,
+            on_toggle: Some(Rc::new(f)),
+        }
+    }
+}
+
+impl<M: 'static> HasBool for ToggleButton<M> {
+    fn get_bool(&self) -> bool {
+        self.state
+    }
+
+    /// Set the state
+    ///
+    /// Note that other members of a group are not updated by this method.
+    fn set_bool(&mut self, state: bool) -> TkAction {
+        self.state = state;
+        TkAction::Redraw
+    }
+}
+
+impl<M: 'static> HasStr for ToggleButton<M> {
+    fn get_str(&self) -> &str {
+        self.content.label.as_str()
+    }
+}
+
+impl<M: 'static> SetAccel for ToggleButton<M> {
+    fn set_accel_string(&mut self, string: AccelString) -> TkAction {
+        kas::text::util::set_text_and_prepare(&mut self.content.label, string)
+    }
+}
+
+impl<M: 'static> event::Handler for ToggleButton<M> {
+    type Msg = M;
+
+    #[inline]
+    fn activation_via_press(&self) -> bool {
+        true
+    }
+
+    fn handle(&mut self, mgr: &mut Manager, event: Event) -> Response<M> {
+        match event {
+            Event::Activate => {
+                if self.group.is_some() && self.state {
+                    return Response::None;
+                }
+                self.state = !self.state;
+                mgr.redraw(self.id());
+                if let (Some(handle), true) = (self.group, self.state) {
+                    mgr.trigger_update(handle, self.id().into());
+                }
+                if let Some(ref f) = self.on_toggle {
+                    f(self.state).into()
+                } else {
+                    Response::None
+                }
+            }
+            Event::HandleUpdate { handle, payload } if Some(handle) == self.group => {
+                let id = WidgetId::try_from(payload).unwrap();
+                if id != self.id() && self.state {
+                    self.state = false;
+                    mgr.redraw(self.id());
+                }
+                Response::None
+            }
+            event => Response::Unhandled(event),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::TestSizeHandle;
+
+    #[test]
+    fn icon_sizes() {
+        let mut size_handle = TestSizeHandle::new();
+        let horiz = AxisInfo::new(false, None);
+        let vert = AxisInfo::new(true, None);
+        let icon = ImageId::new(1);
+
+        // Icon-only buttons are square
+        let mut button = IconButton::new(icon, ()).with_label("Open");
+        let w = button.size_rules(&mut size_handle, horiz).ideal_size();
+        let h = button.size_rules(&mut size_handle, vert).ideal_size();
+        assert_eq!((w, h), (28, 28));
+        assert_eq!(button.accessible().label.as_deref(), Some("Open"));
+
+        // An icon adds its size plus one (inner) margin
+        let mut plain = TextButton::new("Open", ());
+        let mut button = TextButton::new("Open", ()).with_icon(icon);
+        let plain_w = plain.size_rules(&mut size_handle, horiz).ideal_size();
+        let w = button.size_rules(&mut size_handle, horiz).ideal_size();
+        assert_eq!(w, plain_w + 16 + 2);
+        let plain_h = plain.size_rules(&mut size_handle, vert).ideal_size();
+        let h = button.size_rules(&mut size_handle, vert).ideal_size();
+        assert_eq!(h, plain_h);
+    }
+
+    #[test]
+    fn toggle_access() {
+        let bold = ToggleButton::new("&Bold").state(true);
+        let node = bold.accessible();
+        assert_eq!(node.role, Role::CheckBox);
+        assert_eq!(node.label.as_deref(), Some("Bold"));
+        assert_eq!(node.checked, Some(true));
+
+        let left = ToggleButton::new("Left").with_group(UpdateHandle::new());
+        assert_eq!(left.accessible().role, Role::RadioButton);
+        assert_eq!(left.accessible().checked, Some(false));
+    }
+
+    #[cfg(feature = "winit")]
+    #[layout(single)]
+    #[handler(msg = VoidMsg)]
+    #[derive(Debug, Widget)]
+    struct Collect {
+        #[widget_core]
+        core: CoreData,
+        #[widget(handler = collect)]
+        button: TextButton<u32>,
+        msgs: Vec<u32>,
+    }
+
+    #[cfg(feature = "winit")]
+    impl Collect {
+        fn collect(&mut self, _: &mut Manager, msg: u32) -> Response<VoidMsg> {
+            self.msgs.push(msg);
+            Response::None
+        }
+    }
+
+    #[cfg(feature = "winit")]
+    #[test]
+    fn slow_click() {
+        use crate::event::{EventConfig, MouseButton};
+        use crate::test_util::TestManager;
+        use std::time::Duration;
+
+        let _ = crate::text::fonts::fonts().load_default();
+        let mut test = TestManager::new(Collect {
+            core: Default::default(),
+            button: TextButton::new("button", 1),
+            msgs: vec![],
+        });
+        test.set_rect(Rect::new(Coord::ZERO, Size(80, 30)));
+        // A long-press fires on the first timer update
+        test.state.set_config(EventConfig {
+            long_press_time: Duration::from_secs(0),
+            ..Default::default()
+        });
+
+        // The button does not handle long-presses, so a slow click activates
+        test.mouse_move(Coord(40, 15));
+        test.mouse_input(MouseButton::Left, true);
+        test.with(|mgr, widget| mgr.update_timer(widget));
+        test.mouse_input(MouseButton::Left, false);
+        assert_eq!(test.widget.msgs, vec![1]);
+    }
+}
@@ -9,10 +9,11 @@ use std::fmt::{self, Debug};
 use std::rc::Rc;
 
 use super::AccelLabel;
+use kas::access::{AccessNode, Role};
 use kas::{event, prelude::*};
 
 /// A bare checkbox (no label)
-#[widget(config(key_nav = true))]
+#[widget(config=noauto)]
 #[handler(handle=noauto)]
 #[derive(Clone, Default, Widget)]
 pub struct CheckBoxBare<M: 'static> {
@@ -52,6 +53,16 @@ impl<M: 'static> Layout for CheckBoxBare<M> {
     }
 }
 
+impl<M: 'static> WidgetConfig for CheckBoxBare<M> {
+    fn key_nav(&self) -> bool {
+        true
+    }
+
+    fn accessible(&self) -> AccessNode {
+        AccessNode::new(Role::CheckBox).with_checked(self.state)
+    }
+}
+
 impl<M: 'static> CheckBoxBare<M> {
     /// Construct a checkbox which calls `f` when toggled
     ///
@@ -238,6 +249,10 @@ impl<M: 'static> WidgetConfig for CheckBox<M> {
     fn configure(&mut self, mgr: &mut Manager) {
         mgr.add_accel_keys(self.checkbox.id(), self.label.keys());
     }
+
+    fn accessible(&self) -> AccessNode {
+        self.checkbox.accessible().with_label(self.label.get_str())
+    }
 }
 
 impl<M: 'static> HasBool for CheckBox<M> {
@@ -251,3 +266,92 @@ impl<M: 'static> HasBool for CheckBox<M> {
         self.checkbox.set_bool(state)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::{DrawCommand, RecordingDrawHandle, TestSizeHandle};
+
+    #[test]
+    fn labelled_checkbox() {
+        let _ = crate::text::fonts::fonts().load_default();
+
+        let mut checkbox = CheckBox::new("Label");
+        let mut id = WidgetId::FIRST;
+        checkbox.walk_mut(|w| {
+            w.core_data_mut().id = id;
+            id = id.next();
+        });
+
+        let mut size_handle = TestSizeHandle::new();
+        let horiz = checkbox.size_rules(&mut size_handle, AxisInfo::new(false, None));
+        let axis = AxisInfo::new(true, Some(horiz.ideal_size()));
+        let vert = checkbox.size_rules(&mut size_handle, axis);
+        let rect = Rect::new(Coord::ZERO, Size(horiz.ideal_size(), vert.ideal_size()));
+        checkbox.set_rect(rect, AlignHints::NONE);
+
+        // The box is followed by its label; both activate the box
+        let (box_rect, label_rect) = (checkbox.checkbox.rect(), checkbox.label.rect());
+        assert!(box_rect.pos_end().0 <= label_rect.pos.0);
+        let box_id = checkbox.checkbox.id();
+        assert_eq!(checkbox.find_id(box_rect.pos), Some(box_id));
+        assert_eq!(checkbox.find_id(label_rect.pos), Some(box_id));
+
+        let mgr = ManagerState::new();
+        let checked = |checkbox: &CheckBox<VoidMsg>| {
+            let mut draw_handle = RecordingDrawHandle::new(rect);
+            checkbox.draw(&mut draw_handle, &mgr, false);
+            let commands = draw_handle.take_commands();
+            let marks = commands.iter().filter_map(|c| match c {
+                DrawCommand::Checkbox(_, checked, _) => Some(*checked),
+                _ => None,
+            });
+            marks.collect::<Vec<_>>()
+        };
+        assert_eq!(checked(&checkbox), vec![false]);
+        let _ = checkbox.set_bool(true);
+        assert_eq!(checked(&checkbox), vec![true]);
+    }
+
+    #[test]
+    fn disabled_dimmed() {
+        let _ = crate::text::fonts::fonts().load_default();
+
+        let mut checkbox = CheckBox::new("Label");
+        let mut size_handle = TestSizeHandle::new();
+        let horiz = checkbox.size_rules(&mut size_handle, AxisInfo::new(false, None));
+        let axis = AxisInfo::new(true, Some(horiz.ideal_size()));
+        let vert = checkbox.size_rules(&mut size_handle, axis);
+        let rect = Rect::new(Coord::ZERO, Size(horiz.ideal_size(), vert.ideal_size()));
+        checkbox.set_rect(rect, AlignHints::NONE);
+
+        let mgr = ManagerState::new();
+        let draw = |disabled: bool| {
+            let mut draw_handle = RecordingDrawHandle::new(rect);
+            checkbox.draw(&mut draw_handle, &mgr, disabled);
+            draw_handle.take_commands()
+        };
+
+        let box_state = |commands: &[DrawCommand]| {
+            commands.iter().find_map(|c| match c {
+                DrawCommand::Checkbox(_, _, state) => Some(state.disabled),
+                _ => None,
+            })
+        };
+        let text_disabled = |commands: &[DrawCommand]| {
+            commands.iter().find_map(|c| match c {
+                DrawCommand::TextAccel(.., disabled) => Some(*disabled),
+                _ => None,
+            })
+        };
+
+        let commands = draw(false);
+        assert_eq!(box_state(&commands), Some(false));
+        assert_eq!(text_disabled(&commands), Some(false));
+
+        // The label is drawn in the disabled style
+        let commands = draw(true);
+        assert_eq!(box_state(&commands), Some(true));
+        assert_eq!(text_disabled(&commands), Some(true));
+    }
+}
@@ -0,0 +1,517 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Colour picker
+
+use super::{Column, MenuFrame, Row, Slider};
+use kas::draw::{Colour, TextClass};
+use kas::event::{self, ControlKey, GrabMode};
+use kas::prelude::*;
+use kas::WindowId;
+
+/// Number of swatches per palette row
+const PALETTE_ROW: usize = 8;
+
+/// The default palette
+const PALETTE: [[f32; 3]; 16] = [
+    [0.0, 0.0, 0.0],
+    [0.25, 0.25, 0.25],
+    [0.5, 0.5, 0.5],
+    [0.75, 0.75, 0.75],
+    [1.0, 1.0, 1.0],
+    [0.5, 0.25, 0.0],
+    [1.0, 0.5, 0.5],
+    [1.0, 0.85, 0.7],
+    [1.0, 0.0, 0.0],
+    [1.0, 0.5, 0.0],
+    [1.0, 1.0, 0.0],
+    [0.0, 0.75, 0.0],
+    [0.0, 0.75, 0.75],
+    [0.0, 0.0, 1.0],
+    [0.5, 0.0, 1.0],
+    [1.0, 0.0, 1.0],
+];
+
+/// A colour swatch entry within the palette
+#[widget(config(key_nav = true))]
+#[handler(handle=noauto)]
+#[derive(Clone, Debug, Default, Widget)]
+struct ColourSwatch {
+    #[widget_core]
+    core: CoreData,
+    colour: Colour,
+    frame: Size,
+    inner: Rect,
+}
+
+impl Layout for ColourSwatch {
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        self.frame = size_handle.menu_frame();
+        let len = size_handle.line_height(TextClass::Label);
+        let size = Size(len, len) + self.frame + self.frame;
+        SizeRules::extract_fixed(axis.is_vertical(), size, Margins::ZERO)
+    }
+
+    fn set_rect(&mut self, rect: Rect, _: AlignHints) {
+        self.core.rect = rect;
+        let size = rect.size - self.frame - self.frame;
+        self.inner = Rect::new(rect.pos + self.frame, size);
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, mgr: &event::ManagerState, disabled: bool) {
+        draw_handle.menu_entry(self.core.rect, self.input_state(mgr, disabled));
+        draw_handle.swatch(self.inner, self.colour);
+    }
+}
+
+impl event::Handler for ColourSwatch {
+    type Msg = Colour;
+
+    fn handle(&mut self, _: &mut Manager, event: Event) -> Response<Colour> {
+        match event {
+            Event::Activate => Response::Msg(self.colour),
+            event => Response::Unhandled(event),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Pick {
+    Colour(Colour),
+    Alpha(f32),
+}
+
+#[layout(column)]
+#[handler(msg = Pick)]
+#[derive(Clone, Debug, Widget)]
+struct ColourPopup {
+    #[widget_core]
+    core: CoreData,
+    #[layout_data]
+    layout_data: <Self as kas::LayoutData>::Data,
+    #[widget(handler = pick_colour)]
+    palette: Column<Row<ColourSwatch>>,
+    // Contains a slider only when alpha is enabled
+    #[widget(handler = pick_alpha)]
+    alpha: Column<Slider<f32, kas::Right>>,
+}
+
+impl ColourPopup {
+    fn new(palette: &[Colour]) -> Self {
+        ColourPopup {
+            core: Default::default(),
+            layout_data: Default::default(),
+            palette: Self::palette(palette),
+            alpha: Column::new(vec![]),
+        }
+    }
+
+    fn palette(palette: &[Colour]) -> Column<Row<ColourSwatch>> {
+        let rows = palette.chunks(PALETTE_ROW).map(|row| {
+            let swatches = row.iter().map(|colour| ColourSwatch {
+                core: Default::default(),
+                colour: *colour,
+                frame: Size::ZERO,
+                inner: Rect::default(),
+            });
+            Row::new(swatches.collect())
+        });
+        Column::new(rows.collect())
+    }
+
+    fn swatches(&self) -> impl Iterator<Item = &ColourSwatch> {
+        (0..self.palette.len()).flat_map(move |i| self.palette[i].iter())
+    }
+
+    fn find_swatch(&self, colour: Colour) -> Option<WidgetId> {
+        self.swatches()
+            .find(|swatch| {
+                let c = swatch.colour;
+                (c.r, c.g, c.b) == (colour.r, colour.g, colour.b)
+            })
+            .map(|swatch| swatch.id())
+    }
+
+    // Whether id is a swatch (not e.g. a row of the palette)
+    fn is_swatch(&self, id: WidgetId) -> bool {
+        self.swatches().any(|swatch| swatch.id() == id)
+    }
+
+    fn pick_colour(&mut self, _: &mut Manager, colour: Colour) -> Response<Pick> {
+        Response::Msg(Pick::Colour(colour))
+    }
+
+    fn pick_alpha(&mut self, _: &mut Manager, alpha: f32) -> Response<Pick> {
+        Response::Msg(Pick::Alpha(alpha))
+    }
+}
+
+/// A button showing a colour, opening a pop-up palette
+///
+/// When clicked, a pop-up palette of colours is shown (see
+/// [`ColourButton::with_palette`]). Selecting a colour closes the pop-up like
+/// a menu entry and emits the new [`Colour`] from the event handler.
+///
+/// Optionally, an alpha slider is shown below the palette (see
+/// [`ColourButton::with_alpha`]). Changes to the slider emit the new colour
+/// without closing the pop-up. Colours picked from the palette keep the
+/// current alpha value. Note that swatches are drawn opaque.
+#[widget(config(key_nav = true))]
+#[handler(noauto)]
+#[derive(Clone, Debug, Widget)]
+pub struct ColourButton {
+    #[widget_core]
+    core: CoreData,
+    sides: (Size, Size),
+    swatch: Rect,
+    colour: Colour,
+    #[widget]
+    popup: MenuFrame<ColourPopup>,
+    opening: bool,
+    popup_id: Option<WindowId>,
+}
+
+impl ColourButton {
+    /// Construct, with the initial `colour`
+    ///
+    /// The pop-up uses a default palette of sixteen colours.
+    pub fn new(colour: Colour) -> Self {
+        let palette: Vec<Colour> = PALETTE
+            .iter()
+            .map(|c| Colour::new(c[0], c[1], c[2]))
+            .collect();
+        ColourButton {
+            core: Default::default(),
+            sides: (Size::ZERO, Size::ZERO),
+            swatch: Rect::default(),
+            colour,
+            popup: MenuFrame::new(ColourPopup::new(&palette)),
+            opening: false,
+            popup_id: None,
+        }
+    }
+
+    /// Set the palette (chain style)
+    ///
+    /// The palette is shown in rows of up to eight colours.
+    pub fn with_palette(mut self, palette: &[Colour]) -> Self {
+        self.popup.inner.palette = ColourPopup::palette(palette);
+        self
+    }
+
+    /// Show an alpha slider (chain style)
+    ///
+    /// By default, no alpha slider is shown.
+    pub fn with_alpha(mut self, alpha: bool) -> Self {
+        let sliders = match alpha {
+            true => vec![Slider::new(0.0, 1.0, 0.05).with_value(self.colour.a)],
+            false => vec![],
+        };
+        self.popup.inner.alpha = Column::new(sliders);
+        self
+    }
+
+    /// Get the current colour
+    #[inline]
+    pub fn colour(&self) -> Colour {
+        self.colour
+    }
+
+    /// Set the colour
+    pub fn set_colour(&mut self, colour: Colour) -> TkAction {
+        self.colour = colour;
+        let mut action = TkAction::Redraw;
+        if !self.popup.inner.alpha.is_empty() {
+            action += self.popup.inner.alpha[0].set_value(colour.a);
+        }
+        action
+    }
+
+    fn open_popup(&mut self, mgr: &mut Manager) {
+        let id = mgr.add_popup(kas::Popup {
+            id: self.popup.id(),
+            parent: self.id(),
+            direction: Direction::Down,
+            modal: false,
+            anchor: None,
+        });
+        self.popup_id = Some(id);
+        if let Some(id) = self.popup.inner.find_swatch(self.colour) {
+            mgr.set_nav_focus(id);
+        }
+    }
+
+    fn close_popup(&mut self, mgr: &mut Manager) {
+        if let Some(id) = self.popup_id {
+            mgr.close_window(id);
+        }
+    }
+
+    fn map_response(&mut self, mgr: &mut Manager, r: Response<Pick>) -> Response<Colour> {
+        match r {
+            Response::None => Response::None,
+            Response::Unhandled(Event::Control(key)) => {
+                let rev = match key {
+                    ControlKey::Up | ControlKey::Left => true,
+                    ControlKey::Down | ControlKey::Right => false,
+                    key => return Response::Unhandled(Event::Control(key)),
+                };
+                mgr.next_nav_focus(self, rev);
+                Response::None
+            }
+            Response::Unhandled(ev) => Response::Unhandled(ev),
+            Response::Focus(x) => Response::Focus(x),
+            Response::Msg(Pick::Colour(colour)) => {
+                self.colour = Colour {
+                    a: self.colour.a,
+                    ..colour
+                };
+                mgr.redraw(self.id());
+                self.close_popup(mgr);
+                Response::Msg(self.colour)
+            }
+            Response::Msg(Pick::Alpha(alpha)) => {
+                self.colour.a = alpha;
+                mgr.redraw(self.id());
+                Response::Msg(self.colour)
+            }
+        }
+    }
+}
+
+impl Layout for ColourButton {
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let sides = size_handle.button_surround();
+        self.sides = sides;
+        let margins = size_handle.outer_margins();
+        let frame_rules = SizeRules::extract_fixed(axis.is_vertical(), sides.0 + sides.1, margins);
+
+        let height = size_handle.line_height(TextClass::Button);
+        let size = Size(2 * height, height);
+        let content_rules = SizeRules::extract_fixed(axis.is_vertical(), size, Margins::ZERO);
+        content_rules.surrounded_by(frame_rules, true)
+    }
+
+    fn set_rect(&mut self, rect: Rect, _: AlignHints) {
+        self.core.rect = rect;
+        let size = rect.size - self.sides.0 - self.sides.1;
+        self.swatch = Rect::new(rect.pos + self.sides.0, size);
+    }
+
+    fn spatial_range(&self) -> (usize, usize) {
+        // Our only child is the pop-up, which is not drawn within our rect;
+        // return an empty range
+        (0, std::usize::MAX)
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, mgr: &event::ManagerState, disabled: bool) {
+        let mut state = self.input_state(mgr, disabled);
+        if self.popup_id.is_some() {
+            state.depress = true;
+        }
+        draw_handle.button(self.core.rect, state);
+        draw_handle.swatch(self.swatch, self.colour);
+    }
+}
+
+impl event::Handler for ColourButton {
+    type Msg = Colour;
+
+    fn handle(&mut self, mgr: &mut Manager, event: Event) -> Response<Colour> {
+        match event {
+            Event::Activate => {
+                if self.popup_id.is_some() {
+                    self.close_popup(mgr);
+                } else {
+                    self.open_popup(mgr);
+                }
+            }
+            Event::PressStart {
+                source,
+                start_id,
+                coord,
+            } => {
+                if self.popup.inner.alpha.is_ancestor_of(start_id) {
+                    // The slider handles its own input
+                    let event = Event::PressStart {
+                        source,
+                        start_id,
+                        coord,
+                    };
+                    let r = self.popup.send(mgr, start_id, event);
+                    return self.map_response(mgr, r);
+                } else if self.is_ancestor_of(start_id) {
+                    if source.is_primary() {
+                        mgr.request_grab(self.id(), source, coord, GrabMode::Grab, None);
+                        mgr.set_grab_depress(source, Some(start_id));
+                        self.opening = self.popup_id.is_none();
+                    }
+                } else {
+                    self.close_popup(mgr);
+                    return Response::Unhandled(Event::None);
+                }
+            }
+            Event::PressMove { source, cur_id, .. } => {
+                if self.popup_id.is_none() {
+                    self.open_popup(mgr);
+                }
+                let target = cur_id.filter(|id| self.popup.inner.is_swatch(*id));
+                mgr.set_grab_depress(source, target);
+                if let Some(id) = target {
+                    mgr.set_nav_focus(id);
+                }
+            }
+            Event::PressEnd { end_id, .. } => {
+                if let Some(id) = end_id {
+                    if id == self.id() {
+                        if self.opening {
+                            if self.popup_id.is_none() {
+                                self.open_popup(mgr);
+                            }
+                            return Response::None;
+                        }
+                    } else if self.popup_id.is_some() && self.popup.inner.is_swatch(id) {
+                        let r = self.popup.send(mgr, id, Event::Activate);
+                        return self.map_response(mgr, r);
+                    }
+                }
+                self.close_popup(mgr);
+            }
+            Event::NewPopup(id) => {
+                if id != self.popup.id() {
+                    self.close_popup(mgr);
+                }
+            }
+            Event::PopupRemoved(id) => {
+                debug_assert_eq!(Some(id), self.popup_id);
+                self.popup_id = None;
+            }
+            event => return Response::Unhandled(event),
+        }
+        Response::None
+    }
+}
+
+impl event::SendEvent for ColourButton {
+    fn send(&mut self, mgr: &mut Manager, id: WidgetId, event: Event) -> Response<Self::Msg> {
+        if self.is_disabled() {
+            return Response::Unhandled(event);
+        }
+
+        if id <= self.popup.id() {
+            let r = self.popup.send(mgr, id, event);
+            self.map_response(mgr, r)
+        } else {
+            Manager::handle_generic(self, mgr, event)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::event::{ManagerState, MouseButton, PressSource, VoidMsg};
+    use crate::test_util::{DrawCommand, RecordingDrawHandle, TestManager, TestSizeHandle};
+
+    #[layout(single)]
+    #[handler(msg = VoidMsg)]
+    #[derive(Debug, Widget)]
+    struct Collect {
+        #[widget_core]
+        core: CoreData,
+        #[widget(handler = collect)]
+        button: ColourButton,
+        msgs: Vec<Colour>,
+    }
+
+    impl Collect {
+        fn new(button: ColourButton) -> TestManager<Self> {
+            TestManager::new(Collect {
+                core: Default::default(),
+                button,
+                msgs: vec![],
+            })
+        }
+
+        fn collect(&mut self, _: &mut Manager, colour: Colour) -> Response<VoidMsg> {
+            self.msgs.push(colour);
+            Response::None
+        }
+    }
+
+    fn nav_focus(test: &mut TestManager<Collect>) -> Option<WidgetId> {
+        test.with(|mgr, _| mgr.nav_focus())
+    }
+
+    #[test]
+    fn draw() {
+        let colour = Colour::new(0.5, 0.25, 0.0);
+        let mut button = ColourButton::new(colour);
+        let mut size_handle = TestSizeHandle::new();
+        button.size_rules(&mut size_handle, AxisInfo::new(false, None));
+        button.size_rules(&mut size_handle, AxisInfo::new(true, None));
+        let rect = Rect::new(Coord(10, 10), Size(40, 20));
+        button.set_rect(rect, AlignHints::NONE);
+
+        let mut draw_handle = RecordingDrawHandle::new(rect);
+        button.draw(&mut draw_handle, &ManagerState::new(), false);
+        let (start, end) = size_handle.button_surround();
+        let swatch = Rect::new(rect.pos + start, rect.size - start - end);
+        let commands = [
+            DrawCommand::Button(rect, InputState::default()),
+            DrawCommand::Swatch(swatch, colour),
+        ];
+        assert_eq!(draw_handle.commands(), &commands);
+    }
+
+    #[test]
+    fn pick_colour() {
+        let red = Colour::new(1.0, 0.0, 0.0);
+        let blue = Colour::new(0.0, 0.0, 1.0);
+        let mut test = Collect::new(ColourButton::new(Colour { a: 0.5, ..red }));
+        let id = test.widget.button.id();
+        let popup = &test.widget.button.popup.inner;
+        let (red_id, blue_id) = (popup.find_swatch(red), popup.find_swatch(blue));
+        assert!(red_id.is_some() && blue_id.is_some());
+
+        // Opening gives focus to the swatch of the current colour
+        test.send(id, Event::Activate);
+        assert!(test.widget.button.popup_id.is_some());
+        assert_eq!(nav_focus(&mut test), red_id);
+
+        // Picking a swatch closes the pop-up and keeps the current alpha
+        test.send(blue_id.unwrap(), Event::Activate);
+        assert!(test.widget.button.popup_id.is_none());
+        assert_eq!(test.widget.msgs, vec![Colour { a: 0.5, ..blue }]);
+        assert_eq!(test.widget.button.colour(), Colour { a: 0.5, ..blue });
+    }
+
+    #[test]
+    fn press_move_focus() {
+        let mut test = Collect::new(ColourButton::new(Colour::new(0.0, 0.0, 0.0)));
+        let id = test.widget.button.id();
+        test.send(id, Event::Activate);
+        let black_id = nav_focus(&mut test);
+        assert!(black_id.is_some());
+
+        let source = PressSource::Mouse(MouseButton::Left, 1);
+        let press_move = |cur_id| Event::PressMove {
+            source,
+            cur_id: Some(cur_id),
+            coord: Coord::ZERO,
+            delta: Coord::ZERO,
+        };
+
+        // A palette row (between swatches) is not focussable: focus is kept
+        let row_id = test.widget.button.popup.inner.palette[0].id();
+        test.send(id, press_move(row_id));
+        assert_eq!(nav_focus(&mut test), black_id);
+
+        // A swatch under the press is focussed
+        let white_id = test.widget.button.popup.inner.palette[0][4].id();
+        test.send(id, press_move(white_id));
+        assert_eq!(nav_focus(&mut test), Some(white_id));
+    }
+}
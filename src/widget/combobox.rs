@@ -9,13 +9,14 @@ use std::fmt::Debug;
 use std::iter::FromIterator;
 
 use super::{Column, MenuEntry, MenuFrame};
+use kas::access::{AccessNode, Role};
 use kas::draw::TextClass;
 use kas::event::{self, ControlKey, GrabMode};
 use kas::prelude::*;
 use kas::WindowId;
 
 /// A pop-up multiple choice menu
-#[widget(config(key_nav = true))]
+#[widget(config=noauto)]
 #[handler(noauto)]
 #[derive(Clone, Debug, Widget)]
 pub struct ComboBox<M: Clone + Debug + 'static> {
@@ -25,11 +26,23 @@ pub struct ComboBox<M: Clone + Debug + 'static> {
     #[widget]
     popup: ComboPopup,
     messages: Vec<M>, // TODO: is this a useless lookup step?
+    dismiss_msg: Option<M>,
     active: usize,
     opening: bool,
+    selected: bool,
     popup_id: Option<WindowId>,
 }
 
+impl<M: Clone + Debug + 'static> WidgetConfig for ComboBox<M> {
+    fn key_nav(&self) -> bool {
+        true
+    }
+
+    fn accessible(&self) -> AccessNode {
+        AccessNode::new(Role::ComboBox).with_value(self.label.as_str())
+    }
+}
+
 impl<M: Clone + Debug + 'static> kas::Layout for ComboBox<M> {
     fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
         let sides = size_handle.button_surround();
@@ -59,7 +72,8 @@ impl<M: Clone + Debug + 'static> kas::Layout for ComboBox<M> {
             state.depress = true;
         }
         draw_handle.button(self.core.rect, state);
-        draw_handle.text(self.core.rect.pos, &self.label, TextClass::Button);
+        let class = TextClass::Button;
+        draw_handle.text(self.core.rect.pos, &self.label, class, state.disabled);
     }
 }
 
@@ -96,12 +110,25 @@ impl<M: Clone + Debug + 'static> ComboBox<M> {
                 inner: MenuFrame::new(Column::new(column)),
             },
             messages,
+            dismiss_msg: None,
             active: 0,
             opening: false,
+            selected: false,
             popup_id: None,
         }
     }
 
+    /// Set a message to emit when the menu is dismissed (chain style)
+    ///
+    /// When the pop-up menu is closed without a choice being selected (e.g.
+    /// via the Escape key or a click outside the menu), this message is
+    /// emitted by the event handler. By default no message is emitted.
+    #[inline]
+    pub fn with_dismiss_msg(mut self, msg: M) -> Self {
+        self.dismiss_msg = Some(msg);
+        self
+    }
+
     /// Get the index of the active choice
     #[inline]
     pub fn active(&self) -> usize {
@@ -221,6 +248,7 @@ impl<M: Clone + Debug + 'static> ComboBox<M> {
             Response::Msg(msg) => {
                 let index = msg as usize;
                 *mgr += self.set_active(index);
+                self.selected = true;
                 if let Some(id) = self.popup_id {
                     mgr.close_window(id);
                 }
@@ -270,8 +298,11 @@ impl<M: Clone + Debug + 'static> event::Handler for ComboBox<M> {
                 id: s.popup.id(),
                 parent: s.id(),
                 direction: Direction::Down,
+                modal: false,
+                anchor: None,
             });
             s.popup_id = Some(id);
+            s.selected = false;
             if let Some(id) = s.popup.inner.inner.get(s.active).map(|w| w.id()) {
                 mgr.set_nav_focus(id);
             }
@@ -347,6 +378,11 @@ impl<M: Clone + Debug + 'static> event::Handler for ComboBox<M> {
             Event::PopupRemoved(id) => {
                 debug_assert_eq!(Some(id), self.popup_id);
                 self.popup_id = None;
+                if !self.selected {
+                    if let Some(msg) = self.dismiss_msg.clone() {
+                        return Response::Msg(msg);
+                    }
+                }
             }
             event => return Response::Unhandled(event),
         }
@@ -378,3 +414,54 @@ struct ComboPopup {
     #[widget]
     inner: MenuFrame<Column<MenuEntry<u64>>>,
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::event::VirtualKeyCode;
+    use crate::test_util::TestManager;
+
+    #[layout(single)]
+    #[handler(msg = VoidMsg)]
+    #[derive(Debug, Widget)]
+    struct Collect {
+        #[widget_core]
+        core: CoreData,
+        #[widget(handler = collect)]
+        combo: ComboBox<i32>,
+        msgs: Vec<i32>,
+    }
+
+    impl Collect {
+        fn collect(&mut self, _: &mut Manager, msg: i32) -> Response<VoidMsg> {
+            self.msgs.push(msg);
+            Response::None
+        }
+    }
+
+    #[test]
+    fn dismiss_msg() {
+        let combo = ComboBox::new([("one", 1), ("two", 2)].iter()).with_dismiss_msg(-1);
+        let mut test = TestManager::new(Collect {
+            core: Default::default(),
+            combo,
+            msgs: vec![],
+        });
+        let id = test.widget.combo.id();
+        let is_open = |test: &TestManager<Collect>| test.widget.combo.popup_id.is_some();
+
+        // Closing via Escape emits the dismiss message
+        test.send(id, Event::Activate);
+        assert!(is_open(&test));
+        test.key(VirtualKeyCode::Escape);
+        assert!(!is_open(&test));
+        assert_eq!(test.widget.msgs, vec![-1]);
+
+        // Selecting a choice emits only the choice's message
+        test.send(id, Event::Activate);
+        let choice = test.widget.combo.popup.inner.inner[1].id();
+        test.send(choice, Event::Activate);
+        assert!(!is_open(&test));
+        assert_eq!(test.widget.msgs, vec![-1, 2]);
+    }
+}
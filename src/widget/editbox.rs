@@ -5,16 +5,22 @@
 
 //! Text widgets
 
+use std::collections::VecDeque;
 use std::fmt::{self, Debug};
 use std::ops::Range;
-use std::time::Duration;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
 use unicode_segmentation::{GraphemeCursor, UnicodeSegmentation};
 
-use kas::draw::TextClass;
+use kas::access::{AccessNode, Role};
+use kas::draw::{CaretShape, TextClass};
 use kas::event::{self, ControlKey, GrabMode, PressSource, ScrollDelta};
 use kas::geom::Vec2;
 use kas::prelude::*;
-use kas::text::SelectionHelper;
+use kas::text::format::{FontToken, FormattableText};
+#[cfg(not(feature = "gat"))]
+use kas::text::OwningVecIter;
+use kas::text::{Effect, EffectFlags, SelectionHelper};
 
 #[derive(Clone, Debug, PartialEq)]
 enum LastEdit {
@@ -30,6 +36,153 @@ impl Default for LastEdit {
     }
 }
 
+// Text and selection, as saved for undo
+#[derive(Clone, Debug, PartialEq)]
+struct EditState {
+    text: String,
+    edit_pos: usize,
+    sel_pos: usize,
+}
+
+// Default maximum number of undo steps
+const UNDO_LIMIT: usize = 100;
+
+// Bounded undo / redo history
+#[derive(Clone, Debug)]
+struct EditHistory {
+    undo: VecDeque<EditState>,
+    redo: Vec<EditState>,
+    limit: usize,
+    last_edit: LastEdit,
+}
+
+impl Default for EditHistory {
+    fn default() -> Self {
+        EditHistory::new(UNDO_LIMIT)
+    }
+}
+
+impl EditHistory {
+    fn new(limit: usize) -> Self {
+        EditHistory {
+            undo: VecDeque::new(),
+            redo: vec![],
+            limit,
+            last_edit: LastEdit::None,
+        }
+    }
+
+    fn set_limit(&mut self, limit: usize) {
+        self.limit = limit;
+        while self.undo.len() > limit {
+            self.undo.pop_front();
+        }
+    }
+
+    fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.last_edit = LastEdit::None;
+    }
+
+    // Push an undo step, dropping the oldest if over the limit
+    fn push_undo(&mut self, state: EditState) {
+        if self.limit == 0 {
+            return;
+        }
+        if self.undo.len() == self.limit {
+            self.undo.pop_front();
+        }
+        self.undo.push_back(state);
+    }
+
+    // Start an edit of kind `edit`; returns true if this starts a new undo
+    // step, in which case the caller should push the prior state.
+    //
+    // Consecutive inserts (or deletions) are coalesced into a single undo
+    // step, unless `replace` is true (the edit replaces a selection).
+    fn begin_edit(&mut self, edit: LastEdit, replace: bool) -> bool {
+        self.redo.clear();
+        let coalesce = match edit {
+            LastEdit::Insert | LastEdit::Delete => !replace && edit == self.last_edit,
+            _ => false,
+        };
+        self.last_edit = edit;
+        !coalesce
+    }
+
+    // End coalescing of edits (e.g. after the edit position is moved)
+    fn break_edit(&mut self) {
+        self.last_edit = LastEdit::None;
+    }
+
+    fn undo(&mut self, current: EditState) -> Option<EditState> {
+        let state = self.undo.pop_back()?;
+        self.redo.push(current);
+        self.last_edit = LastEdit::None;
+        Some(state)
+    }
+
+    fn redo(&mut self, current: EditState) -> Option<EditState> {
+        let state = self.redo.pop()?;
+        self.push_undo(current);
+        self.last_edit = LastEdit::None;
+        Some(state)
+    }
+}
+
+// Text displayed while an input method (IME) composes: the content with the
+// preedit string in place of the selection, underlined
+#[derive(Clone, Debug, PartialEq)]
+struct PreeditText {
+    text: String,
+    effects: [Effect<()>; 2],
+}
+
+impl PreeditText {
+    fn new(text: String, range: Range<usize>) -> Self {
+        let effects = [
+            Effect {
+                start: range.start as u32,
+                flags: EffectFlags::UNDERLINE,
+                aux: (),
+            },
+            Effect {
+                start: range.end as u32,
+                flags: EffectFlags::empty(),
+                aux: (),
+            },
+        ];
+        PreeditText { text, effects }
+    }
+}
+
+impl FormattableText for PreeditText {
+    #[cfg(feature = "gat")]
+    type FontTokenIter<'a> = std::iter::Empty<FontToken>;
+
+    #[inline]
+    fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    #[cfg(feature = "gat")]
+    #[inline]
+    fn font_tokens<'a>(&'a self, _: f32, _: f32) -> Self::FontTokenIter<'a> {
+        std::iter::empty()
+    }
+    #[cfg(not(feature = "gat"))]
+    #[inline]
+    fn font_tokens(&self, _: f32, _: f32) -> OwningVecIter<FontToken> {
+        OwningVecIter::new(vec![])
+    }
+
+    fn effect_tokens(&self) -> &[Effect<()>] {
+        &self.effects
+    }
+}
+
+#[derive(PartialEq)]
 enum EditAction {
     None,
     Unhandled,
@@ -103,6 +256,15 @@ impl<F: Fn(&str) -> Option<M>, M> EditGuard for EditActivate<F, M> {
     }
 }
 
+/// An [`EditGuard`] impl which calls a closure when focus is lost
+pub struct EditFocusLost<F: Fn(&str) -> Option<M>, M>(pub F);
+impl<F: Fn(&str) -> Option<M>, M> EditGuard for EditFocusLost<F, M> {
+    type Msg = M;
+    fn focus_lost(edit: &mut EditBox<Self>) -> Option<Self::Msg> {
+        (edit.guard.0)(edit.text.text())
+    }
+}
+
 /// An [`EditGuard`] impl which calls a closure when activated or focus is lost
 pub struct EditAFL<F: Fn(&str) -> Option<M>, M>(pub F);
 impl<F: Fn(&str) -> Option<M>, M> EditGuard for EditAFL<F, M> {
@@ -124,14 +286,107 @@ impl<F: Fn(&str) -> Option<M>, M> EditGuard for EditEdit<F, M> {
     }
 }
 
+/// An [`EditGuard`] impl which calls one closure when activated and another when edited
+///
+/// Usually constructed via [`EditBox::on_activate`] and [`EditBox::on_edit`].
+pub struct EditActivateEdit<FA, FE, M>(pub FA, pub FE)
+where
+    FA: Fn(&str) -> Option<M>,
+    FE: Fn(&str) -> Option<M>;
+impl<FA, FE, M> EditGuard for EditActivateEdit<FA, FE, M>
+where
+    FA: Fn(&str) -> Option<M>,
+    FE: Fn(&str) -> Option<M>,
+{
+    type Msg = M;
+    fn activate(edit: &mut EditBox<Self>) -> Option<Self::Msg> {
+        (edit.guard.0)(edit.text.text())
+    }
+    fn edit(edit: &mut EditBox<Self>) -> Option<Self::Msg> {
+        (edit.guard.1)(edit.text.text())
+    }
+}
+
+/// An [`EditGuard`] impl which parses the text when activated or focus is lost
+///
+/// If the closure returns a value, this is emitted as a message and the error
+/// state is cleared; otherwise the error state is set.
+pub struct EditParse<F: Fn(&str) -> Option<T>, T>(pub F);
+impl<F: Fn(&str) -> Option<T>, T> EditGuard for EditParse<F, T> {
+    type Msg = T;
+    fn activate(edit: &mut EditBox<Self>) -> Option<Self::Msg> {
+        let value = (edit.guard.0)(edit.text.text());
+        edit.set_error_state(value.is_none());
+        value
+    }
+    fn focus_lost(edit: &mut EditBox<Self>) -> Option<Self::Msg> {
+        Self::activate(edit)
+    }
+}
+
+/// Handling of line breaks pasted into a single-line [`EditBox`]
+///
+/// See [`EditBox::paste_newlines`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PasteNewlines {
+    /// Replace each line break with a space
+    Space,
+    /// Paste only the content before the first line break
+    Truncate,
+}
+
+impl Default for PasteNewlines {
+    fn default() -> Self {
+        PasteNewlines::Space
+    }
+}
+
 const TOUCH_DUR: Duration = Duration::from_secs(1);
+const FLASH_DUR: Duration = Duration::from_millis(300);
+
+// The text resulting from replacing `range` of `text` with `s`
+fn replaced(text: &str, range: Range<usize>, s: &str) -> String {
+    let mut result = String::with_capacity(text.len() - range.len() + s.len());
+    result.push_str(&text[..range.start]);
+    result.push_str(s);
+    result.push_str(&text[range.end..]);
+    result
+}
+
+// Sanitise pasted text
+//
+// Line breaks (`\r\n`, `\r` or `\n`) are normalised to `\n`, or in
+// single-line mode handled according to `newlines`. Other control characters
+// except tab are removed.
+fn sanitise_paste(content: &str, multi_line: bool, newlines: PasteNewlines) -> String {
+    let mut result = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\r' | '\n' => {
+                if c == '\r' && chars.peek() == Some(&'\n') {
+                    chars.next();
+                }
+                match (multi_line, newlines) {
+                    (true, _) => result.push('\n'),
+                    (false, PasteNewlines::Space) => result.push(' '),
+                    (false, PasteNewlines::Truncate) => break,
+                }
+            }
+            '\t' => result.push(c),
+            c if c.is_control() => (),
+            c => result.push(c),
+        }
+    }
+    result
+}
 
 #[derive(Clone, Debug, PartialEq)]
 enum TouchPhase {
     None,
-    Start(u64, Coord), // id, coord
-    Pan(u64),          // id
-    Cursor(u64),       // id
+    Start(u64, Coord, Instant), // id, coord, end time
+    Pan(u64),                   // id
+    Cursor(u64),                // id
 }
 
 impl Default for TouchPhase {
@@ -148,7 +403,20 @@ impl Default for TouchPhase {
 /// Optionally, [`EditBox::multi_line`] mode can be activated (enabling
 /// line-wrapping and a larger vertical height). This mode is only recommended
 /// for short texts for performance reasons.
-#[widget(config(key_nav = true, cursor_icon = event::CursorIcon::Text))]
+///
+/// Input methods (IME) are supported: text being composed is shown underlined
+/// at the edit position (see [`Event::ImePreedit`]) and only inserted once
+/// committed.
+///
+/// Input may be restricted via [`EditBox::with_validator`]: edits resulting
+/// in invalid text are rejected. Text may be reformatted when committed via
+/// [`EditBox::with_formatter`], and parsed via [`EditBox::with_parser`].
+///
+/// Edits may be undone with <kbd>Ctrl+Z</kbd> and redone with
+/// <kbd>Ctrl+Shift+Z</kbd>; undo restores both the text and the selection.
+/// Consecutive typed characters (or deletions) form a single undo step. The
+/// number of undo steps is bounded (see [`EditBox::undo_limit`]).
+#[widget(config=noauto)]
 #[handler(handle=noauto, generics = <> where G: EditGuard)]
 #[derive(Clone, Default, Widget)]
 pub struct EditBox<G: 'static> {
@@ -160,14 +428,27 @@ pub struct EditBox<G: 'static> {
     view_offset: Coord,
     editable: bool,
     multi_line: bool,
+    insert_tab: bool,
+    paste_newlines: PasteNewlines,
+    caret_shape: CaretShape,
     text: Text<String>,
     required: Vec2,
     selection: SelectionHelper,
     edit_x_coord: Option<f32>,
-    old_state: Option<(String, usize, usize)>,
-    last_edit: LastEdit,
+    history: EditHistory,
+    undo_set_string: bool,
+    validator: Option<Rc<dyn Fn(&str) -> bool>>,
+    formatter: Option<Rc<dyn Fn(&str) -> String>>,
+    reject_flash: bool,
+    flash_end: Option<Instant>,
     error_state: bool,
     touch_phase: TouchPhase,
+    caret_shown: bool,
+    caret_blink: Option<Instant>,
+    blink_interval: Option<Duration>,
+    preedit: Option<(Text<PreeditText>, Range<usize>)>,
+    edit_delay: Option<Duration>,
+    edit_pending: Option<Instant>,
     /// The associated [`EditGuard`] implementation
     pub guard: G,
 }
@@ -184,23 +465,40 @@ impl<G> Debug for EditBox<G> {
     }
 }
 
-impl<G: 'static> Layout for EditBox<G> {
-    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
-        let frame_sides = size_handle.edit_surround();
-        let inner = size_handle.inner_margin();
-        let frame_offset = frame_sides.0 + inner;
-        let frame_size = frame_offset + frame_sides.1 + inner;
+impl<G: 'static> WidgetConfig for EditBox<G> {
+    fn key_nav(&self) -> bool {
+        true
+    }
 
-        let margins = size_handle.outer_margins();
-        let frame_rules = SizeRules::extract_fixed(axis.is_vertical(), frame_size, margins);
+    fn cursor_icon(&self) -> event::CursorIcon {
+        event::CursorIcon::Text
+    }
+
+    fn accessible(&self) -> AccessNode {
+        AccessNode::new(Role::TextBox).with_value(self.text.text())
+    }
+}
 
+impl<G: 'static> Layout for EditBox<G> {
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
         let class = if self.multi_line {
             TextClass::EditMulti
         } else {
             TextClass::Edit
         };
+
+        // Position content within the frame's safe area
+        let inset = size_handle.content_inset(class);
+        let inner = size_handle.inner_margin();
+        let frame_offset = Size(inset.horiz.0 as u32, inset.vert.0 as u32) + inner;
+        let frame_size = frame_offset + Size(inset.horiz.1 as u32, inset.vert.1 as u32) + inner;
+
+        let margins = size_handle.outer_margins();
+        let frame_rules = SizeRules::extract_fixed(axis.is_vertical(), frame_size, margins);
+
         let content_rules = size_handle.text_bound(&mut self.text, class, axis);
         let m = content_rules.margins();
+        self.blink_interval = size_handle.edit_marker_blink();
 
         // Note: we do not allocate space for the edit marker (size_handle.edit_marker_width());
         // instead we simply draw it in the margin (inner_margin() should be sufficient).
@@ -239,6 +537,12 @@ impl<G: 'static> Layout for EditBox<G> {
                 env.set_wrap(multi_line);
             })
             .into();
+        if let Some((text, _)) = self.preedit.as_mut() {
+            let _ = text.update_env(|env| {
+                env.set_bounds(size.into());
+                env.set_wrap(multi_line);
+            });
+        }
         self.set_view_offset_from_edit_pos();
     }
 
@@ -249,9 +553,41 @@ impl<G: 'static> Layout for EditBox<G> {
             TextClass::Edit
         };
         let mut input_state = self.input_state(mgr, disabled);
-        input_state.error = self.error_state;
+        input_state.error = self.error_state || self.flash_end.is_some();
         draw_handle.edit_box(self.core.rect, input_state);
+        let disabled = input_state.disabled;
         let bounds = self.text.env().bounds.into();
+        let marker = |draw_handle: &mut dyn DrawHandle| {
+            let pos = self.selection.edit_pos();
+            let mut cursor = GraphemeCursor::new(pos, self.text.str_len(), true);
+            let next = cursor.next_boundary(self.text.text(), 0).unwrap();
+            draw_handle.edit_marker(
+                self.text_pos,
+                bounds,
+                self.view_offset,
+                self.text.as_ref(),
+                class,
+                pos..next.unwrap_or(pos),
+                self.caret_shape,
+            );
+        };
+        // A block marker is drawn under the text
+        let block = self.caret_shape == CaretShape::Block;
+        let show_marker = input_state.char_focus && self.caret_shown;
+        if let Some((text, cursor)) = self.preedit.as_ref() {
+            // The composition cursor is always drawn as a bar
+            let (pos, offset) = (self.text_pos, self.view_offset);
+            draw_handle.text_effects(pos, offset, text, class, disabled);
+            if show_marker {
+                let (text, range) = (text.as_ref(), cursor.clone());
+                let shape = CaretShape::Bar;
+                draw_handle.edit_marker(pos, bounds, offset, text, class, range, shape);
+            }
+            return;
+        }
+        if show_marker && block {
+            marker(draw_handle);
+        }
         if self.selection.is_empty() {
             draw_handle.text_offset(
                 self.text_pos,
@@ -259,6 +595,7 @@ impl<G: 'static> Layout for EditBox<G> {
                 self.view_offset,
                 self.text.as_ref(),
                 class,
+                disabled,
             );
         } else {
             // TODO(opt): we could cache the selection rectangles here to make
@@ -271,17 +608,11 @@ impl<G: 'static> Layout for EditBox<G> {
                 &self.text,
                 self.selection.range(),
                 class,
+                disabled,
             );
         }
-        if input_state.char_focus {
-            draw_handle.edit_marker(
-                self.text_pos,
-                bounds,
-                self.view_offset,
-                self.text.as_ref(),
-                class,
-                self.selection.edit_pos(),
-            );
+        if show_marker && !block {
+            marker(draw_handle);
         }
     }
 }
@@ -299,14 +630,27 @@ impl EditBox<EditVoid> {
             view_offset: Default::default(),
             editable: true,
             multi_line: false,
+            insert_tab: false,
+            paste_newlines: PasteNewlines::Space,
+            caret_shape: CaretShape::Bar,
             text: Text::new(Default::default(), text.into()),
             required: Vec2::ZERO,
             selection: SelectionHelper::new(len, len),
             edit_x_coord: None,
-            old_state: None,
-            last_edit: LastEdit::None,
+            history: EditHistory::default(),
+            undo_set_string: false,
+            validator: None,
+            formatter: None,
+            reject_flash: true,
+            flash_end: None,
             error_state: false,
             touch_phase: TouchPhase::None,
+            caret_shown: true,
+            caret_blink: None,
+            blink_interval: None,
+            preedit: None,
+            edit_delay: None,
+            edit_pending: None,
             guard: EditVoid,
         }
     }
@@ -319,26 +663,7 @@ impl EditBox<EditVoid> {
     /// This method calls [`EditGuard::edit`] after applying `guard` to `self`
     /// and discards any message emitted.
     pub fn with_guard<G: EditGuard>(self, guard: G) -> EditBox<G> {
-        let mut edit = EditBox {
-            core: self.core,
-            frame_offset: self.frame_offset,
-            frame_size: self.frame_size,
-            text_pos: self.text_pos,
-            view_offset: self.view_offset,
-            editable: self.editable,
-            multi_line: self.multi_line,
-            text: self.text,
-            required: self.required,
-            selection: self.selection,
-            edit_x_coord: self.edit_x_coord,
-            old_state: self.old_state,
-            last_edit: self.last_edit,
-            error_state: self.error_state,
-            touch_phase: self.touch_phase,
-            guard,
-        };
-        let _ = G::edit(&mut edit);
-        edit
+        self.map_guard(|_| guard)
     }
 
     /// Set a guard function, called on activation
@@ -347,6 +672,8 @@ impl EditBox<EditVoid> {
     /// "enter" key is pressed).
     /// Its result, if not `None`, is the event handler's response.
     ///
+    /// This may be combined with [`EditBox::on_edit`] (in either order).
+    ///
     /// This method is a parametisation of [`EditBox::with_guard`]. Any guard
     /// previously assigned to the `EditBox` will be replaced.
     pub fn on_activate<F: Fn(&str) -> Option<M>, M>(self, f: F) -> EditBox<EditActivate<F, M>> {
@@ -356,7 +683,8 @@ impl EditBox<EditVoid> {
     /// Set a guard function, called on activation and input-focus lost
     ///
     /// The closure `f` is called when the `EditBox` is activated (when the
-    /// "enter" key is pressed) and when keyboard focus is lost.
+    /// "enter" key is pressed) and when keyboard focus is lost, i.e. whenever
+    /// the user commits the text.
     /// Its result, if not `None`, is the event handler's response.
     ///
     /// This method is a parametisation of [`EditBox::with_guard`]. Any guard
@@ -365,6 +693,21 @@ impl EditBox<EditVoid> {
         self.with_guard(EditAFL(f))
     }
 
+    /// Set a guard function, called on input-focus lost
+    ///
+    /// The closure `f` is called when the `EditBox` loses character input
+    /// focus (see [`Event::LostCharFocus`]), for example when the user clicks
+    /// or tabs to another widget. This happens once each time focus is lost;
+    /// focus moving within the `EditBox` itself (e.g. by clicking it again)
+    /// does not count. Its result, if not `None`, is the event handler's
+    /// response.
+    ///
+    /// This method is a parametisation of [`EditBox::with_guard`]. Any guard
+    /// previously assigned to the `EditBox` will be replaced.
+    pub fn on_focus_lost<F: Fn(&str) -> Option<M>, M>(self, f: F) -> EditBox<EditFocusLost<F, M>> {
+        self.with_guard(EditFocusLost(f))
+    }
+
     /// Set a guard function, called on edit
     ///
     /// The closure `f` is called when the `EditBox` is edited by the user.
@@ -372,16 +715,94 @@ impl EditBox<EditVoid> {
     ///
     /// The closure `f` is also called initially (by this method) and on
     /// programmatic edits, however in these cases any results returned by `f`
-    /// are discarded.
+    /// are discarded. User edits may be coalesced via [`EditBox::edit_delay`].
+    ///
+    /// This may be combined with [`EditBox::on_activate`] (in either order).
     ///
     /// This method is a parametisation of [`EditBox::with_guard`]. Any guard
     /// previously assigned to the `EditBox` will be replaced.
     pub fn on_edit<F: Fn(&str) -> Option<M>, M>(self, f: F) -> EditBox<EditEdit<F, M>> {
         self.with_guard(EditEdit(f))
     }
+
+    /// Set a parser, called on activation and input-focus lost
+    ///
+    /// The closure `f` parses the text. If successful, the value is the event
+    /// handler's response; if not, the error state is set (see
+    /// [`EditBox::has_error`]). For example, `EditBox::new("0").with_parser(|s|
+    /// s.parse::<u32>().ok())` emits a `u32`.
+    ///
+    /// This method is a parametisation of [`EditBox::with_guard`]. Any guard
+    /// previously assigned to the `EditBox` will be replaced.
+    pub fn with_parser<F: Fn(&str) -> Option<T>, T>(self, f: F) -> EditBox<EditParse<F, T>> {
+        self.with_guard(EditParse(f))
+    }
+}
+
+impl<F: Fn(&str) -> Option<M>, M> EditBox<EditActivate<F, M>> {
+    /// Add a guard function, called on edit
+    ///
+    /// This is equivalent to [`EditBox::on_edit`], but retains the activation
+    /// guard.
+    pub fn on_edit<FE>(self, f: FE) -> EditBox<EditActivateEdit<F, FE, M>>
+    where
+        FE: Fn(&str) -> Option<M>,
+    {
+        self.map_guard(|guard| EditActivateEdit(guard.0, f))
+    }
+}
+
+impl<F: Fn(&str) -> Option<M>, M> EditBox<EditEdit<F, M>> {
+    /// Add a guard function, called on activation
+    ///
+    /// This is equivalent to [`EditBox::on_activate`], but retains the edit
+    /// guard.
+    pub fn on_activate<FA>(self, f: FA) -> EditBox<EditActivateEdit<FA, F, M>>
+    where
+        FA: Fn(&str) -> Option<M>,
+    {
+        self.map_guard(|guard| EditActivateEdit(f, guard.0))
+    }
 }
 
 impl<G> EditBox<G> {
+    // Replace the guard, calling EditGuard::edit on the result
+    fn map_guard<H: EditGuard, F: FnOnce(G) -> H>(self, f: F) -> EditBox<H> {
+        let mut edit = EditBox {
+            core: self.core,
+            frame_offset: self.frame_offset,
+            frame_size: self.frame_size,
+            text_pos: self.text_pos,
+            view_offset: self.view_offset,
+            editable: self.editable,
+            multi_line: self.multi_line,
+            insert_tab: self.insert_tab,
+            paste_newlines: self.paste_newlines,
+            caret_shape: self.caret_shape,
+            text: self.text,
+            required: self.required,
+            selection: self.selection,
+            edit_x_coord: self.edit_x_coord,
+            history: self.history,
+            undo_set_string: self.undo_set_string,
+            validator: self.validator,
+            formatter: self.formatter,
+            reject_flash: self.reject_flash,
+            flash_end: self.flash_end,
+            error_state: self.error_state,
+            touch_phase: self.touch_phase,
+            caret_shown: self.caret_shown,
+            caret_blink: self.caret_blink,
+            blink_interval: self.blink_interval,
+            preedit: self.preedit,
+            edit_delay: self.edit_delay,
+            edit_pending: self.edit_pending,
+            guard: f(self.guard),
+        };
+        let _ = H::edit(&mut edit);
+        edit
+    }
+
     /// Set whether this `EditBox` is editable (inline)
     pub fn editable(mut self, editable: bool) -> Self {
         self.editable = editable;
@@ -404,6 +825,103 @@ impl<G> EditBox<G> {
         self
     }
 
+    /// Set whether the Tab key inserts a tab character (chain style)
+    ///
+    /// By default this is disabled: Tab (and Shift+Tab) moves keyboard focus
+    /// to the next (previous) widget, committing the text as when focus is
+    /// lost otherwise.
+    pub fn insert_tab(mut self, insert: bool) -> Self {
+        self.insert_tab = insert;
+        self
+    }
+
+    /// Set how line breaks are pasted in single-line mode (chain style)
+    ///
+    /// Pasted text is sanitised: control characters other than tab are
+    /// removed. In multi-line mode, line breaks are normalised to `\n`;
+    /// otherwise they are handled according to `newlines`. The default is
+    /// [`PasteNewlines::Space`].
+    pub fn paste_newlines(mut self, newlines: PasteNewlines) -> Self {
+        self.paste_newlines = newlines;
+        self
+    }
+
+    /// Set the shape of the edit marker (caret)
+    ///
+    /// The default shape is [`CaretShape::Bar`].
+    pub fn caret_shape(mut self, shape: CaretShape) -> Self {
+        self.caret_shape = shape;
+        self
+    }
+
+    /// Set the maximum number of undo steps
+    ///
+    /// The default is 100. When the limit is reached, the oldest
+    /// step is forgotten. A limit of zero disables undo.
+    pub fn undo_limit(mut self, limit: usize) -> Self {
+        self.history.set_limit(limit);
+        self
+    }
+
+    /// Set whether [`HasString::set_string`] may be undone
+    ///
+    /// By default (`false`), setting the text programmatically clears the undo
+    /// history. If `true`, the previous text is instead recorded as an undo
+    /// step.
+    pub fn undo_set_string(mut self, undo: bool) -> Self {
+        self.undo_set_string = undo;
+        self
+    }
+
+    /// Set a validator (chain style)
+    ///
+    /// The closure `f` is called with the text which would result from each
+    /// user edit (typed characters, deletion, paste and input method commits).
+    /// If it returns `false`, the edit is rejected: the text is not modified.
+    /// A paste is accepted or rejected as a whole.
+    ///
+    /// Programmatic edits (e.g. [`HasString::set_string`]) and undo are not
+    /// validated.
+    pub fn with_validator<F: Fn(&str) -> bool + 'static>(mut self, f: F) -> Self {
+        self.validator = Some(Rc::new(f));
+        self
+    }
+
+    /// Set a formatter (chain style)
+    ///
+    /// The closure `f` is called with the text when committed (on activation
+    /// and when input focus is lost); its result replaces the text. This
+    /// happens before [`EditGuard::activate`] or [`EditGuard::focus_lost`]
+    /// is called. Formatting may be undone.
+    pub fn with_formatter<F: Fn(&str) -> String + 'static>(mut self, f: F) -> Self {
+        self.formatter = Some(Rc::new(f));
+        self
+    }
+
+    /// Set whether rejected edits flash the error state (chain style)
+    ///
+    /// By default this is enabled: when the validator rejects an edit, the
+    /// input field is briefly drawn in its error state.
+    pub fn reject_flash(mut self, flash: bool) -> Self {
+        self.reject_flash = flash;
+        self
+    }
+
+    /// Delay edit notifications (chain style)
+    ///
+    /// By default, [`EditGuard::edit`] is called on each user edit. With a
+    /// delay, it is instead called once no further edit has occurred for
+    /// `delay`, thus rapid typing results in a single notification (with the
+    /// final text). A pending notification is cancelled by a programmatic
+    /// edit (e.g. [`HasString::set_string`]). Activation and loss of focus
+    /// are not delayed; a pending notification is delivered first (where both
+    /// guards return a message, only that from activation or loss of focus is
+    /// returned).
+    pub fn edit_delay(mut self, delay: Duration) -> Self {
+        self.edit_delay = Some(delay);
+        self
+    }
+
     /// Get whether the input state is erroneous
     pub fn has_error(&self) -> bool {
         self.error_state
@@ -417,6 +935,82 @@ impl<G> EditBox<G> {
         self.error_state = error_state;
     }
 
+    // Show the caret, restart blinking and report its position to the IME
+    fn reset_blink(&mut self, mgr: &mut Manager) {
+        if !self.caret_shown {
+            self.caret_shown = true;
+            mgr.redraw(self.id());
+        }
+        if let Some(interval) = self.blink_interval {
+            self.caret_blink = Some(Instant::now() + interval);
+            mgr.update_on_timer(interval, self.id());
+        }
+        self.set_ime_position(mgr);
+    }
+
+    // Report the edit marker's rect, for placement of the IME candidate window
+    fn set_ime_position(&self, mgr: &mut Manager) {
+        let marker = match self.preedit.as_ref() {
+            Some((text, cursor)) => text.text_glyph_pos(cursor.start).next_back(),
+            None => self
+                .text
+                .text_glyph_pos(self.selection.edit_pos())
+                .next_back(),
+        };
+        if let Some(marker) = marker {
+            let top = Vec2(marker.pos.0, marker.pos.1 - marker.ascent);
+            let pos = self.text_pos - self.view_offset + Coord::from(top);
+            let height = (marker.ascent - marker.descent).ceil() as u32;
+            mgr.set_ime_position(self.id(), Rect::new(pos, Size(1, height)));
+        }
+    }
+
+    // Replace the IME preedit string (shown in place of the selection)
+    fn set_preedit(&mut self, mgr: &mut Manager, text: String, cursor: Option<Range<usize>>) {
+        self.preedit = None;
+        if self.editable && !text.is_empty() {
+            let range = self.selection.range();
+            let content = self.text.text();
+            let mut s = String::with_capacity(content.len() + text.len());
+            s.push_str(&content[..range.start]);
+            s.push_str(&text);
+            s.push_str(&content[range.end..]);
+
+            let start = range.start;
+            let end = start + text.len();
+            let cursor = match cursor {
+                Some(r) => (start + r.start)..(start + r.end),
+                None => end..end,
+            };
+            let mut display = Text::new(self.text.env().clone(), PreeditText::new(s, start..end));
+            display.prepare();
+            self.preedit = Some((display, cursor));
+        }
+        mgr.redraw(self.id());
+    }
+
+    fn ime_commit(&mut self, mgr: &mut Manager, text: &str) -> EditAction {
+        if self.preedit.take().is_some() {
+            mgr.redraw(self.id());
+        }
+        if !self.editable {
+            return EditAction::Unhandled;
+        }
+
+        let selection = self.selection.range();
+        if !self.check_edit(mgr, selection.clone(), text) {
+            return EditAction::None;
+        }
+        self.record_edit(LastEdit::Insert, true);
+        let _ = self.text.replace_range(selection.clone(), text);
+        self.selection.set_pos(selection.start + text.len());
+        self.edit_x_coord = None;
+        self.text.prepare();
+        self.set_view_offset_from_edit_pos();
+        mgr.redraw(self.id());
+        EditAction::Edit
+    }
+
     fn received_char(&mut self, mgr: &mut Manager, c: char) -> EditAction {
         if !self.editable {
             return EditAction::Unhandled;
@@ -425,13 +1019,18 @@ impl<G> EditBox<G> {
         let pos = self.selection.edit_pos();
         let selection = self.selection.range();
         let have_sel = selection.start < selection.end;
-        if self.last_edit != LastEdit::Insert || have_sel {
-            self.old_state = Some((self.text.clone_string(), pos, self.selection.sel_pos()));
-            self.last_edit = LastEdit::Insert;
+        let mut buf = [0u8; 4];
+        let s = c.encode_utf8(&mut buf);
+        let range = if have_sel {
+            selection.clone()
+        } else {
+            pos..pos
+        };
+        if !self.check_edit(mgr, range, s) {
+            return EditAction::None;
         }
+        self.record_edit(LastEdit::Insert, have_sel);
         if have_sel {
-            let mut buf = [0u8; 4];
-            let s = c.encode_utf8(&mut buf);
             let _ = self.text.replace_range(selection.clone(), s);
             self.selection.set_pos(selection.start + s.len());
         } else {
@@ -445,6 +1044,75 @@ impl<G> EditBox<G> {
         EditAction::Edit
     }
 
+    fn edit_state(&self) -> EditState {
+        EditState {
+            text: self.text.clone_string(),
+            edit_pos: self.selection.edit_pos(),
+            sel_pos: self.selection.sel_pos(),
+        }
+    }
+
+    // Check whether replacing `range` with `s` gives valid text
+    fn is_valid_edit(&self, range: Range<usize>, s: &str) -> bool {
+        match self.validator.as_ref() {
+            Some(validator) => validator(&replaced(self.text.text(), range, s)),
+            None => true,
+        }
+    }
+
+    // As `is_valid_edit`, but flash the error state if invalid
+    fn check_edit(&mut self, mgr: &mut Manager, range: Range<usize>, s: &str) -> bool {
+        let valid = self.is_valid_edit(range, s);
+        if !valid && self.reject_flash {
+            self.flash_end = Some(Instant::now() + FLASH_DUR);
+            mgr.update_on_timer(FLASH_DUR, self.id());
+            mgr.redraw(self.id());
+        }
+        valid
+    }
+
+    // Apply the formatter, if any (on commit)
+    fn format(&mut self) {
+        let text = match self.formatter.as_ref() {
+            Some(formatter) => formatter(self.text.text()),
+            None => return,
+        };
+        if text != self.text.text() {
+            self.record_edit(LastEdit::Paste, true);
+            let len = text.len();
+            let _ = kas::text::util::set_string_and_prepare(&mut self.text, text);
+            self.selection.set_pos(len);
+            self.edit_x_coord = None;
+            self.set_view_offset_from_edit_pos();
+        }
+    }
+
+    // Record the current state for undo, before an edit
+    fn record_edit(&mut self, edit: LastEdit, replace: bool) {
+        if self.history.begin_edit(edit, replace) {
+            let state = self.edit_state();
+            self.history.push_undo(state);
+        }
+    }
+
+    // Restore text and selection (for undo / redo)
+    fn restore_state(&mut self, state: EditState) {
+        let mut text = state.text;
+        self.text.swap_string(&mut text);
+        self.selection.set_edit_pos(state.edit_pos);
+        self.selection.set_sel_pos(state.sel_pos);
+        self.edit_x_coord = None;
+    }
+
+    // True if the text direction at the edit marker is right-to-left
+    fn is_rtl_at(&self, pos: usize) -> bool {
+        self.text
+            .text_glyph_pos(pos)
+            .next_back()
+            .map(|marker| !marker.is_ltr())
+            .unwrap_or(false)
+    }
+
     fn control_key(&mut self, mgr: &mut Manager, key: ControlKey) -> EditAction {
         if !self.editable {
             return EditAction::Unhandled;
@@ -456,6 +1124,14 @@ impl<G> EditBox<G> {
         let have_sel = selection.end > selection.start;
         let ctrl = mgr.modifiers().ctrl();
         let mut shift = mgr.modifiers().shift();
+
+        // Left and Right move visually: in right-to-left text, this is the
+        // reverse of the logical direction.
+        let key = match key {
+            ControlKey::Left if self.is_rtl_at(pos) => ControlKey::Right,
+            ControlKey::Right if self.is_rtl_at(pos) => ControlKey::Left,
+            key => key,
+        };
         let string;
 
         enum Action<'a> {
@@ -482,7 +1158,9 @@ impl<G> EditBox<G> {
             ControlKey::Return if self.multi_line => {
                 Action::Insert('\n'.encode_utf8(&mut buf), LastEdit::Insert)
             }
-            ControlKey::Tab => Action::Insert('\t'.encode_utf8(&mut buf), LastEdit::Insert),
+            ControlKey::Tab if self.insert_tab => {
+                Action::Insert('\t'.encode_utf8(&mut buf), LastEdit::Insert)
+            }
             ControlKey::Home if ctrl => Action::Move(0, None),
             ControlKey::Home => {
                 let pos = self.text.find_line(pos).map(|r| r.1.start).unwrap_or(0);
@@ -656,43 +1334,41 @@ impl<G> EditBox<G> {
             }
             ControlKey::Paste => {
                 if let Some(content) = mgr.get_clipboard() {
-                    let mut end = content.len();
-                    if !self.multi_line {
-                        // We cut the content short on control characters and
-                        // ignore them (preventing line-breaks and ignoring any
-                        // actions such as recursive-paste).
-                        for (i, c) in content.char_indices() {
-                            if c < '\u{20}' || (c >= '\u{7f}' && c <= '\u{9f}') {
-                                end = i;
-                                break;
-                            }
-                        }
-                    }
-
-                    string = content;
-                    Action::Insert(&string[0..end], LastEdit::Paste)
+                    string = sanitise_paste(&content, self.multi_line, self.paste_newlines);
+                    Action::Insert(&string, LastEdit::Paste)
                 } else {
                     Action::None
                 }
             }
             ControlKey::Undo | ControlKey::Redo => {
-                // TODO: maintain full edit history (externally?)
-                // NOTE: undo *and* redo shortcuts map to this control char
-                if let Some((state, pos2, sel_pos)) = self.old_state.as_mut() {
-                    self.text.swap_string(state);
-                    self.selection.set_edit_pos(*pos2);
-                    *pos2 = pos;
-                    let pos = *sel_pos;
-                    *sel_pos = self.selection.sel_pos();
-                    self.selection.set_sel_pos(pos);
-                    self.edit_x_coord = None;
-                    self.last_edit = LastEdit::None;
+                let current = self.edit_state();
+                let state = match key {
+                    ControlKey::Undo => self.history.undo(current),
+                    _ => self.history.redo(current),
+                };
+                if let Some(state) = state {
+                    self.restore_state(state);
+                    mgr.redraw(self.id());
+                    Action::Edit
+                } else {
+                    Action::None
                 }
-                Action::Edit
             }
             _ => Action::Unhandled,
         };
 
+        // Reject invalid edits
+        let range = if have_sel {
+            selection.clone()
+        } else {
+            pos..pos
+        };
+        let action = match action {
+            Action::Insert(s, _) if !self.check_edit(mgr, range.clone(), s) => Action::None,
+            Action::Delete(sel) if !self.check_edit(mgr, sel.clone(), "") => Action::None,
+            action => action,
+        };
+
         let result = match action {
             Action::None => EditAction::None,
             Action::Unhandled => EditAction::Unhandled,
@@ -700,20 +1376,11 @@ impl<G> EditBox<G> {
             Action::Edit => EditAction::Edit,
             Action::Insert(s, edit) => {
                 let mut pos = pos;
+                self.record_edit(edit, have_sel);
                 if have_sel {
-                    self.old_state =
-                        Some((self.text.clone_string(), pos, self.selection.sel_pos()));
-                    self.last_edit = edit;
-
                     self.text.replace_range(selection.clone(), s);
                     pos = selection.start;
                 } else {
-                    if self.last_edit != edit {
-                        self.old_state =
-                            Some((self.text.clone_string(), pos, self.selection.sel_pos()));
-                        self.last_edit = edit;
-                    }
-
                     self.text.replace_range(pos..pos, s);
                 }
                 self.selection.set_pos(pos + s.len());
@@ -721,11 +1388,7 @@ impl<G> EditBox<G> {
                 EditAction::Edit
             }
             Action::Delete(sel) => {
-                if self.last_edit != LastEdit::Delete {
-                    self.old_state =
-                        Some((self.text.clone_string(), pos, self.selection.sel_pos()));
-                    self.last_edit = LastEdit::Delete;
-                }
+                self.record_edit(LastEdit::Delete, have_sel);
 
                 self.text.replace_range(sel.clone(), "");
                 self.selection.set_pos(sel.start);
@@ -733,6 +1396,7 @@ impl<G> EditBox<G> {
                 EditAction::Edit
             }
             Action::Move(pos, x_coord) => {
+                self.history.break_edit();
                 self.selection.set_edit_pos(pos);
                 if !shift {
                     self.selection.set_empty();
@@ -757,6 +1421,7 @@ impl<G> EditBox<G> {
     }
 
     fn set_edit_pos_from_coord(&mut self, mgr: &mut Manager, coord: Coord) {
+        self.history.break_edit();
         let rel_pos = (coord - self.text_pos + self.view_offset).into();
         self.selection
             .set_edit_pos(self.text.text_index_nearest(rel_pos));
@@ -802,6 +1467,25 @@ impl<G> EditBox<G> {
     }
 }
 
+impl<G: EditGuard> EditBox<G> {
+    // Notify the guard of a user edit, unless delayed
+    fn edited(&mut self, mgr: &mut Manager) -> Response<G::Msg> {
+        match self.edit_delay {
+            Some(delay) => {
+                self.edit_pending = Some(Instant::now() + delay);
+                mgr.update_on_timer(delay, self.id());
+                Response::None
+            }
+            None => G::edit(self).into(),
+        }
+    }
+
+    // Deliver any pending (delayed) edit notification
+    fn flush_edit(&mut self) -> Option<G::Msg> {
+        self.edit_pending.take().and_then(|_| G::edit(self))
+    }
+}
+
 impl<G: EditGuard> HasStr for EditBox<G> {
     fn get_str(&self) -> &str {
         self.text.text()
@@ -810,7 +1494,14 @@ impl<G: EditGuard> HasStr for EditBox<G> {
 
 impl<G: EditGuard> HasString for EditBox<G> {
     fn set_string(&mut self, string: String) -> TkAction {
+        self.preedit = None;
+        if self.undo_set_string {
+            self.record_edit(LastEdit::Paste, true);
+        } else {
+            self.history.clear();
+        }
         let action = kas::text::util::set_string_and_prepare(&mut self.text, string);
+        self.edit_pending = None;
         let _ = G::edit(self);
         action
     }
@@ -823,32 +1514,90 @@ impl<G: EditGuard + 'static> event::Handler for EditBox<G> {
         match event {
             Event::Activate => {
                 mgr.request_char_focus(self.id());
+                self.reset_blink(mgr);
                 Response::None
             }
-            Event::LostCharFocus => G::focus_lost(self)
-                .map(|msg| msg.into())
-                .unwrap_or(Response::None),
+            Event::NavFocus => {
+                // Character focus may be given with nav focus (see
+                // Manager::set_key_focus); if so, start blinking
+                if mgr.has_char_focus(self.id()) {
+                    self.reset_blink(mgr);
+                }
+                Response::Focus(self.rect())
+            }
+            Event::LostCharFocus => {
+                // Stop blinking; the marker is not drawn without focus
+                self.caret_shown = true;
+                self.caret_blink = None;
+                if self.preedit.take().is_some() {
+                    mgr.redraw(self.id());
+                }
+                if self.formatter.is_some() {
+                    self.format();
+                    mgr.redraw(self.id());
+                }
+                let edit = self.flush_edit();
+                G::focus_lost(self)
+                    .or(edit)
+                    .map(|msg| msg.into())
+                    .unwrap_or(Response::None)
+            }
             Event::LostSelFocus => {
                 self.selection.set_empty();
                 mgr.redraw(self.id());
                 Response::None
             }
-            Event::Control(key) => match self.control_key(mgr, key) {
-                EditAction::None => Response::None,
-                EditAction::Unhandled => Response::Unhandled(Event::Control(key)),
-                EditAction::Activate => G::activate(self).into(),
-                EditAction::Edit => G::edit(self).into(),
-            },
-            Event::ReceivedCharacter(c) => match self.received_char(mgr, c) {
-                EditAction::None => Response::None,
-                EditAction::Unhandled => Response::Unhandled(Event::ReceivedCharacter(c)),
-                EditAction::Activate => G::activate(self).into(),
-                EditAction::Edit => G::edit(self).into(),
+            Event::Control(key) => {
+                let action = self.control_key(mgr, key);
+                if action != EditAction::Unhandled {
+                    self.reset_blink(mgr);
+                }
+                match action {
+                    EditAction::None => Response::None,
+                    EditAction::Unhandled => Response::Unhandled(Event::Control(key)),
+                    EditAction::Activate => {
+                        if self.formatter.is_some() {
+                            self.format();
+                            mgr.redraw(self.id());
+                        }
+                        let edit = self.flush_edit();
+                        G::activate(self).or(edit).into()
+                    }
+                    EditAction::Edit => self.edited(mgr),
+                }
+            }
+            Event::ReceivedCharacter(c) => {
+                let action = self.received_char(mgr, c);
+                if action != EditAction::Unhandled {
+                    self.reset_blink(mgr);
+                }
+                match action {
+                    EditAction::None => Response::None,
+                    EditAction::Unhandled => Response::Unhandled(Event::ReceivedCharacter(c)),
+                    EditAction::Activate => {
+                        let edit = self.flush_edit();
+                        G::activate(self).or(edit).into()
+                    }
+                    EditAction::Edit => self.edited(mgr),
+                }
+            }
+            Event::ImePreedit { text, cursor_range } => {
+                self.set_preedit(mgr, text, cursor_range);
+                self.reset_blink(mgr);
+                Response::None
+            }
+            Event::ImeCommit { text } => match self.ime_commit(mgr, &text) {
+                EditAction::Unhandled => Response::Unhandled(Event::ImeCommit { text }),
+                _ => {
+                    self.reset_blink(mgr);
+                    self.edited(mgr)
+                }
             },
             Event::PressStart { source, coord, .. } if source.is_primary() => {
                 if let PressSource::Touch(touch_id) = source {
                     if self.touch_phase == TouchPhase::None {
-                        self.touch_phase = TouchPhase::Start(touch_id, coord);
+                        let end = Instant::now() + TOUCH_DUR;
+                        self.touch_phase = TouchPhase::Start(touch_id, coord, end);
                         mgr.update_on_timer(TOUCH_DUR, self.id());
                     }
                 } else if let PressSource::Mouse(_, repeats) = source {
@@ -867,6 +1616,7 @@ impl<G: EditGuard + 'static> event::Handler for EditBox<G> {
                 }
                 mgr.request_grab(self.id(), source, coord, GrabMode::Grab, None);
                 mgr.request_char_focus(self.id());
+                self.reset_blink(mgr);
                 Response::None
             }
             Event::PressMove {
@@ -905,7 +1655,7 @@ impl<G: EditGuard + 'static> event::Handler for EditBox<G> {
             }
             Event::PressEnd { source, .. } => {
                 match self.touch_phase {
-                    TouchPhase::Start(id, coord) if source == PressSource::Touch(id) => {
+                    TouchPhase::Start(id, coord, _) if source == PressSource::Touch(id) => {
                         if !mgr.modifiers().ctrl() {
                             self.set_edit_pos_from_coord(mgr, coord);
                             if !mgr.modifiers().shift() {
@@ -934,15 +1684,18 @@ impl<G: EditGuard + 'static> event::Handler for EditBox<G> {
                     }
                     ScrollDelta::PixelDelta(coord) => coord,
                 };
-                if self.pan_delta(mgr, delta2) {
+                // Panning moves content with the delta; scrolling moves it against
+                if self.pan_delta(mgr, Coord::ZERO - delta2) {
                     Response::None
                 } else {
                     Response::Unhandled(Event::Scroll(delta))
                 }
             }
             Event::TimerUpdate => {
+                // Timers are shared: check which are due
+                let now = Instant::now();
                 match self.touch_phase {
-                    TouchPhase::Start(touch_id, coord) => {
+                    TouchPhase::Start(touch_id, coord, end) if end <= now => {
                         if !mgr.modifiers().ctrl() {
                             self.set_edit_pos_from_coord(mgr, coord);
                             if !mgr.modifiers().shift() {
@@ -951,11 +1704,430 @@ impl<G: EditGuard + 'static> event::Handler for EditBox<G> {
                         }
                         self.touch_phase = TouchPhase::Cursor(touch_id);
                     }
+                    TouchPhase::Start(_, _, end) => mgr.update_on_timer(end - now, self.id()),
                     _ => (),
                 }
+                if let Some(end) = self.flash_end {
+                    if end <= now {
+                        self.flash_end = None;
+                        mgr.redraw(self.id());
+                    } else {
+                        mgr.update_on_timer(end - now, self.id());
+                    }
+                }
+                if let Some(time) = self.caret_blink {
+                    if time > now {
+                        mgr.update_on_timer(time - now, self.id());
+                    } else if let Some(interval) = self.blink_interval {
+                        self.caret_shown = !self.caret_shown;
+                        mgr.redraw(self.id());
+                        self.caret_blink = Some(now + interval);
+                        mgr.update_on_timer(interval, self.id());
+                    } else {
+                        // Blinking was disabled (e.g. by a theme change)
+                        self.caret_shown = true;
+                        self.caret_blink = None;
+                        mgr.redraw(self.id());
+                    }
+                }
+                if let Some(end) = self.edit_pending {
+                    if end <= now {
+                        self.edit_pending = None;
+                        return G::edit(self).into();
+                    }
+                    mgr.update_on_timer(end - now, self.id());
+                }
                 Response::None
             }
             event => Response::Unhandled(event),
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::event::VirtualKeyCode;
+    use crate::test_util::TestManager;
+    use crate::widget::{Column, ScrollRegion};
+    use std::cell::Cell;
+
+    fn state(text: &str) -> EditState {
+        EditState {
+            text: text.to_string(),
+            edit_pos: text.len(),
+            sel_pos: text.len(),
+        }
+    }
+
+    // Simulate an edit from text `before`
+    fn edit(history: &mut EditHistory, edit: LastEdit, replace: bool, before: &str) {
+        if history.begin_edit(edit, replace) {
+            history.push_undo(state(before));
+        }
+    }
+
+    #[test]
+    fn undo_coalescing() {
+        let mut history = EditHistory::default();
+        edit(&mut history, LastEdit::Insert, false, "");
+        edit(&mut history, LastEdit::Insert, false, "a");
+        edit(&mut history, LastEdit::Insert, false, "ab");
+        edit(&mut history, LastEdit::Delete, false, "abc");
+        history.break_edit();
+        edit(&mut history, LastEdit::Insert, false, "ab");
+        // Replacing a selection is never coalesced
+        edit(&mut history, LastEdit::Insert, true, "abd");
+        assert_eq!(history.undo.len(), 4);
+
+        assert_eq!(history.undo(state("abe")), Some(state("abd")));
+        assert_eq!(history.undo(state("abd")), Some(state("ab")));
+        assert_eq!(history.undo(state("ab")), Some(state("abc")));
+        assert_eq!(history.undo(state("abc")), Some(state("")));
+        assert_eq!(history.undo(state("")), None);
+
+        assert_eq!(history.redo(state("")), Some(state("abc")));
+        assert_eq!(history.redo(state("abc")), Some(state("ab")));
+        // A new edit clears the redo history
+        edit(&mut history, LastEdit::Insert, false, "ab");
+        assert_eq!(history.redo(state("abx")), None);
+        assert_eq!(history.undo(state("abx")), Some(state("ab")));
+    }
+
+    #[test]
+    fn undo_limit() {
+        let mut history = EditHistory::new(3);
+        let texts = ["", "a", "ab", "abc", "abcd"];
+        for text in &texts {
+            edit(&mut history, LastEdit::Paste, false, text);
+        }
+        // The oldest steps are forgotten
+        assert_eq!(history.undo(state("abcde")), Some(state("abcd")));
+        assert_eq!(history.undo(state("abcd")), Some(state("abc")));
+        assert_eq!(history.undo(state("abc")), Some(state("ab")));
+        assert_eq!(history.undo(state("ab")), None);
+        assert_eq!(history.redo(state("ab")), Some(state("abc")));
+
+        history.set_limit(1);
+        assert_eq!(history.undo.len(), 1);
+        history.set_limit(0);
+        edit(&mut history, LastEdit::Paste, false, "x");
+        assert!(history.undo.is_empty());
+    }
+
+    #[test]
+    fn validation() {
+        let digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+        let edit = EditBox::new("12").with_validator(digits);
+        assert!(edit.is_valid_edit(2..2, "3"));
+        assert!(!edit.is_valid_edit(2..2, "a"));
+        assert!(edit.is_valid_edit(0..1, ""));
+        // A paste is rejected as a whole
+        assert!(edit.is_valid_edit(1..1, "345"));
+        assert!(!edit.is_valid_edit(1..1, "34x5"));
+        assert!(EditBox::new("12").is_valid_edit(1..1, "34x5"));
+    }
+
+    #[test]
+    fn parse_and_format() {
+        let _ = crate::text::fonts::fonts().load_default();
+
+        let separators = |s: &str| {
+            let digits: Vec<char> = s.chars().filter(|c| *c != ',').collect();
+            let mut result = String::new();
+            for (i, c) in digits.iter().enumerate() {
+                if i > 0 && (digits.len() - i) % 3 == 0 {
+                    result.push(',');
+                }
+                result.push(*c);
+            }
+            result
+        };
+        let mut edit = EditBox::new("1234567")
+            .with_formatter(separators)
+            .with_parser(|s| s.replace(',', "").parse::<u32>().ok());
+        edit.format();
+        assert_eq!(edit.get_str(), "1,234,567");
+        assert_eq!(edit.selection.edit_pos(), 9);
+        assert_eq!(EditGuard::activate(&mut edit), Some(1234567));
+        assert!(!edit.has_error());
+
+        let _ = edit.set_string("12x".to_string());
+        assert_eq!(EditGuard::activate(&mut edit), None);
+        assert!(edit.has_error());
+    }
+
+    #[test]
+    fn paste() {
+        let crlf = "one\r\ntwo\rthree\n";
+        let space = PasteNewlines::Space;
+        let truncate = PasteNewlines::Truncate;
+        assert_eq!(sanitise_paste(crlf, true, space), "one\ntwo\nthree\n");
+        assert_eq!(sanitise_paste(crlf, false, space), "one two three ");
+        assert_eq!(sanitise_paste(crlf, false, truncate), "one");
+
+        let nul = "a\0b\tc\u{7f}\u{85}d";
+        assert_eq!(sanitise_paste(nul, false, space), "ab\tcd");
+        assert_eq!(sanitise_paste(nul, true, truncate), "ab\tcd");
+    }
+
+    #[test]
+    fn edit_and_activate_guards() {
+        let _ = crate::text::fonts::fonts().load_default();
+
+        let edits = Rc::new(Cell::new(0));
+        let activations = Rc::new(Cell::new(0));
+        let new_edit = || {
+            let (e, a) = (edits.clone(), activations.clone());
+            EditBox::new("")
+                .on_activate(move |_| {
+                    a.set(a.get() + 1);
+                    None::<VoidMsg>
+                })
+                .on_edit(move |_| {
+                    e.set(e.get() + 1);
+                    None
+                })
+        };
+        let edit = new_edit();
+        // Setting the edit guard calls it once
+        assert_eq!(edits.replace(0), 1);
+
+        let mut test = TestManager::new(edit);
+        let id = test.widget.id();
+
+        // Editing calls only the edit guard
+        test.send(id, Event::ReceivedCharacter('a'));
+        test.send(id, Event::ReceivedCharacter('b'));
+        assert_eq!((edits.get(), activations.get()), (2, 0));
+        assert_eq!(test.widget.get_str(), "ab");
+
+        // Activation calls only the activation guard
+        test.send(id, Event::Control(ControlKey::Return));
+        assert_eq!((edits.get(), activations.get()), (2, 1));
+
+        // With a delay, edits are coalesced until the timer fires
+        let mut test = TestManager::new(new_edit().edit_delay(Duration::from_secs(0)));
+        let id = test.widget.id();
+        edits.set(0);
+        test.send(id, Event::ReceivedCharacter('a'));
+        test.send(id, Event::ReceivedCharacter('b'));
+        assert_eq!(edits.get(), 0);
+        test.send(id, Event::TimerUpdate);
+        assert_eq!(edits.get(), 1);
+        test.send(id, Event::TimerUpdate);
+        assert_eq!(edits.get(), 1);
+
+        // A programmatic edit cancels a pending notification (but calls the
+        // guard, discarding its result)
+        test.send(id, Event::ReceivedCharacter('c'));
+        let _ = test.widget.set_string("x".to_string());
+        test.send(id, Event::TimerUpdate);
+        assert_eq!(edits.get(), 2);
+
+        // A pending notification is delivered before activation
+        test.send(id, Event::ReceivedCharacter('d'));
+        activations.set(0);
+        test.send(id, Event::Control(ControlKey::Return));
+        assert_eq!((edits.get(), activations.get()), (3, 1));
+        test.send(id, Event::TimerUpdate);
+        assert_eq!(edits.get(), 3);
+    }
+
+    // Counts calls to the edit and focus-lost guards
+    struct Counter(Rc<Cell<(u32, u32)>>);
+    impl EditGuard for Counter {
+        type Msg = VoidMsg;
+        fn focus_lost(edit: &mut EditBox<Self>) -> Option<VoidMsg> {
+            let (edits, lost) = edit.guard.0.get();
+            edit.guard.0.set((edits, lost + 1));
+            None
+        }
+        fn edit(edit: &mut EditBox<Self>) -> Option<VoidMsg> {
+            let (edits, lost) = edit.guard.0.get();
+            edit.guard.0.set((edits + 1, lost));
+            None
+        }
+    }
+
+    #[test]
+    fn edit_focus_lost() {
+        let _ = crate::text::fonts::fonts().load_default();
+
+        let count = Rc::new(Cell::new((0, 0)));
+        let new_edit = || {
+            EditBox::new("")
+                .with_guard(Counter(count.clone()))
+                .edit_delay(Duration::from_secs(60))
+        };
+        let mut test = TestManager::new(Column::new(vec![new_edit(), new_edit()]));
+        let ids: Vec<_> = test.widget.iter().map(|w| w.id()).collect();
+        count.set((0, 0));
+
+        // Activating an edit box gives it character focus; activating it
+        // again does not lose focus
+        test.send(ids[0], Event::Activate);
+        test.send(ids[0], Event::Activate);
+        assert_eq!(count.get(), (0, 0));
+
+        // Focus lost by moving to another widget is reported once, after
+        // delivering the pending edit notification
+        test.send(ids[0], Event::ReceivedCharacter('a'));
+        assert_eq!(count.get(), (0, 0));
+        test.send(ids[1], Event::Activate);
+        assert_eq!(count.get(), (1, 1));
+        test.update();
+        assert_eq!(count.get(), (1, 1));
+
+        test.with(|mgr, _| mgr.set_char_focus(None));
+        assert_eq!(count.get(), (1, 2));
+    }
+
+    #[test]
+    fn tab_from_edit_box() {
+        let _ = crate::text::fonts::fonts().load_default();
+
+        let column = Column::new(vec![EditBox::new(""), EditBox::new("").insert_tab(true)]);
+        let mut test = TestManager::new(column);
+        let ids: Vec<_> = test.widget.iter().map(|w| w.id()).collect();
+
+        // By default, Tab moves focus from an edit box
+        test.send(ids[0], Event::Activate);
+        assert_eq!(test.state.char_focus(ids[0]), (true, true));
+        test.key(VirtualKeyCode::Tab);
+        assert_eq!(test.state.char_focus(ids[0]), (false, false));
+        assert!(test.state.nav_focus(ids[1]));
+        assert_eq!(test.widget[0].get_str(), "");
+
+        // An edit box may instead insert a tab
+        test.send(ids[1], Event::Activate);
+        test.key(VirtualKeyCode::Tab);
+        assert_eq!(test.state.char_focus(ids[1]), (true, true));
+        assert!(test.state.nav_focus(ids[1]));
+        assert_eq!(test.widget[1].get_str(), "\t");
+    }
+
+    #[test]
+    fn caret_blink() {
+        let _ = crate::text::fonts::fonts().load_default();
+
+        let column = Column::new(vec![EditBox::new("a"), EditBox::new("b")]);
+        let mut test = TestManager::new(column);
+        let ids: Vec<_> = test.widget.iter().map(|w| w.id()).collect();
+        let rect = Rect::new(Coord::ZERO, Size(200, 100));
+        // A zero interval makes each blink due immediately
+        let interval = Duration::from_millis(0);
+        test.tkw.size_handle.edit_marker_blink = Some(interval);
+        test.set_rect(rect);
+        assert_eq!(test.widget[0].blink_interval, Some(interval));
+
+        // Tab gives nav focus only
+        test.key(VirtualKeyCode::Tab);
+        assert!(test.state.nav_focus(ids[0]));
+        assert_eq!(test.state.char_focus(ids[0]), (false, false));
+        assert!(test.widget[0].caret_blink.is_none());
+
+        // Manager::set_key_focus gives character focus and starts blinking
+        test.with(|mgr, _| mgr.set_key_focus(ids[0]));
+        assert_eq!(test.state.char_focus(ids[0]), (true, true));
+        assert!(test.widget[0].caret_blink.is_some());
+        let (state, tkw, widget) = (&mut test.state, &mut test.tkw, &mut test.widget);
+        state.with(tkw, |mgr| {
+            let _ = widget.send(mgr, ids[0], Event::TimerUpdate);
+        });
+        assert_eq!(test.update(), TkAction::Redraw);
+        assert!(!test.widget[0].caret_shown);
+
+        // Blinking stops on focus loss
+        test.with(|mgr, _| mgr.set_key_focus(ids[1]));
+        assert_eq!(test.state.char_focus(ids[1]), (true, true));
+        assert!(test.widget[1].caret_blink.is_some());
+        assert!(test.widget[0].caret_blink.is_none());
+        assert!(test.widget[0].caret_shown);
+
+        // Where the theme disables blinking, the caret is always shown
+        test.tkw.size_handle.edit_marker_blink = None;
+        test.set_rect(rect);
+        test.send(ids[0], Event::Activate);
+        assert!(test.widget[0].caret_blink.is_none());
+        assert!(test.widget[0].caret_shown);
+    }
+
+    #[test]
+    fn ime_preedit_and_commit() {
+        let _ = crate::text::fonts::fonts().load_default();
+
+        let edit = EditBox::new("one\ntwo\nthree\nfour").multi_line(true);
+        let mut test = TestManager::new(ScrollRegion::new(edit));
+        test.set_rect(Rect::new(Coord::ZERO, Size(100, 20)));
+        let id = test.widget.inner().id();
+        let text = |test: &TestManager<ScrollRegion<_>>| test.widget.inner().get_str().to_string();
+
+        // Without character focus, input-method events are ignored
+        test.with(|mgr, w| mgr.handle_ime_commit(w, "x".to_string()));
+        assert_eq!(text(&test), "one\ntwo\nthree\nfour");
+
+        test.send(id, Event::Activate);
+        let pos = test.tkw.ime_position.unwrap().pos;
+
+        // Preedit text is displayed without editing the contents; the IME
+        // position (here: of the preedit cursor, at its start) is reported in
+        // window coordinates
+        assert_eq!(test.widget.set_offset(Coord(0, 10)), TkAction::RegionMoved);
+        let cursor = Some(0..0);
+        test.with(|mgr, w| mgr.handle_ime_preedit(w, "xy".to_string(), cursor));
+        assert_eq!(text(&test), "one\ntwo\nthree\nfour");
+        assert!(test.widget.inner().preedit.is_some());
+        assert_eq!(test.tkw.ime_position.unwrap().pos, pos - Coord(0, 10));
+
+        // Committed text replaces the preedit text
+        test.with(|mgr, w| mgr.handle_ime_commit(w, "xy".to_string()));
+        assert_eq!(text(&test), "one\ntwo\nthree\nfourxy");
+        assert!(test.widget.inner().preedit.is_none());
+    }
+
+    #[test]
+    fn rtl_layout() {
+        let _ = crate::text::fonts::fonts().load_default();
+
+        // Each Hebrew letter is two bytes long
+        let mut test = TestManager::new(EditBox::new("\u{5d0}\u{5d1}\u{5d2}"));
+        test.set_rect(Rect::new(Coord::ZERO, Size(200, 30)));
+        let id = test.widget.id();
+        let x = |edit: &EditBox<EditVoid>, index| {
+            let marker = edit.text.text_glyph_pos(index).next_back().unwrap();
+            assert!(!marker.is_ltr());
+            marker.pos.0
+        };
+
+        // Right-to-left text is laid out from right to left
+        assert!(x(&test.widget, 0) > x(&test.widget, 2));
+        assert!(x(&test.widget, 2) > x(&test.widget, 4));
+
+        // Left and Right move the caret visually
+        test.send(id, Event::Activate);
+        test.send(id, Event::Control(ControlKey::Home));
+        assert_eq!(test.widget.selection.edit_pos(), 0);
+        test.send(id, Event::Control(ControlKey::Left));
+        assert_eq!(test.widget.selection.edit_pos(), 2);
+        test.send(id, Event::Control(ControlKey::Left));
+        assert_eq!(test.widget.selection.edit_pos(), 4);
+        test.send(id, Event::Control(ControlKey::Right));
+        assert_eq!(test.widget.selection.edit_pos(), 2);
+
+        // Mixed-direction text does not panic on navigation or hit-testing
+        let _ = test.widget.set_string("ab \u{5d0}\u{5d1} cd".to_string());
+        test.send(id, Event::Control(ControlKey::Home));
+        for key in [ControlKey::Left, ControlKey::Right].iter() {
+            for _ in 0..12 {
+                test.send(id, Event::Control(*key));
+                let pos = test.widget.selection.edit_pos();
+                assert!(test.widget.get_str().is_char_boundary(pos));
+            }
+        }
+        for x in (0..200).step_by(5) {
+            let index = test.widget.text.text_index_nearest(Vec2(x as f32, 10.0));
+            assert!(test.widget.get_str().is_char_boundary(index));
+        }
+    }
+}
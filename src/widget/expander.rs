@@ -0,0 +1,320 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! A collapsible section
+
+use std::fmt::{self, Debug};
+use std::rc::Rc;
+
+use kas::access::{AccessNode, Role};
+use kas::draw::TextClass;
+use kas::{event, prelude::*};
+
+/// A collapsible section: a clickable header above some content
+///
+/// The header shows a chevron and a label. Clicking the header (or pressing
+/// Enter or Space while it has keyboard focus) shows or hides the content.
+/// The chevron points right when collapsed and down when expanded.
+///
+/// While collapsed, the content does not take space, is not drawn, and is
+/// excluded from both coordinate search and keyboard navigation.
+///
+/// Optionally, a message may be emitted on toggle (see
+/// [`Expander::on_toggle`]), e.g. to persist the state.
+#[handler(noauto)]
+#[widget(config=noauto)]
+#[derive(Clone, Widget)]
+pub struct Expander<W: Widget> {
+    #[widget_core]
+    core: CoreData,
+    #[widget]
+    child: W,
+    label: Text<String>,
+    expanded: bool,
+    // Header area relative to our rect
+    header: Rect,
+    // Size of the chevron and its gap to the label
+    mark_size: Size,
+    mark_gap: u32,
+    // Vertical offset of the child from our rect
+    child_offset: u32,
+    on_toggle: Option<Rc<dyn Fn(bool) -> <W as event::Handler>::Msg>>,
+}
+
+impl<W: Widget> Debug for Expander<W> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Expander {{ core: {:?}, child: {:?}, label: {:?}, expanded: {:?}, ... }}",
+            self.core, self.child, self.label, self.expanded,
+        )
+    }
+}
+
+impl<W: Widget> Expander<W> {
+    /// Construct a collapsed section with the given header `label`
+    #[inline]
+    pub fn new<T: ToString>(label: T, child: W) -> Self {
+        Expander {
+            core: Default::default(),
+            child,
+            label: Text::new_single(label.to_string()),
+            expanded: false,
+            header: Rect::default(),
+            mark_size: Size::ZERO,
+            mark_gap: 0,
+            child_offset: 0,
+            on_toggle: None,
+        }
+    }
+
+    /// Set the initial state (chain style)
+    #[inline]
+    pub fn with_expanded(mut self, expanded: bool) -> Self {
+        self.expanded = expanded;
+        self
+    }
+
+    /// Set the event handler to be called on toggle (chain style)
+    ///
+    /// The closure `f` is called with the new state (true when expanded)
+    /// when toggled via the header, and the result of `f` is returned from the
+    /// event handler.
+    #[inline]
+    pub fn on_toggle<F>(mut self, f: F) -> Self
+    where
+        F: Fn(bool) -> <W as event::Handler>::Msg + 'static,
+    {
+        self.on_toggle = Some(Rc::new(f));
+        self
+    }
+
+    /// True if the content is shown
+    #[inline]
+    pub fn is_expanded(&self) -> bool {
+        self.expanded
+    }
+
+    /// Show or hide the content
+    ///
+    /// This does not call the [`Expander::on_toggle`] handler.
+    pub fn set_expanded(&mut self, expanded: bool) -> TkAction {
+        if expanded == self.expanded {
+            return TkAction::None;
+        }
+        self.expanded = expanded;
+        TkAction::Resize
+    }
+
+    /// Get a reference to the content
+    #[inline]
+    pub fn inner(&self) -> &W {
+        &self.child
+    }
+
+    /// Get a mutable reference to the content
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut W {
+        &mut self.child
+    }
+
+    fn toggle(&mut self, mgr: &mut Manager) -> Response<<W as event::Handler>::Msg> {
+        let expanded = !self.expanded;
+        *mgr += self.set_expanded(expanded);
+        if !expanded {
+            // Focus may not remain within hidden content
+            if let Some(id) = mgr.nav_focus() {
+                if self.child.is_ancestor_of(id) {
+                    mgr.set_nav_focus(self.id());
+                }
+            }
+        }
+        match self.on_toggle {
+            Some(ref f) => f(expanded).into(),
+            None => Response::None,
+        }
+    }
+}
+
+impl<W: Widget> WidgetConfig for Expander<W> {
+    fn key_nav(&self) -> bool {
+        true
+    }
+
+    fn accessible(&self) -> AccessNode {
+        AccessNode::new(Role::Generic).with_label(self.label.as_str())
+    }
+}
+
+impl<W: Widget> Layout for Expander<W> {
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let label_axis = AxisInfo::new(axis.is_vertical(), None);
+        let label_rules =
+            size_handle.text_bound(&mut self.label, TextClass::LabelSingle, label_axis);
+        let child_rules = self.child.size_rules(size_handle, axis);
+
+        let header_rules = if axis.is_horizontal() {
+            self.mark_size.0 = size_handle.checkbox().0;
+            self.mark_gap = size_handle.inner_margin().0;
+            let width = self.mark_size.0 + self.mark_gap + label_rules.min_size();
+            SizeRules::fixed(width, label_rules.margins())
+        } else {
+            self.mark_size.1 = size_handle.checkbox().1;
+            self.header.size.1 = self.mark_size.1.max(label_rules.min_size());
+            SizeRules::fixed(self.header.size.1, label_rules.margins())
+        };
+
+        if !self.expanded {
+            header_rules
+        } else if axis.is_horizontal() {
+            header_rules.max(child_rules)
+        } else {
+            let gap = header_rules.margins().1.max(child_rules.margins().0);
+            self.child_offset = self.header.size.1 + gap as u32;
+            header_rules.appended(child_rules)
+        }
+    }
+
+    fn set_rect(&mut self, mut rect: Rect, align: AlignHints) {
+        self.core.rect = rect;
+        self.header.size.0 = rect.size.0;
+        let label_width = rect.size.0.saturating_sub(self.mark_size.0 + self.mark_gap);
+        let bounds = Size(label_width, self.header.size.1);
+        self.label.update_env(|env| {
+            env.set_bounds(bounds.into());
+            env.set_align((Align::Default, Align::Centre));
+        });
+        if self.expanded {
+            rect.pos.1 += self.child_offset as i32;
+            rect.size.1 = rect.size.1.saturating_sub(self.child_offset);
+            self.child.set_rect(rect, align);
+        }
+    }
+
+    fn spatial_range(&self) -> (usize, usize) {
+        // Exclude hidden content from keyboard navigation
+        match self.expanded {
+            true => (0, 0),
+            false => (0, std::usize::MAX),
+        }
+    }
+
+    fn find_id(&self, coord: Coord) -> Option<WidgetId> {
+        if !self.rect().contains(coord) {
+            return None;
+        }
+        if (self.header + self.core.rect.pos).contains(coord) {
+            return Some(self.id());
+        }
+        match self.expanded {
+            true => self.child.find_id(coord),
+            false => None,
+        }
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, mgr: &event::ManagerState, disabled: bool) {
+        let header = self.header + self.core.rect.pos;
+        let y = (header.size.1 - self.mark_size.1) as i32 / 2;
+        let mark = Rect::new(header.pos + Coord(0, y), self.mark_size);
+        let dir = match self.expanded {
+            true => Direction::Down,
+            false => Direction::Right,
+        };
+        let state = self.input_state(mgr, disabled);
+        draw_handle.chevron(mark, dir, state);
+        let pos = header.pos + Coord((self.mark_size.0 + self.mark_gap) as i32, 0);
+        draw_handle.text(pos, &self.label, TextClass::LabelSingle, state.disabled);
+
+        if self.expanded {
+            let disabled = disabled || self.is_disabled();
+            self.child.draw(draw_handle, mgr, disabled);
+        }
+    }
+}
+
+impl<W: Widget> HasStr for Expander<W> {
+    fn get_str(&self) -> &str {
+        self.label.as_str()
+    }
+}
+
+impl<W: Widget> HasString for Expander<W> {
+    fn set_string(&mut self, text: String) -> TkAction {
+        kas::text::util::set_text_and_prepare(&mut self.label, text)
+    }
+}
+
+impl<W: Widget> event::Handler for Expander<W> {
+    type Msg = <W as event::Handler>::Msg;
+
+    #[inline]
+    fn activation_via_press(&self) -> bool {
+        true
+    }
+
+    fn handle(&mut self, mgr: &mut Manager, event: Event) -> Response<Self::Msg> {
+        match event {
+            Event::Activate => self.toggle(mgr),
+            event => Response::Unhandled(event),
+        }
+    }
+}
+
+impl<W: Widget> event::SendEvent for Expander<W> {
+    fn send(&mut self, mgr: &mut Manager, id: WidgetId, event: Event) -> Response<Self::Msg> {
+        if self.is_disabled() {
+            return Response::Unhandled(event);
+        }
+
+        if id <= self.child.id() {
+            self.child.send(mgr, id, event)
+        } else {
+            Manager::handle_generic(self, mgr, event)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::TestSizeHandle;
+    use crate::widget::StrLabel;
+
+    #[test]
+    fn collapsed_size() {
+        let _ = crate::text::fonts::fonts().load_default();
+
+        let mut size_handle = TestSizeHandle::new();
+        let mut rules = |w: &mut Expander<StrLabel>| {
+            let horiz = w.size_rules(&mut size_handle, AxisInfo::new(false, None));
+            let axis = AxisInfo::new(true, Some(horiz.min_size()));
+            (horiz, w.size_rules(&mut size_handle, axis))
+        };
+
+        let mut expander = Expander::new("More", StrLabel::new("Hidden content"));
+        let (horiz, vert) = rules(&mut expander);
+        // Chevron (16px), gap (2px) and label (4 chars of 8px); the label's
+        // margins apply to the whole header
+        assert_eq!(horiz.min_size(), 16 + 2 + 4 * 8);
+        assert_eq!(vert.min_size(), 16);
+
+        assert_eq!(expander.set_expanded(true), TkAction::Resize);
+        assert_eq!(expander.set_expanded(true), TkAction::None);
+        let (horiz, vert) = rules(&mut expander);
+        assert_eq!(horiz.min_size(), 14 * 8);
+        // The child's margin (4px) separates it from the header
+        assert_eq!(vert.min_size(), 16 + 4 + 16);
+
+        let rect = Rect::new(Coord::ZERO, Size(horiz.min_size(), vert.min_size()));
+        expander.set_rect(rect, AlignHints::NONE);
+        assert_eq!(expander.inner().rect().pos.1, 16 + 4);
+        assert_eq!(expander.find_id(Coord(4, 4)), Some(expander.id()));
+        assert_eq!(expander.spatial_range(), (0, 0));
+
+        let _ = expander.set_expanded(false);
+        assert_eq!(expander.find_id(Coord(4, 30)), None);
+        assert_eq!(expander.spatial_range(), (0, std::usize::MAX));
+    }
+}
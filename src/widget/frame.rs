@@ -5,6 +5,7 @@
 
 //! A simple frame
 
+use kas::draw::TextClass;
 use kas::{event, prelude::*};
 
 /// A frame around content
@@ -106,3 +107,216 @@ impl<W: SetAccel + Widget> SetAccel for Frame<W> {
         self.child.set_accel_string(accel)
     }
 }
+
+/// A frame with a title (group box)
+///
+/// Like [`Frame`], this draws a frame around its contents, but with a title
+/// embedded in the top edge. Space is reserved above the child for the title;
+/// the frame is at least wide enough to show the title in full.
+///
+/// The title is drawn using the theme's label colour. The frame's top edge is
+/// interrupted around the title, which is never clipped.
+#[handler(msg = <W as Handler>::Msg)]
+#[derive(Clone, Debug, Widget)]
+pub struct TitledFrame<W: Widget> {
+    #[widget_core]
+    core: CoreData,
+    #[widget]
+    child: W,
+    title: Text<String>,
+    // Position of the title relative to our rect
+    title_rect: Rect,
+    // Horizontal space left clear of the frame on each side of the title
+    title_gap: u32,
+    // Offset of the frame's top edge from our rect
+    frame_off: u32,
+    m0: Size,
+    m1: Size,
+}
+
+impl<W: Widget> TitledFrame<W> {
+    /// Construct a frame with the given `title`
+    #[inline]
+    pub fn new<T: ToString>(title: T, child: W) -> Self {
+        TitledFrame {
+            core: Default::default(),
+            child,
+            title: Text::new_single(title.to_string()),
+            title_rect: Rect::default(),
+            title_gap: 0,
+            frame_off: 0,
+            m0: Size::ZERO,
+            m1: Size::ZERO,
+        }
+    }
+
+    /// Get a reference to the child
+    #[inline]
+    pub fn inner(&self) -> &W {
+        &self.child
+    }
+
+    /// Get a mutable reference to the child
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut W {
+        &mut self.child
+    }
+}
+
+impl<W: Widget> Layout for TitledFrame<W> {
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let size = size_handle.frame();
+        let title_axis = AxisInfo::new(axis.is_vertical(), None);
+        let title_rules =
+            size_handle.text_bound(&mut self.title, TextClass::LabelSingle, title_axis);
+        let title_size = title_rules.min_size();
+
+        let child_rules = self.child.size_rules(size_handle, axis);
+        let m = child_rules.margins();
+
+        if axis.is_horizontal() {
+            self.title_gap = size_handle.inner_margin().0;
+            self.title_rect.pos.0 = (size.0 + self.title_gap) as i32;
+            self.title_rect.size.0 = title_size;
+            self.m0.0 = size.0 + m.0 as u32;
+            self.m1.0 = size.0 + m.1 as u32;
+
+            let frame_rules = SizeRules::extract_fixed(false, size + size, Margins::ZERO);
+            let mut rules = child_rules.surrounded_by(frame_rules, true);
+            let width = title_size + 2 * (size.0 + self.title_gap);
+            rules.max_with(SizeRules::fixed(width, (0, 0)));
+            rules
+        } else {
+            // The frame's top edge is centred on the title
+            self.frame_off = title_size.saturating_sub(size.1) / 2;
+            let top = title_size.max(self.frame_off + size.1);
+            self.title_rect.size.1 = title_size;
+            self.m0.1 = top + m.0 as u32;
+            self.m1.1 = size.1 + m.1 as u32;
+
+            let frame_size = Size(0, top + size.1);
+            let frame_rules = SizeRules::extract_fixed(true, frame_size, Margins::ZERO);
+            child_rules.surrounded_by(frame_rules, true)
+        }
+    }
+
+    fn set_rect(&mut self, mut rect: Rect, align: AlignHints) {
+        self.core.rect = rect;
+        let bounds = self.title_rect.size;
+        self.title.update_env(|env| {
+            env.set_bounds(bounds.into());
+            env.set_align((Align::Default, Align::Centre));
+        });
+        rect.pos += self.m0;
+        rect.size -= self.m0 + self.m1;
+        self.child.set_rect(rect, align);
+    }
+
+    #[inline]
+    fn find_id(&self, coord: Coord) -> Option<WidgetId> {
+        if !self.rect().contains(coord) {
+            return None;
+        }
+        self.child.find_id(coord).or(Some(self.id()))
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, mgr: &event::ManagerState, disabled: bool) {
+        let disabled = disabled || self.is_disabled();
+        let rect = self.core.rect;
+        let mut frame_rect = rect;
+        frame_rect.pos.1 += self.frame_off as i32;
+        frame_rect.size.1 -= self.frame_off;
+
+        if self.title.as_str().is_empty() {
+            draw_handle.outer_frame(frame_rect);
+        } else {
+            let title_rect = self.title_rect + rect.pos;
+            draw_handle.titled_frame(frame_rect, title_rect);
+            let class = TextClass::LabelSingle;
+            draw_handle.text(title_rect.pos, &self.title, class, disabled);
+        }
+
+        self.child.draw(draw_handle, mgr, disabled);
+    }
+}
+
+impl<W: Widget> HasStr for TitledFrame<W> {
+    fn get_str(&self) -> &str {
+        self.title.as_str()
+    }
+}
+
+impl<W: Widget> HasString for TitledFrame<W> {
+    fn set_string(&mut self, text: String) -> TkAction {
+        kas::text::util::set_text_and_prepare(&mut self.title, text)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::TestSizeHandle;
+    use crate::widget::StrLabel;
+
+    #[test]
+    fn title_inset() {
+        let _ = crate::text::fonts::fonts().load_default();
+
+        let mut frame = TitledFrame::new("Options", StrLabel::new("On"));
+        let mut size_handle = TestSizeHandle::new();
+        let horiz = frame.size_rules(&mut size_handle, AxisInfo::new(false, None));
+        let axis = AxisInfo::new(true, Some(horiz.min_size()));
+        let vert = frame.size_rules(&mut size_handle, axis);
+
+        // The title (7 chars of 8px) plus gap and frame on each side is wider
+        // than the child (2 chars) plus frame and margins
+        assert_eq!(horiz.min_size(), 7 * 8 + 2 * (4 + 2));
+        // The title (16px) is reserved above the child; the frame is below
+        assert_eq!(vert.min_size(), 16 + (4 + 16 + 4) + 4);
+
+        let size = Size(horiz.min_size(), vert.min_size());
+        frame.set_rect(Rect::new(Coord::ZERO, size), AlignHints::NONE);
+        assert_eq!(frame.title_rect, Rect::new(Coord(6, 0), Size(56, 16)));
+        assert_eq!(frame.frame_off, 6);
+        assert_eq!(frame.inner().rect().pos, Coord(4 + 4, 16 + 4));
+    }
+
+    #[test]
+    fn draw_title() {
+        use crate::test_util::{DrawCommand, RecordingDrawHandle};
+        let _ = crate::text::fonts::fonts().load_default();
+
+        let mut frame = TitledFrame::new("Options", StrLabel::new("On"));
+        let mut size_handle = TestSizeHandle::new();
+        frame.size_rules(&mut size_handle, AxisInfo::new(false, None));
+        frame.size_rules(&mut size_handle, AxisInfo::new(true, None));
+        let rect = Rect::new(Coord(10, 10), Size(100, 60));
+        frame.set_rect(rect, AlignHints::NONE);
+
+        // The frame is drawn in one call, below the title, without clipping
+        let mut draw_handle = RecordingDrawHandle::new(rect);
+        frame.draw(&mut draw_handle, &ManagerState::new(), false);
+        let frame_rect = Rect::new(Coord(10, 16), Size(100, 54));
+        let title_rect = Rect::new(Coord(16, 10), Size(56, 16));
+        let commands = draw_handle.take_commands();
+        assert_eq!(
+            commands[0],
+            DrawCommand::TitledFrame(frame_rect, title_rect)
+        );
+        let (bounds, class) = (title_rect.size.into(), TextClass::LabelSingle);
+        let text = DrawCommand::Text(title_rect.pos, bounds, Coord::ZERO, class, false);
+        assert_eq!(commands[1], text);
+        let clip = |c: &DrawCommand| matches!(c, DrawCommand::ClipStart(..));
+        assert!(!commands.iter().any(clip));
+
+        // Without a title, a plain frame is drawn
+        let mut frame = TitledFrame::new("", StrLabel::new("On"));
+        frame.size_rules(&mut size_handle, AxisInfo::new(false, None));
+        frame.size_rules(&mut size_handle, AxisInfo::new(true, None));
+        frame.set_rect(rect, AlignHints::NONE);
+        let mut draw_handle = RecordingDrawHandle::new(rect);
+        frame.draw(&mut draw_handle, &ManagerState::new(), false);
+        let commands = draw_handle.take_commands();
+        assert!(matches!(commands[0], DrawCommand::OuterFrame(_)));
+    }
+}
@@ -0,0 +1,410 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! A grid with run-time adjustable contents
+
+use kas::event::{self, ControlKey};
+use kas::layout::{self, GridChildInfo, GridStorage, RulesSetter, RulesSolver};
+use kas::prelude::*;
+
+/// A grid of boxed widgets
+///
+/// This is parameterised over handler message type.
+///
+/// See documentation of [`Grid`] type.
+pub type BoxGrid<M> = Grid<BoxedWidget<M>>;
+
+/// A generic grid widget
+///
+/// Each child occupies a cell or a span of cells, given by a
+/// [`GridChildInfo`]. The number of rows and columns is determined by the
+/// children. Children may be of any single type `W`; use [`BoxGrid`] to mix
+/// types.
+///
+/// Optionally, alternate rows may be given a striped background
+/// ([`Grid::striped`]) and lines may be drawn between cells
+/// ([`Grid::grid_lines`]), as is common for data tables. Colours are determined
+/// by the theme. Lines are not drawn through spanned cells. Both are disabled
+/// by default.
+///
+/// Arrow keys not handled by the child with navigation focus move the focus
+/// to the nearest child in that direction (see
+/// [`Manager::nav_focus_spatial`]).
+///
+/// For fixed configurations of child widgets, [`make_widget`] with
+/// `#[layout(grid)]` can be used instead.
+///
+/// [`make_widget`]: ../macros/index.html#the-make_widget-macro
+#[handler(send=noauto, msg=<W as event::Handler>::Msg)]
+#[widget(children=noauto)]
+#[derive(Clone, Debug, Widget)]
+pub struct Grid<W: Widget> {
+    first_id: WidgetId,
+    #[widget_core]
+    core: CoreData,
+    widgets: Vec<(GridChildInfo, W)>,
+    dim: (usize, usize),
+    data: layout::DynGridStorage,
+    striped: bool,
+    grid_lines: bool,
+    line_width: u32,
+    // Rects of stripes and line segments, found by set_rect
+    stripes: Vec<Rect>,
+    lines: Vec<Rect>,
+}
+
+impl<W: Widget> Default for Grid<W> {
+    fn default() -> Self {
+        Grid::new()
+    }
+}
+
+impl<W: Widget> WidgetChildren for Grid<W> {
+    #[inline]
+    fn first_id(&self) -> WidgetId {
+        self.first_id
+    }
+    fn record_first_id(&mut self, id: WidgetId) {
+        self.first_id = id;
+    }
+    #[inline]
+    fn len(&self) -> usize {
+        self.widgets.len()
+    }
+    #[inline]
+    fn get(&self, index: usize) -> Option<&dyn WidgetConfig> {
+        self.widgets.get(index).map(|w| w.1.as_widget())
+    }
+    #[inline]
+    fn get_mut(&mut self, index: usize) -> Option<&mut dyn WidgetConfig> {
+        self.widgets.get_mut(index).map(|w| w.1.as_widget_mut())
+    }
+}
+
+impl<W: Widget> Layout for Grid<W> {
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        self.line_width = size_handle.scale_factor().round().max(1.0) as u32;
+
+        let is_span = |info: &GridChildInfo| match axis.is_horizontal() {
+            false => info.row_span() > 1,
+            true => info.col_span() > 1,
+        };
+        let n = self.widgets.iter().filter(|w| is_span(&w.0)).count();
+        let spans = vec![Default::default(); n];
+        let spans = match axis.is_horizontal() {
+            false => (vec![], spans),
+            true => (spans, vec![]),
+        };
+
+        let mut solver = layout::GridSolver::<Vec<_>, Vec<_>, _>::new_with_spans(
+            axis,
+            self.dim,
+            spans,
+            &mut self.data,
+        );
+        for (info, child) in &mut self.widgets {
+            solver.for_child(&mut self.data, *info, |axis| {
+                child.size_rules(size_handle, axis)
+            });
+        }
+        solver.finish(&mut self.data)
+    }
+
+    fn set_rect(&mut self, rect: Rect, align: AlignHints) {
+        self.core.rect = rect;
+        let mut setter =
+            layout::GridSetter::<Vec<u32>, Vec<u32>, _>::new(rect, self.dim, align, &mut self.data);
+
+        for (info, child) in &mut self.widgets {
+            let align = AlignHints::default();
+            child.set_rect(setter.child_rect(&mut self.data, *info), align);
+        }
+
+        self.update_decorations();
+    }
+
+    fn find_id(&self, coord: Coord) -> Option<WidgetId> {
+        if !self.rect().contains(coord) {
+            return None;
+        }
+
+        for (_, child) in &self.widgets {
+            if let Some(id) = child.find_id(coord) {
+                return Some(id);
+            }
+        }
+
+        Some(self.id())
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, mgr: &event::ManagerState, disabled: bool) {
+        let disabled = disabled || self.is_disabled();
+        for rect in &self.stripes {
+            draw_handle.grid_stripe(*rect);
+        }
+        for rect in &self.lines {
+            draw_handle.grid_line(*rect);
+        }
+        for (_, child) in &self.widgets {
+            child.draw(draw_handle, mgr, disabled);
+        }
+    }
+}
+
+impl<W: Widget> event::SendEvent for Grid<W> {
+    fn send(&mut self, mgr: &mut Manager, id: WidgetId, event: Event) -> Response<Self::Msg> {
+        if !self.is_disabled() {
+            for (_, child) in &mut self.widgets {
+                if id <= child.id() {
+                    return match child.send(mgr, id, event) {
+                        Response::Unhandled(Event::Control(key)) => {
+                            let dir = match key {
+                                ControlKey::Left => Direction::Left,
+                                ControlKey::Right => Direction::Right,
+                                ControlKey::Up => Direction::Up,
+                                ControlKey::Down => Direction::Down,
+                                key => return Response::Unhandled(Event::Control(key)),
+                            };
+                            if mgr.nav_focus_spatial(self.as_widget(), dir) {
+                                Response::None
+                            } else {
+                                Response::Unhandled(Event::Control(key))
+                            }
+                        }
+                        r => r,
+                    };
+                }
+            }
+        }
+
+        Response::Unhandled(event)
+    }
+}
+
+impl<W: Widget> Grid<W> {
+    /// Construct, with no children
+    pub fn new() -> Self {
+        Grid {
+            first_id: Default::default(),
+            core: Default::default(),
+            widgets: vec![],
+            dim: (0, 0),
+            data: Default::default(),
+            striped: false,
+            grid_lines: false,
+            line_width: 1,
+            stripes: vec![],
+            lines: vec![],
+        }
+    }
+
+    /// Add a child in cell `(col, row)` (chain style)
+    pub fn with_cell(mut self, col: u32, row: u32, widget: W) -> Self {
+        let _ = self.push(GridChildInfo::single(col, row), widget);
+        self
+    }
+
+    /// Add a child over a span of cells (chain style)
+    pub fn with_span(mut self, info: GridChildInfo, widget: W) -> Self {
+        let _ = self.push(info, widget);
+        self
+    }
+
+    /// Enable or disable striping of alternate rows (chain style)
+    pub fn striped(mut self, striped: bool) -> Self {
+        self.striped = striped;
+        self
+    }
+
+    /// Enable or disable lines between cells (chain style)
+    pub fn grid_lines(mut self, grid_lines: bool) -> Self {
+        self.grid_lines = grid_lines;
+        self
+    }
+
+    /// Get the number of columns and rows
+    pub fn dim(&self) -> (usize, usize) {
+        self.dim
+    }
+
+    /// True if there are no child widgets
+    pub fn is_empty(&self) -> bool {
+        self.widgets.is_empty()
+    }
+
+    /// Returns the number of child widgets
+    pub fn len(&self) -> usize {
+        self.widgets.len()
+    }
+
+    /// Add a child widget
+    ///
+    /// Triggers a [reconfigure action](Manager::send_action).
+    pub fn push(&mut self, info: GridChildInfo, widget: W) -> TkAction {
+        self.dim.0 = self.dim.0.max(info.col_end as usize);
+        self.dim.1 = self.dim.1.max(info.row_end as usize);
+        self.widgets.push((info, widget));
+        TkAction::Reconfigure
+    }
+
+    /// Iterate over children and their cell information
+    pub fn iter(&self) -> impl Iterator<Item = (&GridChildInfo, &W)> {
+        self.widgets.iter().map(|w| (&w.0, &w.1))
+    }
+
+    // Find rects of stripes and grid lines; requires solved storage
+    fn update_decorations(&mut self) {
+        self.stripes.clear();
+        self.lines.clear();
+        let (cols, rows) = (self.dim.0 as u32, self.dim.1 as u32);
+        if (!self.striped && !self.grid_lines) || cols == 0 || rows == 0 {
+            return;
+        }
+
+        // Bands include half of the margin on each side, thus adjacent bands
+        // meet and follow cell boundaries even where sizes differ.
+        let data = &mut self.data;
+        let x = bands(
+            (0..cols)
+                .map(|col| data.cell_rect(col, 0))
+                .map(|r| (r.pos.0, r.pos_end().0)),
+        );
+        let y = bands(
+            (0..rows)
+                .map(|row| data.cell_rect(0, row))
+                .map(|r| (r.pos.1, r.pos_end().1)),
+        );
+        let (x0, x1) = (x[0].0, x[x.len() - 1].1);
+
+        if self.striped {
+            for band in y.iter().skip(1).step_by(2) {
+                let pos = Coord(x0, band.0);
+                let size = Size((x1 - x0) as u32, (band.1 - band.0) as u32);
+                self.stripes.push(Rect::new(pos, size));
+            }
+        }
+
+        if self.grid_lines {
+            let lw = self.line_width;
+            let half = (lw / 2) as i32;
+            let infos: Vec<GridChildInfo> = self.widgets.iter().map(|w| w.0).collect();
+            // True if a child spans the line left of / above cell (col, row)
+            let spanned = |col: u32, row: u32, vertical: bool| {
+                infos.iter().any(|info| {
+                    let within = (info.col..info.col_end).contains(&col)
+                        && (info.row..info.row_end).contains(&row);
+                    match vertical {
+                        true => within && info.col < col,
+                        false => within && info.row < row,
+                    }
+                })
+            };
+
+            for col in 1..cols {
+                let lx = x[col as usize].0 - half;
+                for row in (0..rows).filter(|row| !spanned(col, *row, true)) {
+                    let band = y[row as usize];
+                    let size = Size(lw, (band.1 - band.0) as u32);
+                    self.lines.push(Rect::new(Coord(lx, band.0), size));
+                }
+            }
+            for row in 1..rows {
+                let ly = y[row as usize].0 - half;
+                for col in (0..cols).filter(|col| !spanned(*col, row, false)) {
+                    let band = x[col as usize];
+                    let size = Size((band.1 - band.0) as u32, lw);
+                    self.lines.push(Rect::new(Coord(band.0, ly), size));
+                }
+            }
+        }
+    }
+}
+
+// Convert cell ranges `(start, end)` into bands which meet half way across
+// each margin
+fn bands<I: Iterator<Item = (i32, i32)>>(cells: I) -> Vec<(i32, i32)> {
+    let mut bands: Vec<(i32, i32)> = cells.collect();
+    for i in 1..bands.len() {
+        let mid = (bands[i - 1].1 + bands[i].0) / 2;
+        bands[i - 1].1 = mid;
+        bands[i].0 = mid;
+    }
+    bands
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::{DrawCommand, RecordingDrawHandle, TestSizeHandle};
+    use crate::widget::Filler;
+
+    #[test]
+    fn decorations() {
+        let mut grid = Grid::new().striped(true).grid_lines(true);
+        for (col, row) in &[(0, 0), (1, 0), (2, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            grid = grid.with_cell(*col, *row, Filler::new());
+        }
+        grid = grid.with_span(GridChildInfo::span(0, 2, 1, 2), Filler::new());
+        assert_eq!(grid.dim(), (3, 3));
+
+        let mut size_handle = TestSizeHandle::new();
+        grid.size_rules(&mut size_handle, AxisInfo::new(false, None));
+        grid.size_rules(&mut size_handle, AxisInfo::new(true, Some(30)));
+        let rect = Rect::new(Coord::ZERO, Size(30, 30));
+        grid.set_rect(rect, AlignHints::NONE);
+
+        let mut draw_handle = RecordingDrawHandle::new(rect);
+        grid.draw(&mut draw_handle, &ManagerState::new(), false);
+        let line = |x, y, w, h| DrawCommand::GridLine(Rect::new(Coord(x, y), Size(w, h)));
+        let expected = vec![
+            DrawCommand::GridStripe(Rect::new(Coord(0, 10), Size(30, 10))),
+            // No line is drawn through the span over cells (0, 1) and (1, 1)
+            line(10, 0, 1, 10),
+            line(10, 20, 1, 10),
+            line(20, 0, 1, 10),
+            line(20, 10, 1, 10),
+            line(20, 20, 1, 10),
+            line(0, 10, 10, 1),
+            line(10, 10, 10, 1),
+            line(20, 10, 10, 1),
+            line(0, 20, 10, 1),
+            line(10, 20, 10, 1),
+            line(20, 20, 10, 1),
+        ];
+        assert_eq!(draw_handle.commands(), &expected[..]);
+
+        // Decorations are disabled by default
+        let mut grid = Grid::new().with_cell(0, 0, Filler::new());
+        grid.set_rect(rect, AlignHints::NONE);
+        assert!(grid.stripes.is_empty() && grid.lines.is_empty());
+    }
+
+    #[test]
+    fn arrow_keys() {
+        use crate::event::VirtualKeyCode;
+        use crate::test_util::TestManager;
+        use crate::widget::CheckBoxBare;
+
+        let mut grid = Grid::new();
+        for (col, row) in &[(0, 0), (1, 0), (0, 1), (1, 1)] {
+            grid = grid.with_cell(*col, *row, CheckBoxBare::new());
+        }
+        let mut test = TestManager::new(grid);
+        test.set_rect(Rect::new(Coord::ZERO, Size(60, 60)));
+        let ids: Vec<_> = test.widget.iter().map(|(_, w)| w.id()).collect();
+
+        test.with(|mgr, _| mgr.set_nav_focus(ids[0]));
+        test.key(VirtualKeyCode::Right);
+        assert!(test.state.nav_focus(ids[1]));
+        test.key(VirtualKeyCode::Down);
+        assert!(test.state.nav_focus(ids[3]));
+        test.key(VirtualKeyCode::Left);
+        assert!(test.state.nav_focus(ids[2]));
+
+        // At an edge, focus does not move
+        test.key(VirtualKeyCode::Down);
+        assert!(test.state.nav_focus(ids[2]));
+    }
+}
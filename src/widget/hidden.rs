@@ -0,0 +1,258 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Visibility adapter
+
+use std::fmt::{self, Debug};
+
+use kas::event;
+use kas::prelude::*;
+
+/// Adapter allowing a widget to be hidden
+///
+/// Like [`super::SizeOverride`], this widget is transparent: it shares the
+/// identifier, rect and children of the wrapped widget and forwards all
+/// methods to it while visible.
+///
+/// While hidden, the wrapped widget is *collapsed*: it is removed from the
+/// layout entirely (reporting [`SizeRules::COLLAPSED`], thus neither it nor
+/// its margins occupy any space), is not drawn, and is excluded from
+/// [`Layout::find_id`] and keyboard navigation. This is unlike a disabled
+/// widget, which is still shown. (Hiding a widget while reserving its space
+/// is not currently supported.)
+///
+/// Changing visibility requires a [`TkAction::Resize`]. Hiding a widget via
+/// [`Hidden::set_visible`] removes keyboard focus from it (and its children).
+#[derive(Clone)]
+pub struct Hidden<W: Widget> {
+    inner: W,
+    visible: bool,
+}
+
+impl<W: Widget> Hidden<W> {
+    /// Construct, visible
+    #[inline]
+    pub fn new(inner: W) -> Self {
+        Hidden {
+            inner,
+            visible: true,
+        }
+    }
+
+    /// Set whether the widget is visible (chain style)
+    #[inline]
+    pub fn with_visible(mut self, visible: bool) -> Self {
+        self.visible = visible;
+        self
+    }
+
+    /// True if the widget is visible
+    #[inline]
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Show or hide the widget
+    ///
+    /// When hiding, navigation focus and character focus are cleared if held
+    /// by the widget or one of its children.
+    pub fn set_visible(&mut self, mgr: &mut Manager, visible: bool) {
+        *mgr += self.set_visible_keep_focus(visible);
+        if !visible {
+            // Focus may not remain within hidden content
+            if let Some(id) = mgr.nav_focus() {
+                if self.inner.is_ancestor_of(id) {
+                    mgr.clear_nav_focus();
+                }
+            }
+            if has_char_focus(mgr, &self.inner) {
+                mgr.clear_char_focus();
+            }
+        }
+    }
+
+    /// Show or hide the widget, without adjusting focus
+    ///
+    /// Unlike [`Hidden::set_visible`], this does not require a [`Manager`] but
+    /// leaves keyboard focus unchanged. The caller should ensure that hidden
+    /// content does not have focus.
+    pub fn set_visible_keep_focus(&mut self, visible: bool) -> TkAction {
+        if visible == self.visible {
+            return TkAction::None;
+        }
+        self.visible = visible;
+        TkAction::Resize
+    }
+
+    /// Get the inner widget
+    #[inline]
+    pub fn inner(&self) -> &W {
+        &self.inner
+    }
+
+    /// Get the inner widget mutably
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Unwrap, returning the inner widget
+    #[inline]
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+// True if w or any descendant has character focus
+fn has_char_focus(mgr: &Manager, w: &dyn WidgetConfig) -> bool {
+    mgr.has_char_focus(w.id()) || (0..w.len()).any(|i| has_char_focus(mgr, w.get(i).unwrap()))
+}
+
+impl<W: Widget> Debug for Hidden<W> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Hidden {{ inner: {:?}, visible: {:?} }}",
+            self.inner, self.visible,
+        )
+    }
+}
+
+impl_adapter!([W: Widget] Hidden<W>; key_nav(self) self.visible && self.inner.key_nav());
+
+impl<W: Widget> Layout for Hidden<W> {
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let rules = self.inner.size_rules(size_handle, axis);
+        match self.visible {
+            true => rules,
+            false => SizeRules::COLLAPSED,
+        }
+    }
+
+    fn set_rect(&mut self, rect: Rect, align: AlignHints) {
+        // When hidden, this is a zero-size rect (unless the parent does not
+        // respect collapsed rules); we still set this to keep child positions
+        // ordered for coordinate search
+        self.inner.set_rect(rect, align);
+    }
+
+    fn translation(&self, child_index: usize) -> Coord {
+        self.inner.translation(child_index)
+    }
+
+    fn spatial_range(&self) -> (usize, usize) {
+        match self.visible {
+            true => self.inner.spatial_range(),
+            false => (0, std::usize::MAX),
+        }
+    }
+
+    fn find_id(&self, coord: Coord) -> Option<WidgetId> {
+        match self.visible {
+            true => self.inner.find_id(coord),
+            false => None,
+        }
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, mgr: &event::ManagerState, disabled: bool) {
+        if self.visible {
+            self.inner.draw(draw_handle, mgr, disabled);
+        }
+    }
+}
+
+impl<W: Widget> event::Handler for Hidden<W> {
+    type Msg = W::Msg;
+
+    fn activation_via_press(&self) -> bool {
+        self.inner.activation_via_press()
+    }
+
+    fn handle(&mut self, mgr: &mut Manager, event: Event) -> Response<Self::Msg> {
+        self.inner.handle(mgr, event)
+    }
+}
+
+impl<W: Widget> event::SendEvent for Hidden<W> {
+    fn send(&mut self, mgr: &mut Manager, id: WidgetId, event: Event) -> Response<Self::Msg> {
+        self.inner.send(mgr, id, event)
+    }
+}
+
+impl<W: Widget> Widget for Hidden<W> {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::event::VirtualKeyCode;
+    use crate::test_util::{TestManager, TestSizeHandle};
+    use crate::widget::{Column, EditBox, EditBoxVoid, StrLabel};
+
+    #[test]
+    fn hidden_in_column() {
+        let _ = crate::text::fonts::fonts().load_default();
+
+        let mut size_handle = TestSizeHandle::new();
+        let mut column = Column::new(vec![
+            Hidden::new(StrLabel::new("a")),
+            Hidden::new(StrLabel::new("b")),
+            Hidden::new(StrLabel::new("c")),
+        ]);
+        let mut height = |column: &mut Column<Hidden<StrLabel>>| {
+            let horiz = column.size_rules(&mut size_handle, AxisInfo::new(false, None));
+            let axis = AxisInfo::new(true, Some(horiz.min_size()));
+            column.size_rules(&mut size_handle, axis).min_size()
+        };
+
+        // Three lines (16px) with margins (4px) between
+        assert_eq!(height(&mut column), 3 * 16 + 2 * 4);
+
+        // A hidden child takes no space, including its margins
+        assert_eq!(column[1].set_visible_keep_focus(false), TkAction::Resize);
+        assert_eq!(height(&mut column), 2 * 16 + 4);
+        let rect = Rect::new(Coord::ZERO, Size(8, 36));
+        column.set_rect(rect, AlignHints::NONE);
+        assert_eq!(column[2].rect().pos, Coord(0, 20));
+        assert_eq!(column[1].rect(), Rect::new(Coord(0, 16), Size(8, 0)));
+
+        // Likewise for the first child
+        let _ = column[1].set_visible_keep_focus(true);
+        let _ = column[0].set_visible_keep_focus(false);
+        assert_eq!(height(&mut column), 2 * 16 + 4);
+        column.set_rect(rect, AlignHints::NONE);
+        assert_eq!(column[1].rect().pos, Coord(0, 0));
+        assert_eq!(column[2].rect().pos, Coord(0, 20));
+    }
+
+    #[test]
+    fn hiding_clears_focus() {
+        let _ = crate::text::fonts::fonts().load_default();
+
+        type Entry = Hidden<Column<EditBoxVoid>>;
+        let entry = |text: &str| -> Entry { Hidden::new(Column::new(vec![EditBox::new(text)])) };
+        let mut test = TestManager::new(Column::new(vec![entry("a"), entry("b")]));
+        let ids: Vec<_> = test.widget.iter().map(|w| w.inner()[0].id()).collect();
+        let hide = |test: &mut TestManager<Column<Entry>>, index: usize| {
+            let (state, tkw, widget) = (&mut test.state, &mut test.tkw, &mut test.widget);
+            state.with(tkw, |mgr| widget[index].set_visible(mgr, false));
+            test.update()
+        };
+
+        test.key(VirtualKeyCode::Tab);
+        assert!(test.state.nav_focus(ids[0]));
+        assert!(test.state.char_focus(ids[0]).0);
+
+        // Hiding other content does not affect focus
+        assert_eq!(hide(&mut test, 1), TkAction::Resize);
+        assert!(test.state.nav_focus(ids[0]));
+        assert!(test.state.char_focus(ids[0]).0);
+
+        // Focus may not remain within hidden content
+        assert_eq!(hide(&mut test, 0), TkAction::Resize);
+        assert!(!test.state.nav_focus(ids[0]));
+        assert!(!test.state.char_focus(ids[0]).0);
+        assert_eq!(hide(&mut test, 0), TkAction::None);
+    }
+}
@@ -0,0 +1,227 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Image widget
+
+use std::fmt;
+
+use kas::draw::{ImageFilter, ImageHandle};
+use kas::{event, prelude::*};
+
+/// An image
+///
+/// The image is given as RGBA data (see [`Manager::load_image`]). It is
+/// loaded by the toolkit when the widget is configured and freed when the
+/// widget is dropped.
+///
+/// By default the image is drawn at its natural size: one image pixel per
+/// physical screen pixel. [`Image::with_stretch`] allows the image to be
+/// scaled up to fill available space, and [`Image::with_fixed_aspect`]
+/// preserves the image's aspect ratio when scaling.
+#[widget(config=noauto)]
+#[derive(Widget)]
+pub struct Image {
+    #[widget_core]
+    core: CoreData,
+    size: Size,
+    data: Vec<u8>,
+    filter: ImageFilter,
+    stretch: StretchPolicy,
+    fixed_aspect: bool,
+    handle: Option<ImageHandle>,
+    image_rect: Rect,
+}
+
+impl Image {
+    /// Construct from RGBA data
+    ///
+    /// The `data` is in RGBA format (8 bits per channel, sRGB colour space,
+    /// un-premultiplied alpha), row by row from the top.
+    ///
+    /// Panics if `data.len() != 4 * size.0 * size.1` (or this overflows).
+    pub fn new(size: Size, data: Vec<u8>) -> Self {
+        let len = (size.0 as usize)
+            .checked_mul(size.1 as usize)
+            .and_then(|pixels| pixels.checked_mul(4))
+            .expect("Image::new: size overflow");
+        assert_eq!(data.len(), len);
+        Image {
+            core: Default::default(),
+            size,
+            data,
+            filter: ImageFilter::default(),
+            stretch: StretchPolicy::Fixed,
+            fixed_aspect: false,
+            handle: None,
+            image_rect: Rect::default(),
+        }
+    }
+
+    /// Set the filter used when scaling (chain style)
+    ///
+    /// Use [`ImageFilter::Nearest`] for pixel art. The default is
+    /// [`ImageFilter::Linear`]. This must be set before the widget is
+    /// configured.
+    pub fn with_filter(mut self, filter: ImageFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Set the stretch policy (chain style)
+    ///
+    /// With the default, [`StretchPolicy::Fixed`], the image is never drawn
+    /// larger than its natural size. Other policies allow the image to be
+    /// scaled up to fill available space.
+    pub fn with_stretch(mut self, stretch: StretchPolicy) -> Self {
+        self.stretch = stretch;
+        self
+    }
+
+    /// Preserve the aspect ratio when scaling (chain style)
+    ///
+    /// If true, the image is scaled to the largest size fitting the available
+    /// space without distortion, and positioned according to alignment hints
+    /// (by default, centred). If false (the default), the image is stretched
+    /// to fill available space (subject to [`Image::with_stretch`]).
+    pub fn with_fixed_aspect(mut self, fixed: bool) -> Self {
+        self.fixed_aspect = fixed;
+        self
+    }
+
+    /// Get the natural size of the image, in pixels
+    #[inline]
+    pub fn image_size(&self) -> Size {
+        self.size
+    }
+}
+
+impl Clone for Image {
+    /// Clone the image
+    ///
+    /// The clone loads its own copy of the image when configured.
+    fn clone(&self) -> Self {
+        Image {
+            core: self.core.clone(),
+            size: self.size,
+            data: self.data.clone(),
+            filter: self.filter,
+            stretch: self.stretch,
+            fixed_aspect: self.fixed_aspect,
+            handle: None,
+            image_rect: self.image_rect,
+        }
+    }
+}
+
+impl fmt::Debug for Image {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Image")
+            .field("core", &self.core)
+            .field("size", &self.size)
+            .field("filter", &self.filter)
+            .field("stretch", &self.stretch)
+            .field("fixed_aspect", &self.fixed_aspect)
+            .field("handle", &self.handle)
+            .finish()
+    }
+}
+
+impl WidgetConfig for Image {
+    fn configure(&mut self, mgr: &mut Manager) {
+        if self.handle.is_none() {
+            self.handle = Some(mgr.load_image(self.size, &self.data, self.filter));
+        }
+    }
+}
+
+impl Layout for Image {
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let margins = size_handle.outer_margins();
+        let (len, margins) = match axis.is_horizontal() {
+            true => (self.size.0, margins.horiz),
+            false => (self.size.1, margins.vert),
+        };
+        SizeRules::new(len, len, margins, self.stretch)
+    }
+
+    fn set_rect(&mut self, rect: Rect, align: AlignHints) {
+        self.core.rect = rect;
+        let avail = match self.stretch {
+            StretchPolicy::Fixed => rect.size.min(self.size),
+            _ => rect.size,
+        };
+        let ideal = match self.fixed_aspect {
+            true => aspect_fit(self.size, avail),
+            false => avail,
+        };
+        self.image_rect = align
+            .complete(Align::Centre, Align::Centre, ideal)
+            .apply(rect);
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, _: &event::ManagerState, _: bool) {
+        if let Some(handle) = self.handle.as_ref() {
+            draw_handle.image(handle.id(), self.image_rect);
+        }
+    }
+}
+
+/// Largest size with the aspect ratio of `image` fitting within `avail`
+fn aspect_fit(image: Size, avail: Size) -> Size {
+    if image.0 == 0 || image.1 == 0 {
+        return avail;
+    }
+    let scale_x = avail.0 as f64 / image.0 as f64;
+    let scale_y = avail.1 as f64 / image.1 as f64;
+    let scale = scale_x.min(scale_y);
+    let w = (image.0 as f64 * scale).round() as u32;
+    let h = (image.1 as f64 * scale).round() as u32;
+    Size(w, h).min(avail)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn aspect_fit_scaling() {
+        assert_eq!(aspect_fit(Size(40, 20), Size(40, 20)), Size(40, 20));
+        assert_eq!(aspect_fit(Size(40, 20), Size(100, 100)), Size(100, 50));
+        assert_eq!(aspect_fit(Size(40, 20), Size(20, 100)), Size(20, 10));
+        assert_eq!(aspect_fit(Size(3, 7), Size(30, 30)), Size(13, 30));
+    }
+
+    #[test]
+    fn fixed_stretch_at_most_natural_size() {
+        let data = vec![0; 4 * 16 * 8];
+        let mut image = Image::new(Size(16, 8), data);
+        let rect = Rect::new(Coord(10, 10), Size(100, 100));
+        image.set_rect(rect, AlignHints::NONE);
+        assert_eq!(image.image_rect, Rect::new(Coord(52, 56), Size(16, 8)));
+
+        let mut image = image.with_stretch(StretchPolicy::LowUtility);
+        image.set_rect(rect, AlignHints::NONE);
+        assert_eq!(image.image_rect, rect);
+
+        let mut image = image.with_fixed_aspect(true);
+        image.set_rect(rect, AlignHints::NONE);
+        assert_eq!(image.image_rect, Rect::new(Coord(10, 35), Size(100, 50)));
+    }
+
+    #[test]
+    #[should_panic(expected = "size overflow")]
+    fn size_overflow() {
+        let _ = Image::new(Size(u32::MAX, u32::MAX), vec![]);
+    }
+
+    #[test]
+    fn load_on_configure() {
+        use crate::test_util::TestManager;
+
+        let test = TestManager::new(Image::new(Size(2, 3), vec![0; 4 * 2 * 3]));
+        let id = test.widget.handle.as_ref().unwrap().id();
+        assert_eq!(test.tkw.images, vec![(id, Size(2, 3))]);
+    }
+}
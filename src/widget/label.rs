@@ -5,20 +5,44 @@
 
 //! Text widgets
 
+use std::fmt;
+
+use kas::access::{AccessNode, Role};
 use kas::draw::TextClass;
+use kas::text::fonts::FontId;
 use kas::text::format::{EditableText, FormattableText};
+use kas::text::RichText;
 use kas::{event, prelude::*};
 
 /// A text label
 ///
 /// This type is generic over the text type. Some aliases are available:
 /// [`StrLabel`], [`StringLabel`], [`AccelLabel`].
+#[widget(config=noauto)]
 #[derive(Clone, Default, Debug, Widget)]
 pub struct Label<T: FormattableText + 'static> {
     #[widget_core]
     core: CoreData,
     reserve: Option<T>,
+    align: AlignHints,
     label: Text<T>,
+    draw_fn: Option<DrawFn<T>>,
+}
+
+// Draw override for labels whose text has styling not available via
+// FormattableText (see Label::rich)
+struct DrawFn<T: FormattableText + 'static>(fn(&Label<T>, &mut dyn DrawHandle, bool));
+
+impl<T: FormattableText + 'static> Clone for DrawFn<T> {
+    fn clone(&self) -> Self {
+        DrawFn(self.0)
+    }
+}
+
+impl<T: FormattableText + 'static> fmt::Debug for DrawFn<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "DrawFn")
+    }
 }
 
 mod impls {
@@ -31,7 +55,8 @@ mod impls {
     ) -> SizeRules {
         let mut prepared = None;
         let text = if let Some(s) = obj.reserve.take() {
-            prepared = Some(Text::new_multi(s));
+            // Use the same environment (including font) as the label
+            prepared = Some(Text::new(obj.label.env().clone(), s));
             prepared.as_mut().unwrap()
         } else {
             &mut obj.label
@@ -54,10 +79,46 @@ mod impls {
         align: AlignHints,
     ) {
         obj.core.rect = rect;
-        obj.label.update_env(|env| {
+        let (horiz, vert) = obj.align.or(align).unwrap_or(Align::Default, Align::Centre);
+        let required = obj.label.update_env(|env| {
             env.set_bounds(rect.size.into());
-            env.set_align(align.unwrap_or(Align::Default, Align::Centre));
+            env.set_align((horiz, vert));
         });
+        let anchored = anchor_valign(vert, required.1, rect.size.1);
+        if anchored != vert {
+            obj.label.update_env(|env| env.set_align((horiz, anchored)));
+        }
+    }
+
+    pub fn draw<T: FormattableText + 'static>(
+        obj: &Label<T>,
+        draw_handle: &mut dyn DrawHandle,
+        disabled: bool,
+    ) {
+        let disabled = disabled || obj.is_disabled();
+        if let Some(DrawFn(f)) = obj.draw_fn.as_ref() {
+            f(obj, draw_handle, disabled);
+        } else {
+            let pos = obj.core.rect.pos;
+            let class = TextClass::Label;
+            draw_handle.text_effects(pos, Coord::ZERO, &obj.label, class, disabled);
+        }
+    }
+
+    /// Text taller than the available height is anchored to the top (and
+    /// clipped at the bottom), regardless of vertical alignment
+    pub fn anchor_valign(vert: Align, text_height: f32, height: u32) -> Align {
+        if text_height > height as f32 {
+            Align::TL
+        } else {
+            vert
+        }
+    }
+}
+
+impl<T: FormattableText + 'static> WidgetConfig for Label<T> {
+    fn accessible(&self) -> AccessNode {
+        AccessNode::new(Role::Label).with_label(self.label.as_str())
     }
 }
 
@@ -71,44 +132,38 @@ impl<T: FormattableText + 'static> Layout for Label<T> {
     }
 
     #[cfg(feature = "min_spec")]
-    default fn draw(&self, draw_handle: &mut dyn DrawHandle, _: &ManagerState, _: bool) {
-        draw_handle.text_effects(
-            self.core.rect.pos,
-            Coord::ZERO,
-            &self.label,
-            TextClass::Label,
-        );
+    default fn draw(&self, draw_handle: &mut dyn DrawHandle, _: &ManagerState, disabled: bool) {
+        impls::draw(self, draw_handle, disabled);
     }
     #[cfg(not(feature = "min_spec"))]
-    fn draw(&self, draw_handle: &mut dyn DrawHandle, _: &ManagerState, _: bool) {
-        draw_handle.text_effects(
-            self.core.rect.pos,
-            Coord::ZERO,
-            &self.label,
-            TextClass::Label,
-        );
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, _: &ManagerState, disabled: bool) {
+        impls::draw(self, draw_handle, disabled);
     }
 }
 
 #[cfg(feature = "min_spec")]
 impl Layout for AccelLabel {
-    fn draw(&self, draw_handle: &mut dyn DrawHandle, mgr: &ManagerState, _: bool) {
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, mgr: &ManagerState, disabled: bool) {
+        let disabled = disabled || self.is_disabled();
         let state = mgr.show_accel_labels();
-        draw_handle.text_accel(self.core.rect.pos, &self.label, state, TextClass::Label);
+        let class = TextClass::Label;
+        draw_handle.text_accel(self.core.rect.pos, &self.label, state, class, disabled);
     }
 }
 
 // Str/String representations have no effects, so use simpler draw call
 #[cfg(feature = "min_spec")]
 impl<'a> Layout for Label<&'a str> {
-    fn draw(&self, draw_handle: &mut dyn DrawHandle, _: &ManagerState, _: bool) {
-        draw_handle.text(self.core.rect.pos, &self.label, TextClass::Label);
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, _: &ManagerState, disabled: bool) {
+        let disabled = disabled || self.is_disabled();
+        draw_handle.text(self.core.rect.pos, &self.label, TextClass::Label, disabled);
     }
 }
 #[cfg(feature = "min_spec")]
 impl Layout for StringLabel {
-    fn draw(&self, draw_handle: &mut dyn DrawHandle, _: &ManagerState, _: bool) {
-        draw_handle.text(self.core.rect.pos, &self.label, TextClass::Label);
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, _: &ManagerState, disabled: bool) {
+        let disabled = disabled || self.is_disabled();
+        draw_handle.text(self.core.rect.pos, &self.label, TextClass::Label, disabled);
     }
 }
 
@@ -138,7 +193,9 @@ impl<T: FormattableText + 'static> Label<T> {
         Label {
             core: Default::default(),
             reserve: None,
+            align: AlignHints::NONE,
             label: Text::new_multi(label),
+            draw_fn: None,
         }
     }
 
@@ -151,6 +208,84 @@ impl<T: FormattableText + 'static> Label<T> {
         self
     }
 
+    /// Override alignment (chain style)
+    ///
+    /// Alignment hints given here take priority over those provided by the
+    /// parent widget.
+    ///
+    /// Horizontal alignment [`Align::Stretch`] justifies wrapped text:
+    /// extra space is distributed between words on all lines except the last
+    /// line of each paragraph.
+    pub fn with_align(mut self, align: AlignHints) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Override horizontal alignment (chain style)
+    ///
+    /// See [`Label::with_align`].
+    pub fn with_halign(mut self, horiz: Align) -> Self {
+        self.align.horiz = Some(horiz);
+        self
+    }
+
+    /// Override vertical alignment (chain style)
+    ///
+    /// This positions the text within the label's rect when the rect is taller
+    /// than the text, e.g. `Label::from("x").with_valign(Align::BR)` places
+    /// text at the bottom. Text taller than the rect is always aligned to the
+    /// top.
+    ///
+    /// See [`Label::with_align`].
+    pub fn with_valign(mut self, vert: Align) -> Self {
+        self.align.vert = Some(vert);
+        self
+    }
+
+    /// Get the alignment override
+    pub fn align(&self) -> AlignHints {
+        self.align
+    }
+
+    /// Set the alignment override
+    ///
+    /// Hints given here take priority over those provided by the parent
+    /// widget; `AlignHints::NONE` removes the override. The change is applied
+    /// on the next call to [`Layout::set_rect`], hence this returns
+    /// [`TkAction::Resize`].
+    pub fn set_align(&mut self, align: AlignHints) -> TkAction {
+        self.align = align;
+        TkAction::Resize
+    }
+
+    /// Select a font (chain style)
+    ///
+    /// By default, the default font (`FontId::default()`) is used. Fonts are
+    /// loaded via [`kas::text::fonts`].
+    ///
+    /// The font is stored in the text's environment, which themes do not
+    /// override; measurement and drawing thus use the same font.
+    pub fn with_font(mut self, font: FontId) -> Self {
+        let mut env = self.label.env().clone();
+        env.font_id = font;
+        self.label = Text::new(env, self.label.take_text());
+        self
+    }
+
+    /// Get the selected font
+    pub fn font(&self) -> FontId {
+        self.label.env().font_id
+    }
+
+    /// Select a font
+    ///
+    /// Note: this must not be called before fonts have been initialised
+    /// (usually done by the theme when the main loop starts).
+    pub fn set_font(&mut self, font: FontId) -> TkAction {
+        self.label.update_env(|env| env.set_font_id(font));
+        TkAction::Resize
+    }
+
     /// Set text in an existing `Label`
     ///
     /// Note: this must not be called before fonts have been initialised
@@ -184,6 +319,30 @@ pub type StrLabel = Label<&'static str>;
 /// Label with `String` as backing type
 pub type StringLabel = Label<String>;
 
+/// Label with [`RichText`] as backing type
+///
+/// Construct with [`Label::rich`], which enables drawing of run colours.
+/// (A label constructed via [`Label::new`] uses the theme's text colour.)
+pub type RichLabel = Label<RichText>;
+
+impl RichLabel {
+    /// Construct from a sequence of `(text, style)` runs
+    ///
+    /// See [`RichText`] and [`kas::text::TextStyle`].
+    pub fn rich<S, I>(runs: I) -> Self
+    where
+        S: AsRef<str>,
+        I: IntoIterator<Item = (S, kas::text::TextStyle)>,
+    {
+        let mut label = Label::new(RichText::new(runs));
+        label.draw_fn = Some(DrawFn(|label, draw_handle, disabled| {
+            let pos = label.core.rect.pos;
+            draw_handle.text_rich(pos, &label.label, TextClass::Label, disabled);
+        }));
+        label
+    }
+}
+
 /// A label supporting an accelerator key
 ///
 /// Accelerator keys are not useful on plain labels, but this widget may be
@@ -203,3 +362,141 @@ impl SetAccel for AccelLabel {
         kas::text::util::set_text_and_prepare(&mut self.label, string)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::TestSizeHandle;
+
+    #[test]
+    fn rich_label() {
+        use crate::test_util::{DrawCommand, RecordingDrawHandle};
+        use kas::draw::Colour;
+        use kas::text::TextStyle;
+
+        let red = Colour::new(1.0, 0.0, 0.0);
+        let blue = Colour::new(0.0, 0.0, 1.0);
+        let mut label = Label::rich(vec![
+            ("red ", TextStyle::new().with_colour(red)),
+            ("blue", TextStyle::new().with_colour(blue)),
+        ]);
+        let mut size_handle = TestSizeHandle::new();
+        let axis = AxisInfo::new(false, None);
+        assert_eq!(label.size_rules(&mut size_handle, axis).ideal_size(), 64);
+
+        let rect = Rect::new(Coord::ZERO, Size(64, 16));
+        let mut draw_handle = RecordingDrawHandle::new(rect);
+        label.draw(&mut draw_handle, &ManagerState::new(), false);
+        let runs = vec![(0, Some(red)), (4, Some(blue))];
+        let text = "red blue".into();
+        let command = DrawCommand::TextRich(Coord::ZERO, text, runs, TextClass::Label, false);
+        assert_eq!(draw_handle.commands(), &[command]);
+    }
+
+    #[test]
+    fn font_selection() {
+        let mono = FontId(1);
+        let mut size_handle = TestSizeHandle::new();
+        size_handle.font_glyph_advance = vec![(mono, 10)];
+
+        let mut label = Label::from("code");
+        let mut code = Label::from("code").with_font(mono);
+        assert_eq!(label.font(), FontId::default());
+        assert_eq!(code.font(), mono);
+
+        let axis = AxisInfo::new(false, None);
+        assert_eq!(label.size_rules(&mut size_handle, axis).ideal_size(), 32);
+        assert_eq!(code.size_rules(&mut size_handle, axis).ideal_size(), 40);
+    }
+
+    #[test]
+    fn valign_builders() {
+        let label = Label::from("x").with_valign(Align::BR);
+        assert_eq!(label.align().horiz, None);
+        assert_eq!(label.align().vert, Some(Align::BR));
+        let label = label.with_halign(Align::Centre);
+        assert_eq!(label.align().horiz, Some(Align::Centre));
+        assert_eq!(label.align().vert, Some(Align::BR));
+    }
+
+    #[test]
+    fn valign_anchor_tall_text() {
+        for &vert in &[Align::Default, Align::TL, Align::Centre, Align::BR] {
+            assert_eq!(impls::anchor_valign(vert, 20.0, 40), vert);
+            assert_eq!(impls::anchor_valign(vert, 60.0, 40), Align::TL);
+        }
+    }
+
+    #[test]
+    fn align_override() {
+        let _ = crate::text::fonts::fonts().load_default();
+
+        let mut label = Label::from("a");
+        let rect = Rect::new(Coord::ZERO, Size(200, 20));
+        let first_x =
+            |label: &Label<&str>| label.label.text_glyph_pos(0).next_back().unwrap().pos.0;
+        label.set_rect(rect, AlignHints::NONE);
+        let left = first_x(&label);
+
+        // An override takes effect on the next set_rect, taking priority over
+        // the parent's hints
+        let centre = AlignHints::new(Some(Align::Centre), None);
+        assert_eq!(label.set_align(centre), TkAction::Resize);
+        assert_eq!(label.align().horiz, Some(Align::Centre));
+        label.set_rect(rect, AlignHints::new(Some(Align::TL), None));
+        assert!(first_x(&label) > left + 50.0);
+
+        // Removing the override restores the parent's alignment
+        assert_eq!(label.set_align(AlignHints::NONE), TkAction::Resize);
+        label.set_rect(rect, AlignHints::NONE);
+        assert_eq!(first_x(&label), left);
+    }
+
+    #[test]
+    fn justified_wrap() {
+        let _ = crate::text::fonts::fonts().load_default();
+
+        let text = "one two three four five six seven eight nine ten eleven twelve";
+        let mut left = Label::new(text);
+        let mut justified = Label::new(text).with_halign(Align::Stretch);
+
+        // Measurement does not depend on alignment
+        let mut size_handle = TestSizeHandle::new();
+        for &axis in &[AxisInfo::new(false, None), AxisInfo::new(true, Some(100))] {
+            let rules = left.size_rules(&mut size_handle, axis);
+            assert_eq!(justified.size_rules(&mut size_handle, axis), rules);
+        }
+
+        let rect = Rect::new(Coord::ZERO, Size(100, 200));
+        left.set_rect(rect, AlignHints::NONE);
+        justified.set_rect(rect, AlignHints::NONE);
+        let x = |label: &Label<&str>, index| {
+            label.label.text_glyph_pos(index).next_back().unwrap().pos.0
+        };
+        let line = |label: &Label<&str>, index| label.label.find_line(index).unwrap();
+
+        // Text wraps identically: each word starts on the same line
+        let starts: Vec<usize> = std::iter::once(0)
+            .chain(text.match_indices(' ').map(|(i, _)| i + 1))
+            .collect();
+        for &i in &starts {
+            assert_eq!(line(&justified, i), line(&left, i));
+        }
+        let last_line = line(&left, text.len()).0;
+        assert!(last_line > 0);
+
+        // Words move right, except at the start of each line and on the last
+        // line, which is not justified
+        let mut moved = false;
+        for &i in &starts {
+            let (index, range) = line(&left, i);
+            if range.start == i || index == last_line {
+                assert_eq!(x(&justified, i), x(&left, i));
+            } else {
+                assert!(x(&justified, i) >= x(&left, i));
+                moved |= x(&justified, i) > x(&left, i);
+            }
+        }
+        assert!(moved);
+    }
+}
@@ -0,0 +1,424 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! A stack of lazily-constructed pages
+
+use std::fmt::{self, Debug};
+use std::rc::Rc;
+
+use kas::event::{self, ConfigureManager};
+use kas::prelude::*;
+
+struct Page<W> {
+    factory: Rc<dyn Fn() -> W>,
+    widget: Option<W>,
+    last_used: u64,
+}
+
+impl<W: Clone> Clone for Page<W> {
+    fn clone(&self) -> Self {
+        Page {
+            factory: self.factory.clone(),
+            widget: self.widget.clone(),
+            last_used: self.last_used,
+        }
+    }
+}
+
+impl<W: Debug> Debug for Page<W> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Page")
+            .field("factory", &"<closure>")
+            .field("widget", &self.widget)
+            .field("last_used", &self.last_used)
+            .finish()
+    }
+}
+
+/// A stack of lazily-constructed pages
+///
+/// Like [`Stack`](super::Stack), only a single page is visible at a time, but
+/// pages are added as *factories*: closures constructing the page's widget.
+/// A page's widget is only constructed when the page is first made active,
+/// after which it is cached. Switching away from a page keeps its widget
+/// (and thus its state).
+///
+/// Constructing a page requires [configuring](WidgetConfig::configure) it,
+/// which assigns [`WidgetId`]s. Thus [`LazyStack::set_active`] returns
+/// [`TkAction::Reconfigure`] when the new page has not been constructed; the
+/// page is constructed by the resulting reconfigure, before its children are
+/// configured. Switching to a cached page only requires
+/// [`TkAction::RegionMoved`].
+///
+/// Optionally, the number of cached pages may be limited via
+/// [`LazyStack::with_max_cached`]; when a page is constructed beyond this
+/// limit, the least-recently-used inactive page is dropped (and will be
+/// reconstructed by its factory if shown again).
+///
+/// Sizing considers constructed pages only, thus the stack may grow when a
+/// new page is constructed.
+///
+/// Only constructed pages are children in the sense of [`WidgetChildren`]:
+/// [`WidgetChildren::len`] and [`WidgetChildren::get`] index constructed
+/// pages in page order, thus child indices need not match page indices.
+#[handler(send=noauto, msg=<W as event::Handler>::Msg)]
+#[widget(config=noauto, children=noauto)]
+#[derive(Clone, Debug, Widget)]
+pub struct LazyStack<W: Widget> {
+    first_id: WidgetId,
+    #[widget_core]
+    core: CoreData,
+    pages: Vec<Page<W>>,
+    // Indices of constructed pages, in order
+    built: Vec<usize>,
+    active: usize,
+    max_cached: Option<usize>,
+    clock: u64,
+}
+
+impl<W: Widget> Default for LazyStack<W> {
+    fn default() -> Self {
+        LazyStack::new(0)
+    }
+}
+
+impl<W: Widget> WidgetChildren for LazyStack<W> {
+    #[inline]
+    fn first_id(&self) -> WidgetId {
+        self.first_id
+    }
+    fn record_first_id(&mut self, id: WidgetId) {
+        self.first_id = id;
+    }
+    #[inline]
+    fn len(&self) -> usize {
+        self.built.len()
+    }
+    #[inline]
+    fn get(&self, index: usize) -> Option<&dyn WidgetConfig> {
+        let page = self.pages.get(*self.built.get(index)?)?;
+        page.widget.as_ref().map(|w| w.as_widget())
+    }
+    #[inline]
+    fn get_mut(&mut self, index: usize) -> Option<&mut dyn WidgetConfig> {
+        let page = self.pages.get_mut(*self.built.get(index)?)?;
+        page.widget.as_mut().map(|w| w.as_widget_mut())
+    }
+}
+
+impl<W: Widget> WidgetConfig for LazyStack<W> {
+    fn configure_recurse<'a, 'b>(&mut self, mut cmgr: ConfigureManager<'a, 'b>) {
+        // Construct the active page (if required) before configuring children
+        self.construct_active();
+
+        self.record_first_id(cmgr.peek_next());
+        for page in &mut self.pages {
+            if let Some(w) = page.widget.as_mut() {
+                w.configure_recurse(cmgr.child());
+            }
+        }
+        self.core_data_mut().id = cmgr.next_id(self.id());
+        self.configure(cmgr.mgr());
+    }
+}
+
+impl<W: Widget> Layout for LazyStack<W> {
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let mut rules = SizeRules::EMPTY;
+        for w in self
+            .pages
+            .iter_mut()
+            .filter_map(|page| page.widget.as_mut())
+        {
+            rules = rules.max(w.size_rules(size_handle, axis));
+        }
+        rules
+    }
+
+    fn set_rect(&mut self, rect: Rect, align: AlignHints) {
+        self.core.rect = rect;
+        for w in self
+            .pages
+            .iter_mut()
+            .filter_map(|page| page.widget.as_mut())
+        {
+            w.set_rect(rect, align.clone());
+        }
+    }
+
+    fn spatial_range(&self) -> (usize, usize) {
+        // Only the active page is navigable
+        match self.built.binary_search(&self.active) {
+            Ok(index) => (index, index),
+            Err(_) => (0, std::usize::MAX),
+        }
+    }
+
+    fn find_id(&self, coord: Coord) -> Option<WidgetId> {
+        self.active().and_then(|w| w.find_id(coord))
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, mgr: &event::ManagerState, disabled: bool) {
+        let disabled = disabled || self.is_disabled();
+        if let Some(w) = self.active() {
+            w.draw(draw_handle, mgr, disabled);
+        }
+    }
+}
+
+impl<W: Widget> event::SendEvent for LazyStack<W> {
+    fn send(&mut self, mgr: &mut Manager, id: WidgetId, event: Event) -> Response<Self::Msg> {
+        if !self.is_disabled() {
+            for index in 0..self.pages.len() {
+                let child = match self.pages[index].widget.as_mut() {
+                    Some(w) => w,
+                    None => continue,
+                };
+                if id <= child.id() {
+                    return match child.send(mgr, id, event) {
+                        Response::Focus(rect) => {
+                            *mgr += self.set_active(index);
+                            Response::Focus(rect)
+                        }
+                        r => r,
+                    };
+                }
+            }
+        }
+
+        Response::Unhandled(event)
+    }
+}
+
+impl<W: Widget> LazyStack<W> {
+    /// Construct a new instance, without pages
+    ///
+    /// Page `active` will be visible once added (see [`LazyStack::set_active`]).
+    pub fn new(active: usize) -> Self {
+        LazyStack {
+            first_id: Default::default(),
+            core: Default::default(),
+            pages: vec![],
+            built: vec![],
+            active,
+            max_cached: None,
+            clock: 0,
+        }
+    }
+
+    /// Add a page (chain style)
+    ///
+    /// The `factory` is called to construct the page's widget when first
+    /// shown, and again if shown after the widget has been dropped.
+    pub fn with_page<F: Fn() -> W + 'static>(mut self, factory: F) -> Self {
+        self.push_page(factory);
+        self
+    }
+
+    /// Limit the number of cached pages (chain style)
+    ///
+    /// When a page is constructed and more than `max` pages would be cached,
+    /// the least-recently-used inactive pages are dropped. The active page is
+    /// never dropped. By default, there is no limit.
+    pub fn with_max_cached(mut self, max: usize) -> Self {
+        self.max_cached = Some(max);
+        self
+    }
+
+    /// Append a page
+    ///
+    /// This does not construct the page. If the new page is
+    /// [active](LazyStack::active_index), the caller should trigger a
+    /// [reconfigure action](Manager::send_action).
+    pub fn push_page<F: Fn() -> W + 'static>(&mut self, factory: F) {
+        self.pages.push(Page {
+            factory: Rc::new(factory),
+            widget: None,
+            last_used: 0,
+        });
+    }
+
+    /// Returns the number of pages
+    pub fn len(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// True if there are no pages
+    pub fn is_empty(&self) -> bool {
+        self.pages.is_empty()
+    }
+
+    /// True if the widget of page `index` is constructed (cached)
+    pub fn is_constructed(&self, index: usize) -> bool {
+        self.pages
+            .get(index)
+            .map(|page| page.widget.is_some())
+            .unwrap_or(false)
+    }
+
+    /// Get the index of the active page
+    pub fn active_index(&self) -> usize {
+        self.active
+    }
+
+    /// Change the active page via index
+    ///
+    /// If the page has not yet been constructed, this returns
+    /// [`TkAction::Reconfigure`]; the page is constructed during the resulting
+    /// reconfigure. As with [`Stack`](super::Stack), `active` need not be a
+    /// valid index; if not, no page is shown.
+    pub fn set_active(&mut self, active: usize) -> TkAction {
+        if self.active == active {
+            return TkAction::None;
+        }
+        self.active = active;
+        match self.pages.get_mut(active) {
+            Some(page) if page.widget.is_none() => TkAction::Reconfigure,
+            Some(page) => {
+                self.clock += 1;
+                page.last_used = self.clock;
+                TkAction::RegionMoved
+            }
+            None => TkAction::RegionMoved,
+        }
+    }
+
+    /// Get a direct reference to the active page's widget, if constructed
+    pub fn active(&self) -> Option<&W> {
+        self.pages.get(self.active)?.widget.as_ref()
+    }
+
+    /// Get a direct mutable reference to the active page's widget, if constructed
+    pub fn active_mut(&mut self) -> Option<&mut W> {
+        self.pages.get_mut(self.active)?.widget.as_mut()
+    }
+
+    /// Drop the widget of page `index`
+    ///
+    /// The page remains and will be reconstructed when next shown. Returns
+    /// [`TkAction::Reconfigure`] if a widget was dropped. The active page may
+    /// not be dropped; in this case nothing happens.
+    pub fn evict(&mut self, index: usize) -> TkAction {
+        if index == self.active {
+            return TkAction::None;
+        }
+        match self
+            .pages
+            .get_mut(index)
+            .and_then(|page| page.widget.take())
+        {
+            Some(_) => {
+                self.update_built();
+                TkAction::Reconfigure
+            }
+            None => TkAction::None,
+        }
+    }
+
+    fn update_built(&mut self) {
+        self.built = (self.pages.iter().enumerate())
+            .filter(|(_, page)| page.widget.is_some())
+            .map(|(i, _)| i)
+            .collect();
+    }
+
+    // Construct the active page, if not already, applying the cache limit
+    fn construct_active(&mut self) {
+        let active = self.active;
+        let page = match self.pages.get_mut(active) {
+            Some(page) => page,
+            None => return,
+        };
+        if page.widget.is_some() {
+            return;
+        }
+        page.widget = Some((page.factory)());
+        self.clock += 1;
+        page.last_used = self.clock;
+
+        if let Some(max) = self.max_cached {
+            let mut cached: Vec<(u64, usize)> = (self.pages.iter().enumerate())
+                .filter(|(i, page)| *i != active && page.widget.is_some())
+                .map(|(i, page)| (page.last_used, i))
+                .collect();
+            cached.sort();
+            // The active page counts towards the limit
+            let excess = (cached.len() + 1).saturating_sub(max.max(1));
+            for (_, i) in cached.into_iter().take(excess) {
+                self.pages[i].widget = None;
+            }
+        }
+        self.update_built();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::TestManager;
+    use crate::widget::StrLabel;
+    use std::cell::Cell;
+
+    #[test]
+    fn lazy_stack() {
+        let count = Rc::new(Cell::new(0));
+        let page = |label: &'static str| {
+            let count = count.clone();
+            move || {
+                count.set(count.get() + 1);
+                StrLabel::new(label)
+            }
+        };
+        let stack = LazyStack::new(0)
+            .with_page(page("A"))
+            .with_page(page("B"))
+            .with_page(page("C"))
+            .with_max_cached(2);
+
+        let mut test = TestManager::new(stack);
+        assert_eq!(count.get(), 1);
+        let stack = &test.widget;
+        assert!(stack.is_constructed(0) && !stack.is_constructed(1));
+        assert!(stack.is_ancestor_of(stack.active().unwrap().id()));
+
+        // Showing a new page requires a reconfigure, which constructs it
+        assert_eq!(test.widget.set_active(1), TkAction::Reconfigure);
+        test.configure();
+        assert_eq!(count.get(), 2);
+        assert!(test.widget.is_constructed(1));
+
+        // Cached pages are shown without reconstruction
+        assert_eq!(test.widget.set_active(0), TkAction::RegionMoved);
+        assert_eq!(count.get(), 2);
+
+        // Beyond the cache limit, the least-recently-used page is dropped
+        assert_eq!(test.widget.set_active(2), TkAction::Reconfigure);
+        test.configure();
+        assert_eq!(count.get(), 3);
+        let stack = &test.widget;
+        assert!(stack.is_constructed(0) && !stack.is_constructed(1));
+        assert!(stack.is_constructed(2));
+    }
+
+    #[test]
+    fn nav_skips_unbuilt_pages() {
+        use crate::widget::{CheckBoxBare, Row};
+        use kas::event::VirtualKeyCode;
+
+        let page = || Row::new(vec![CheckBoxBare::new(), CheckBoxBare::new()]);
+        let stack = LazyStack::new(1).with_page(page).with_page(page);
+        let mut test = TestManager::new(stack);
+        assert!(!test.widget.is_constructed(0));
+        assert_eq!(WidgetChildren::len(&test.widget), 1);
+        assert_eq!(test.widget.spatial_range(), (0, 0));
+
+        let active = test.widget.active().unwrap();
+        let ids = [active[0].id(), active[1].id()];
+        test.with(|mgr, _| mgr.set_nav_focus(ids[0]));
+        assert_eq!(test.state.nav_focus, Some(ids[0]));
+        test.key(VirtualKeyCode::Tab);
+        assert_eq!(test.state.nav_focus, Some(ids[1]));
+    }
+}
@@ -0,0 +1,323 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Text with clickable links
+
+use std::fmt::Debug;
+use std::ops::Range;
+
+use kas::access::{AccessNode, Role};
+use kas::draw::{InputState, TextClass};
+use kas::geom::Vec2;
+use kas::text::{RichText, TextStyle};
+use kas::{event, prelude::*};
+
+/// A link within a [`LinkLabel`]
+///
+/// Links are child widgets of the label, thus support hover highlighting,
+/// keyboard navigation and activation like any other widget. They are not
+/// sized or drawn independently; the parent [`LinkLabel`] handles this.
+#[widget(config(key_nav = true, cursor_icon = event::CursorIcon::Hand))]
+#[handler(handle=noauto)]
+#[derive(Clone, Debug, Widget)]
+struct Link<M: Clone + Debug + 'static> {
+    #[widget_core]
+    core: CoreData,
+    range: Range<usize>,
+    // The link's rect on each line it occupies
+    rects: Vec<Rect>,
+    msg: M,
+}
+
+impl<M: Clone + Debug + 'static> Layout for Link<M> {
+    fn size_rules(&mut self, _: &mut dyn SizeHandle, _: AxisInfo) -> SizeRules {
+        SizeRules::EMPTY
+    }
+
+    fn find_id(&self, coord: Coord) -> Option<WidgetId> {
+        if self.rects.iter().any(|rect| rect.contains(coord)) {
+            Some(self.id())
+        } else {
+            None
+        }
+    }
+
+    fn draw(&self, _: &mut dyn DrawHandle, _: &event::ManagerState, _: bool) {}
+}
+
+impl<M: Clone + Debug + 'static> event::Handler for Link<M> {
+    type Msg = M;
+
+    #[inline]
+    fn activation_via_press(&self) -> bool {
+        true
+    }
+
+    fn handle(&mut self, _: &mut Manager, event: Event) -> Response<M> {
+        match event {
+            Event::Activate => Response::Msg(self.msg.clone()),
+            event => Response::Unhandled(event),
+        }
+    }
+}
+
+/// A text label containing clickable links
+///
+/// The text is a [`RichText`] built from a sequence of plain runs and links.
+/// Each link is associated with a message (for example a URL), which is
+/// emitted when the link is clicked or activated via the keyboard.
+///
+/// Links are highlighted when hovered by the mouse and show a pointer cursor.
+/// A link wrapped over multiple lines is clickable on each line.
+/// For keyboard navigation, each link is a separate stop: <kbd>Tab</kbd>
+/// moves to the next link.
+///
+/// [`Layout::find_id`] returns the identifier of the link under the
+/// coordinate, if any, and otherwise the label's own identifier.
+#[widget(config=noauto, children=noauto)]
+#[handler(send=noauto, msg=M)]
+#[derive(Clone, Debug, Widget)]
+pub struct LinkLabel<M: Clone + Debug + 'static> {
+    first_id: WidgetId,
+    #[widget_core]
+    core: CoreData,
+    align: AlignHints,
+    label: Text<RichText>,
+    links: Vec<Link<M>>,
+}
+
+impl<M: Clone + Debug + 'static> Default for LinkLabel<M> {
+    fn default() -> Self {
+        LinkLabel::new()
+    }
+}
+
+impl<M: Clone + Debug + 'static> LinkLabel<M> {
+    /// Construct, with empty text
+    ///
+    /// Use [`LinkLabel::with_text`] and [`LinkLabel::with_link`] to add text.
+    pub fn new() -> Self {
+        LinkLabel {
+            first_id: Default::default(),
+            core: Default::default(),
+            align: AlignHints::NONE,
+            label: Text::new_multi(RichText::default()),
+            links: vec![],
+        }
+    }
+
+    /// Append a run of plain text (chain style)
+    pub fn with_text(mut self, text: &str, style: TextStyle) -> Self {
+        let mut rich = self.label.take_text();
+        rich.push(text, style);
+        self.label = Text::new_multi(rich);
+        self
+    }
+
+    /// Append a link (chain style)
+    ///
+    /// When the link is activated, `msg` is emitted. The link's colour is
+    /// determined by the theme.
+    pub fn with_link(mut self, text: &str, msg: M) -> Self {
+        let mut rich = self.label.take_text();
+        let start = rich.as_str().len();
+        rich.push(text, TextStyle::default());
+        let range = start..rich.as_str().len();
+        self.label = Text::new_multi(rich);
+        self.links.push(Link {
+            core: Default::default(),
+            range,
+            rects: vec![],
+            msg,
+        });
+        self
+    }
+
+    /// Override alignment (chain style)
+    ///
+    /// Alignment hints given here take priority over those provided by the
+    /// parent widget.
+    pub fn with_align(mut self, align: AlignHints) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Get the text of each link, in order
+    pub fn links(&self) -> impl Iterator<Item = &str> {
+        let text = self.label.as_str();
+        self.links.iter().map(move |link| &text[link.range.clone()])
+    }
+
+    // Find the rect of each link fragment; requires prepared text
+    fn update_link_rects(&mut self) {
+        let pos = self.core.rect.pos;
+        for link in &mut self.links {
+            let lines = self.label.as_ref().highlight_lines(link.range.clone());
+            link.rects = lines
+                .iter()
+                .map(|(p1, p2)| {
+                    let p1 = pos + Coord::from(Vec2::from(*p1));
+                    let p2 = pos + Coord::from(Vec2::from(*p2));
+                    Rect::new(p1, Size::from(p2 - p1))
+                })
+                .collect();
+
+            let mut bound = link.rects.first().cloned().unwrap_or_default();
+            for rect in link.rects.iter().skip(1) {
+                let p1 = Coord(bound.pos.0.min(rect.pos.0), bound.pos.1.min(rect.pos.1));
+                let p2 = bound.pos_end();
+                let p3 = rect.pos_end();
+                let p2 = Coord(p2.0.max(p3.0), p2.1.max(p3.1));
+                bound = Rect::new(p1, Size::from(p2 - p1));
+            }
+            link.core.rect = bound;
+        }
+    }
+}
+
+impl<M: Clone + Debug + 'static> WidgetChildren for LinkLabel<M> {
+    #[inline]
+    fn first_id(&self) -> WidgetId {
+        self.first_id
+    }
+    fn record_first_id(&mut self, id: WidgetId) {
+        self.first_id = id;
+    }
+    #[inline]
+    fn len(&self) -> usize {
+        self.links.len()
+    }
+    #[inline]
+    fn get(&self, index: usize) -> Option<&dyn WidgetConfig> {
+        self.links.get(index).map(|w| w.as_widget())
+    }
+    #[inline]
+    fn get_mut(&mut self, index: usize) -> Option<&mut dyn WidgetConfig> {
+        self.links.get_mut(index).map(|w| w.as_widget_mut())
+    }
+}
+
+impl<M: Clone + Debug + 'static> WidgetConfig for LinkLabel<M> {
+    fn accessible(&self) -> AccessNode {
+        AccessNode::new(Role::Label).with_label(self.label.as_str())
+    }
+}
+
+impl<M: Clone + Debug + 'static> Layout for LinkLabel<M> {
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        size_handle.text_bound(&mut self.label, TextClass::Label, axis)
+    }
+
+    fn set_rect(&mut self, rect: Rect, align: AlignHints) {
+        self.core.rect = rect;
+        let align = self
+            .align
+            .or(align)
+            .unwrap_or(Align::Default, Align::Centre);
+        self.label.update_env(|env| {
+            env.set_bounds(rect.size.into());
+            env.set_align(align);
+        });
+        self.update_link_rects();
+    }
+
+    fn find_id(&self, coord: Coord) -> Option<WidgetId> {
+        if !self.rect().contains(coord) {
+            return None;
+        }
+
+        for link in &self.links {
+            if let Some(id) = link.find_id(coord) {
+                return Some(id);
+            }
+        }
+
+        Some(self.id())
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, mgr: &event::ManagerState, disabled: bool) {
+        let disabled = disabled || self.is_disabled();
+        let links: Vec<_> = self
+            .links
+            .iter()
+            .map(|link| (link.range.clone(), link.input_state(mgr, disabled)))
+            .collect();
+        let class = TextClass::Label;
+        draw_handle.text_links(self.core.rect.pos, &self.label, &links, class, disabled);
+    }
+}
+
+impl<M: Clone + Debug + 'static> event::SendEvent for LinkLabel<M> {
+    fn send(&mut self, mgr: &mut Manager, id: WidgetId, event: Event) -> Response<Self::Msg> {
+        if !self.is_disabled() {
+            for child in &mut self.links {
+                if id <= child.id() {
+                    return child.send(mgr, id, event);
+                }
+            }
+        }
+
+        Response::Unhandled(event)
+    }
+}
+
+impl<M: Clone + Debug + 'static> HasStr for LinkLabel<M> {
+    fn get_str(&self) -> &str {
+        self.label.as_str()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::{DrawCommand, RecordingDrawHandle, TestSizeHandle};
+
+    #[test]
+    fn links() {
+        let _ = crate::text::fonts::fonts().load_default();
+
+        let mut label = LinkLabel::new()
+            .with_text("See ", TextStyle::new())
+            .with_link("kas", "https://github.com/kas-gui/kas")
+            .with_text(" or ", TextStyle::new())
+            .with_link("docs", "https://docs.rs/kas");
+        assert_eq!(label.get_str(), "See kas or docs");
+        assert_eq!(label.links().collect::<Vec<_>>(), vec!["kas", "docs"]);
+        let mut id = WidgetId::FIRST;
+        label.walk_mut(|w| {
+            w.core_data_mut().id = id;
+            id = id.next();
+        });
+
+        let mut size_handle = TestSizeHandle::new();
+        let horiz = label.size_rules(&mut size_handle, AxisInfo::new(false, None));
+        let axis = AxisInfo::new(true, Some(horiz.ideal_size()));
+        let vert = label.size_rules(&mut size_handle, axis);
+        let rect = Rect::new(Coord::ZERO, Size(horiz.ideal_size(), vert.ideal_size()));
+        label.set_rect(rect, AlignHints::NONE);
+
+        // Each link is found within its own rect; other text finds the label
+        for link in &label.links {
+            assert_eq!(link.rects.len(), 1);
+            assert_eq!(label.find_id(link.rects[0].pos), Some(link.id()));
+        }
+        assert_eq!(label.find_id(Coord::ZERO), Some(label.id()));
+
+        let mut draw_handle = RecordingDrawHandle::new(rect);
+        label.draw(&mut draw_handle, &ManagerState::new(), false);
+        let links = vec![
+            (4..7, InputState::default()),
+            (11..15, InputState::default()),
+        ];
+        let command = DrawCommand::TextLinks(
+            Coord::ZERO,
+            "See kas or docs".into(),
+            links,
+            TextClass::Label,
+            false,
+        );
+        assert_eq!(draw_handle.commands(), &[command]);
+    }
+}
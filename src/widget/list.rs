@@ -7,6 +7,7 @@
 
 use std::ops::{Index, IndexMut};
 
+use kas::event::TypeAhead;
 use kas::layout::{self, RulesSetter, RulesSolver};
 use kas::{event, prelude::*};
 
@@ -39,7 +40,7 @@ pub type BoxColumn<M> = BoxList<kas::Down, M>;
 /// This is parameterised over directionality and handler message type.
 ///
 /// See documentation of [`List`] type.
-pub type BoxList<D, M> = List<D, Box<dyn Widget<Msg = M>>>;
+pub type BoxList<D, M> = List<D, BoxedWidget<M>>;
 
 /// A row of widget references
 ///
@@ -84,6 +85,8 @@ pub type RefList<'a, D, M> = List<D, &'a mut dyn Widget<Msg = M>>;
 /// disadvantage that drawing and event handling are O(n) in the number of
 /// children.
 ///
+/// Optionally, [type-ahead search](List::with_type_ahead) may be enabled.
+///
 /// [`make_widget`]: ../macros/index.html#the-make_widget-macro
 #[handler(send=noauto, msg=<W as event::Handler>::Msg)]
 #[widget(children=noauto)]
@@ -95,6 +98,7 @@ pub struct List<D: Directional, W: Widget> {
     widgets: Vec<W>,
     data: layout::DynRowStorage,
     direction: D,
+    type_ahead: Option<TypeAhead>,
 }
 
 impl<D: Directional, W: Widget> WidgetChildren for List<D, W> {
@@ -180,6 +184,23 @@ impl<D: Directional, W: Widget> event::SendEvent for List<D, W> {
                     return child.send(mgr, id, event);
                 }
             }
+
+            if let Some(type_ahead) = self.type_ahead.as_mut() {
+                match event {
+                    Event::ReceivedCharacter(c) if id == self.core.id => {
+                        let search = type_ahead.push(mgr, id, c).to_string();
+                        if let Some(target) = TypeAhead::find(self.as_widget(), &search) {
+                            mgr.set_nav_focus(target);
+                        }
+                        return Response::None;
+                    }
+                    Event::TimerUpdate if id == self.core.id => {
+                        type_ahead.on_timer(mgr, id);
+                        return Response::None;
+                    }
+                    _ => (),
+                }
+            }
         }
 
         Response::Unhandled(event)
@@ -199,6 +220,7 @@ impl<D: Directional + Default, W: Widget> List<D, W> {
             widgets,
             data: Default::default(),
             direction: Default::default(),
+            type_ahead: None,
         }
     }
 }
@@ -212,9 +234,21 @@ impl<D: Directional, W: Widget> List<D, W> {
             widgets,
             data: Default::default(),
             direction,
+            type_ahead: None,
         }
     }
 
+    /// Enable type-ahead search (chain style)
+    ///
+    /// While a child (or descendant) has navigation focus and no widget has
+    /// character focus, typing moves navigation focus to the first child
+    /// whose label starts with the characters typed (see [`TypeAhead`]).
+    /// If no child matches, focus is unchanged.
+    pub fn with_type_ahead(mut self) -> Self {
+        self.type_ahead = Some(TypeAhead::new());
+        self
+    }
+
     /// Get the direction of contents
     pub fn direction(&self) -> Direction {
         self.direction.as_direction()
@@ -404,3 +438,73 @@ impl<'a, D: Directional, W: Widget> ExactSizeIterator for ListIter<'a, D, W> {
         self.list.widgets.len() - self.index
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::event::{EventConfig, VoidMsg};
+    use crate::test_util::{DrawCommand, RecordingDrawHandle, TestManager, TestSizeHandle};
+    use crate::widget::{MenuEntry, StrLabel, SubMenu};
+    use std::time::Duration;
+
+    #[test]
+    fn draw_visible_only() {
+        let _ = crate::text::fonts::fonts().load_default();
+
+        let mut column = Column::new(vec![StrLabel::new("x"); 1000]);
+        let mut size_handle = TestSizeHandle::new();
+        let horiz = column.size_rules(&mut size_handle, AxisInfo::new(false, None));
+        let axis = AxisInfo::new(true, Some(horiz.min_size()));
+        let vert = column.size_rules(&mut size_handle, axis);
+        // Each label is 16px high with 4px between
+        assert_eq!(vert.min_size(), 1000 * 16 + 999 * 4);
+        let size = Size(horiz.min_size(), vert.min_size());
+        column.set_rect(Rect::new(Coord::ZERO, size), AlignHints::NONE);
+
+        // Only children within the target rect are drawn (partially visible
+        // children included)
+        let mgr = event::ManagerState::new();
+        let visible = Rect::new(Coord(0, 1000), Size(size.0, 400));
+        let mut draw_handle = RecordingDrawHandle::new(visible);
+        column.draw(&mut draw_handle, &mgr, false);
+        let commands = draw_handle.take_commands();
+        let is_text =
+            |c: &&DrawCommand| matches!(c, DrawCommand::Text(..) | DrawCommand::TextEffects(..));
+        assert_eq!(commands.iter().filter(is_text).count(), 20);
+    }
+
+    #[test]
+    fn type_ahead() {
+        let labels = ["Apple", "Apricot", "Banana", "Blueberry"];
+        let entries = labels.iter().map(|label| SubMenu::right(*label, vec![]));
+        let list: Column<SubMenu<kas::Right, MenuEntry<VoidMsg>>> =
+            Column::new(entries.collect()).with_type_ahead();
+        let mut test = TestManager::new(list);
+        let id = test.widget.id();
+        let ids: Vec<_> = test.widget.iter().map(|w| w.id()).collect();
+        let type_char = |test: &mut TestManager<_>, c| {
+            test.send(id, Event::ReceivedCharacter(c));
+        };
+
+        // The timeout is taken from the event configuration
+        test.state.set_config(EventConfig {
+            type_ahead_timeout: Duration::from_secs(0),
+            ..Default::default()
+        });
+        type_char(&mut test, 'b');
+        assert!(test.state.nav_focus(ids[2]));
+        type_char(&mut test, 'a');
+        assert!(test.state.nav_focus(ids[0]));
+
+        // Focus moves to the first match, refined by further characters
+        test.state.set_config(EventConfig::default());
+        type_char(&mut test, 'b');
+        assert!(test.state.nav_focus(ids[2]));
+        type_char(&mut test, 'L');
+        assert!(test.state.nav_focus(ids[3]));
+
+        // Without a match, focus is unchanged
+        type_char(&mut test, 'x');
+        assert!(test.state.nav_focus(ids[3]));
+    }
+}
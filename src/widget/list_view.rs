@@ -0,0 +1,762 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! A scrollable list over a data model
+
+use std::fmt::{self, Debug};
+use std::rc::Rc;
+
+use kas::draw::{ClipRegion, InputState, TextClass};
+use kas::event::ScrollDelta::{LineDelta, PixelDelta};
+use kas::event::{self, ControlKey, ScrollDelta};
+use kas::prelude::*;
+
+use super::Hidden;
+
+/// Number of rows shown by default (see [`ListView::with_view_rows`])
+const DEFAULT_VIEW_ROWS: usize = 16;
+
+#[derive(Clone, Debug)]
+struct Row<W> {
+    // Index of the data item shown, if visible
+    index: Option<usize>,
+    // Hidden while not assigned an item (the widget's own disabled state is
+    // left to the user)
+    widget: Hidden<W>,
+}
+
+/// A scrollable, vertical list over a data model
+///
+/// Unlike [`super::Column`], this widget does not construct a widget per data
+/// item. Instead, a small pool of row widgets is constructed and only rows
+/// within the view are shown. When scrolled, rows leaving the view are
+/// recycled: they are assigned a new data item via the `set_row` closure
+/// passed to [`ListView::new`]. Memory usage and the cost of drawing and event
+/// handling thus depend on the size of the view, not the number of items.
+///
+/// The pool grows as needed to fill the view. Rows added by
+/// [`ListView::set_data`] are configured via the returned action. Rows added
+/// when resized are drawn immediately but receive input only once the list is
+/// reconfigured, which the list requests when it next receives an event.
+///
+/// Rows are assumed to have uniform height, measured from the pool. Where
+/// heights differ, use [`ListView::with_uniform_rows`] to measure each item;
+/// this is O(n) in the number of items but is cached until the width or data
+/// changes.
+///
+/// Scrolling is built in (mouse wheel, touchpad and navigation keys); this
+/// widget should not be placed within a [`super::ScrollRegion`]. Clicking a
+/// row not handling the click itself selects it; selection is tracked by data
+/// index and thus survives recycling (see [`ListView::selected`]).
+#[handler(send=noauto, msg=<W as event::Handler>::Msg)]
+#[widget(children=noauto, config=noauto)]
+#[derive(Clone, Widget)]
+pub struct ListView<T: 'static, W: Widget> {
+    first_id: WidgetId,
+    #[widget_core]
+    core: CoreData,
+    data: Vec<T>,
+    new_row: Rc<dyn Fn(&T) -> W>,
+    set_row: Rc<dyn Fn(&mut W, &T) -> TkAction>,
+    rows: Vec<Row<W>>,
+    view_rows: usize,
+    uniform: bool,
+    row_height: u32,
+    // If measured: top of each row, plus the end (length: data.len() + 1)
+    offsets: Vec<u32>,
+    measured_width: Option<Option<u32>>,
+    offset: u32,
+    scroll_rate: u32,
+    selected: Option<usize>,
+    unconfigured: bool,
+}
+
+impl<T: 'static, W: Widget> Debug for ListView<T, W> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "ListView {{ core: {:?}, len: {}, rows: {:?}, offset: {}, selected: {:?}, ... }}",
+            self.core,
+            self.data.len(),
+            self.rows,
+            self.offset,
+            self.selected,
+        )
+    }
+}
+
+impl<T: 'static, W: Widget> ListView<T, W> {
+    /// Construct a list view over `data`
+    ///
+    /// Row widgets are constructed by `new_row`. When a row is recycled,
+    /// `set_row` is called to assign the row a new data item; it should return
+    /// the action required (usually [`TkAction::Redraw`], as returned by e.g.
+    /// [`HasString::set_string`]).
+    pub fn new<NF, SF>(data: Vec<T>, new_row: NF, set_row: SF) -> Self
+    where
+        NF: Fn(&T) -> W + 'static,
+        SF: Fn(&mut W, &T) -> TkAction + 'static,
+    {
+        let mut list = ListView {
+            first_id: Default::default(),
+            core: Default::default(),
+            data,
+            new_row: Rc::new(new_row),
+            set_row: Rc::new(set_row),
+            rows: vec![],
+            view_rows: DEFAULT_VIEW_ROWS,
+            uniform: true,
+            row_height: 0,
+            offsets: vec![],
+            measured_width: None,
+            offset: 0,
+            scroll_rate: 0,
+            selected: None,
+            unconfigured: false,
+        };
+        list.grow_pool();
+        list
+    }
+
+    /// Set the number of rows shown at the ideal size (chain style)
+    ///
+    /// This also sets the initial size of the pool of row widgets. The default
+    /// is 16 rows.
+    pub fn with_view_rows(mut self, rows: usize) -> Self {
+        self.view_rows = rows;
+        self.grow_pool();
+        self
+    }
+
+    /// Set whether rows have uniform height (chain style)
+    ///
+    /// By default (`true`), all rows are assumed to have the height of the
+    /// tallest row in the pool. If `false`, each item is measured.
+    pub fn with_uniform_rows(mut self, uniform: bool) -> Self {
+        self.uniform = uniform;
+        self
+    }
+
+    /// Access the data
+    pub fn data(&self) -> &[T] {
+        &self.data
+    }
+
+    /// Replace the data
+    ///
+    /// The selection is kept if still in range. Returns
+    /// [`TkAction::Reconfigure`] if new row widgets were constructed, otherwise
+    /// [`TkAction::Resize`].
+    pub fn set_data(&mut self, data: Vec<T>) -> TkAction {
+        self.data = data;
+        self.offsets.clear();
+        self.measured_width = None;
+        for row in &mut self.rows {
+            row.index = None;
+            let _ = row.widget.set_visible_keep_focus(false);
+        }
+        if self.selected.map(|i| i >= self.data.len()).unwrap_or(false) {
+            self.selected = None;
+        }
+        let len = self.rows.len();
+        self.grow_pool();
+        if self.rows.len() > len {
+            TkAction::Reconfigure
+        } else {
+            TkAction::Resize
+        }
+    }
+
+    /// Get the number of row widgets
+    ///
+    /// This is the size of the pool, not the number of items.
+    pub fn num_rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Get the index of the selected item, if any
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    /// Set the selected item
+    pub fn set_selected(&mut self, index: Option<usize>) -> TkAction {
+        let index = index.filter(|i| *i < self.data.len());
+        if index == self.selected {
+            TkAction::None
+        } else {
+            self.selected = index;
+            TkAction::Redraw
+        }
+    }
+
+    /// Get the current scroll offset
+    pub fn offset(&self) -> u32 {
+        self.offset
+    }
+
+    /// Get the maximum scroll offset
+    pub fn max_offset(&self) -> u32 {
+        self.content_height().saturating_sub(self.core.rect.size.1)
+    }
+
+    /// Set the scroll offset
+    ///
+    /// The offset is clamped to [`ListView::max_offset`]. Returns
+    /// [`TkAction::None`] if unchanged.
+    pub fn set_offset(&mut self, offset: u32) -> TkAction {
+        let offset = offset.min(self.max_offset());
+        if offset == self.offset {
+            TkAction::None
+        } else {
+            self.offset = offset;
+            self.update_rows() + TkAction::RegionMoved
+        }
+    }
+
+    // Construct rows up to the initial pool size
+    fn grow_pool(&mut self) {
+        let len = (self.view_rows + 1).min(self.data.len());
+        while self.rows.len() < len {
+            let widget = (self.new_row)(&self.data[self.rows.len()]);
+            self.rows.push(Row {
+                index: None,
+                widget: Hidden::new(widget).with_visible(false),
+            });
+            self.unconfigured = true;
+        }
+    }
+
+    fn is_measured(&self) -> bool {
+        !self.uniform && self.offsets.len() == self.data.len() + 1
+    }
+
+    fn row_top(&self, index: usize) -> u32 {
+        if self.is_measured() {
+            self.offsets[index]
+        } else {
+            index as u32 * self.row_height
+        }
+    }
+
+    fn row_rect(&self, index: usize) -> Rect {
+        let top = self.row_top(index);
+        let height = if self.is_measured() {
+            self.offsets[index + 1] - top
+        } else {
+            self.row_height
+        };
+        let pos = self.core.rect.pos + Coord(0, top as i32);
+        Rect::new(pos, Size(self.core.rect.size.0, height))
+    }
+
+    fn content_height(&self) -> u32 {
+        if self.is_measured() {
+            *self.offsets.last().unwrap()
+        } else {
+            self.data.len() as u32 * self.row_height
+        }
+    }
+
+    // Index of the item at `y` (relative to the content top); may be out of range
+    fn index_at(&self, y: u32) -> usize {
+        if self.is_measured() {
+            match self.offsets.binary_search(&y) {
+                Ok(i) => i,
+                Err(i) => i.saturating_sub(1),
+            }
+        } else if self.row_height > 0 {
+            (y / self.row_height) as usize
+        } else {
+            0
+        }
+    }
+
+    // Measure the height of each item, using the first row widget
+    fn measure(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) {
+        let empty = self.rows.is_empty() || self.data.is_empty();
+        if empty || self.measured_width == Some(axis.other()) {
+            return;
+        }
+        self.measured_width = Some(axis.other());
+
+        let row = &mut self.rows[0];
+        self.offsets.clear();
+        self.offsets.reserve(self.data.len() + 1);
+        let mut top = 0;
+        for item in &self.data {
+            self.offsets.push(top);
+            let _ = (self.set_row)(row.widget.inner_mut(), item);
+            let rules = row.widget.inner_mut().size_rules(size_handle, axis);
+            let (m0, m1) = rules.margins();
+            top += rules.ideal_size() + m0.max(m1) as u32;
+        }
+        self.offsets.push(top);
+
+        // Restore the row's content
+        let index = row.index.unwrap_or(0);
+        let _ = (self.set_row)(row.widget.inner_mut(), &self.data[index]);
+    }
+
+    // Assign rows to visible items, growing the pool if necessary
+    fn update_rows(&mut self) -> TkAction {
+        let len = self.data.len();
+        let range = if len == 0 || self.content_height() == 0 {
+            0..0
+        } else {
+            let first = self.index_at(self.offset);
+            let end = self.offset + self.core.rect.size.1.max(1) - 1;
+            first.min(len)..(self.index_at(end) + 1).min(len)
+        };
+
+        for row in &mut self.rows {
+            if row.index.map(|i| !range.contains(&i)).unwrap_or(false) {
+                row.index = None;
+                let _ = row.widget.set_visible_keep_focus(false);
+            }
+        }
+
+        let mut action = TkAction::None;
+        for index in range {
+            if self.rows.iter().any(|row| row.index == Some(index)) {
+                continue;
+            }
+            let rect = self.row_rect(index);
+            let item = &self.data[index];
+            if let Some(n) = self.rows.iter().position(|row| row.index.is_none()) {
+                let row = &mut self.rows[n];
+                action += (self.set_row)(row.widget.inner_mut(), item);
+                let _ = row.widget.set_visible_keep_focus(true);
+                row.index = Some(index);
+                row.widget.set_rect(rect, AlignHints::NONE);
+            } else {
+                // New rows require configuration (and sizing)
+                let mut widget = (self.new_row)(item);
+                widget.set_rect(rect, AlignHints::NONE);
+                self.rows.push(Row {
+                    index: Some(index),
+                    widget: Hidden::new(widget),
+                });
+                self.unconfigured = true;
+                action += TkAction::Reconfigure;
+            }
+        }
+        action
+    }
+
+    fn scroll(&mut self, mgr: &mut Manager, delta: ScrollDelta) -> Response<W::Msg> {
+        let d = match delta {
+            LineDelta(_, y) => (self.scroll_rate as f32 * y) as i32,
+            PixelDelta(d) => d.1,
+        };
+        let offset = (self.offset as i32 + d).max(0) as u32;
+        let action = self.set_offset(offset);
+        if action != TkAction::None {
+            *mgr += action;
+            Response::None
+        } else {
+            Response::Unhandled(Event::Scroll(delta))
+        }
+    }
+}
+
+impl<T: 'static, W: Widget> WidgetChildren for ListView<T, W> {
+    #[inline]
+    fn first_id(&self) -> WidgetId {
+        self.first_id
+    }
+    fn record_first_id(&mut self, id: WidgetId) {
+        self.first_id = id;
+    }
+    #[inline]
+    fn len(&self) -> usize {
+        self.rows.len()
+    }
+    #[inline]
+    fn get(&self, index: usize) -> Option<&dyn WidgetConfig> {
+        self.rows.get(index).map(|row| row.widget.as_widget())
+    }
+    #[inline]
+    fn get_mut(&mut self, index: usize) -> Option<&mut dyn WidgetConfig> {
+        self.rows
+            .get_mut(index)
+            .map(|row| row.widget.as_widget_mut())
+    }
+}
+
+impl<T: 'static, W: Widget> WidgetConfig for ListView<T, W> {
+    fn configure(&mut self, mgr: &mut Manager) {
+        self.unconfigured = false;
+        mgr.register_nav_fallback(self.id());
+    }
+}
+
+impl<T: 'static, W: Widget> Layout for ListView<T, W> {
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let mut rules = SizeRules::EMPTY;
+        for row in &mut self.rows {
+            // Measure hidden rows too (these report collapsed rules)
+            rules = rules.max(row.widget.inner_mut().size_rules(size_handle, axis));
+        }
+        if axis.is_horizontal() {
+            return rules;
+        }
+
+        let (m0, m1) = rules.margins();
+        self.row_height = rules.ideal_size() + m0.max(m1) as u32;
+        self.scroll_rate = 3 * size_handle.line_height(TextClass::Label);
+        if !self.uniform {
+            self.measure(size_handle, axis);
+        }
+
+        let rows = self.view_rows.min(self.data.len());
+        let ideal = if self.is_measured() {
+            self.offsets[rows]
+        } else {
+            rows as u32 * self.row_height
+        };
+        let min = self.row_height.min(ideal);
+        SizeRules::new(min, ideal, rules.margins(), StretchPolicy::HighUtility)
+    }
+
+    fn set_rect(&mut self, rect: Rect, _: AlignHints) {
+        self.core.rect = rect;
+        self.offset = self.offset.min(self.max_offset());
+        for n in 0..self.rows.len() {
+            if let Some(index) = self.rows[n].index {
+                let rect = self.row_rect(index);
+                self.rows[n].widget.set_rect(rect, AlignHints::NONE);
+            }
+        }
+        // Resizing implies a redraw; if new rows were constructed, these are
+        // configured once the list next receives an event (see `send`)
+        let _ = self.update_rows();
+    }
+
+    #[inline]
+    fn translation(&self, _: usize) -> Coord {
+        Coord(0, self.offset as i32)
+    }
+
+    fn find_id(&self, coord: Coord) -> Option<WidgetId> {
+        if !self.rect().contains(coord) {
+            return None;
+        }
+        if self.unconfigured {
+            // Rows may not have valid identifiers
+            return Some(self.id());
+        }
+
+        let coord = coord + Coord(0, self.offset as i32);
+        self.rows
+            .iter()
+            .filter(|row| row.index.is_some())
+            .find_map(|row| row.widget.find_id(coord))
+            .or(Some(self.id()))
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, mgr: &event::ManagerState, disabled: bool) {
+        let disabled = disabled || self.is_disabled();
+        let offset = Coord(0, self.offset as i32);
+        let rect = self.core.rect;
+        draw_handle.clip_region(rect, offset, ClipRegion::Scroll, &mut |draw_handle| {
+            for row in &self.rows {
+                if let Some(index) = row.index {
+                    if self.selected == Some(index) {
+                        let state = InputState {
+                            nav_focus: true,
+                            ..Default::default()
+                        };
+                        draw_handle.menu_entry(self.row_rect(index), state);
+                    }
+                    row.widget.draw(draw_handle, mgr, disabled);
+                }
+            }
+        });
+    }
+}
+
+impl<T: 'static, W: Widget> event::SendEvent for ListView<T, W> {
+    fn send(&mut self, mgr: &mut Manager, id: WidgetId, event: Event) -> Response<Self::Msg> {
+        if self.is_disabled() {
+            return Response::Unhandled(event);
+        }
+        if self.unconfigured {
+            // New rows were constructed without access to a Manager
+            *mgr += TkAction::Reconfigure;
+        }
+
+        // Translate coordinates to those of the content
+        let offset = Coord(0, self.offset as i32);
+        let event = match event {
+            Event::PressStart {
+                source,
+                start_id,
+                coord,
+            } => Event::PressStart {
+                source,
+                start_id,
+                coord: coord + offset,
+            },
+            Event::PressMove {
+                source,
+                cur_id,
+                coord,
+                delta,
+            } => Event::PressMove {
+                source,
+                cur_id,
+                coord: coord + offset,
+                delta,
+            },
+            Event::PressEnd {
+                source,
+                end_id,
+                coord,
+            } => Event::PressEnd {
+                source,
+                end_id,
+                coord: coord + offset,
+            },
+            event => event,
+        };
+
+        let event = if id < self.id() {
+            let n = self.rows.iter().position(|row| id <= row.widget.id());
+            match n.map(|n| self.rows[n].widget.send(mgr, id, event)) {
+                Some(Response::Unhandled(event)) => event,
+                Some(Response::Focus(rect)) => {
+                    let view_end = self.core.rect.pos_end().1 + self.offset as i32;
+                    let mut top = self.offset as i32;
+                    top = top.max(top + rect.pos_end().1 - view_end);
+                    top = top.min(rect.pos.1 - self.core.rect.pos.1);
+                    *mgr += self.set_offset(top.max(0) as u32);
+                    return Response::Focus(rect - Coord(0, self.offset as i32));
+                }
+                Some(r) => return r,
+                None => return Response::None,
+            }
+        } else {
+            event
+        };
+
+        match event {
+            Event::PressStart { coord, .. } => {
+                let y = coord.1 - self.core.rect.pos.1;
+                if y >= 0 && (y as u32) < self.content_height() {
+                    *mgr += self.set_selected(Some(self.index_at(y as u32)));
+                }
+                Response::None
+            }
+            Event::Scroll(delta) => self.scroll(mgr, delta),
+            Event::Control(key) => {
+                let page = self.core.rect.size.1 as f32 / self.scroll_rate.max(1) as f32;
+                let delta = match key {
+                    ControlKey::Up => LineDelta(0.0, -1.0),
+                    ControlKey::Down => LineDelta(0.0, 1.0),
+                    ControlKey::PageUp => LineDelta(0.0, -page),
+                    ControlKey::PageDown => LineDelta(0.0, page),
+                    ControlKey::Home | ControlKey::End => {
+                        *mgr += self.set_offset(match key {
+                            ControlKey::Home => 0,
+                            _ => self.max_offset(),
+                        });
+                        return Response::None;
+                    }
+                    key => return Response::Unhandled(Event::Control(key)),
+                };
+                self.scroll(mgr, delta)
+            }
+            event => Response::Unhandled(event),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::{RecordingDrawHandle, TestManager, TestSizeHandle};
+    use crate::widget::StringLabel;
+    use std::time::{Duration, Instant};
+
+    fn label_list(len: usize) -> ListView<String, StringLabel> {
+        let data = (0..len).map(|i| format!("Item {}", i)).collect();
+        ListView::new(
+            data,
+            |item: &String| StringLabel::new(item.clone()),
+            |label: &mut StringLabel, item: &String| label.set_string(item.clone()),
+        )
+    }
+
+    fn resize(list: &mut ListView<String, StringLabel>, size: Size) {
+        let mut size_handle = TestSizeHandle::new();
+        let _ = list.size_rules(&mut size_handle, AxisInfo::new(false, None));
+        let _ = list.size_rules(&mut size_handle, AxisInfo::new(true, Some(size.0)));
+        list.set_rect(Rect::new(Coord::ZERO, size), AlignHints::NONE);
+    }
+
+    fn shown(list: &ListView<String, StringLabel>) -> Vec<usize> {
+        let mut shown: Vec<usize> = list.rows.iter().filter_map(|row| row.index).collect();
+        shown.sort();
+        shown
+    }
+
+    #[test]
+    fn recycling() {
+        let _ = crate::text::fonts::fonts().load_default();
+
+        let mut list = label_list(1000).with_view_rows(4);
+        assert_eq!(list.num_rows(), 5);
+        // Rows are 20px apart (16px line height plus 4px margin)
+        resize(&mut list, Size(100, 70));
+        assert_eq!(shown(&list), vec![0, 1, 2, 3]);
+
+        let _ = list.set_selected(Some(2));
+        let _ = list.set_offset(30);
+        assert_eq!(shown(&list), vec![1, 2, 3, 4]);
+        assert_eq!(list.num_rows(), 5);
+        let _ = list.set_offset(200);
+        assert_eq!(shown(&list), vec![10, 11, 12, 13]);
+        let row = list.rows.iter().find(|row| row.index == Some(11)).unwrap();
+        assert_eq!(row.widget.inner().get_str(), "Item 11");
+        assert_eq!(row.widget.rect().pos, Coord(0, 220));
+
+        // Selection is tracked by index, not row widget
+        assert_eq!(list.selected(), Some(2));
+        assert_eq!(list.max_offset(), 1000 * 20 - 70);
+    }
+
+    #[test]
+    fn hidden_rows() {
+        let _ = crate::text::fonts::fonts().load_default();
+
+        let mut list = label_list(10).with_view_rows(4);
+        resize(&mut list, Size(100, 70));
+        let _ = list.set_offset(100);
+        assert_eq!(shown(&list), vec![5, 6, 7, 8]);
+        assert_eq!(list.num_rows(), 5);
+        for row in &list.rows {
+            // Unassigned rows are hidden, not disabled
+            assert_eq!(row.widget.is_visible(), row.index.is_some());
+            assert!(!row.widget.is_disabled());
+        }
+        let hidden = list.rows.iter().find(|row| row.index.is_none()).unwrap();
+        assert_eq!(hidden.widget.find_id(hidden.widget.rect().pos), None);
+        assert_eq!(hidden.widget.spatial_range().1, usize::MAX);
+
+        // A disabled row stays disabled when recycled
+        let n = list
+            .rows
+            .iter()
+            .position(|row| row.index == Some(5))
+            .unwrap();
+        let _ = list.rows[n].widget.set_disabled(true);
+        let _ = list.set_offset(0);
+        let _ = list.set_offset(100);
+        assert!(list.rows[n].widget.is_disabled());
+    }
+
+    #[test]
+    fn new_rows_reconfigure() {
+        let _ = crate::text::fonts::fonts().load_default();
+
+        let mut test = TestManager::new(label_list(100).with_view_rows(2));
+        assert_eq!(test.widget.num_rows(), 3);
+        test.set_rect(Rect::new(Coord::ZERO, Size(100, 90)));
+        assert_eq!(test.widget.num_rows(), 5);
+
+        // Drawing requests nothing; new rows are configured once the list
+        // receives an event
+        let mut draw_handle = RecordingDrawHandle::new(test.widget.rect());
+        test.widget.draw(&mut draw_handle, &test.state, false);
+        assert_eq!(test.update(), TkAction::None);
+        let id = test.widget.id();
+        assert_eq!(test.widget.find_id(Coord(10, 85)), Some(id));
+        let (state, tkw, widget) = (&mut test.state, &mut test.tkw, &mut test.widget);
+        state.with(tkw, |mgr| {
+            let _ = widget.send(mgr, id, Event::Control(ControlKey::Home));
+        });
+        assert_eq!(test.update(), TkAction::Reconfigure);
+
+        test.configure();
+        test.set_rect(Rect::new(Coord::ZERO, Size(100, 90)));
+        let last = test.widget.rows.last().unwrap().widget.id();
+        assert_eq!(test.widget.find_id(Coord(10, 85)), Some(last));
+    }
+
+    #[test]
+    fn measured_rows() {
+        let _ = crate::text::fonts::fonts().load_default();
+
+        let data = vec!["a".to_string(), "b\nb".to_string(), "c".to_string()];
+        let mut list = label_list(0).with_uniform_rows(false);
+        assert_eq!(list.set_data(data), TkAction::Reconfigure);
+        assert_eq!(list.num_rows(), 3);
+        resize(&mut list, Size(100, 40));
+        assert_eq!(list.offsets, vec![0, 20, 56, 76]);
+        assert_eq!(list.index_at(25), 1);
+        assert_eq!(shown(&list), vec![0, 1]);
+        let _ = list.set_offset(24);
+        assert_eq!(shown(&list), vec![1, 2]);
+    }
+
+    #[test]
+    fn replace_data() {
+        let _ = crate::text::fonts::fonts().load_default();
+
+        let mut list = label_list(10).with_view_rows(4).with_uniform_rows(false);
+        resize(&mut list, Size(100, 70));
+        assert_eq!(shown(&list), vec![0, 1, 2, 3]);
+
+        // Old rows are hidden; measuring empty data is a no-op
+        assert_eq!(list.set_data(vec![]), TkAction::Resize);
+        assert!(list.rows.iter().all(|row| !row.widget.is_visible()));
+        resize(&mut list, Size(100, 70));
+        assert!(shown(&list).is_empty());
+        assert_eq!(list.max_offset(), 0);
+    }
+
+    #[test]
+    fn scroll_direction() {
+        let _ = crate::text::fonts::fonts().load_default();
+
+        let mut test = TestManager::new(label_list(100).with_view_rows(4));
+        test.set_rect(Rect::new(Coord::ZERO, Size(100, 70)));
+        let id = test.widget.id();
+
+        // Positive deltas scroll towards the end, as does the Down key
+        test.send(id, Event::Scroll(PixelDelta(Coord(0, 30))));
+        assert_eq!(test.widget.offset(), 30);
+        test.send(id, Event::Control(ControlKey::Up));
+        assert_eq!(test.widget.offset(), 0);
+        test.send(id, Event::Control(ControlKey::Down));
+        assert!(test.widget.offset() > 0);
+    }
+
+    // Run with: cargo test --release -- --ignored scroll_100k
+    #[test]
+    #[ignore]
+    fn scroll_100k() {
+        let _ = crate::text::fonts::fonts().load_default();
+
+        let mut list = label_list(100_000);
+        resize(&mut list, Size(200, 256));
+        let start = Instant::now();
+        let mut steps = 0;
+        while list.set_offset(list.offset() + 40) != TkAction::None {
+            steps += 1;
+        }
+        let elapsed = start.elapsed();
+        // 20px rows; the last step is clamped to the maximum offset
+        assert_eq!(steps, (100_000 * 20 - 256 + 39) / 40);
+        assert!(
+            elapsed / steps < Duration::from_micros(100),
+            "scrolling took {:?} per step",
+            elapsed / steps
+        );
+        assert_eq!(list.offset(), list.max_offset());
+        assert_eq!(list.num_rows(), DEFAULT_VIEW_ROWS + 1);
+    }
+}
@@ -7,17 +7,143 @@
 
 use std::ops::{Deref, DerefMut};
 
+mod context_menu;
 mod menu_entry;
 mod menu_frame;
 mod menubar;
 mod submenu;
 
-pub use menu_entry::{MenuEntry, MenuToggle};
+pub use context_menu::ContextMenu;
+pub use menu_entry::{MenuEntry, MenuRadio, MenuToggle};
 pub use menu_frame::MenuFrame;
 pub use menubar::MenuBar;
 pub use submenu::SubMenu;
 
-use kas::{event, prelude::*};
+use kas::access::AccessNode;
+use kas::draw::TextClass;
+use kas::event::{self, ConfigureManager, TypeAhead, VirtualKeyCode};
+use kas::prelude::*;
+use kas::widget::Column;
+
+/// Layout of a menu entry: a frame around a label, with space for a mark
+///
+/// Where any entry of a menu has a mark (see [`Menu::menu_has_mark`]), all
+/// entries reserve space for a mark to the left of the label, thus labels
+/// align. This is decided by the menu (see [`reserve_marks`]).
+#[derive(Clone, Debug, Default)]
+struct EntryLayout {
+    reserve_mark: bool,
+    label_off: Coord,
+    mark: Size,
+}
+
+impl EntryLayout {
+    fn size_rules(
+        &mut self,
+        size_handle: &mut dyn SizeHandle,
+        axis: AxisInfo,
+        label: &mut Text<AccelString>,
+    ) -> SizeRules {
+        let frame = size_handle.menu_frame();
+        self.mark = match self.reserve_mark {
+            true => size_handle.checkbox(),
+            false => Size::ZERO,
+        };
+        self.label_off = Coord(frame.0 as i32 + self.mark.0 as i32, frame.1 as i32);
+
+        let frame_size = frame + frame + Size(self.mark.0, 0);
+        let frame_rules = SizeRules::extract_fixed(axis.is_vertical(), frame_size, Margins::ZERO);
+        let mut text_rules = size_handle.text_bound(label, TextClass::LabelSingle, axis);
+        if axis.is_vertical() {
+            text_rules.max_with(SizeRules::fixed(self.mark.1, (0, 0)));
+        }
+        text_rules.surrounded_by(frame_rules, true)
+    }
+
+    /// The rect of the mark, given the entry's `rect`
+    fn mark_rect(&self, rect: Rect) -> Rect {
+        let x = rect.pos.0 + self.label_off.0 - self.mark.0 as i32;
+        let y = rect.pos.1 + (rect.size.1.saturating_sub(self.mark.1) / 2) as i32;
+        Rect::new(Coord(x, y), self.mark)
+    }
+}
+
+/// Reserve space for marks in all entries of `list` if any entry has a mark
+fn reserve_marks<W: Menu>(list: &mut Column<W>) {
+    let reserve = (0..list.len()).any(|i| list[i].menu_has_mark());
+    for i in 0..list.len() {
+        list[i].menu_reserve_mark(reserve);
+    }
+}
+
+/// Find the entry of `list` matching the mnemonic character `c`
+///
+/// Disabled entries are skipped. Where multiple entries match, the result is
+/// the first match after `nav_focus`, wrapping around; `unique` reports
+/// whether there is only one match.
+fn find_mnemonic<W: Menu>(
+    list: &Column<W>,
+    nav_focus: Option<WidgetId>,
+    c: char,
+) -> Option<(WidgetId, bool)> {
+    let keys = kas::text::find_vkeys(c);
+    let matches: Vec<usize> = (0..list.len())
+        .filter(|i| !list[*i].is_disabled())
+        .filter(|i| list[*i].menu_mnemonics().iter().any(|k| keys.contains(k)))
+        .collect();
+    let index = match nav_focus {
+        Some(id) => matches.iter().position(|i| list[*i].is_ancestor_of(id)),
+        None => None,
+    };
+    let next = match index {
+        Some(j) => matches.get((j + 1) % matches.len()),
+        None => matches.first(),
+    };
+    next.map(|i| (list[*i].id(), matches.len() == 1))
+}
+
+/// Handle character `c` typed within the open menu `list`
+///
+/// The character continues a type-ahead search if the result matches some
+/// entry, otherwise it is matched against mnemonics (see [`find_mnemonic`]),
+/// otherwise it starts a new search. Focus is moved to the matching entry.
+/// Returns the entry to activate (a unique mnemonic match), if any.
+///
+/// The menu `id` receives timer updates for the `type_ahead` search.
+fn menu_char<W: Menu>(
+    mgr: &mut Manager,
+    id: WidgetId,
+    list: &Column<W>,
+    type_ahead: &mut TypeAhead,
+    c: char,
+) -> Option<WidgetId> {
+    if type_ahead.is_active() {
+        let search = format!("{}{}", type_ahead.as_str(), c);
+        if let Some(target) = TypeAhead::find(list.as_widget(), &search) {
+            type_ahead.push(mgr, id, c);
+            mgr.set_nav_focus(target);
+            return None;
+        }
+    }
+
+    match find_mnemonic(list, mgr.nav_focus(), c) {
+        Some((target, true)) => {
+            type_ahead.clear();
+            return Some(target);
+        }
+        Some((target, false)) => {
+            type_ahead.clear();
+            mgr.set_nav_focus(target);
+        }
+        None => {
+            type_ahead.push(mgr, id, c);
+            if let Some(target) = type_ahead.find_child(list.as_widget()) {
+                mgr.set_nav_focus(target);
+            }
+        }
+    }
+    None
+}
 
 /// Trait governing menus, sub-menus and menu-entries
 pub trait Menu: Widget {
@@ -36,6 +162,47 @@ pub trait Menu: Widget {
     ///
     /// `target == None` implies that all menus should close.
     fn menu_path(&mut self, _mgr: &mut Manager, _target: Option<WidgetId>) {}
+
+    /// Whether a message from the entry `id` should close the menu
+    ///
+    /// Here `id` identifies `self` or a descendant. Sub-menus should forward
+    /// the query to the child containing `id`.
+    ///
+    /// By default, this is `true`.
+    fn menu_closes_on_msg(&self, _id: WidgetId) -> bool {
+        true
+    }
+
+    /// Whether this entry draws a mark (e.g. a check mark) beside its label
+    ///
+    /// By default, this is `false`.
+    fn menu_has_mark(&self) -> bool {
+        false
+    }
+
+    /// Set whether to reserve space for a mark beside the label
+    ///
+    /// Menus call this on each entry when configured: space is reserved only
+    /// if some entry of the menu has a mark (see [`Menu::menu_has_mark`]).
+    ///
+    /// By default, this does nothing.
+    fn menu_reserve_mark(&mut self, _reserve: bool) {}
+
+    /// Get the entry's mnemonic keys
+    ///
+    /// While a menu is open, typing a character matching the mnemonic of an
+    /// entry (without Alt) activates that entry. Where several entries share
+    /// a mnemonic, repeated presses cycle navigation focus between them.
+    ///
+    /// Characters matching no mnemonic start a type-ahead search (see
+    /// [`TypeAhead`]); while a search is in progress, characters extending
+    /// it to a matching entry take priority over mnemonics.
+    ///
+    /// By default, this is empty. Entries with an [`AccelString`] label
+    /// should return its [keys](AccelString::keys).
+    fn menu_mnemonics(&self) -> &[VirtualKeyCode] {
+        &[]
+    }
 }
 
 impl<M: 'static> WidgetCore for Box<dyn Menu<Msg = M>> {
@@ -101,6 +268,9 @@ impl<M: 'static> WidgetConfig for Box<dyn Menu<Msg = M>> {
     fn configure(&mut self, mgr: &mut Manager) {
         self.as_mut().configure(mgr);
     }
+    fn configure_recurse<'a, 'b>(&mut self, cmgr: ConfigureManager<'a, 'b>) {
+        self.as_mut().configure_recurse(cmgr);
+    }
 
     fn key_nav(&self) -> bool {
         self.as_ref().key_nav()
@@ -108,6 +278,9 @@ impl<M: 'static> WidgetConfig for Box<dyn Menu<Msg = M>> {
     fn cursor_icon(&self) -> event::CursorIcon {
         self.as_ref().cursor_icon()
     }
+    fn accessible(&self) -> AccessNode {
+        self.as_ref().accessible()
+    }
 }
 
 impl<M: 'static> Layout for Box<dyn Menu<Msg = M>> {
@@ -119,6 +292,14 @@ impl<M: 'static> Layout for Box<dyn Menu<Msg = M>> {
         self.as_mut().set_rect(rect, align);
     }
 
+    fn translation(&self, child_index: usize) -> Coord {
+        self.as_ref().translation(child_index)
+    }
+
+    fn spatial_range(&self) -> (usize, usize) {
+        self.as_ref().spatial_range()
+    }
+
     fn find_id(&self, coord: Coord) -> Option<WidgetId> {
         self.as_ref().find_id(coord)
     }
@@ -155,6 +336,18 @@ impl<M: 'static> Menu for Box<dyn Menu<Msg = M>> {
     fn menu_path(&mut self, mgr: &mut Manager, target: Option<WidgetId>) {
         self.deref_mut().menu_path(mgr, target)
     }
+    fn menu_closes_on_msg(&self, id: WidgetId) -> bool {
+        self.deref().menu_closes_on_msg(id)
+    }
+    fn menu_has_mark(&self) -> bool {
+        self.deref().menu_has_mark()
+    }
+    fn menu_reserve_mark(&mut self, reserve: bool) {
+        self.deref_mut().menu_reserve_mark(reserve)
+    }
+    fn menu_mnemonics(&self) -> &[VirtualKeyCode] {
+        self.deref().menu_mnemonics()
+    }
 }
 
 impl<M: Menu + Sized> Boxed<dyn Menu<Msg = M::Msg>> for M {
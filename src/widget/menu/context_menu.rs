@@ -0,0 +1,217 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Context menu
+
+use super::{menu_char, reserve_marks, Menu, MenuFrame};
+use kas::event::{self, ControlKey, GrabMode, TypeAhead};
+use kas::prelude::*;
+use kas::widget::Column;
+use kas::WindowId;
+
+/// A widget with a context menu
+///
+/// This wraps an `inner` widget. Pressing the secondary (right) mouse button
+/// over `inner` opens a pop-up menu at the cursor position, flipped where
+/// necessary to stay within the window. The menu closes when an entry is
+/// selected (emitting its message), on a click outside the menu or via the
+/// Escape key. Entries may include [`SubMenu`](super::SubMenu)s. While open,
+/// typing an entry's mnemonic or the start of its label selects it (see
+/// [`Menu::menu_mnemonics`]).
+///
+/// Other widgets may follow the same convention by handling
+/// [`Event::PressStart`] where [`PressSource::is_secondary`] and calling
+/// [`Manager::open_context_menu`].
+///
+/// [`PressSource::is_secondary`]: kas::event::PressSource::is_secondary
+#[widget(config=noauto)]
+#[handler(noauto)]
+#[derive(Clone, Debug, Widget)]
+pub struct ContextMenu<W: Widget, M: Menu<Msg = <W as event::Handler>::Msg>> {
+    #[widget_core]
+    core: CoreData,
+    #[widget]
+    pub inner: W,
+    #[widget]
+    pub menu: MenuFrame<Column<M>>,
+    popup_id: Option<WindowId>,
+    type_ahead: TypeAhead,
+}
+
+impl<W: Widget, M: Menu<Msg = <W as event::Handler>::Msg>> ContextMenu<W, M> {
+    /// Construct from an `inner` widget and menu entries
+    #[inline]
+    pub fn new(inner: W, entries: Vec<M>) -> Self {
+        ContextMenu {
+            core: Default::default(),
+            inner,
+            menu: MenuFrame::new(Column::new(entries)),
+            popup_id: None,
+            type_ahead: Default::default(),
+        }
+    }
+
+    /// True if the menu is open
+    #[inline]
+    pub fn is_open(&self) -> bool {
+        self.popup_id.is_some()
+    }
+
+    /// Open the menu at `coord`
+    ///
+    /// Any open menu is closed first.
+    pub fn open_menu(&mut self, mgr: &mut Manager, coord: Coord) {
+        self.close_menu(mgr);
+        let id = mgr.open_context_menu(self.menu.id(), self.id(), coord);
+        self.popup_id = Some(id);
+    }
+
+    /// Close the menu, if open
+    pub fn close_menu(&mut self, mgr: &mut Manager) {
+        if let Some(id) = self.popup_id.take() {
+            mgr.close_window(id);
+        }
+    }
+}
+
+impl<W: Widget, M: Menu<Msg = <W as event::Handler>::Msg>> WidgetConfig for ContextMenu<W, M> {
+    fn configure(&mut self, _: &mut Manager) {
+        reserve_marks(&mut self.menu.inner);
+    }
+}
+
+impl<W: Widget, M: Menu<Msg = <W as event::Handler>::Msg>> Layout for ContextMenu<W, M> {
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        self.inner.size_rules(size_handle, axis)
+    }
+
+    fn set_rect(&mut self, rect: Rect, align: AlignHints) {
+        self.core.rect = rect;
+        self.inner.set_rect(rect, align);
+    }
+
+    fn spatial_range(&self) -> (usize, usize) {
+        // Exclude the pop-up menu
+        (0, 0)
+    }
+
+    fn find_id(&self, coord: Coord) -> Option<WidgetId> {
+        if !self.rect().contains(coord) {
+            return None;
+        }
+        self.inner.find_id(coord).or(Some(self.id()))
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, mgr: &event::ManagerState, disabled: bool) {
+        let disabled = disabled || self.is_disabled();
+        self.inner.draw(draw_handle, mgr, disabled);
+    }
+}
+
+impl<W: Widget, M: Menu<Msg = <W as event::Handler>::Msg>> event::Handler for ContextMenu<W, M> {
+    type Msg = <W as event::Handler>::Msg;
+
+    fn handle(&mut self, mgr: &mut Manager, event: Event) -> Response<Self::Msg> {
+        match event {
+            Event::PressStart {
+                source,
+                start_id,
+                coord,
+            } => {
+                if self.menu.is_ancestor_of(start_id) {
+                    if source.is_primary() {
+                        mgr.request_grab(self.id(), source, coord, GrabMode::Grab, None);
+                        mgr.set_grab_depress(source, Some(start_id));
+                    }
+                } else if source.is_secondary() && self.inner.is_ancestor_of(start_id) {
+                    self.open_menu(mgr, coord);
+                } else {
+                    self.close_menu(mgr);
+                    let event = Event::PressStart {
+                        source,
+                        start_id,
+                        coord,
+                    };
+                    return Response::Unhandled(event);
+                }
+            }
+            Event::PressMove { source, cur_id, .. } if self.popup_id.is_some() => {
+                let target = cur_id.filter(|id| self.menu.is_ancestor_of(*id));
+                mgr.set_grab_depress(source, target);
+            }
+            Event::PressEnd {
+                end_id: Some(id), ..
+            } if self.popup_id.is_some() && self.menu.is_ancestor_of(id) => {
+                return self.send(mgr, id, Event::Activate);
+            }
+            Event::NewPopup(id) => {
+                if self.popup_id.is_some() && !self.is_ancestor_of(id) {
+                    self.close_menu(mgr);
+                }
+            }
+            Event::PopupRemoved(id) => {
+                if self.popup_id == Some(id) {
+                    self.popup_id = None;
+                }
+            }
+            Event::ReceivedCharacter(c) if self.popup_id.is_some() => {
+                let id = self.id();
+                let list = &self.menu.inner;
+                if let Some(id) = menu_char(mgr, id, list, &mut self.type_ahead, c) {
+                    return self.send(mgr, id, Event::Activate);
+                }
+            }
+            Event::TimerUpdate => self.type_ahead.on_timer(mgr, self.id()),
+            event => return Response::Unhandled(event),
+        }
+        Response::None
+    }
+}
+
+impl<W: Widget, M: Menu<Msg = <W as event::Handler>::Msg>> event::SendEvent for ContextMenu<W, M> {
+    fn send(&mut self, mgr: &mut Manager, id: WidgetId, event: Event) -> Response<Self::Msg> {
+        if self.is_disabled() {
+            return Response::Unhandled(event);
+        }
+
+        if id <= self.inner.id() {
+            match self.inner.send(mgr, id, event) {
+                Response::Unhandled(event) => self.handle(mgr, event),
+                r => r,
+            }
+        } else if id <= self.menu.id() {
+            match self.menu.send(mgr, id, event) {
+                Response::Unhandled(Event::Control(key)) if self.popup_id.is_some() => {
+                    let rev = match key {
+                        ControlKey::Up => true,
+                        ControlKey::Down => false,
+                        ControlKey::Home => {
+                            mgr.clear_nav_focus();
+                            false
+                        }
+                        ControlKey::End => {
+                            mgr.clear_nav_focus();
+                            true
+                        }
+                        key => return Response::Unhandled(Event::Control(key)),
+                    };
+                    mgr.next_nav_focus(self, rev);
+                    Response::None
+                }
+                Response::Msg(msg) => {
+                    let inner = &self.menu.inner;
+                    let index = (0..inner.len()).find(|i| inner[*i].is_ancestor_of(id));
+                    if index.map(|i| inner[i].menu_closes_on_msg(id)) != Some(false) {
+                        self.close_menu(mgr);
+                    }
+                    Response::Msg(msg)
+                }
+                r => r,
+            }
+        } else {
+            Manager::handle_generic(self, mgr, event)
+        }
+    }
+}
@@ -5,14 +5,15 @@
 
 //! Menu Entries
 
+use std::convert::TryFrom;
 use std::fmt::{self, Debug};
+use std::rc::Rc;
 
-use super::Menu;
+use super::{EntryLayout, Menu};
+use kas::access::{AccessNode, Role};
 use kas::draw::TextClass;
-use kas::event;
-use kas::layout::{self, RulesSetter, RulesSolver};
+use kas::event::{self, UpdateHandle, VirtualKeyCode};
 use kas::prelude::*;
-use kas::widget::{AccelLabel, CheckBoxBare};
 
 /// A standard menu entry
 #[widget(config=noauto)]
@@ -22,7 +23,7 @@ pub struct MenuEntry<M: Clone + Debug + 'static> {
     #[widget_core]
     core: kas::CoreData,
     label: Text<AccelString>,
-    label_off: Coord,
+    layout: EntryLayout,
     msg: M,
 }
 
@@ -34,15 +35,15 @@ impl<M: Clone + Debug + 'static> WidgetConfig for MenuEntry<M> {
     fn key_nav(&self) -> bool {
         true
     }
+
+    fn accessible(&self) -> AccessNode {
+        AccessNode::new(Role::MenuItem).with_label(self.label.as_str())
+    }
 }
 
 impl<M: Clone + Debug + 'static> Layout for MenuEntry<M> {
     fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
-        let size = size_handle.menu_frame();
-        self.label_off = size.into();
-        let frame_rules = SizeRules::extract_fixed(axis.is_vertical(), size + size, Margins::ZERO);
-        let text_rules = size_handle.text_bound(&mut self.label, TextClass::LabelSingle, axis);
-        text_rules.surrounded_by(frame_rules, true)
+        self.layout.size_rules(size_handle, axis, &mut self.label)
     }
 
     fn set_rect(&mut self, rect: Rect, align: AlignHints) {
@@ -54,9 +55,11 @@ impl<M: Clone + Debug + 'static> Layout for MenuEntry<M> {
     }
 
     fn draw(&self, draw_handle: &mut dyn DrawHandle, mgr: &event::ManagerState, disabled: bool) {
-        draw_handle.menu_entry(self.core.rect, self.input_state(mgr, disabled));
-        let pos = self.core.rect.pos + self.label_off;
-        draw_handle.text_accel(pos, &self.label, mgr.show_accel_labels(), TextClass::Label);
+        let state = self.input_state(mgr, disabled);
+        draw_handle.menu_entry(self.core.rect, state);
+        let pos = self.core.rect.pos + self.layout.label_off;
+        let accel = mgr.show_accel_labels();
+        draw_handle.text_accel(pos, &self.label, accel, TextClass::Label, state.disabled);
     }
 }
 
@@ -70,7 +73,7 @@ impl<M: Clone + Debug + 'static> MenuEntry<M> {
         MenuEntry {
             core: Default::default(),
             label: Text::new_single(label.into()),
-            label_off: Coord::ZERO,
+            layout: Default::default(),
             msg,
         }
     }
@@ -104,28 +107,43 @@ impl<M: Clone + Debug + 'static> event::Handler for MenuEntry<M> {
     }
 }
 
-impl<M: Clone + Debug> Menu for MenuEntry<M> {}
+impl<M: Clone + Debug> Menu for MenuEntry<M> {
+    fn menu_reserve_mark(&mut self, reserve: bool) {
+        self.layout.reserve_mark = reserve;
+    }
+
+    fn menu_mnemonics(&self) -> &[VirtualKeyCode] {
+        self.label.text().keys()
+    }
+}
 
 /// A menu entry which can be toggled
-#[handler(msg = M, generics = <> where M: From<VoidMsg>)]
+///
+/// When active, a check mark is drawn to the left of the label. Activation
+/// (by click or keyboard) toggles the state; if a closure was set via
+/// [`MenuToggle::on_toggle`] its result is emitted as a message.
+///
+/// By default, toggling does not close the menu; see
+/// [`MenuToggle::with_close_menu`].
+#[handler(handle=noauto)]
 #[widget(config=noauto)]
 #[derive(Clone, Default, Widget)]
 pub struct MenuToggle<M: 'static> {
     #[widget_core]
     core: CoreData,
-    layout_data: layout::FixedRowStorage<[SizeRules; 3], [u32; 2]>,
-    #[widget]
-    checkbox: CheckBoxBare<M>,
-    #[widget]
-    label: AccelLabel,
+    label: Text<AccelString>,
+    layout: EntryLayout,
+    state: bool,
+    close_menu: bool,
+    on_toggle: Option<Rc<dyn Fn(bool) -> M>>,
 }
 
 impl<M: 'static> Debug for MenuToggle<M> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "MenuToggle {{ core: {:?}, layout_data: {:?}, checkbox: {:?}, label: {:?} }}",
-            self.core, self.layout_data, self.checkbox, self.label,
+            "MenuToggle {{ core: {:?}, label: {:?}, state: {:?}, close_menu: {:?}, ... }}",
+            self.core, self.label, self.state, self.close_menu,
         )
     }
 }
@@ -143,16 +161,29 @@ impl<M: 'static> MenuToggle<M> {
     {
         MenuToggle {
             core: Default::default(),
-            layout_data: Default::default(),
-            checkbox: CheckBoxBare::new_on(f),
-            label: AccelLabel::new(label.into()),
+            label: Text::new_single(label.into()),
+            layout: Default::default(),
+            state: false,
+            close_menu: false,
+            on_toggle: Some(Rc::new(f)),
         }
     }
 
     /// Set the initial state of the checkbox.
     #[inline]
     pub fn state(mut self, state: bool) -> Self {
-        self.checkbox = self.checkbox.state(state);
+        self.state = state;
+        self
+    }
+
+    /// Set whether toggling closes the menu (chain style)
+    ///
+    /// By default this is `false`: the menu stays open, allowing several
+    /// entries to be toggled. (The menu only closes when a message is
+    /// emitted.)
+    #[inline]
+    pub fn with_close_menu(mut self, close: bool) -> Self {
+        self.close_menu = close;
         self
     }
 }
@@ -163,9 +194,11 @@ impl MenuToggle<VoidMsg> {
     pub fn new<T: Into<AccelString>>(label: T) -> Self {
         MenuToggle {
             core: Default::default(),
-            layout_data: Default::default(),
-            checkbox: CheckBoxBare::new(),
-            label: AccelLabel::new(label.into()),
+            label: Text::new_single(label.into()),
+            layout: Default::default(),
+            state: false,
+            close_menu: false,
+            on_toggle: None,
         }
     }
 
@@ -180,81 +213,470 @@ impl MenuToggle<VoidMsg> {
     {
         MenuToggle {
             core: self.core,
-            layout_data: self.layout_data,
-            checkbox: self.checkbox.on_toggle(f),
             label: self.label,
+            layout: self.layout,
+            state: self.state,
+            close_menu: self.close_menu,
+            on_toggle: Some(Rc::new(f)),
         }
     }
 }
 
 impl<M: 'static> WidgetConfig for MenuToggle<M> {
     fn configure(&mut self, mgr: &mut Manager) {
-        mgr.add_accel_keys(self.checkbox.id(), self.label.keys());
+        mgr.add_accel_keys(self.id(), &self.label.text().keys());
+    }
+
+    fn key_nav(&self) -> bool {
+        true
+    }
+
+    fn accessible(&self) -> AccessNode {
+        AccessNode::new(Role::CheckBox)
+            .with_label(self.label.as_str())
+            .with_checked(self.state)
     }
 }
 
 impl<M: 'static> Layout for MenuToggle<M> {
-    // NOTE: This code is mostly copied from the macro expansion.
-    // Only draw() is significantly different.
-    fn size_rules(
-        &mut self,
-        size_handle: &mut dyn SizeHandle,
-        axis: AxisInfo,
-    ) -> kas::layout::SizeRules {
-        let mut solver = layout::RowSolver::new(axis, (kas::Right, 2usize), &mut self.layout_data);
-        let child = &mut self.checkbox;
-        solver.for_child(&mut self.layout_data, 0usize, |axis| {
-            child.size_rules(size_handle, axis)
-        });
-        let child = &mut self.label;
-        solver.for_child(&mut self.layout_data, 1usize, |axis| {
-            child.size_rules(size_handle, axis)
-        });
-        solver.finish(&mut self.layout_data)
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        self.layout.size_rules(size_handle, axis, &mut self.label)
     }
 
     fn set_rect(&mut self, rect: Rect, align: AlignHints) {
         self.core.rect = rect;
-        let mut setter = layout::RowSetter::<_, [u32; 2], _>::new(
-            rect,
-            (kas::Right, 2usize),
-            align,
-            &mut self.layout_data,
-        );
-        let align = kas::AlignHints::NONE;
-        self.checkbox.set_rect(
-            setter.child_rect(&mut self.layout_data, 0usize),
-            align.clone(),
-        );
-        self.label
-            .set_rect(setter.child_rect(&mut self.layout_data, 1usize), align);
-    }
-
-    fn find_id(&self, coord: Coord) -> Option<WidgetId> {
-        if !self.rect().contains(coord) {
-            return None;
-        }
-        Some(self.checkbox.id())
+        self.label.update_env(|env| {
+            env.set_bounds(rect.size.into());
+            env.set_align(align.unwrap_or(Align::Default, Align::Centre));
+        });
     }
 
     fn draw(&self, draw_handle: &mut dyn DrawHandle, mgr: &event::ManagerState, disabled: bool) {
-        let state = self.checkbox.input_state(mgr, disabled);
+        let state = self.input_state(mgr, disabled);
         draw_handle.menu_entry(self.core.rect, state);
-        self.checkbox.draw(draw_handle, mgr, state.disabled);
-        self.label.draw(draw_handle, mgr, state.disabled);
+        if self.state {
+            draw_handle.menu_mark(self.layout.mark_rect(self.core.rect), false, state);
+        }
+        let pos = self.core.rect.pos + self.layout.label_off;
+        let accel = mgr.show_accel_labels();
+        draw_handle.text_accel(pos, &self.label, accel, TextClass::Label, state.disabled);
     }
 }
 
-impl<M: From<VoidMsg>> Menu for MenuToggle<M> {}
+impl<M: 'static> event::Handler for MenuToggle<M> {
+    type Msg = M;
+
+    #[inline]
+    fn activation_via_press(&self) -> bool {
+        true
+    }
+
+    fn handle(&mut self, mgr: &mut Manager, event: Event) -> Response<M> {
+        match event {
+            Event::Activate => {
+                self.state = !self.state;
+                mgr.redraw(self.id());
+                match self.on_toggle {
+                    Some(ref f) => f(self.state).into(),
+                    None => Response::None,
+                }
+            }
+            event => Response::Unhandled(event),
+        }
+    }
+}
+
+impl<M: 'static> Menu for MenuToggle<M> {
+    fn menu_closes_on_msg(&self, _: WidgetId) -> bool {
+        self.close_menu
+    }
+
+    fn menu_has_mark(&self) -> bool {
+        true
+    }
+
+    fn menu_reserve_mark(&mut self, reserve: bool) {
+        self.layout.reserve_mark = reserve;
+    }
+
+    fn menu_mnemonics(&self) -> &[VirtualKeyCode] {
+        self.label.text().keys()
+    }
+}
 
 impl<M: 'static> HasBool for MenuToggle<M> {
     #[inline]
     fn get_bool(&self) -> bool {
-        self.checkbox.get_bool()
+        self.state
+    }
+
+    #[inline]
+    fn set_bool(&mut self, state: bool) -> TkAction {
+        self.state = state;
+        TkAction::Redraw
+    }
+}
+
+impl<M: 'static> HasStr for MenuToggle<M> {
+    fn get_str(&self) -> &str {
+        self.label.as_str()
+    }
+}
+
+/// A radio entry within a menu
+///
+/// All instances of [`MenuRadio`] constructed over the same [`UpdateHandle`]
+/// form a group, of which at most one is active. The active entry is marked
+/// with a bullet to the left of the label. Activating an inactive entry
+/// selects it (deselecting others in the group); if a closure was set via
+/// [`MenuRadio::on_select`] its result is emitted as a message.
+///
+/// By default, selection does not close the menu; see
+/// [`MenuRadio::with_close_menu`].
+#[handler(handle=noauto)]
+#[widget(config=noauto)]
+#[derive(Clone, Widget)]
+pub struct MenuRadio<M: 'static> {
+    #[widget_core]
+    core: CoreData,
+    label: Text<AccelString>,
+    layout: EntryLayout,
+    state: bool,
+    close_menu: bool,
+    handle: UpdateHandle,
+    on_select: Option<Rc<dyn Fn(WidgetId) -> M>>,
+}
+
+impl<M: 'static> Debug for MenuRadio<M> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "MenuRadio {{ core: {:?}, label: {:?}, state: {:?}, close_menu: {:?}, handle: {:?}, ... }}",
+            self.core, self.label, self.state, self.close_menu, self.handle,
+        )
+    }
+}
+
+impl<M: 'static> MenuRadio<M> {
+    /// Construct a radio menu entry with a given `label` and closure
+    ///
+    /// This is a shortcut for `MenuRadio::new(handle, label).on_select(f)`.
+    /// The closure `f` is called with the entry's identifier when selected,
+    /// and the result of `f` is returned from the event handler.
+    #[inline]
+    pub fn new_on<T: Into<AccelString>, F>(f: F, handle: UpdateHandle, label: T) -> Self
+    where
+        F: Fn(WidgetId) -> M + 'static,
+    {
+        MenuRadio {
+            core: Default::default(),
+            label: Text::new_single(label.into()),
+            layout: Default::default(),
+            state: false,
+            close_menu: false,
+            handle,
+            on_select: Some(Rc::new(f)),
+        }
+    }
+
+    /// Set the initial state of the entry.
+    #[inline]
+    pub fn state(mut self, state: bool) -> Self {
+        self.state = state;
+        self
+    }
+
+    /// Set whether selection closes the menu (chain style)
+    ///
+    /// By default this is `false`. (The menu only closes when a message is
+    /// emitted.)
+    #[inline]
+    pub fn with_close_menu(mut self, close: bool) -> Self {
+        self.close_menu = close;
+        self
+    }
+}
+
+impl MenuRadio<VoidMsg> {
+    /// Construct a radio menu entry with a given `label`
+    ///
+    /// All instances of [`MenuRadio`] constructed over the same `handle` will
+    /// be considered part of a single group.
+    #[inline]
+    pub fn new<T: Into<AccelString>>(handle: UpdateHandle, label: T) -> Self {
+        MenuRadio {
+            core: Default::default(),
+            label: Text::new_single(label.into()),
+            layout: Default::default(),
+            state: false,
+            close_menu: false,
+            handle,
+            on_select: None,
+        }
+    }
+
+    /// Set the event handler to be called on selection.
+    ///
+    /// The closure `f` is called with the entry's identifier when selected,
+    /// and the result of `f` is returned from the event handler.
+    #[inline]
+    pub fn on_select<M, F>(self, f: F) -> MenuRadio<M>
+    where
+        F: Fn(WidgetId) -> M + 'static,
+    {
+        MenuRadio {
+            core: self.core,
+            label: self.label,
+            layout: self.layout,
+            state: self.state,
+            close_menu: self.close_menu,
+            handle: self.handle,
+            on_select: Some(Rc::new(f)),
+        }
+    }
+}
+
+impl<M: 'static> WidgetConfig for MenuRadio<M> {
+    fn configure(&mut self, mgr: &mut Manager) {
+        mgr.update_on_handle(self.handle, self.id());
+        mgr.add_accel_keys(self.id(), &self.label.text().keys());
+    }
+
+    fn key_nav(&self) -> bool {
+        true
+    }
+
+    fn accessible(&self) -> AccessNode {
+        AccessNode::new(Role::RadioButton)
+            .with_label(self.label.as_str())
+            .with_checked(self.state)
+    }
+}
+
+impl<M: 'static> Layout for MenuRadio<M> {
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        self.layout.size_rules(size_handle, axis, &mut self.label)
+    }
+
+    fn set_rect(&mut self, rect: Rect, align: AlignHints) {
+        self.core.rect = rect;
+        self.label.update_env(|env| {
+            env.set_bounds(rect.size.into());
+            env.set_align(align.unwrap_or(Align::Default, Align::Centre));
+        });
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, mgr: &event::ManagerState, disabled: bool) {
+        let state = self.input_state(mgr, disabled);
+        draw_handle.menu_entry(self.core.rect, state);
+        if self.state {
+            draw_handle.menu_mark(self.layout.mark_rect(self.core.rect), true, state);
+        }
+        let pos = self.core.rect.pos + self.layout.label_off;
+        let accel = mgr.show_accel_labels();
+        draw_handle.text_accel(pos, &self.label, accel, TextClass::Label, state.disabled);
+    }
+}
+
+impl<M: 'static> event::Handler for MenuRadio<M> {
+    type Msg = M;
+
+    #[inline]
+    fn activation_via_press(&self) -> bool {
+        true
+    }
+
+    fn handle(&mut self, mgr: &mut Manager, event: Event) -> Response<M> {
+        match event {
+            Event::Activate => {
+                if self.state {
+                    return Response::None;
+                }
+                self.state = true;
+                mgr.redraw(self.id());
+                mgr.trigger_update(self.handle, self.id().into());
+                match self.on_select {
+                    Some(ref f) => f(self.id()).into(),
+                    None => Response::None,
+                }
+            }
+            Event::HandleUpdate { payload, .. } => {
+                let id = WidgetId::try_from(payload).unwrap();
+                if id != self.id() && self.state {
+                    self.state = false;
+                    mgr.redraw(self.id());
+                }
+                Response::None
+            }
+            event => Response::Unhandled(event),
+        }
+    }
+}
+
+impl<M: 'static> Menu for MenuRadio<M> {
+    fn menu_closes_on_msg(&self, _: WidgetId) -> bool {
+        self.close_menu
+    }
+
+    fn menu_has_mark(&self) -> bool {
+        true
+    }
+
+    fn menu_reserve_mark(&mut self, reserve: bool) {
+        self.layout.reserve_mark = reserve;
+    }
+
+    fn menu_mnemonics(&self) -> &[VirtualKeyCode] {
+        self.label.text().keys()
+    }
+}
+
+impl<M: 'static> HasBool for MenuRadio<M> {
+    #[inline]
+    fn get_bool(&self) -> bool {
+        self.state
     }
 
     #[inline]
     fn set_bool(&mut self, state: bool) -> TkAction {
-        self.checkbox.set_bool(state)
+        self.state = state;
+        TkAction::Redraw
+    }
+}
+
+impl<M: 'static> HasStr for MenuRadio<M> {
+    fn get_str(&self) -> &str {
+        self.label.as_str()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::event::ManagerState;
+    use crate::test_util::{DrawCommand, RecordingDrawHandle, TestManager, TestSizeHandle};
+    use crate::widget::SubMenu;
+
+    type Entry = Box<dyn Menu<Msg = u32>>;
+
+    #[layout(single)]
+    #[handler(msg = VoidMsg)]
+    #[derive(Debug, Widget)]
+    struct Collect {
+        #[widget_core]
+        core: CoreData,
+        #[widget(handler = collect)]
+        menu: SubMenu<kas::Right, Entry>,
+        msgs: Vec<u32>,
+    }
+
+    impl Collect {
+        fn new(entries: Vec<Entry>) -> TestManager<Self> {
+            TestManager::new(Collect {
+                core: Default::default(),
+                menu: SubMenu::right("Menu", entries),
+                msgs: vec![],
+            })
+        }
+
+        fn collect(&mut self, _: &mut Manager, msg: u32) -> Response<VoidMsg> {
+            self.msgs.push(msg);
+            Response::None
+        }
+    }
+
+    fn entry_width(test: &mut TestManager<Collect>, index: usize) -> u32 {
+        let entry = &mut test.widget.menu.list.inner[index];
+        let axis = AxisInfo::new(false, None);
+        entry
+            .size_rules(&mut TestSizeHandle::new(), axis)
+            .min_size()
+    }
+
+    #[test]
+    fn mark_space() {
+        let plain = || -> Entry { Box::new(MenuEntry::new("Plain", 0u32)) };
+
+        // Space for marks is reserved only when some entry has a mark
+        let mut test = Collect::new(vec![plain(), plain()]);
+        let width = entry_width(&mut test, 0);
+        assert_eq!(entry_width(&mut test, 1), width);
+
+        let toggle: Entry = Box::new(MenuToggle::new_on(|state| state as u32, "Plain"));
+        let mut test = Collect::new(vec![plain(), toggle]);
+        let checkbox = TestSizeHandle::new().checkbox;
+        assert_eq!(entry_width(&mut test, 0), width + checkbox);
+        assert_eq!(entry_width(&mut test, 1), width + checkbox);
+
+        let radio: Entry = Box::new(MenuRadio::new_on(|_| 2u32, UpdateHandle::new(), "Plain"));
+        let mut test = Collect::new(vec![radio, plain()]);
+        assert_eq!(entry_width(&mut test, 1), width + checkbox);
+    }
+
+    #[test]
+    fn toggle_and_radio() {
+        let handle = UpdateHandle::new();
+        let mut test = Collect::new(vec![
+            Box::new(MenuToggle::new_on(|state| state as u32, "Toggle")),
+            Box::new(MenuRadio::new_on(|_| 10u32, handle, "A")),
+            Box::new(MenuRadio::new_on(|_| 11u32, handle, "B")),
+            Box::new(MenuEntry::new("Close", 20u32)),
+        ]);
+        let inner = &test.widget.menu.list.inner;
+        let ids: Vec<WidgetId> = (0..4).map(|i| inner[i].id()).collect();
+        let get_bool = |test: &TestManager<Collect>, i: usize| {
+            let w = test.widget.menu.list.inner[i].as_any();
+            match i {
+                0 => w.downcast_ref::<MenuToggle<u32>>().unwrap().get_bool(),
+                _ => w.downcast_ref::<MenuRadio<u32>>().unwrap().get_bool(),
+            }
+        };
+        let activate = |test: &mut TestManager<Collect>, id| {
+            test.send(id, Event::Activate);
+            // Deliver any update triggered by a radio entry
+            for handle in std::mem::take(&mut test.tkw.updates) {
+                test.with(|mgr, w| mgr.update_handle(w, handle, id.into()));
+            }
+        };
+
+        let menu_id = test.widget.menu.id();
+        activate(&mut test, menu_id);
+        assert!(test.widget.menu.menu_is_open());
+
+        // Toggles and radio entries do not close the menu by default
+        activate(&mut test, ids[0]);
+        assert!(get_bool(&test, 0));
+        activate(&mut test, ids[0]);
+        assert!(!get_bool(&test, 0));
+        activate(&mut test, ids[1]);
+        assert!(get_bool(&test, 1) && !get_bool(&test, 2));
+        activate(&mut test, ids[2]);
+        assert!(!get_bool(&test, 1) && get_bool(&test, 2));
+        assert!(test.widget.menu.menu_is_open());
+        assert_eq!(test.widget.msgs, vec![1, 0, 10, 11]);
+
+        // Other entries close the menu
+        activate(&mut test, ids[3]);
+        assert!(!test.widget.menu.menu_is_open());
+        assert_eq!(test.widget.msgs, vec![1, 0, 10, 11, 20]);
+    }
+
+    #[test]
+    fn disabled_text() {
+        let mut entry = MenuEntry::new("Entry", 0u32);
+        let rect = Rect::new(Coord::ZERO, Size(80, 20));
+        entry.set_rect(rect, AlignHints::NONE);
+
+        let mgr = ManagerState::new();
+        let text_disabled = |disabled: bool| {
+            let mut draw_handle = RecordingDrawHandle::new(rect);
+            entry.draw(&mut draw_handle, &mgr, disabled);
+            draw_handle.take_commands().iter().find_map(|c| match c {
+                DrawCommand::TextAccel(.., disabled) => Some(*disabled),
+                _ => None,
+            })
+        };
+        assert_eq!(text_disabled(false), Some(false));
+        assert_eq!(text_disabled(true), Some(true));
     }
 }
@@ -5,10 +5,12 @@
 
 //! Menus
 
+use kas::access::{AccessNode, Role};
 use kas::{event, prelude::*};
 
 /// A frame around content, plus background
 #[handler(msg = <W as Handler>::Msg)]
+#[widget(config=noauto)]
 #[derive(Clone, Debug, Default, Widget)]
 pub struct MenuFrame<W: Widget> {
     #[widget_core]
@@ -32,6 +34,12 @@ impl<W: Widget> MenuFrame<W> {
     }
 }
 
+impl<W: Widget> WidgetConfig for MenuFrame<W> {
+    fn accessible(&self) -> AccessNode {
+        AccessNode::new(Role::Menu)
+    }
+}
+
 impl<W: Widget> Layout for MenuFrame<W> {
     fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
         let size = size_handle.frame();
@@ -8,6 +8,8 @@
 use std::time::Duration;
 
 use super::{Menu, SubMenu};
+use kas::access::{AccessNode, Role};
+use kas::draw::ClipRegion;
 use kas::event::{self, ControlKey, GrabMode};
 use kas::prelude::*;
 use kas::widget::List;
@@ -17,8 +19,19 @@ const DELAY: Duration = Duration::from_millis(200);
 /// A menu-bar
 ///
 /// This widget houses a sequence of menu buttons, allowing input actions across
-/// menus.
+/// menus. Once a menu is open (by click or keyboard), hovering over another
+/// menu button opens that menu instead. The arrow keys move between menus
+/// along the bar; the perpendicular arrow key (e.g. Down for a horizontal
+/// bar) opens the focussed menu.
+///
+/// Accelerator keys of the menu buttons (e.g. <kbd>Alt+F</kbd> for "&File")
+/// are registered in the layer enclosing the bar, thus work from anywhere in
+/// the window, including while another menu is open.
+///
+/// The bar does not wrap: when the available space is too small, the bar is
+/// truncated.
 #[handler(noauto)]
+#[widget(config=noauto)]
 #[derive(Clone, Debug, Widget)]
 pub struct MenuBar<D: Directional, W: Menu> {
     #[widget_core]
@@ -28,6 +41,7 @@ pub struct MenuBar<D: Directional, W: Menu> {
     // Open mode. Used to close with click on root only when previously open.
     opening: bool,
     delayed_open: Option<WidgetId>,
+    bar_min: u32,
 }
 
 impl<D: Directional + Default, W: Menu> MenuBar<D, W> {
@@ -45,20 +59,51 @@ impl<D: Directional, W: Menu> MenuBar<D, W> {
             bar: List::new_with_direction(direction, menus),
             opening: false,
             delayed_open: None,
+            bar_min: 0,
         }
     }
 }
 
+impl<D: Directional, W: Menu> MenuBar<D, W> {
+    // Index of the menu button with this id, if any
+    fn bar_index(&self, id: WidgetId) -> Option<usize> {
+        (0..self.bar.len()).find(|i| self.bar[*i].id() == id)
+    }
+
+    /// True if any menu is open
+    pub fn is_open(&self) -> bool {
+        (0..self.bar.len()).any(|i| self.bar[i].menu_is_open())
+    }
+}
+
 // NOTE: we could use layout(single) except for alignment
+impl<D: Directional, W: Menu> WidgetConfig for MenuBar<D, W> {
+    fn accessible(&self) -> AccessNode {
+        AccessNode::new(Role::Menu)
+    }
+}
+
 impl<D: Directional, W: Menu> Layout for MenuBar<D, W> {
     fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
-        self.bar.size_rules(size_handle, axis)
+        let mut rules = self.bar.size_rules(size_handle, axis);
+        if axis.is_horizontal() == self.bar.direction().is_horizontal() {
+            // The bar does not wrap, but may be truncated
+            self.bar_min = rules.min_size();
+            rules.reduce_min_to(0);
+        }
+        rules
     }
 
     fn set_rect(&mut self, rect: Rect, _: AlignHints) {
         self.core_data_mut().rect = rect;
+        let mut bar_rect = rect;
+        if self.bar.direction().is_horizontal() {
+            bar_rect.size.0 = bar_rect.size.0.max(self.bar_min);
+        } else {
+            bar_rect.size.1 = bar_rect.size.1.max(self.bar_min);
+        }
         let align = AlignHints::new(Some(Align::Default), Some(Align::Default));
-        self.bar.set_rect(rect, align);
+        self.bar.set_rect(bar_rect, align);
     }
 
     #[inline]
@@ -73,7 +118,15 @@ impl<D: Directional, W: Menu> Layout for MenuBar<D, W> {
     }
 
     fn draw(&self, draw_handle: &mut dyn DrawHandle, mgr: &event::ManagerState, disabled: bool) {
-        self.bar.draw(draw_handle, mgr, disabled);
+        if self.bar.rect().size == self.core.rect.size {
+            self.bar.draw(draw_handle, mgr, disabled);
+        } else {
+            // The bar is truncated
+            let rect = self.core.rect;
+            draw_handle.clip_region(rect, Coord::ZERO, ClipRegion::Scroll, &mut |handle| {
+                self.bar.draw(handle, mgr, disabled)
+            });
+        }
     }
 }
 impl<D: Directional, W: Menu<Msg = M>, M> event::Handler for MenuBar<D, W> {
@@ -130,8 +183,17 @@ impl<D: Directional, W: Menu<Msg = M>, M> event::Handler for MenuBar<D, W> {
                         let id = cur_id.unwrap();
                         mgr.set_grab_depress(source, Some(id));
                         mgr.set_nav_focus(id);
-                        self.delayed_open = Some(id);
-                        mgr.update_on_timer(DELAY, self.id());
+                        let index = self.bar_index(id).filter(|_| self.is_open());
+                        if let Some(i) = index {
+                            // Tracking: switch menus immediately
+                            self.delayed_open = None;
+                            if !self.bar[i].menu_is_open() {
+                                self.menu_path(mgr, Some(id));
+                            }
+                        } else {
+                            self.delayed_open = Some(id);
+                            mgr.update_on_timer(DELAY, self.id());
+                        }
                     }
                 } else {
                     mgr.set_grab_depress(source, None);
@@ -175,17 +237,25 @@ impl<D: Directional, W: Menu<Msg = M>, M> event::Handler for MenuBar<D, W> {
                         key => return Response::Unhandled(Event::Control(key)),
                     };
 
-                for i in 0..self.bar.len() {
-                    if self.bar[i].menu_is_open() {
-                        let index = if reverse { i.wrapping_sub(1) } else { i + 1 };
-                        if index < self.bar.len() {
-                            self.delayed_open = None;
-                            self.bar[i].menu_path(mgr, None);
-                            let w = &mut self.bar[index];
-                            w.menu_path(mgr, Some(w.id()));
-                        }
-                        break;
+                let next = |i: usize| match reverse {
+                    false => i + 1,
+                    true => i.wrapping_sub(1),
+                };
+                if let Some(i) = (0..self.bar.len()).find(|i| self.bar[*i].menu_is_open()) {
+                    let index = next(i);
+                    if index < self.bar.len() {
+                        self.delayed_open = None;
+                        self.bar[i].menu_path(mgr, None);
+                        let w = &mut self.bar[index];
+                        w.menu_path(mgr, Some(w.id()));
+                    }
+                } else if let Some(i) = mgr.nav_focus().and_then(|id| self.bar_index(id)) {
+                    // No menu is open: move focus along the bar
+                    if let Some(w) = self.bar.get(next(i)) {
+                        mgr.set_nav_focus(w.id());
                     }
+                } else {
+                    return Response::Unhandled(Event::Control(key));
                 }
             }
             e => return Response::Unhandled(e),
@@ -5,26 +5,42 @@
 
 //! Sub-menu
 
-use super::{Menu, MenuFrame};
+use super::{menu_char, reserve_marks, EntryLayout, Menu, MenuFrame};
+use kas::access::{AccessNode, Role};
 use kas::draw::TextClass;
-use kas::event::{self, ConfigureManager, ControlKey};
+use kas::event::{self, ConfigureManager, ControlKey, GrabMode, TypeAhead, VirtualKeyCode};
 use kas::prelude::*;
 use kas::widget::Column;
 use kas::WindowId;
+use std::fmt::{self, Debug};
+use std::rc::Rc;
 
 /// A sub-menu
 #[widget(config=noauto)]
 #[handler(noauto)]
-#[derive(Clone, Debug, Widget)]
+#[derive(Clone, Widget)]
 pub struct SubMenu<D: Directional, W: Menu> {
     #[widget_core]
     core: CoreData,
     direction: D,
     label: Text<AccelString>,
-    label_off: Coord,
+    layout: EntryLayout,
     #[widget]
     pub list: MenuFrame<Column<W>>,
     popup_id: Option<WindowId>,
+    type_ahead: TypeAhead,
+    selected: bool,
+    on_dismiss: Option<Rc<dyn Fn() -> <W as event::Handler>::Msg>>,
+}
+
+impl<D: Directional, W: Menu> Debug for SubMenu<D, W> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "SubMenu {{ core: {:?}, direction: {:?}, label: {:?}, list: {:?}, popup_id: {:?}, ... }}",
+            self.core, self.direction, self.label, self.list, self.popup_id,
+        )
+    }
 }
 
 impl<D: Directional + Default, W: Menu> SubMenu<D, W> {
@@ -62,9 +78,12 @@ impl<D: Directional, W: Menu> SubMenu<D, W> {
             core: Default::default(),
             direction,
             label: Text::new_single(label.into()),
-            label_off: Coord::ZERO,
+            layout: Default::default(),
             list: MenuFrame::new(Column::new(list)),
             popup_id: None,
+            type_ahead: Default::default(),
+            selected: false,
+            on_dismiss: None,
         }
     }
 
@@ -74,8 +93,11 @@ impl<D: Directional, W: Menu> SubMenu<D, W> {
                 id: self.list.id(),
                 parent: self.id(),
                 direction: self.direction.as_direction(),
+                modal: false,
+                anchor: None,
             });
             self.popup_id = Some(id);
+            self.selected = false;
             mgr.next_nav_focus(self, false);
         }
     }
@@ -86,10 +108,25 @@ impl<D: Directional, W: Menu> SubMenu<D, W> {
     }
 }
 
+impl<D: Directional, M: Clone + 'static, W: Menu<Msg = M>> SubMenu<D, W> {
+    /// Set a message to emit when the menu is dismissed (chain style)
+    ///
+    /// When the pop-up menu is closed without an entry being selected (e.g.
+    /// via the Escape key or a click outside the menu), this message is
+    /// emitted by the event handler. By default no message is emitted.
+    #[inline]
+    pub fn with_dismiss_msg(mut self, msg: M) -> Self {
+        self.on_dismiss = Some(Rc::new(move || msg.clone()));
+        self
+    }
+}
+
 impl<D: Directional, W: Menu> WidgetConfig for SubMenu<D, W> {
     fn configure_recurse<'a, 'b>(&mut self, mut cmgr: ConfigureManager<'a, 'b>) {
-        cmgr.mgr().push_accel_layer(true);
+        // Entries are matched without Alt via mnemonics (see Menu::menu_mnemonics)
+        cmgr.mgr().push_accel_layer(false);
         self.list.configure_recurse(cmgr.child());
+        reserve_marks(&mut self.list.inner);
         self.core_data_mut().id = cmgr.next_id(self.id());
         let mgr = cmgr.mgr();
         mgr.pop_accel_layer(self.id());
@@ -99,15 +136,15 @@ impl<D: Directional, W: Menu> WidgetConfig for SubMenu<D, W> {
     fn key_nav(&self) -> bool {
         true
     }
+
+    fn accessible(&self) -> AccessNode {
+        AccessNode::new(Role::MenuItem).with_label(self.label.as_str())
+    }
 }
 
 impl<D: Directional, W: Menu> kas::Layout for SubMenu<D, W> {
     fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
-        let size = size_handle.menu_frame();
-        self.label_off = size.into();
-        let frame_rules = SizeRules::extract_fixed(axis.is_vertical(), size + size, Margins::ZERO);
-        let text_rules = size_handle.text_bound(&mut self.label, TextClass::LabelSingle, axis);
-        text_rules.surrounded_by(frame_rules, true)
+        self.layout.size_rules(size_handle, axis, &mut self.label)
     }
 
     fn set_rect(&mut self, rect: Rect, align: AlignHints) {
@@ -127,8 +164,9 @@ impl<D: Directional, W: Menu> kas::Layout for SubMenu<D, W> {
         let mut state = self.input_state(mgr, disabled);
         state.depress = state.depress || self.popup_id.is_some();
         draw_handle.menu_entry(self.core.rect, state);
-        let pos = self.core.rect.pos + self.label_off;
-        draw_handle.text_accel(pos, &self.label, mgr.show_accel_labels(), TextClass::Label);
+        let pos = self.core.rect.pos + self.layout.label_off;
+        let accel = mgr.show_accel_labels();
+        draw_handle.text_accel(pos, &self.label, accel, TextClass::Label, state.disabled);
     }
 }
 
@@ -142,6 +180,28 @@ impl<D: Directional, M, W: Menu<Msg = M>> event::Handler for SubMenu<D, W> {
                     self.open_menu(mgr);
                 }
             }
+            Event::PressStart {
+                source,
+                start_id,
+                coord,
+            } if self.popup_id.is_some() && self.list.is_ancestor_of(start_id) => {
+                // Presses within our pop-up do not close it
+                if source.is_primary() {
+                    mgr.request_grab(self.id(), source, coord, GrabMode::Grab, None);
+                    mgr.set_grab_depress(source, Some(start_id));
+                }
+            }
+            Event::PressMove { source, cur_id, .. } if self.popup_id.is_some() => {
+                let target = cur_id.filter(|id| self.list.is_ancestor_of(*id));
+                mgr.set_grab_depress(source, target);
+                // Parents (e.g. MenuBar) may also track the cursor
+                return Response::Unhandled(event);
+            }
+            Event::PressEnd {
+                end_id: Some(id), ..
+            } if self.popup_id.is_some() && self.list.is_ancestor_of(id) => {
+                return self.send(mgr, id, Event::Activate);
+            }
             Event::NewPopup(id) => {
                 if self.popup_id.is_some() && !self.is_ancestor_of(id) {
                     self.close_menu(mgr);
@@ -150,7 +210,20 @@ impl<D: Directional, M, W: Menu<Msg = M>> event::Handler for SubMenu<D, W> {
             Event::PopupRemoved(id) => {
                 debug_assert_eq!(Some(id), self.popup_id);
                 self.popup_id = None;
+                if !self.selected {
+                    if let Some(ref f) = self.on_dismiss {
+                        return Response::Msg(f());
+                    }
+                }
+            }
+            Event::ReceivedCharacter(c) if self.popup_id.is_some() => {
+                let id = self.id();
+                let list = &self.list.inner;
+                if let Some(id) = menu_char(mgr, id, list, &mut self.type_ahead, c) {
+                    return self.send(mgr, id, Event::Activate);
+                }
             }
+            Event::TimerUpdate => self.type_ahead.on_timer(mgr, self.id()),
             Event::Control(key) => match (self.direction.as_direction(), key) {
                 (Direction::Left, ControlKey::Left) => self.open_menu(mgr),
                 (Direction::Right, ControlKey::Right) => self.open_menu(mgr),
@@ -209,6 +282,7 @@ impl<D: Directional, W: Menu> event::SendEvent for SubMenu<D, W> {
                                 ControlKey::Right if dir == Left => self.close_menu(mgr),
                                 ControlKey::Up if dir == Down => self.close_menu(mgr),
                                 ControlKey::Down if dir == Up => self.close_menu(mgr),
+                                ControlKey::Escape => self.close_menu(mgr),
                                 key => return Response::Unhandled(Event::Control(key)),
                             }
                         }
@@ -217,7 +291,10 @@ impl<D: Directional, W: Menu> event::SendEvent for SubMenu<D, W> {
                     ev => Response::Unhandled(ev),
                 },
                 Response::Msg(msg) => {
-                    self.close_menu(mgr);
+                    if self.menu_closes_on_msg(id) {
+                        self.selected = true;
+                        self.close_menu(mgr);
+                    }
                     Response::Msg(msg)
                 }
                 r => r,
@@ -233,6 +310,22 @@ impl<D: Directional, W: Menu> Menu for SubMenu<D, W> {
         self.popup_id.is_some()
     }
 
+    fn menu_mnemonics(&self) -> &[VirtualKeyCode] {
+        self.label.text().keys()
+    }
+
+    fn menu_reserve_mark(&mut self, reserve: bool) {
+        self.layout.reserve_mark = reserve;
+    }
+
+    fn menu_closes_on_msg(&self, id: WidgetId) -> bool {
+        let inner = &self.list.inner;
+        match (0..inner.len()).find(|i| inner[*i].is_ancestor_of(id)) {
+            Some(i) => inner[i].menu_closes_on_msg(id),
+            None => true,
+        }
+    }
+
     fn menu_path(&mut self, mgr: &mut Manager, target: Option<WidgetId>) {
         match target {
             Some(id) if self.is_ancestor_of(id) => {
@@ -281,3 +374,74 @@ impl<D: Directional, W: Menu> SetAccel for SubMenu<D, W> {
         kas::text::util::set_text_and_prepare(&mut self.label, string)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::TestManager;
+    use crate::widget::MenuEntry;
+
+    #[layout(single)]
+    #[handler(msg = VoidMsg)]
+    #[derive(Debug, Widget)]
+    struct Collect {
+        #[widget_core]
+        core: CoreData,
+        #[widget(handler = collect)]
+        menu: SubMenu<kas::Right, MenuEntry<u32>>,
+        msgs: Vec<u32>,
+    }
+
+    impl Collect {
+        fn new() -> TestManager<Self> {
+            let entries = vec![MenuEntry::new("A", 1), MenuEntry::new("B", 2)];
+            TestManager::new(Collect {
+                core: Default::default(),
+                menu: SubMenu::right("Menu", entries).with_dismiss_msg(0),
+                msgs: vec![],
+            })
+        }
+
+        fn collect(&mut self, _: &mut Manager, msg: u32) -> Response<VoidMsg> {
+            self.msgs.push(msg);
+            Response::None
+        }
+    }
+
+    #[test]
+    fn dismiss_msg() {
+        let mut test = Collect::new();
+        let id = test.widget.menu.id();
+
+        // Closing via Escape emits the dismiss message
+        test.send(id, Event::Activate);
+        assert!(test.widget.menu.menu_is_open());
+        test.key(VirtualKeyCode::Escape);
+        assert!(!test.widget.menu.menu_is_open());
+        assert_eq!(test.widget.msgs, vec![0]);
+
+        // Selecting an entry emits only the entry's message
+        test.send(id, Event::Activate);
+        let entry = test.widget.menu.list.inner[1].id();
+        test.send(entry, Event::Activate);
+        assert!(!test.widget.menu.menu_is_open());
+        assert_eq!(test.widget.msgs, vec![0, 2]);
+    }
+
+    #[cfg(feature = "winit")]
+    #[test]
+    fn dismiss_msg_on_click() {
+        let mut test = Collect::new();
+        test.set_rect(Rect::new(Coord::ZERO, Size(100, 20)));
+        let id = test.widget.menu.id();
+        test.send(id, Event::Activate);
+        assert!(test.widget.menu.menu_is_open());
+
+        // A click outside the pop-up (here on the menu's label) closes it
+        test.mouse_move(Coord(10, 10));
+        test.mouse_input(event::MouseButton::Left, true);
+        test.mouse_input(event::MouseButton::Left, false);
+        assert!(!test.widget.menu.menu_is_open());
+        assert_eq!(test.widget.msgs, vec![0]);
+    }
+}
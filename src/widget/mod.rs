@@ -16,34 +16,48 @@
 //! ## Container widgets
 //!
 //! -   [`Frame`]: a simple frame around a single child
+//! -   [`TitledFrame`]: a frame with a title (group box)
+//! -   [`Expander`]: a section which may be collapsed via its header
+//! -   [`Badge`]: a count bubble drawn over a single child
 //! -   [`ScrollRegion`]: may be larger on the inside than the outside
 //! -   [`Stack`]: a stack of widgets in the same rect (TODO: `TabbedStack`)
+//! -   [`LazyStack`]: a stack of pages constructed when first shown
+//! -   [`Overlay`]: widgets drawn over each other in the same rect
 //! -   [`List`]: a dynamic row / column of children
+//! -   [`Grid`]: a dynamic grid of children, optionally with stripes and lines
 //! -   [`Splitter`]: similar to [`List`] but with resizing handles
+//! -   [`SideBar`]: a side pane which may be resized or collapsed
 //! -   [`Window`] is usually the root widget and has special handling for
 //!     pop-ups and callbacks
 //!
 //! ## Menus
 //!
 //! -   [`ComboBox`]: a simple pop-up selector
+//! -   [`ColourButton`]: a colour swatch with a pop-up palette
 //! -   [`MenuBar`], [`SubMenu`]: menu parent widgets
-//! -   [`MenuEntry`], [`MenuToggle`], [`Separator`]: menu entries
+//! -   [`ContextMenu`]: a widget with a pop-up menu on right-click
+//! -   [`MenuEntry`], [`MenuToggle`], [`MenuRadio`], [`Separator`]: menu entries
 //! -   [`MenuFrame`]: edges of a pop-up menu
 //!
 //! ## Controls
 //!
 //! -   [`TextButton`]: a simple button
+//! -   [`IconButton`]: a button showing an icon
+//! -   [`ToggleButton`]: a button which latches while on
 //! -   [`CheckBox`]: a checkable box
 //! -   [`RadioBox`]: a checkable box bound to a group
 //! -   [`EditBox`]: a text-editing box
 //! -   [`ScrollBar`]: a scrollbar
 //! -   [`Slider`]: a slider
+//! -   [`Spinner`]: a numeric entry field with increment / decrement buttons
 //!
 //! ## Static widgets
 //!
 //! -   [`Filler`]: an empty widget, sometimes used to fill space
 //! -   [`Separator`]: a visible bar to separate things
 //! -   [`Label`]: a simple text label
+//! -   [`LinkLabel`]: a text label with clickable links
+//! -   [`Image`]: an image (bitmap)
 //!
 //! ## Components
 //!
@@ -52,42 +66,156 @@
 //! -   [`RadioBoxBare`]: `RadioBox` without its label
 //! -   [`DragHandle`]: a handle (e.g. for a slider, splitter or scrollbar)
 
+// Implement WidgetCore, WidgetChildren and WidgetConfig for a transparent
+// adapter (see e.g. MsgInto), forwarding to the field `inner`. Optionally,
+// either the `key_nav` body or the `as_any` and `as_any_mut` bodies may be
+// replaced.
+macro_rules! impl_adapter {
+    (@impl [$($gen:tt)*] $ty:ty;
+        key_nav($s1:ident) $key_nav:expr;
+        as_any($s2:ident) $any:expr, $any_mut:expr
+    ) => {
+        impl<$($gen)*> kas::WidgetCore for $ty {
+            fn as_any(&$s2) -> &dyn std::any::Any {
+                $any
+            }
+            fn as_any_mut(&mut $s2) -> &mut dyn std::any::Any {
+                $any_mut
+            }
+
+            fn core_data(&self) -> &kas::CoreData {
+                self.inner.core_data()
+            }
+            fn core_data_mut(&mut self) -> &mut kas::CoreData {
+                self.inner.core_data_mut()
+            }
+
+            fn widget_name(&self) -> &'static str {
+                self.inner.widget_name()
+            }
+
+            fn as_widget(&self) -> &dyn kas::WidgetConfig {
+                self
+            }
+            fn as_widget_mut(&mut self) -> &mut dyn kas::WidgetConfig {
+                self
+            }
+        }
+
+        impl<$($gen)*> kas::WidgetChildren for $ty {
+            fn first_id(&self) -> kas::WidgetId {
+                self.inner.first_id()
+            }
+            fn record_first_id(&mut self, id: kas::WidgetId) {
+                self.inner.record_first_id(id)
+            }
+            fn len(&self) -> usize {
+                self.inner.len()
+            }
+            fn get(&self, index: usize) -> Option<&dyn kas::WidgetConfig> {
+                self.inner.get(index)
+            }
+            fn get_mut(&mut self, index: usize) -> Option<&mut dyn kas::WidgetConfig> {
+                self.inner.get_mut(index)
+            }
+        }
+
+        impl<$($gen)*> kas::WidgetConfig for $ty {
+            fn configure(&mut self, mgr: &mut kas::event::Manager) {
+                self.inner.configure(mgr);
+            }
+            fn configure_recurse<'a, 'b>(&mut self, cmgr: kas::event::ConfigureManager<'a, 'b>) {
+                self.inner.configure_recurse(cmgr);
+            }
+
+            fn key_nav(&$s1) -> bool {
+                $key_nav
+            }
+            fn cursor_icon(&self) -> kas::event::CursorIcon {
+                self.inner.cursor_icon()
+            }
+            fn accessible(&self) -> kas::access::AccessNode {
+                self.inner.accessible()
+            }
+        }
+    };
+    ([$($gen:tt)*] $ty:ty) => {
+        impl_adapter!([$($gen)*] $ty; key_nav(self) self.inner.key_nav());
+    };
+    ([$($gen:tt)*] $ty:ty; key_nav($self:ident) $key_nav:expr) => {
+        impl_adapter!(@impl [$($gen)*] $ty; key_nav($self) $key_nav; as_any(self) self, self);
+    };
+    ([$($gen:tt)*] $ty:ty; as_any($self:ident) $any:expr, $any_mut:expr) => {
+        impl_adapter!(@impl [$($gen)*] $ty;
+            key_nav(self) self.inner.key_nav();
+            as_any($self) $any, $any_mut);
+    };
+}
+
+mod badge;
 mod button;
 mod checkbox;
+mod colour;
 mod combobox;
 mod dialog;
 mod drag;
 mod editbox;
+mod expander;
 mod filler;
 mod frame;
+mod grid;
+mod hidden;
+mod image;
 mod label;
+mod lazy_stack;
+mod link_label;
 mod list;
+mod list_view;
 mod menu;
+mod msg_into;
+mod overlay;
 mod radiobox;
 mod scroll;
 mod scrollbar;
 mod separator;
+mod sidebar;
+mod size_override;
 mod slider;
+mod spinner;
 mod splitter;
 mod stack;
 mod window;
 
-pub use button::TextButton;
+pub use badge::Badge;
+pub use button::{IconButton, TextButton, ToggleButton};
 pub use checkbox::{CheckBox, CheckBoxBare};
+pub use colour::ColourButton;
 pub use combobox::ComboBox;
 pub use dialog::MessageBox;
 pub use drag::DragHandle;
-pub use editbox::{EditBox, EditBoxVoid, EditGuard};
+pub use editbox::{EditBox, EditBoxVoid, EditGuard, EditParse, PasteNewlines};
+pub use expander::Expander;
 pub use filler::Filler;
-pub use frame::Frame;
-pub use label::{AccelLabel, Label, StrLabel, StringLabel};
+pub use frame::{Frame, TitledFrame};
+pub use grid::{BoxGrid, Grid};
+pub use hidden::Hidden;
+pub use image::Image;
+pub use label::{AccelLabel, Label, RichLabel, StrLabel, StringLabel};
+pub use lazy_stack::LazyStack;
+pub use link_label::LinkLabel;
 pub use list::*;
+pub use list_view::ListView;
 pub use menu::*;
+pub use msg_into::MsgInto;
+pub use overlay::{BoxOverlay, Overlay};
 pub use radiobox::{RadioBox, RadioBoxBare};
 pub use scroll::ScrollRegion;
 pub use scrollbar::ScrollBar;
 pub use separator::Separator;
+pub use sidebar::SideBar;
+pub use size_override::SizeOverride;
 pub use slider::{Slider, SliderType};
+pub use spinner::{Spinner, SpinnerType};
 pub use splitter::*;
 pub use stack::{BoxStack, RefStack, Stack};
-pub use window::Window;
+pub use window::{AlreadySetError, Window};
@@ -0,0 +1,181 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Message conversion adapter
+
+use std::fmt::{self, Debug};
+use std::marker::PhantomData;
+
+use kas::event;
+use kas::prelude::*;
+
+/// Adapter converting a widget's message type
+///
+/// This widget is transparent: it shares the identifier, rect and children of
+/// the wrapped widget and forwards all methods to it, converting messages to
+/// type `M` via [`From`].
+///
+/// The primary use is storing widgets with differing message types in a
+/// container of [`BoxedWidget<M>`](kas::BoxedWidget), for example a label
+/// (with message type [`event::VoidMsg`]) next to a button. Downcasting via
+/// [`WidgetCore::as_any`] yields the wrapped widget, thus a boxed label may
+/// be recovered without knowledge of the adapter.
+pub struct MsgInto<W: Widget, M> {
+    inner: W,
+    _msg: PhantomData<M>,
+}
+
+impl<W: Widget, M> MsgInto<W, M> {
+    /// Construct
+    #[inline]
+    pub fn new(inner: W) -> Self {
+        MsgInto {
+            inner,
+            _msg: PhantomData,
+        }
+    }
+
+    /// Get the inner widget
+    #[inline]
+    pub fn inner(&self) -> &W {
+        &self.inner
+    }
+
+    /// Get the inner widget mutably
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Unwrap, returning the inner widget
+    #[inline]
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Widget + Clone, M> Clone for MsgInto<W, M> {
+    fn clone(&self) -> Self {
+        MsgInto::new(self.inner.clone())
+    }
+}
+
+impl<W: Widget, M> Debug for MsgInto<W, M> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "MsgInto {{ inner: {:?} }}", self.inner)
+    }
+}
+
+impl_adapter!([W: Widget, M: 'static] MsgInto<W, M>;
+    as_any(self) self.inner.as_any(), self.inner.as_any_mut());
+
+impl<W: Widget, M: 'static> Layout for MsgInto<W, M> {
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        self.inner.size_rules(size_handle, axis)
+    }
+
+    fn set_rect(&mut self, rect: Rect, align: AlignHints) {
+        self.inner.set_rect(rect, align);
+    }
+
+    fn translation(&self, child_index: usize) -> Coord {
+        self.inner.translation(child_index)
+    }
+
+    fn spatial_range(&self) -> (usize, usize) {
+        self.inner.spatial_range()
+    }
+
+    fn find_id(&self, coord: Coord) -> Option<WidgetId> {
+        self.inner.find_id(coord)
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, mgr: &event::ManagerState, disabled: bool) {
+        self.inner.draw(draw_handle, mgr, disabled);
+    }
+}
+
+impl<W: Widget, M: From<W::Msg> + 'static> event::Handler for MsgInto<W, M> {
+    type Msg = M;
+
+    fn activation_via_press(&self) -> bool {
+        self.inner.activation_via_press()
+    }
+
+    fn handle(&mut self, mgr: &mut Manager, event: Event) -> Response<Self::Msg> {
+        Response::from(self.inner.handle(mgr, event))
+    }
+}
+
+impl<W: Widget, M: From<W::Msg> + 'static> event::SendEvent for MsgInto<W, M> {
+    fn send(&mut self, mgr: &mut Manager, id: WidgetId, event: Event) -> Response<Self::Msg> {
+        Response::from(self.inner.send(mgr, id, event))
+    }
+}
+
+impl<W: Widget, M: From<W::Msg> + 'static> Widget for MsgInto<W, M> {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::{DrawCommand, RecordingDrawHandle, TestSizeHandle};
+    use crate::widget::{CheckBox, Column, Label, StrLabel, TextButton};
+
+    #[test]
+    fn column_of_boxed_widgets() {
+        // Labels prepare their text in set_rect
+        let _ = crate::text::fonts::fonts().load_default();
+
+        let mut column: Column<BoxedWidget<u32>> = Column::new(vec![
+            MsgInto::new(Label::new("label")).boxed(),
+            TextButton::new("button", 1u32).boxed(),
+            MsgInto::new(CheckBox::new("check")).boxed(),
+        ]);
+        let mut id = WidgetId::FIRST;
+        column.walk_mut(|w| {
+            w.core_data_mut().id = id;
+            id = id.next();
+        });
+
+        let mut size_handle = TestSizeHandle::new();
+        let axis = AxisInfo::new(false, None);
+        let horiz = column.size_rules(&mut size_handle, axis);
+        let mut button = TextButton::new("button", 1u32);
+        let rules = button.size_rules(&mut size_handle, axis);
+        assert_eq!(column[1].size_rules(&mut size_handle, axis), rules);
+
+        let axis = AxisInfo::new(true, Some(horiz.ideal_size()));
+        let vert = column.size_rules(&mut size_handle, axis);
+        let size = Size(horiz.ideal_size(), vert.ideal_size());
+        let rect = Rect::new(Coord::ZERO, size);
+        column.set_rect(rect, AlignHints::NONE);
+
+        let label_pos = column[0].rect().pos;
+        assert_eq!(column.find_id(label_pos), Some(column[0].id()));
+        let button_pos = column[1].rect().pos;
+        assert_eq!(column.find_id(button_pos), Some(column[1].id()));
+
+        let mut draw_handle = RecordingDrawHandle::new(rect);
+        column.draw(&mut draw_handle, &ManagerState::new(), false);
+        let commands = draw_handle.take_commands();
+        let button_rect = column[1].rect();
+        let has_button =
+            |c: &DrawCommand| matches!(c, DrawCommand::Button(r, _) if *r == button_rect);
+        assert!(commands.iter().any(has_button));
+        let has_checkbox = |c: &DrawCommand| matches!(c, DrawCommand::Checkbox(..));
+        assert!(commands.iter().any(has_checkbox));
+    }
+
+    #[test]
+    fn downcast() {
+        // The adapter is transparent to downcasting
+        let mut boxed: BoxedWidget<u32> = MsgInto::new(Label::new("label")).boxed();
+        assert!(boxed.as_any().downcast_ref::<StrLabel>().is_some());
+        assert!(boxed.as_any_mut().downcast_mut::<StrLabel>().is_some());
+
+        let boxed: BoxedWidget<u32> = TextButton::new("button", 1u32).boxed();
+        assert!(boxed.as_any().downcast_ref::<TextButton<u32>>().is_some());
+    }
+}
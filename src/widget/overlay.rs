@@ -0,0 +1,239 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! An overlay of widgets
+
+use std::fmt::Debug;
+use std::ops::{Index, IndexMut};
+
+use kas::{event, prelude::*};
+
+/// An overlay of boxed widgets
+///
+/// This is a parametrisation of [`Overlay`].
+pub type BoxOverlay<M> = Overlay<BoxedWidget<M>>;
+
+/// An overlay of widgets
+///
+/// All children are assigned the same rect (that of the overlay) and are
+/// visible simultaneously, for example floating controls over a canvas or a
+/// loading indicator over content. Unlike [`super::Stack`], which shows only
+/// one child at a time, all children are drawn and may receive input.
+///
+/// Z-order follows insertion order: the first child is at the bottom and the
+/// last child on top. Children are drawn in this order, within the same draw
+/// pass as the overlay itself (thus pop-ups remain above). Mouse and touch
+/// input at a given coordinate is only received by the top-most child under
+/// that coordinate (the last child whose [`Layout::find_id`] finds a widget);
+/// children use alignment hints (or their own rules) to leave space
+/// uncovered.
+///
+/// Size requirements are the maximum of all children's requirements on each
+/// axis.
+///
+/// This may only be parametrised with a single widget type; [`BoxOverlay`] is
+/// a parametrisation allowing run-time polymorphism of child widgets.
+#[handler(send=noauto, msg=<W as event::Handler>::Msg)]
+#[widget(children=noauto)]
+#[derive(Clone, Default, Debug, Widget)]
+pub struct Overlay<W: Widget> {
+    first_id: WidgetId,
+    #[widget_core]
+    core: CoreData,
+    widgets: Vec<W>,
+}
+
+impl<W: Widget> WidgetChildren for Overlay<W> {
+    #[inline]
+    fn first_id(&self) -> WidgetId {
+        self.first_id
+    }
+    fn record_first_id(&mut self, id: WidgetId) {
+        self.first_id = id;
+    }
+    #[inline]
+    fn len(&self) -> usize {
+        self.widgets.len()
+    }
+    #[inline]
+    fn get(&self, index: usize) -> Option<&dyn WidgetConfig> {
+        self.widgets.get(index).map(|w| w.as_widget())
+    }
+    #[inline]
+    fn get_mut(&mut self, index: usize) -> Option<&mut dyn WidgetConfig> {
+        self.widgets.get_mut(index).map(|w| w.as_widget_mut())
+    }
+}
+
+impl<W: Widget> Layout for Overlay<W> {
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let mut rules = SizeRules::EMPTY;
+        for child in &mut self.widgets {
+            rules = rules.max(child.size_rules(size_handle, axis));
+        }
+        rules
+    }
+
+    fn set_rect(&mut self, rect: Rect, align: AlignHints) {
+        self.core.rect = rect;
+        for child in &mut self.widgets {
+            child.set_rect(rect, align.clone());
+        }
+    }
+
+    fn find_id(&self, coord: Coord) -> Option<WidgetId> {
+        if !self.rect().contains(coord) {
+            return None;
+        }
+        // The last child is on top
+        let id = self.widgets.iter().rev().find_map(|w| w.find_id(coord));
+        id.or(Some(self.id()))
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, mgr: &event::ManagerState, disabled: bool) {
+        let disabled = disabled || self.is_disabled();
+        for child in &self.widgets {
+            child.draw(draw_handle, mgr, disabled);
+        }
+    }
+}
+
+impl<W: Widget> event::SendEvent for Overlay<W> {
+    fn send(&mut self, mgr: &mut Manager, id: WidgetId, event: Event) -> Response<Self::Msg> {
+        if !self.is_disabled() {
+            for child in &mut self.widgets {
+                if id <= child.id() {
+                    return child.send(mgr, id, event);
+                }
+            }
+        }
+
+        Response::Unhandled(event)
+    }
+}
+
+impl<W: Widget> Overlay<W> {
+    /// Construct a new instance
+    ///
+    /// The last of `widgets` is on top.
+    pub fn new(widgets: Vec<W>) -> Self {
+        Overlay {
+            first_id: Default::default(),
+            core: Default::default(),
+            widgets,
+        }
+    }
+
+    /// True if there are no child widgets
+    pub fn is_empty(&self) -> bool {
+        self.widgets.is_empty()
+    }
+
+    /// Returns the number of child widgets
+    pub fn len(&self) -> usize {
+        self.widgets.len()
+    }
+
+    /// Append a child widget (on top of existing children)
+    ///
+    /// Triggers a [reconfigure action](Manager::send_action).
+    pub fn push(&mut self, widget: W) -> TkAction {
+        self.widgets.push(widget);
+        TkAction::Reconfigure
+    }
+
+    /// Remove the top-most child widget
+    ///
+    /// Returns `None` if there are no children.
+    ///
+    /// Triggers a [reconfigure action](Manager::send_action) if any widget is
+    /// removed.
+    pub fn pop(&mut self) -> (Option<W>, TkAction) {
+        let action = match self.widgets.is_empty() {
+            true => TkAction::None,
+            false => TkAction::Reconfigure,
+        };
+        (self.widgets.pop(), action)
+    }
+
+    /// Inserts a child widget position `index`
+    ///
+    /// Position `0` is the bottom. Panics if `index > len`.
+    ///
+    /// Triggers a [reconfigure action](Manager::send_action).
+    pub fn insert(&mut self, index: usize, widget: W) -> TkAction {
+        self.widgets.insert(index, widget);
+        TkAction::Reconfigure
+    }
+
+    /// Removes the child widget at position `index`
+    ///
+    /// Panics if `index` is out of bounds.
+    ///
+    /// Triggers a [reconfigure action](Manager::send_action).
+    pub fn remove(&mut self, index: usize) -> (W, TkAction) {
+        let r = self.widgets.remove(index);
+        (r, TkAction::Reconfigure)
+    }
+}
+
+impl<W: Widget> Index<usize> for Overlay<W> {
+    type Output = W;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.widgets[index]
+    }
+}
+
+impl<W: Widget> IndexMut<usize> for Overlay<W> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.widgets[index]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::widget::Filler;
+
+    #[test]
+    fn find_id_top_most() {
+        let mut overlay = Overlay::new(vec![Filler::new(), Filler::new()]);
+        let id0 = WidgetId::FIRST;
+        let id1 = id0.next();
+        overlay[0].core_data_mut().id = id0;
+        overlay[1].core_data_mut().id = id1;
+        overlay.core.id = id1.next();
+
+        let rect = Rect::new(Coord::ZERO, Size(100, 100));
+        overlay.set_rect(rect, AlignHints::NONE);
+        // The top child covers only the top-left corner
+        overlay[1].set_rect(Rect::new(Coord::ZERO, Size(20, 20)), AlignHints::NONE);
+
+        assert_eq!(overlay.find_id(Coord(10, 10)), Some(id1));
+        assert_eq!(overlay.find_id(Coord(50, 50)), Some(id0));
+        assert_eq!(overlay.find_id(Coord(150, 50)), None);
+    }
+
+    #[test]
+    fn draw_in_order() {
+        use crate::test_util::{DrawCommand, RecordingDrawHandle};
+        use crate::widget::Separator;
+
+        let mut overlay = Overlay::new(vec![Separator::new(), Separator::new()]);
+        let rect = Rect::new(Coord::ZERO, Size(100, 100));
+        overlay.set_rect(rect, AlignHints::NONE);
+        overlay[1].set_rect(Rect::new(Coord::ZERO, Size(20, 20)), AlignHints::NONE);
+
+        // Upper children are drawn in the same pass, after lower children
+        let mut draw_handle = RecordingDrawHandle::new(rect);
+        overlay.draw(&mut draw_handle, &ManagerState::new(), false);
+        let commands = [
+            DrawCommand::Separator(rect),
+            DrawCommand::Separator(overlay[1].rect()),
+        ];
+        assert_eq!(draw_handle.commands(), &commands);
+    }
+}
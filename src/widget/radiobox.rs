@@ -10,6 +10,7 @@ use std::fmt::{self, Debug};
 use std::rc::Rc;
 
 use super::AccelLabel;
+use kas::access::{AccessNode, Role};
 use kas::event::{self, UpdateHandle};
 use kas::prelude::*;
 
@@ -43,6 +44,10 @@ impl<M: 'static> WidgetConfig for RadioBoxBare<M> {
     fn key_nav(&self) -> bool {
         true
     }
+
+    fn accessible(&self) -> AccessNode {
+        AccessNode::new(Role::RadioButton).with_checked(self.state)
+    }
 }
 
 impl<M: 'static> event::Handler for RadioBoxBare<M> {
@@ -276,6 +281,10 @@ impl<M: 'static> WidgetConfig for RadioBox<M> {
     fn configure(&mut self, mgr: &mut Manager) {
         mgr.add_accel_keys(self.radiobox.id(), self.label.keys());
     }
+
+    fn accessible(&self) -> AccessNode {
+        self.radiobox.accessible().with_label(self.label.get_str())
+    }
 }
 
 impl<M: 'static> HasBool for RadioBox<M> {
@@ -22,6 +22,10 @@ use kas::prelude::*;
 /// Scroll regions translate their contents by an `offset`, which has a
 /// minimum value of [`Coord::ZERO`] and a maximum value of
 /// [`ScrollRegion::max_offset`].
+///
+/// Scrolling may be restricted to one axis (see
+/// [`ScrollRegion::with_scroll_axes`]), in which case the inner widget is
+/// sized to fit the view on the other axis.
 #[widget(config=noauto)]
 #[handler(send=noauto, msg = <W as event::Handler>::Msg)]
 #[derive(Clone, Debug, Default, Widget)]
@@ -36,6 +40,8 @@ pub struct ScrollRegion<W: Widget> {
     bar_width: u32,
     auto_bars: bool,
     show_bars: (bool, bool),
+    bars: (bool, bool),
+    fixed_axes: (bool, bool),
     #[widget]
     horiz_bar: ScrollBar<kas::Right>,
     #[widget]
@@ -58,6 +64,8 @@ impl<W: Widget> ScrollRegion<W> {
             bar_width: 0,
             auto_bars: false,
             show_bars: (false, false),
+            bars: (false, false),
+            fixed_axes: (false, false),
             horiz_bar: ScrollBar::new(),
             vert_bar: ScrollBar::new(),
             inner,
@@ -90,6 +98,27 @@ impl<W: Widget> ScrollRegion<W> {
         self.show_bars = (horiz, vert);
     }
 
+    /// Set which axes may be scrolled (inline)
+    ///
+    /// By default, both axes may be scrolled. On an axis which may not be
+    /// scrolled, the inner widget is sized to the view (e.g. text wraps
+    /// instead of overflowing) and scroll deltas are ignored. If neither axis
+    /// may be scrolled, this is merely a clip region.
+    #[inline]
+    pub fn with_scroll_axes(mut self, horiz: bool, vert: bool) -> Self {
+        self.fixed_axes = (!horiz, !vert);
+        self
+    }
+
+    /// Set which axes may be scrolled
+    ///
+    /// See [`ScrollRegion::with_scroll_axes`].
+    #[inline]
+    pub fn set_scroll_axes(&mut self, horiz: bool, vert: bool) -> TkAction {
+        self.fixed_axes = (!horiz, !vert);
+        TkAction::Resize
+    }
+
     /// Access inner widget directly
     #[inline]
     pub fn inner(&self) -> &W {
@@ -139,19 +168,24 @@ impl<W: Widget> WidgetConfig for ScrollRegion<W> {
 impl<W: Widget> Layout for ScrollRegion<W> {
     fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
         let mut rules = self.inner.size_rules(size_handle, axis);
-        if axis.is_horizontal() {
+        let fixed = if axis.is_horizontal() {
             self.min_child_size.0 = rules.min_size();
+            self.fixed_axes.0
         } else {
             self.min_child_size.1 = rules.min_size();
-        }
+            self.fixed_axes.1
+        };
         let line_height = size_handle.line_height(TextClass::Label);
         self.scroll_rate = 3.0 * line_height as f32;
-        rules.reduce_min_to(line_height);
+        if !fixed {
+            rules.reduce_min_to(line_height);
+        }
         self.bar_width = (size_handle.scrollbar().0).1;
 
-        if axis.is_horizontal() && (self.auto_bars || self.show_bars.1) {
+        let (horiz, vert) = (!self.fixed_axes.0, !self.fixed_axes.1);
+        if axis.is_horizontal() && vert && (self.auto_bars || self.show_bars.1) {
             rules.append(self.vert_bar.size_rules(size_handle, axis));
-        } else if axis.is_vertical() && (self.auto_bars || self.show_bars.0) {
+        } else if axis.is_vertical() && horiz && (self.auto_bars || self.show_bars.0) {
             rules.append(self.horiz_bar.size_rules(size_handle, axis));
         }
         rules
@@ -163,26 +197,37 @@ impl<W: Widget> Layout for ScrollRegion<W> {
         let pos = rect.pos;
         self.inner_size = rect.size;
 
-        if self.auto_bars {
-            self.show_bars = (
+        // Bars are never shown on axes which may not be scrolled
+        self.bars = if self.auto_bars {
+            (
                 self.min_child_size.0 + self.bar_width > rect.size.0,
                 self.min_child_size.1 + self.bar_width > rect.size.1,
-            );
-        }
-        if self.show_bars.0 {
+            )
+        } else {
+            self.show_bars
+        };
+        self.bars.0 &= !self.fixed_axes.0;
+        self.bars.1 &= !self.fixed_axes.1;
+        if self.bars.0 {
             self.inner_size.1 -= self.bar_width;
         }
-        if self.show_bars.1 {
+        if self.bars.1 {
             self.inner_size.0 -= self.bar_width;
         }
 
-        let child_size = self.inner_size.max(self.min_child_size);
+        let mut child_size = self.inner_size.max(self.min_child_size);
+        if self.fixed_axes.0 {
+            child_size.0 = self.inner_size.0;
+        }
+        if self.fixed_axes.1 {
+            child_size.1 = self.inner_size.1;
+        }
         let child_rect = Rect::new(pos, child_size);
         self.inner.set_rect(child_rect, AlignHints::NONE);
         self.max_offset = Coord::from(child_size) - Coord::from(self.inner_size);
         self.offset = self.offset.clamp(Coord::ZERO, self.max_offset);
 
-        if self.show_bars.0 {
+        if self.bars.0 {
             let pos = Coord(pos.0, pos.1 + self.inner_size.1 as i32);
             let size = Size(self.inner_size.0, self.bar_width);
             self.horiz_bar
@@ -191,7 +236,7 @@ impl<W: Widget> Layout for ScrollRegion<W> {
                 .horiz_bar
                 .set_limits(self.max_offset.0 as u32, rect.size.0);
         }
-        if self.show_bars.1 {
+        if self.bars.1 {
             let pos = Coord(pos.0 + self.inner_size.0 as i32, pos.1);
             let size = Size(self.bar_width, self.core.rect.size.1);
             self.vert_bar.set_rect(Rect { pos, size }, AlignHints::NONE);
@@ -214,19 +259,21 @@ impl<W: Widget> Layout for ScrollRegion<W> {
             return None;
         }
 
-        self.horiz_bar
-            .find_id(coord)
-            .or_else(|| self.vert_bar.find_id(coord))
+        let horiz_bar = Some(&self.horiz_bar).filter(|_| self.bars.0);
+        let vert_bar = Some(&self.vert_bar).filter(|_| self.bars.1);
+        horiz_bar
+            .and_then(|bar| bar.find_id(coord))
+            .or_else(|| vert_bar.and_then(|bar| bar.find_id(coord)))
             .or_else(|| self.inner.find_id(coord + self.offset))
             .or(Some(self.id()))
     }
 
     fn draw(&self, draw_handle: &mut dyn DrawHandle, mgr: &event::ManagerState, disabled: bool) {
         let disabled = disabled || self.is_disabled();
-        if self.show_bars.0 {
+        if self.bars.0 {
             self.horiz_bar.draw(draw_handle, mgr, disabled);
         }
-        if self.show_bars.1 {
+        if self.bars.1 {
             self.vert_bar.draw(draw_handle, mgr, disabled);
         }
         let rect = Rect {
@@ -314,10 +361,10 @@ impl<W: Widget> event::SendEvent for ScrollRegion<W> {
 
         let scroll = |w: &mut Self, mgr: &mut Manager, delta| {
             let d = match delta {
-                LineDelta(x, y) => Coord((-w.scroll_rate * x) as i32, (w.scroll_rate * y) as i32),
+                LineDelta(x, y) => Coord((w.scroll_rate * x) as i32, (w.scroll_rate * y) as i32),
                 PixelDelta(d) => d,
             };
-            let action = w.set_offset(w.offset - d);
+            let action = w.set_offset(w.offset + d);
             if action != TkAction::None {
                 *mgr += action
                     + w.horiz_bar.set_value(w.offset.0 as u32)
@@ -333,8 +380,8 @@ impl<W: Widget> event::SendEvent for ScrollRegion<W> {
                 let delta = match key {
                     ControlKey::Left => LineDelta(-1.0, 0.0),
                     ControlKey::Right => LineDelta(1.0, 0.0),
-                    ControlKey::Up => LineDelta(0.0, 1.0),
-                    ControlKey::Down => LineDelta(0.0, -1.0),
+                    ControlKey::Up => LineDelta(0.0, -1.0),
+                    ControlKey::Down => LineDelta(0.0, 1.0),
                     ControlKey::Home | ControlKey::End => {
                         let action = self.set_offset(match key {
                             ControlKey::Home => Coord::ZERO,
@@ -347,10 +394,8 @@ impl<W: Widget> event::SendEvent for ScrollRegion<W> {
                         }
                         return Response::None;
                     }
-                    ControlKey::PageUp => PixelDelta(Coord(0, self.core.rect.size.1 as i32 / 2)),
-                    ControlKey::PageDown => {
-                        PixelDelta(Coord(0, -(self.core.rect.size.1 as i32 / 2)))
-                    }
+                    ControlKey::PageUp => PixelDelta(Coord(0, -(self.core.rect.size.1 as i32 / 2))),
+                    ControlKey::PageDown => PixelDelta(Coord(0, self.core.rect.size.1 as i32 / 2)),
                     key => return Response::Unhandled(Event::Control(key)),
                 };
                 scroll(self, mgr, delta)
@@ -383,3 +428,31 @@ impl<W: Widget> event::SendEvent for ScrollRegion<W> {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::TestManager;
+    use crate::widget::Filler;
+
+    #[test]
+    fn bars_and_scroll_axes() {
+        let scroll = ScrollRegion::new(Filler::new())
+            .with_bars(true, true)
+            .with_scroll_axes(true, false);
+        let mut test = TestManager::new(scroll);
+        let rect = Rect::new(Coord::ZERO, Size(100, 100));
+
+        test.set_rect(rect);
+        assert_eq!(test.widget.bars, (true, false));
+
+        // Locking an axis does not change the user's choice of bars
+        let _ = test.widget.set_scroll_axes(true, true);
+        test.set_rect(rect);
+        assert_eq!(test.widget.bars, (true, true));
+
+        test.widget.show_bars(false, true);
+        test.set_rect(rect);
+        assert_eq!(test.widget.bars, (false, true));
+    }
+}
@@ -0,0 +1,326 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! A side pane which may be collapsed
+
+use std::time::Duration;
+
+use super::DragHandle;
+use kas::anim::Tween;
+use kas::draw::ClipRegion;
+use kas::event;
+use kas::prelude::*;
+
+/// Duration of the collapse / expand animation
+const ANIM_DURATION: Duration = Duration::from_millis(150);
+
+/// A side pane and a main area, separated by a draggable handle
+///
+/// The side pane is placed on the left. Its width may be adjusted by dragging
+/// the handle, and it may be collapsed (hidden) via [`SideBar::set_collapsed`],
+/// in which case the main area expands to fill the whole width. Collapsing and
+/// expanding are animated; an expanded side pane returns to its prior width.
+///
+/// A collapsed side pane is skipped by keyboard navigation.
+#[handler(send=noauto, msg = <W as event::Handler>::Msg)]
+#[derive(Clone, Debug, Widget)]
+pub struct SideBar<S: Widget, W: Widget<Msg = <S as event::Handler>::Msg>> {
+    #[widget_core]
+    core: CoreData,
+    #[widget]
+    side: S,
+    #[widget]
+    handle: DragHandle,
+    #[widget]
+    main: W,
+    handle_width: u32,
+    side_ideal: u32,
+    side_width: Option<u32>,
+    shown: u32,
+    collapsed: bool,
+    // Progress (0 to 1) and initial width of the collapse / expand animation
+    anim: Option<(Tween<f32>, u32)>,
+}
+
+impl<S: Widget, W: Widget<Msg = <S as event::Handler>::Msg>> SideBar<S, W> {
+    /// Construct from a side pane and a main area
+    pub fn new(side: S, main: W) -> Self {
+        SideBar {
+            core: Default::default(),
+            side,
+            handle: DragHandle::new(),
+            main,
+            handle_width: 0,
+            side_ideal: 0,
+            side_width: None,
+            shown: 0,
+            collapsed: false,
+            anim: None,
+        }
+    }
+
+    /// Access the side pane
+    #[inline]
+    pub fn side(&self) -> &S {
+        &self.side
+    }
+
+    /// Access the side pane mutably
+    #[inline]
+    pub fn side_mut(&mut self) -> &mut S {
+        &mut self.side
+    }
+
+    /// Access the main area
+    #[inline]
+    pub fn main(&self) -> &W {
+        &self.main
+    }
+
+    /// Access the main area mutably
+    #[inline]
+    pub fn main_mut(&mut self) -> &mut W {
+        &mut self.main
+    }
+
+    /// True if the side pane is collapsed (or collapsing)
+    #[inline]
+    pub fn is_collapsed(&self) -> bool {
+        self.collapsed
+    }
+
+    /// Collapse or expand the side pane
+    ///
+    /// The change is animated: the side pane slides out of (or into) view
+    /// while the main area expands (or shrinks) to fill the remaining space.
+    pub fn set_collapsed(&mut self, mgr: &mut Manager, collapsed: bool) {
+        if collapsed == self.collapsed {
+            return;
+        }
+        self.collapsed = collapsed;
+        self.anim = Some((Tween::new(0.0, 1.0, ANIM_DURATION), self.shown));
+        mgr.request_animation_frame(self.id());
+        if !collapsed {
+            // Size requirements of the side pane apply again
+            *mgr += TkAction::Resize;
+        }
+    }
+
+    fn layout_children(&mut self) {
+        let rect = self.core.rect;
+        let max_width = rect.size.0.saturating_sub(self.handle_width);
+        let width = self.side_width.unwrap_or(self.side_ideal).min(max_width);
+
+        let target = if self.collapsed { 0 } else { width };
+        self.shown = match self.anim {
+            Some((ref progress, from)) => {
+                let frac = progress.value();
+                (from as f32 + (target as f32 - from as f32) * frac).round() as u32
+            }
+            None => target,
+        };
+        let handle_width = match self.collapsed && self.anim.is_none() {
+            true => 0,
+            false => self.handle_width,
+        };
+
+        // The side pane keeps its width, sliding out of view to the left
+        let pos = Coord(rect.pos.0 + self.shown as i32 - width as i32, rect.pos.1);
+        let side_rect = Rect::new(pos, Size(width, rect.size.1));
+        self.side.set_rect(side_rect, AlignHints::NONE);
+
+        self.handle.set_rect(rect, AlignHints::NONE);
+        let size = Size(handle_width, rect.size.1);
+        let _ = self
+            .handle
+            .set_size_and_offset(size, Coord(self.shown as i32, 0));
+
+        let x = self.shown + handle_width;
+        let pos = Coord(rect.pos.0 + x as i32, rect.pos.1);
+        let size = Size(rect.size.0.saturating_sub(x), rect.size.1);
+        self.main.set_rect(Rect::new(pos, size), AlignHints::NONE);
+    }
+
+    fn animate(&mut self, mgr: &mut Manager, delta: Duration) {
+        let running = match self.anim.as_mut() {
+            Some((progress, _)) => progress.advance(delta),
+            None => return,
+        };
+        if running {
+            mgr.request_animation_frame(self.id());
+        } else {
+            self.anim = None;
+            if self.collapsed {
+                // Size requirements of the side pane no longer apply
+                *mgr += TkAction::Resize;
+            }
+        }
+        self.layout_children();
+        mgr.redraw(self.id());
+    }
+}
+
+impl<S: Widget, W: Widget<Msg = <S as event::Handler>::Msg>> Layout for SideBar<S, W> {
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let side_rules = self.side.size_rules(size_handle, axis);
+        let main_rules = self.main.size_rules(size_handle, axis);
+        if self.collapsed && self.anim.is_none() {
+            return main_rules;
+        }
+
+        if axis.is_horizontal() {
+            self.handle_width = size_handle.frame().0;
+            self.side_ideal = side_rules.ideal_size();
+            let handle_rules = SizeRules::fixed(self.handle_width, (0, 0));
+            side_rules.appended(handle_rules).appended(main_rules)
+        } else {
+            side_rules.max(main_rules)
+        }
+    }
+
+    fn set_rect(&mut self, rect: Rect, _: AlignHints) {
+        self.core.rect = rect;
+        self.layout_children();
+    }
+
+    fn spatial_range(&self) -> (usize, usize) {
+        // Skip the side pane when collapsed
+        match self.collapsed {
+            false => (0, 2),
+            true => (1, 2),
+        }
+    }
+
+    fn find_id(&self, coord: Coord) -> Option<WidgetId> {
+        if !self.rect().contains(coord) {
+            return None;
+        }
+
+        let x = coord.0 - self.core.rect.pos.0;
+        let id = if x < self.shown as i32 {
+            self.side.find_id(coord)
+        } else if self.handle.rect().contains(coord) {
+            self.handle.find_id(coord)
+        } else {
+            self.main.find_id(coord)
+        };
+        id.or(Some(self.id()))
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, mgr: &event::ManagerState, disabled: bool) {
+        let disabled = disabled || self.is_disabled();
+        if self.shown > 0 {
+            if self.shown < self.side.rect().size.0 {
+                // Partially hidden (animating): clip to the visible part
+                let rect = Rect::new(self.core.rect.pos, Size(self.shown, self.core.rect.size.1));
+                let class = ClipRegion::Scroll;
+                draw_handle.clip_region(rect, Coord::ZERO, class, &mut |draw_handle| {
+                    self.side.draw(draw_handle, mgr, disabled)
+                });
+            } else {
+                self.side.draw(draw_handle, mgr, disabled);
+            }
+        }
+        if self.handle.rect().size.0 > 0 {
+            draw_handle.separator(self.handle.rect());
+        }
+        self.main.draw(draw_handle, mgr, disabled);
+    }
+}
+
+impl<S: Widget, W: Widget<Msg = <S as event::Handler>::Msg>> event::SendEvent for SideBar<S, W> {
+    fn send(&mut self, mgr: &mut Manager, id: WidgetId, event: Event) -> Response<Self::Msg> {
+        if self.is_disabled() {
+            return Response::Unhandled(event);
+        }
+
+        if id <= self.side.id() {
+            return self.side.send(mgr, id, event);
+        } else if id <= self.handle.id() {
+            return self
+                .handle
+                .send(mgr, id, event)
+                .try_into()
+                .unwrap_or_else(|offset: Coord| {
+                    // Message is the new offset relative to the track;
+                    // the handle has already adjusted its position
+                    self.side_width = Some(offset.0 as u32);
+                    self.layout_children();
+                    Response::None
+                });
+        } else if id <= self.main.id() {
+            return self.main.send(mgr, id, event);
+        }
+
+        match event {
+            Event::AnimationFrame { delta } => {
+                self.animate(mgr, delta);
+                Response::None
+            }
+            event => Response::Unhandled(event),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::TestManager;
+    use crate::widget::Filler;
+
+    #[test]
+    fn collapse_and_expand() {
+        let mut bar = SideBar::new(Filler::new(), Filler::new());
+        bar.handle_width = 4;
+        bar.side_ideal = 60;
+        let rect = Rect::new(Coord::ZERO, Size(200, 100));
+        bar.set_rect(rect, AlignHints::NONE);
+        assert_eq!(bar.side.rect().size.0, 60);
+        assert_eq!(bar.main.rect(), Rect::new(Coord(64, 0), Size(136, 100)));
+
+        // Drag the handle to give the side pane a custom width
+        bar.side_width = Some(80);
+        bar.layout_children();
+        assert_eq!(bar.main.rect().pos.0, 84);
+
+        bar.collapsed = true;
+        bar.layout_children();
+        assert_eq!(bar.shown, 0);
+        assert_eq!(bar.main.rect(), rect);
+
+        bar.collapsed = false;
+        bar.layout_children();
+        assert_eq!(bar.shown, 80);
+        assert_eq!(bar.side.rect().size.0, 80);
+        assert_eq!(bar.main.rect().pos.0, 84);
+    }
+
+    #[test]
+    fn collapse_animation() {
+        let mut bar = SideBar::new(Filler::new(), Filler::new());
+        bar.side_width = Some(80);
+        let mut test = TestManager::new(bar);
+        test.set_rect(Rect::new(Coord::ZERO, Size(200, 100)));
+        let id = test.widget.id();
+        assert_eq!(test.widget.shown, 80);
+
+        // Collapsing slides the side pane out on animation frames
+        test.with(|mgr, bar| bar.set_collapsed(mgr, true));
+        assert!(test.state.is_animating());
+        assert_eq!(test.widget.shown, 80);
+        let frame = |test: &mut TestManager<_>, delta| {
+            let _ = test.send(id, Event::AnimationFrame { delta });
+        };
+        frame(&mut test, ANIM_DURATION / 2);
+        assert_eq!(test.widget.shown, 40);
+        assert!(test.widget.anim.is_some());
+
+        // The animation ends once its duration has elapsed
+        frame(&mut test, ANIM_DURATION);
+        assert_eq!(test.widget.shown, 0);
+        assert!(test.widget.anim.is_none());
+        assert_eq!(test.widget.handle.rect().size.0, 0);
+    }
+}
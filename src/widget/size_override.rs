@@ -0,0 +1,201 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Size override adapter
+
+use std::fmt::{self, Debug};
+
+use kas::event;
+use kas::geom::LogicalSize;
+use kas::prelude::*;
+
+/// Adapter overriding the minimum and ideal size of a widget
+///
+/// Like [`super::MsgInto`], this widget is transparent: it shares the
+/// identifier, rect and children of the wrapped widget and forwards all
+/// methods to it. The only exception is [`Layout::size_rules`], where the
+/// wrapped widget's minimum and ideal sizes are increased to at least the
+/// given overrides. The wrapped widget is never given less than its own
+/// minimum size.
+///
+/// Override sizes are specified in logical pixels (see [`LogicalSize`]) and
+/// multiplied by the [scale factor](SizeHandle::scale_factor), thus scale with
+/// DPI. A component of zero has no effect. Overrides do not apply while the
+/// wrapped widget is collapsed (see [`super::Hidden`]).
+#[derive(Clone)]
+pub struct SizeOverride<W: Widget> {
+    inner: W,
+    min: LogicalSize,
+    ideal: LogicalSize,
+}
+
+impl<W: Widget> SizeOverride<W> {
+    /// Construct, without any override
+    #[inline]
+    pub fn new(inner: W) -> Self {
+        SizeOverride {
+            inner,
+            min: LogicalSize::default(),
+            ideal: LogicalSize::default(),
+        }
+    }
+
+    /// Set the minimum size (chain style)
+    ///
+    /// This also sets a lower bound on the ideal size.
+    #[inline]
+    pub fn with_min_size(mut self, size: LogicalSize) -> Self {
+        self.min = size;
+        self
+    }
+
+    /// Set the ideal size (chain style)
+    #[inline]
+    pub fn with_ideal_size(mut self, size: LogicalSize) -> Self {
+        self.ideal = size;
+        self
+    }
+
+    /// Set the minimum size
+    pub fn set_min_size(&mut self, size: LogicalSize) -> TkAction {
+        self.min = size;
+        TkAction::Resize
+    }
+
+    /// Set the ideal size
+    pub fn set_ideal_size(&mut self, size: LogicalSize) -> TkAction {
+        self.ideal = size;
+        TkAction::Resize
+    }
+
+    /// Get the inner widget
+    #[inline]
+    pub fn inner(&self) -> &W {
+        &self.inner
+    }
+
+    /// Get the inner widget mutably
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Unwrap, returning the inner widget
+    #[inline]
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Widget> Debug for SizeOverride<W> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "SizeOverride {{ inner: {:?}, min: {:?}, ideal: {:?} }}",
+            self.inner, self.min, self.ideal,
+        )
+    }
+}
+
+impl_adapter!([W: Widget] SizeOverride<W>);
+
+impl<W: Widget> Layout for SizeOverride<W> {
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let rules = self.inner.size_rules(size_handle, axis);
+        if rules.is_collapsed() {
+            // A hidden widget takes no space, even with an override
+            return rules;
+        }
+        let scale_factor = size_handle.scale_factor();
+        let scale = |size: LogicalSize| axis.extract_size(size.to_physical(scale_factor));
+        let min = rules.min_size().max(scale(self.min));
+        let ideal = rules.ideal_size().max(scale(self.ideal));
+        SizeRules::new(min, ideal, rules.margins(), rules.stretch())
+    }
+
+    fn set_rect(&mut self, rect: Rect, align: AlignHints) {
+        self.inner.set_rect(rect, align);
+    }
+
+    fn translation(&self, child_index: usize) -> Coord {
+        self.inner.translation(child_index)
+    }
+
+    fn spatial_range(&self) -> (usize, usize) {
+        self.inner.spatial_range()
+    }
+
+    fn find_id(&self, coord: Coord) -> Option<WidgetId> {
+        self.inner.find_id(coord)
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, mgr: &event::ManagerState, disabled: bool) {
+        self.inner.draw(draw_handle, mgr, disabled);
+    }
+}
+
+impl<W: Widget> event::Handler for SizeOverride<W> {
+    type Msg = W::Msg;
+
+    fn activation_via_press(&self) -> bool {
+        self.inner.activation_via_press()
+    }
+
+    fn handle(&mut self, mgr: &mut Manager, event: Event) -> Response<Self::Msg> {
+        self.inner.handle(mgr, event)
+    }
+}
+
+impl<W: Widget> event::SendEvent for SizeOverride<W> {
+    fn send(&mut self, mgr: &mut Manager, id: WidgetId, event: Event) -> Response<Self::Msg> {
+        self.inner.send(mgr, id, event)
+    }
+}
+
+impl<W: Widget> Widget for SizeOverride<W> {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::TestSizeHandle;
+    use crate::widget::{Hidden, Label};
+
+    #[test]
+    fn overrides() {
+        let mut size_handle = TestSizeHandle::new();
+        let horiz = AxisInfo::new(false, None);
+        let vert = AxisInfo::new(true, None);
+        let mut label = Label::new("label");
+        let label_h = label.size_rules(&mut size_handle, horiz);
+        let label_v = label.size_rules(&mut size_handle, vert);
+
+        let mut w = SizeOverride::new(Label::new("label"))
+            .with_min_size(LogicalSize(100.0, 4.0))
+            .with_ideal_size(LogicalSize(150.0, 0.0));
+        let rules = w.size_rules(&mut size_handle, horiz);
+        assert_eq!(rules.min_size(), 100);
+        assert_eq!(rules.ideal_size(), 150);
+        assert_eq!(rules.margins(), label_h.margins());
+        // The child's natural size is never reduced
+        assert_eq!(w.size_rules(&mut size_handle, vert), label_v);
+
+        size_handle.scale_factor = 1.5;
+        let rules = w.size_rules(&mut size_handle, horiz);
+        assert_eq!(rules.min_size(), 150);
+        assert_eq!(rules.ideal_size(), 225);
+    }
+
+    #[test]
+    fn collapsed() {
+        let mut size_handle = TestSizeHandle::new();
+        let horiz = AxisInfo::new(false, None);
+        let label = Hidden::new(Label::new("label")).with_visible(false);
+        let mut w = SizeOverride::new(label).with_min_size(LogicalSize(100.0, 4.0));
+        assert_eq!(w.size_rules(&mut size_handle, horiz), SizeRules::COLLAPSED);
+
+        let _ = w.inner_mut().set_visible_keep_focus(true);
+        assert_eq!(w.size_rules(&mut size_handle, horiz).min_size(), 100);
+    }
+}
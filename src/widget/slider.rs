@@ -10,6 +10,7 @@ use std::ops::{Add, Sub};
 use std::time::Duration;
 
 use super::DragHandle;
+use kas::access::{AccessNode, Role};
 use kas::event::{self, ControlKey};
 use kas::prelude::*;
 
@@ -82,7 +83,7 @@ impl SliderType for Duration {
 ///
 /// Sliders allow user input of a value from a fixed range.
 #[handler(send=noauto, msg = T)]
-#[widget(config(key_nav = true))]
+#[widget(config=noauto)]
 #[derive(Clone, Debug, Default, Widget)]
 pub struct Slider<T: SliderType, D: Directional> {
     #[widget_core]
@@ -208,6 +209,16 @@ impl<T: SliderType, D: Directional> Slider<T, D> {
     }
 }
 
+impl<T: SliderType, D: Directional> WidgetConfig for Slider<T, D> {
+    fn key_nav(&self) -> bool {
+        true
+    }
+
+    fn accessible(&self) -> AccessNode {
+        AccessNode::new(Role::Slider).with_value(format!("{:?}", self.value))
+    }
+}
+
 impl<T: SliderType, D: Directional> Layout for Slider<T, D> {
     fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
         let (mut size, min_len) = size_handle.slider();
@@ -0,0 +1,389 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! `Spinner` control
+
+use std::fmt::{Debug, Display};
+use std::ops::{Add, RangeInclusive, Sub};
+use std::str::FromStr;
+
+use super::{EditBox, EditGuard, TextButton};
+use kas::prelude::*;
+
+/// Requirements on type used by [`Spinner`]
+///
+/// Implementations are provided for all built-in integer and floating-point
+/// types.
+pub trait SpinnerType:
+    Copy + Debug + Display + FromStr + PartialOrd + Add<Output = Self> + Sub<Output = Self> + 'static
+{
+    /// Correct the result of stepping
+    ///
+    /// Here, `result` is `value + step` or `value - step`. For floating-point
+    /// types this may include a rounding error (e.g. `0.1 + 0.2` yields
+    /// `0.30000000000000004`), which would accumulate and be displayed.
+    /// Implementations may therefore round `result` to the precision of
+    /// `value` and `step`. The default implementation returns `result`.
+    fn round_step(result: Self, value: Self, step: Self) -> Self {
+        let _ = (value, step);
+        result
+    }
+
+    /// Add, returning `None` on overflow
+    ///
+    /// The default implementation does not check for overflow.
+    fn checked_add(self, rhs: Self) -> Option<Self> {
+        Some(self + rhs)
+    }
+
+    /// Subtract, returning `None` on overflow
+    ///
+    /// The default implementation does not check for overflow.
+    fn checked_sub(self, rhs: Self) -> Option<Self> {
+        Some(self - rhs)
+    }
+}
+
+macro_rules! impl_spinner_ty {
+    ($($ty:ty),*) => {
+        $(impl SpinnerType for $ty {
+            fn checked_add(self, rhs: Self) -> Option<Self> {
+                <$ty>::checked_add(self, rhs)
+            }
+            fn checked_sub(self, rhs: Self) -> Option<Self> {
+                <$ty>::checked_sub(self, rhs)
+            }
+        })*
+    };
+}
+impl_spinner_ty!(i8, i16, i32, i64, i128, isize);
+impl_spinner_ty!(u8, u16, u32, u64, u128, usize);
+
+macro_rules! impl_spinner_float {
+    ($($ty:ty),*) => {
+        $(impl SpinnerType for $ty {
+            fn round_step(result: Self, value: Self, step: Self) -> Self {
+                // Display yields the shortest representation, thus the
+                // number of decimal places needed
+                let places = |x: Self| {
+                    let s = x.to_string();
+                    s.find('.').map(|i| s.len() - i - 1).unwrap_or(0)
+                };
+                let places = places(value).max(places(step)).min(15);
+                let scale = (10.0 as $ty).powi(places as i32);
+                let rounded = (result * scale).round() / scale;
+                match rounded.is_finite() {
+                    true => rounded,
+                    false => result,
+                }
+            }
+        })*
+    };
+}
+impl_spinner_float!(f32, f64);
+
+#[derive(Clone, Debug)]
+enum SpinnerEdit<T> {
+    Value(T),
+    Commit,
+}
+
+// The guard holds the spinner's state, shared with the buttons via the parent
+#[derive(Clone, Debug)]
+struct SpinnerGuard<T> {
+    min: T,
+    max: T,
+    value: T,
+}
+
+impl<T: SpinnerType> SpinnerGuard<T> {
+    /// Clamp `value` to the range; `None` if not comparable (e.g. NaN)
+    fn clamp(&self, value: T) -> Option<T> {
+        if value < self.min {
+            Some(self.min)
+        } else if value > self.max {
+            Some(self.max)
+        } else if value <= self.max {
+            Some(value)
+        } else {
+            None
+        }
+    }
+}
+
+impl<T: SpinnerType> EditGuard for SpinnerGuard<T> {
+    type Msg = SpinnerEdit<T>;
+
+    fn activate(_: &mut EditBox<Self>) -> Option<Self::Msg> {
+        Some(SpinnerEdit::Commit)
+    }
+
+    fn focus_lost(_: &mut EditBox<Self>) -> Option<Self::Msg> {
+        Some(SpinnerEdit::Commit)
+    }
+
+    fn edit(edit: &mut EditBox<Self>) -> Option<Self::Msg> {
+        let text = edit.get_str().trim();
+        let is_empty = text.is_empty();
+        match text.parse::<T>().ok() {
+            Some(value) if edit.guard.min <= value && value <= edit.guard.max => {
+                edit.set_error_state(false);
+                if value != edit.guard.value {
+                    edit.guard.value = value;
+                    return Some(SpinnerEdit::Value(value));
+                }
+            }
+            // An empty field is not yet an error, but has no value
+            _ => edit.set_error_state(!is_empty),
+        }
+        None
+    }
+}
+
+#[derive(Clone, Debug, VoidMsg)]
+enum SpinnerButton {
+    Decr,
+    Incr,
+}
+
+/// A numeric entry field with increment / decrement buttons
+///
+/// Values may be typed or stepped via the buttons. Typed input which does not
+/// parse or is outside the range is highlighted as an error and not emitted;
+/// on commit (activation or loss of focus) the value is clamped to the range,
+/// or reverted if it does not parse. Stepping clamps at the ends of the range
+/// unless [`Spinner::with_wrapping`] is used.
+///
+/// The widget emits the new value as a message whenever the value changes.
+#[layout(row)]
+#[handler(msg = T)]
+#[derive(Clone, Debug, Widget)]
+pub struct Spinner<T: SpinnerType> {
+    #[widget_core]
+    core: CoreData,
+    #[layout_data]
+    layout_data: <Self as kas::LayoutData>::Data,
+    step: T,
+    wrapping: bool,
+    #[widget(handler = handle_edit)]
+    edit: EditBox<SpinnerGuard<T>>,
+    #[widget(handler = handle_button)]
+    decr: TextButton<SpinnerButton>,
+    #[widget(handler = handle_button)]
+    incr: TextButton<SpinnerButton>,
+}
+
+impl<T: SpinnerType> Spinner<T> {
+    /// Construct a spinner
+    ///
+    /// Values vary within the given `range`. The buttons increment and
+    /// decrement the value by `step`, which should be positive.
+    ///
+    /// The initial value defaults to the range's lower bound but may be
+    /// specified via [`Spinner::with_value`].
+    pub fn new(range: RangeInclusive<T>, step: T) -> Self {
+        let (min, max) = range.into_inner();
+        assert!(min <= max);
+        let guard = SpinnerGuard {
+            min,
+            max,
+            value: min,
+        };
+        Spinner {
+            core: Default::default(),
+            layout_data: Default::default(),
+            step,
+            wrapping: false,
+            edit: EditBox::new(min).with_guard(guard),
+            decr: TextButton::new("-", SpinnerButton::Decr),
+            incr: TextButton::new("+", SpinnerButton::Incr),
+        }
+    }
+
+    /// Set the initial value (chain style)
+    ///
+    /// The value is clamped to the range.
+    pub fn with_value(mut self, value: T) -> Self {
+        let value = self.edit.guard.clamp(value).unwrap_or(self.edit.guard.min);
+        let _ = self.edit.set_string(value.to_string());
+        self
+    }
+
+    /// Set whether stepping wraps at the ends of the range (chain style)
+    ///
+    /// By default (`false`), stepping past either end of the range clamps to
+    /// that end. If `true`, stepping past the end of the range instead goes to
+    /// the other end.
+    pub fn with_wrapping(mut self, wrapping: bool) -> Self {
+        self.wrapping = wrapping;
+        self
+    }
+
+    /// Get the current value
+    #[inline]
+    pub fn value(&self) -> T {
+        self.edit.guard.value
+    }
+
+    /// Set the value
+    ///
+    /// The value is clamped to the range.
+    pub fn set_value(&mut self, value: T) -> TkAction {
+        let value = self.edit.guard.clamp(value).unwrap_or(self.edit.guard.min);
+        self.edit.set_string(value.to_string())
+    }
+
+    // Set value, returning a message if changed
+    fn update(&mut self, mgr: &mut Manager, value: T) -> Response<T> {
+        let old = self.edit.guard.value;
+        *mgr += self.edit.set_string(value.to_string());
+        self.edit.set_error_state(false);
+        if value != old {
+            Response::Msg(value)
+        } else {
+            Response::None
+        }
+    }
+
+    fn handle_edit(&mut self, mgr: &mut Manager, msg: SpinnerEdit<T>) -> Response<T> {
+        match msg {
+            SpinnerEdit::Value(value) => Response::Msg(value),
+            SpinnerEdit::Commit => {
+                let guard = &self.edit.guard;
+                let parsed = self.edit.get_str().trim().parse::<T>().ok();
+                let value = parsed.and_then(|v| guard.clamp(v)).unwrap_or(guard.value);
+                self.update(mgr, value)
+            }
+        }
+    }
+
+    fn handle_button(&mut self, mgr: &mut Manager, msg: SpinnerButton) -> Response<T> {
+        let guard = &self.edit.guard;
+        let (min, max, value, step) = (guard.min, guard.max, guard.value, self.step);
+        let round = |result| T::round_step(result, value, step);
+        // On overflow or beyond the range, wrap or clamp
+        let value = match msg {
+            SpinnerButton::Decr => match value.checked_sub(step).map(round) {
+                Some(result) if result >= min => result,
+                _ if self.wrapping => max,
+                _ => min,
+            },
+            SpinnerButton::Incr => match value.checked_add(step).map(round) {
+                Some(result) if result <= max => result,
+                _ if self.wrapping => min,
+                _ => max,
+            },
+        };
+        self.update(mgr, value)
+    }
+}
+
+impl<T: SpinnerType> HasStr for Spinner<T> {
+    fn get_str(&self) -> &str {
+        self.edit.get_str()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::TestWindow;
+
+    #[test]
+    fn guard_validation() {
+        let spinner = Spinner::new(0..=10, 2).with_value(15);
+        assert_eq!(spinner.value(), 10);
+        assert_eq!(spinner.get_str(), "10");
+
+        let mut edit = spinner.edit;
+        let _ = edit.set_string("4".into());
+        assert_eq!(edit.guard.value, 4);
+        assert!(!edit.has_error());
+
+        // Out of range or non-numeric input is an error and is not stored
+        let _ = edit.set_string("11".into());
+        assert!(edit.has_error());
+        let _ = edit.set_string("x".into());
+        assert!(edit.has_error());
+        assert_eq!(edit.guard.value, 4);
+
+        // An empty field is not an error
+        let _ = edit.set_string("".into());
+        assert!(!edit.has_error());
+        assert_eq!(edit.guard.value, 4);
+    }
+
+    #[test]
+    fn float_nan() {
+        let spinner = Spinner::new(0.0..=1.0, 0.1);
+        assert_eq!(spinner.edit.guard.clamp(2.0), Some(1.0));
+        assert_eq!(spinner.edit.guard.clamp(std::f64::NAN), None);
+    }
+
+    #[test]
+    fn float_steps() {
+        assert_eq!(f64::round_step(0.1 + 0.2, 0.1, 0.2), 0.3);
+        assert_eq!(f32::round_step(1.25 + 0.1, 1.25, 0.1), 1.35);
+        assert_eq!(i32::round_step(7, 5, 2), 7);
+
+        let mut spinner = Spinner::new(0.0..=1.0, 0.1);
+        let mut state = ManagerState::new();
+        let mut tkw = TestWindow::default();
+        let mut press = |spinner: &mut Spinner<f64>, button| {
+            state.with(&mut tkw, |mgr| {
+                let _ = spinner.handle_button(mgr, button);
+            });
+        };
+
+        // Repeated steps do not accumulate rounding errors
+        for _ in 0..3 {
+            press(&mut spinner, SpinnerButton::Incr);
+        }
+        assert_eq!(spinner.value(), 0.3);
+        assert_eq!(spinner.get_str(), "0.3");
+        for _ in 0..10 {
+            press(&mut spinner, SpinnerButton::Incr);
+        }
+        press(&mut spinner, SpinnerButton::Decr);
+        assert_eq!(spinner.get_str(), "0.9");
+        for _ in 0..9 {
+            press(&mut spinner, SpinnerButton::Decr);
+        }
+        assert_eq!(spinner.value(), 0.0);
+        assert_eq!(spinner.get_str(), "0");
+    }
+
+    #[test]
+    fn type_limits() {
+        let mut state = ManagerState::new();
+        let mut tkw = TestWindow::default();
+        let mut press = |spinner: &mut Spinner<i32>, button| {
+            state.with(&mut tkw, |mgr| {
+                let _ = spinner.handle_button(mgr, button);
+            });
+        };
+
+        let (min, max) = (std::i32::MIN, std::i32::MAX);
+        let mut spinner = Spinner::new(min..=max, 10).with_value(min + 5);
+        press(&mut spinner, SpinnerButton::Incr);
+        assert_eq!(spinner.value(), min + 15);
+        press(&mut spinner, SpinnerButton::Decr);
+        press(&mut spinner, SpinnerButton::Decr);
+        assert_eq!(spinner.value(), min);
+
+        let _ = spinner.set_value(max - 5);
+        press(&mut spinner, SpinnerButton::Decr);
+        assert_eq!(spinner.value(), max - 15);
+        press(&mut spinner, SpinnerButton::Incr);
+        press(&mut spinner, SpinnerButton::Incr);
+        assert_eq!(spinner.value(), max);
+
+        let mut spinner = spinner.with_wrapping(true);
+        press(&mut spinner, SpinnerButton::Incr);
+        assert_eq!(spinner.value(), min);
+        press(&mut spinner, SpinnerButton::Decr);
+        assert_eq!(spinner.value(), max);
+    }
+}
@@ -42,7 +42,7 @@ pub type BoxColumnSplitter<M> = BoxSplitter<kas::Down, M>;
 /// This is parameterised over directionality and handler message type.
 ///
 /// See documentation of [`Splitter`] type.
-pub type BoxSplitter<D, M> = Splitter<D, Box<dyn Widget<Msg = M>>>;
+pub type BoxSplitter<D, M> = Splitter<D, BoxedWidget<M>>;
 
 /// A row of widget references
 ///
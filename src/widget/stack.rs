@@ -13,7 +13,7 @@ use kas::{event, prelude::*};
 /// A stack of boxed widgets
 ///
 /// This is a parametrisation of [`Stack`].
-pub type BoxStack<M> = Stack<Box<dyn Widget<Msg = M>>>;
+pub type BoxStack<M> = Stack<BoxedWidget<M>>;
 
 /// A stack of widget references
 ///
@@ -8,39 +8,61 @@
 use smallvec::SmallVec;
 use std::fmt::{self, Debug};
 
-use kas::draw::ClipRegion;
+use kas::draw::{ClipRegion, TextClass};
 use kas::event::{self, UpdateHandle};
+use kas::geom::LogicalSize;
 use kas::layout;
 use kas::prelude::*;
 use kas::{Future, WindowId};
 
+/// Error type returned by [`Window::try_on_drop`]
+///
+/// This error occurs if a drop closure was already set.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AlreadySetError;
+
 /// The main instantiation of the [`Window`] trait.
 #[handler(send=noauto, generics = <> where W: Widget<Msg = VoidMsg>)]
+#[widget(config=noauto)]
 #[derive(Widget)]
 pub struct Window<W: Widget + 'static> {
     #[widget_core]
     core: CoreData,
     restrict_dimensions: (bool, bool),
+    size_bounds: (Option<Size>, Option<Size>),
+    logical_size_bounds: (Option<LogicalSize>, Option<LogicalSize>),
     title: String,
     #[widget]
     w: W,
-    popups: SmallVec<[(WindowId, kas::Popup); 16]>,
+    popups: SmallVec<[(WindowId, kas::Popup, Option<layout::SolveCache>); 16]>,
     drop: Option<(Box<dyn FnMut(&mut W)>, UpdateHandle)>,
+    updates: Vec<(UpdateHandle, Box<dyn FnMut(&mut W, &mut Manager)>)>,
+    close_request: Option<Box<dyn FnMut(&mut W, &mut Manager) -> bool>>,
 }
 
 impl<W: Widget> Debug for Window<W> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "Window {{ core: {:?}, restrict_dimensions: {:?}, title: {:?}, w: {:?}, popups: {:?}, drop: ",
-            self.core, self.restrict_dimensions, self.title, self.w, self.popups,
+            "Window {{ core: {:?}, restrict_dimensions: {:?}, size_bounds: {:?}, logical_size_bounds: {:?}, title: {:?}, w: {:?}, popups: {:?}, drop: ",
+            self.core,
+            self.restrict_dimensions,
+            self.size_bounds,
+            self.logical_size_bounds,
+            self.title,
+            self.w,
+            self.popups,
         )?;
         if let Some(ref d) = self.drop {
             write!(f, "Some(<closure>, {:?})", d.1)?;
         } else {
             write!(f, "None")?;
         }
-        write!(f, " }}")
+        write!(f, ", updates: [<closure>; {}]", self.updates.len())?;
+        match self.close_request {
+            Some(_) => write!(f, ", close_request: Some(<closure>) }}"),
+            None => write!(f, ", close_request: None }}"),
+        }
     }
 }
 
@@ -49,10 +71,14 @@ impl<W: Widget + Clone> Clone for Window<W> {
         Window {
             core: self.core.clone(),
             restrict_dimensions: self.restrict_dimensions.clone(),
+            size_bounds: self.size_bounds,
+            logical_size_bounds: self.logical_size_bounds,
             title: self.title.clone(),
             w: self.w.clone(),
             popups: Default::default(), // these are temporary; don't clone
             drop: None,                 // we cannot clone this!
+            updates: Vec::new(),        // nor these
+            close_request: None,
         }
     }
 }
@@ -63,10 +89,14 @@ impl<W: Widget> Window<W> {
         Window {
             core: Default::default(),
             restrict_dimensions: (true, false),
+            size_bounds: (None, None),
+            logical_size_bounds: (None, None),
             title: title.to_string(),
             w,
             popups: Default::default(),
             drop: None,
+            updates: Vec::new(),
+            close_request: None,
         }
     }
 
@@ -77,6 +107,35 @@ impl<W: Widget> Window<W> {
         self.restrict_dimensions = (min, max);
     }
 
+    /// Set explicit bounds on the window size
+    ///
+    /// Sizes are in physical pixels. For example, `min = Some(Size(400, 300))`
+    /// prevents the window from being resized below 400×300 even if its
+    /// contents could shrink further. These bounds combine with those enabled
+    /// by [`Window::set_restrict_dimensions`] (see
+    /// [`kas::Window::size_bounds`]).
+    ///
+    /// If `max` is less than `min` on either axis, it is increased to `min`.
+    ///
+    /// This should be called before the window is added to the toolkit; if
+    /// called later, bounds are applied when the window is next resized.
+    pub fn set_size_bounds(&mut self, min: Option<Size>, max: Option<Size>) {
+        let max = match (min, max) {
+            (Some(min), Some(max)) => Some(max.max(min)),
+            (_, max) => max,
+        };
+        self.size_bounds = (min, max);
+    }
+
+    /// Set explicit bounds on the window size, in logical pixels
+    ///
+    /// This is like [`Window::set_size_bounds`], except that sizes are in
+    /// logical (DPI-independent) pixels: the toolkit scales these by the
+    /// window's scale factor. Both kinds of bound may be used together.
+    pub fn set_logical_size_bounds(&mut self, min: Option<LogicalSize>, max: Option<LogicalSize>) {
+        self.logical_size_bounds = (min, max);
+    }
+
     /// Set a closure to be called on destruction, and return a future
     ///
     /// The closure `consume` is called when the window is destroyed, and yields
@@ -90,20 +149,82 @@ impl<W: Widget> Window<W> {
     /// the widget. (TODO: given unsized rvalues (rfc#1909), the closure should
     /// consume self.)
     ///
-    /// Panics if called more than once. In case the window is cloned, this
+    /// Panics if called more than once (see [`Window::try_on_drop`] for a
+    /// non-panicking alternative). In case the window is cloned, this
     /// closure is *not* inherited by the clone: in that case, `on_drop` may be
     /// called on the clone.
     pub fn on_drop<T>(
         &mut self,
         consume: Box<dyn FnMut(&mut W) -> T>,
     ) -> (Future<T>, UpdateHandle) {
+        self.try_on_drop(consume)
+            .unwrap_or_else(|_| panic!("Window::on_drop: attempt to set multiple drop closures"))
+    }
+
+    /// Set a closure to be called on destruction, and return a future
+    ///
+    /// This is identical to [`Window::on_drop`] except that, if a closure was
+    /// already set, it returns an error (leaving the existing closure
+    /// installed) instead of panicking.
+    pub fn try_on_drop<T>(
+        &mut self,
+        consume: Box<dyn FnMut(&mut W) -> T>,
+    ) -> Result<(Future<T>, UpdateHandle), AlreadySetError> {
         if self.drop.is_some() {
-            panic!("Window::on_drop: attempt to set multiple drop closures");
+            return Err(AlreadySetError);
         }
         let (future, finish) = Future::new_box_fnmut(consume);
         let update = UpdateHandle::new();
         self.drop = Some((finish, update));
-        (future, update)
+        Ok((future, update))
+    }
+
+    /// Set a closure to be called on each update of `handle`
+    ///
+    /// The closure `update` is called with the window's child widget each time
+    /// [`Manager::trigger_update`] is called with `handle`, allowing other
+    /// parts of the application (for example, background tasks) to push data
+    /// into the widget tree. The closure may use its [`Manager`] argument to
+    /// request redraws or resizes (e.g. `*mgr += TkAction::Redraw`).
+    ///
+    /// The closure runs during event processing, while the window's widgets
+    /// are borrowed: it cannot access other windows and must not expect
+    /// events it triggers to be delivered before it returns. Calling
+    /// [`Manager::trigger_update`] with `handle` from within the closure
+    /// causes another call later, not recursion.
+    ///
+    /// Multiple closures may be registered, including for the same handle.
+    /// In case the window is cloned, these closures are *not* inherited by
+    /// the clone (as with [`Window::on_drop`]).
+    pub fn on_update(
+        &mut self,
+        handle: UpdateHandle,
+        update: Box<dyn FnMut(&mut W, &mut Manager)>,
+    ) {
+        self.updates.push((handle, update));
+    }
+
+    /// Set a closure to be called when the user requests closure
+    ///
+    /// The closure `request` is called with the window's child widget when
+    /// the user asks to close the window (e.g. via the title bar). It should
+    /// return true to allow closure or false to veto it, for example in order
+    /// to ask the user to save changes first. In the latter case the
+    /// application may close the window later via [`TkAction::Close`] (this
+    /// and [`TkAction::CloseAll`] do not consult `request`).
+    ///
+    /// Any previously set closure is replaced. In case the window is cloned,
+    /// the closure is *not* inherited by the clone.
+    pub fn on_close_request(&mut self, request: Box<dyn FnMut(&mut W, &mut Manager) -> bool>) {
+        self.close_request = Some(request);
+    }
+}
+
+impl<W: Widget> WidgetConfig for Window<W> {
+    fn configure(&mut self, mgr: &mut Manager) {
+        for (handle, _) in &self.updates {
+            mgr.update_on_handle(*handle, self.id());
+        }
     }
 }
 
@@ -129,6 +250,10 @@ impl<W: Widget> Layout for Window<W> {
             if let Some(id) = self.w.find(popup.1.id).and_then(|w| w.find_id(coord)) {
                 return Some(id);
             }
+            if popup.1.modal {
+                // Nothing beneath a modal pop-up may be hit
+                return Some(self.id());
+            }
         }
         self.w.find_id(coord).or(Some(self.id()))
     }
@@ -144,6 +269,19 @@ impl<W: Widget> Layout for Window<W> {
                     .map(|w| w.draw(draw_handle, mgr, disabled));
             });
         }
+        if let Some(insp) = mgr.inspection() {
+            let class = ClipRegion::Popup;
+            draw_handle.clip_region(self.core.rect, Coord::ZERO, class, &mut |draw_handle| {
+                draw_handle.outer_frame(insp.rect);
+                // The summary is drawn over a menu frame at the widget's origin
+                let frame = draw_handle.size_handle(|size_handle| size_handle.menu_frame());
+                let bounds = insp.text.env().bounds;
+                let size = Size(bounds.0.ceil() as u32, bounds.1.ceil() as u32);
+                draw_handle.menu_frame(Rect::new(insp.rect.pos, size + frame + frame));
+                let pos = insp.rect.pos + frame;
+                draw_handle.text(pos, &insp.text, TextClass::Label, false);
+            });
+        }
     }
 }
 
@@ -152,7 +290,17 @@ impl<W: Widget<Msg = VoidMsg> + 'static> event::SendEvent for Window<W> {
         if !self.is_disabled() && id <= self.w.id() {
             return self.w.send(mgr, id, event);
         }
-        Response::Unhandled(event)
+        match event {
+            Event::HandleUpdate { handle, .. } if id == self.id() => {
+                for (h, update) in &mut self.updates {
+                    if *h == handle {
+                        update(&mut self.w, mgr);
+                    }
+                }
+                Response::None
+            }
+            event => Response::Unhandled(event),
+        }
     }
 }
 
@@ -165,9 +313,17 @@ impl<W: Widget<Msg = VoidMsg> + 'static> kas::Window for Window<W> {
         self.restrict_dimensions
     }
 
+    fn size_bounds(&self) -> (Option<Size>, Option<Size>) {
+        self.size_bounds
+    }
+
+    fn logical_size_bounds(&self) -> (Option<LogicalSize>, Option<LogicalSize>) {
+        self.logical_size_bounds
+    }
+
     fn add_popup(&mut self, mgr: &mut Manager, id: WindowId, popup: kas::Popup) {
         let index = self.popups.len();
-        self.popups.push((id, popup));
+        self.popups.push((id, popup, None));
         mgr.size_handle(|size_handle| self.resize_popup(size_handle, index));
         mgr.send_action(TkAction::Redraw);
     }
@@ -188,6 +344,19 @@ impl<W: Widget<Msg = VoidMsg> + 'static> kas::Window for Window<W> {
         }
     }
 
+    fn invalidate_popup_rules(&mut self) {
+        for popup in &mut self.popups {
+            popup.2 = None;
+        }
+    }
+
+    fn handle_close_request(&mut self, mgr: &mut Manager) -> bool {
+        match self.close_request.as_mut() {
+            Some(request) => request(&mut self.w, mgr),
+            None => true,
+        }
+    }
+
     fn handle_closure(&mut self, mgr: &mut Manager) {
         if let Some((mut consume, update)) = self.drop.take() {
             consume(&mut self.w);
@@ -204,16 +373,9 @@ fn find_rect(widget: &dyn WidgetConfig, id: WidgetId) -> Option<Rect> {
         return None;
     }
 
-    for i in 0..widget.len() {
-        if let Some(w) = widget.get(i) {
-            if id > w.id() {
-                continue;
-            }
-            return find_rect(w, id).map(|rect| rect - widget.translation(i));
-        }
-        break;
-    }
-    None
+    let index = kas::find_child_index(widget, id)?;
+    let w = widget.get(index).unwrap();
+    find_rect(w, id).map(|rect| rect - widget.translation(index))
 }
 
 impl<W: Widget> Window<W> {
@@ -221,11 +383,23 @@ impl<W: Widget> Window<W> {
         // Notation: p=point/coord, s=size, m=margin
         // r=window/root rect, c=anchor rect
         let r = self.core.rect;
-        let popup = &mut self.popups[index].1;
+        let (_, popup, cache) = &mut self.popups[index];
 
         let c = find_rect(self.w.as_widget(), popup.parent).unwrap();
+        let c = match popup.anchor {
+            Some(coord) => {
+                // Translate from the parent's coordinate space
+                let offset = c.pos - self.w.find(popup.parent).unwrap().rect().pos;
+                Rect::new(coord + offset, Size::ZERO)
+            }
+            None => c,
+        };
         let widget = self.w.find_mut(popup.id).unwrap();
-        let mut cache = layout::SolveCache::find_constraints(widget, size_handle);
+        // Size rules are cached until invalidated; only placement is updated
+        if cache.is_none() {
+            *cache = Some(layout::SolveCache::find_constraints(widget, size_handle));
+        }
+        let cache = cache.as_mut().unwrap();
         let ideal = cache.ideal(false);
         let m = cache.margins();
 
@@ -253,7 +427,11 @@ impl<W: Widget> Window<W> {
             let size = ideal.max(cs).min(rs);
             (pos, size)
         };
-        let rect = if popup.direction.is_horizontal() {
+        let rect = if popup.anchor.is_some() {
+            let (x, w) = place_in(r.pos.0, r.size.0, c.pos.0, 0, ideal.0, m.horiz);
+            let (y, h) = place_in(r.pos.1, r.size.1, c.pos.1, 0, ideal.1, m.vert);
+            Rect::new(Coord(x, y), Size(w, h))
+        } else if popup.direction.is_horizontal() {
             let (x, w) = place_in(r.pos.0, r.size.0, c.pos.0, c.size.0, ideal.0, m.horiz);
             let (y, h) = place_out(r.pos.1, r.size.1, c.pos.1, c.size.1, ideal.1);
             Rect::new(Coord(x, y), Size(w, h))
@@ -266,3 +444,303 @@ impl<W: Widget> Window<W> {
         cache.apply_rect(widget, size_handle, rect, false);
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::{TestManager, TestSizeHandle};
+    use crate::widget::{Column, Filler, LazyStack, StrLabel};
+    use std::cell::Cell;
+    use std::num::NonZeroU32;
+    use std::rc::Rc;
+
+    #[test]
+    fn find_in_deep_tree() {
+        use crate::widget::BoxColumn;
+        let _ = crate::text::fonts::fonts().load_default();
+
+        // Ten nested columns, each with the next level above a filler
+        let mut w: Box<dyn Widget<Msg = VoidMsg>> = Box::new(StrLabel::new("x"));
+        for _ in 0..10 {
+            let filler: Box<dyn Widget<Msg = VoidMsg>> = Box::new(Filler::new());
+            w = Box::new(BoxColumn::new(vec![w, filler]));
+        }
+        let mut id = WidgetId::FIRST;
+        w.walk_mut(|w| {
+            w.core_data_mut().id = id;
+            id = id.next();
+        });
+
+        let mut size_handle = TestSizeHandle::new();
+        let horiz = w.size_rules(&mut size_handle, AxisInfo::new(false, None));
+        let axis = AxisInfo::new(true, Some(horiz.min_size()));
+        let vert = w.size_rules(&mut size_handle, axis);
+        let size = Size(horiz.min_size(), vert.min_size());
+        w.set_rect(Rect::new(Coord::ZERO, size), AlignHints::NONE);
+
+        // The label is the first widget to be assigned an identifier
+        let rect = Rect::new(Coord::ZERO, Size(8, 16));
+        assert_eq!(find_rect(w.as_widget(), WidgetId::FIRST), Some(rect));
+        assert_eq!(w.find_id(Coord(4, 8)), Some(WidgetId::FIRST));
+        assert_eq!(find_rect(w.as_widget(), w.id()), Some(w.rect()));
+        assert_eq!(find_rect(w.as_widget(), w.id().next()), None);
+    }
+
+    #[test]
+    fn try_on_drop_twice() {
+        let mut window = Window::new("test", Filler::new());
+        let (future, update) = window.try_on_drop(Box::new(|_| 1)).unwrap();
+        let result = window.try_on_drop(Box::new(|_| 2));
+        assert_eq!(result.err(), Some(AlreadySetError));
+
+        // The first closure remains installed
+        let (mut consume, handle) = window.drop.take().unwrap();
+        assert_eq!(handle, update);
+        consume(&mut window.w);
+        drop(consume);
+        assert_eq!(future.try_finish().ok(), Some(1));
+    }
+
+    #[test]
+    fn popup_rules_cached() {
+        let _ = crate::text::fonts::fonts().load_default();
+
+        let labels = vec![StrLabel::new("parent"), StrLabel::new("pop-up")];
+        let mut window = Window::new("test", Column::new(labels));
+        let mut id = WidgetId::FIRST;
+        window.walk_mut(|w| {
+            w.core_data_mut().id = id;
+            id = id.next();
+        });
+        let mut size_handle = TestSizeHandle::new();
+        let rect = Rect::new(Coord::ZERO, Size(200, 100));
+        let mut cache = layout::SolveCache::find_constraints(&mut window, &mut size_handle);
+        cache.apply_rect(&mut window, &mut size_handle, rect, false);
+
+        let popup = kas::Popup {
+            id: window.w[1].id(),
+            parent: window.w[0].id(),
+            direction: Direction::Down,
+            modal: false,
+            anchor: None,
+        };
+        let window_id = WindowId::new(NonZeroU32::new(1).unwrap());
+        window.popups.push((window_id, popup, None));
+
+        size_handle.text_bound_calls = 0;
+        kas::Window::resize_popups(&mut window, &mut size_handle);
+        let calls = size_handle.text_bound_calls;
+        assert!(calls > 0);
+
+        // Without content changes, size rules are not recalculated
+        for _ in 0..10 {
+            kas::Window::resize_popups(&mut window, &mut size_handle);
+        }
+        assert_eq!(size_handle.text_bound_calls, calls);
+
+        kas::Window::invalidate_popup_rules(&mut window);
+        kas::Window::resize_popups(&mut window, &mut size_handle);
+        assert_eq!(size_handle.text_bound_calls, 2 * calls);
+    }
+
+    #[test]
+    fn stacked_popups() {
+        let _ = crate::text::fonts::fonts().load_default();
+
+        let labels = vec!["parent", "first", "second"];
+        let labels = labels.into_iter().map(StrLabel::new).collect();
+        let mut test = TestManager::new(Window::new("test", Column::new(labels)));
+        test.set_rect(Rect::new(Coord::ZERO, Size(200, 100)));
+        let ids: Vec<_> = test.widget.w.iter().map(|w| w.id()).collect();
+        let window_id = |n| WindowId::new(NonZeroU32::new(n).unwrap());
+        let popup = |id, parent, direction| kas::Popup {
+            id,
+            parent,
+            direction,
+            modal: false,
+            anchor: None,
+        };
+
+        // The second pop-up is anchored within the first
+        let (state, tkw, window) = (&mut test.state, &mut test.tkw, &mut test.widget);
+        state.with(tkw, |mgr| {
+            let first = popup(ids[1], ids[0], Direction::Down);
+            kas::Window::add_popup(window, mgr, window_id(1), first);
+            let second = popup(ids[2], ids[1], Direction::Right);
+            kas::Window::add_popup(window, mgr, window_id(2), second);
+        });
+        assert_eq!(test.update(), TkAction::Redraw);
+
+        let rects = |test: &TestManager<Window<_>>| -> Vec<Rect> {
+            test.widget.w.iter().map(|w| w.rect()).collect()
+        };
+        let placed = rects(&test);
+        let (parent, first, second) = (placed[0], placed[1], placed[2]);
+        assert_eq!(first.pos.0, parent.pos.0);
+        assert!(first.pos.1 >= parent.pos.1 + parent.size.1 as i32);
+        assert!(second.pos.0 >= first.pos.0 + first.size.0 as i32);
+        assert_eq!(second.pos.1, first.pos.1);
+
+        // Resizing all pop-ups places them identically
+        let mut size_handle = TestSizeHandle::new();
+        kas::Window::resize_popups(&mut test.widget, &mut size_handle);
+        assert_eq!(rects(&test), placed);
+
+        // Removing the first pop-up does not move the second
+        let (state, tkw, window) = (&mut test.state, &mut test.tkw, &mut test.widget);
+        state.with(tkw, |mgr| {
+            kas::Window::remove_popup(window, mgr, window_id(1))
+        });
+        assert_eq!(test.update(), TkAction::RegionMoved);
+        assert_eq!(test.widget.popups.len(), 1);
+        kas::Window::resize_popups(&mut test.widget, &mut size_handle);
+        assert_eq!(rects(&test)[2], second);
+    }
+
+    #[test]
+    fn anchored_popup() {
+        let _ = crate::text::fonts::fonts().load_default();
+
+        let labels = vec![StrLabel::new("parent"), StrLabel::new("pop-up")];
+        let mut test = TestManager::new(Window::new("test", Column::new(labels)));
+        test.set_rect(Rect::new(Coord::ZERO, Size(200, 100)));
+        let (parent, id) = (test.widget.w[0].id(), test.widget.w[1].id());
+        let mut place = |direction, anchor: Coord| -> Rect {
+            let popup = kas::Popup {
+                id,
+                parent,
+                direction,
+                modal: false,
+                anchor: Some(anchor),
+            };
+            let window = &mut test.widget;
+            window.popups.clear();
+            window
+                .popups
+                .push((WindowId::new(NonZeroU32::new(1).unwrap()), popup, None));
+            kas::Window::resize_popups(window, &mut TestSizeHandle::new());
+            window.w[1].rect()
+        };
+
+        // The pop-up opens below and to the right of the point...
+        let anchor = Coord(100, 50);
+        let rect = place(Direction::Down, anchor);
+        assert!(rect.pos.0 >= anchor.0 && rect.pos.1 >= anchor.1);
+
+        // ...or above and to the left if the direction is reversed
+        for &direction in &[Direction::Up, Direction::Left] {
+            let end = place(direction, anchor).pos_end();
+            assert!(end.0 <= anchor.0 && end.1 <= anchor.1);
+        }
+
+        // Near the window's edge, placement flips to stay within the window
+        let anchor = Coord(195, 95);
+        let end = place(Direction::Down, anchor).pos_end();
+        assert!(end.0 <= anchor.0 && end.1 <= anchor.1);
+    }
+
+    #[test]
+    fn find_rect_lazy_stack() {
+        let _ = crate::text::fonts::fonts().load_default();
+
+        let stack = || {
+            LazyStack::new(0)
+                .with_page(|| StrLabel::new("a"))
+                .with_page(|| StrLabel::new("b"))
+                .with_page(|| StrLabel::new("c"))
+        };
+        let window = Window::new("test", Column::new(vec![stack(), stack()]));
+        let mut test = TestManager::new(window);
+        // Pages 0 and 2 are constructed; page 1 is not
+        let _ = test.widget.w[0].set_active(2);
+        test.configure();
+        assert!(!test.widget.w[0].is_constructed(1));
+
+        let window = &mut test.widget;
+        let mut size_handle = TestSizeHandle::new();
+        let rect = Rect::new(Coord::ZERO, Size(200, 100));
+        let mut cache = layout::SolveCache::find_constraints(&mut *window, &mut size_handle);
+        cache.apply_rect(&mut *window, &mut size_handle, rect, false);
+
+        let page = window.w[0].active().unwrap();
+        let (id, rect) = (page.id(), page.rect());
+        assert_eq!(find_rect(window.as_widget(), id), Some(rect));
+        assert_eq!(window.find(id).map(|w| w.id()), Some(id));
+        assert!(window.find(window.w[1].id()).is_some());
+
+        // A pop-up may be anchored to a widget after the unconstructed page
+        let popup = kas::Popup {
+            id: window.w[1].id(),
+            parent: id,
+            direction: Direction::Down,
+            modal: false,
+            anchor: None,
+        };
+        let window_id = WindowId::new(NonZeroU32::new(1).unwrap());
+        window.popups.push((window_id, popup, None));
+        kas::Window::resize_popups(&mut *window, &mut size_handle);
+        assert!(window.w[1].rect().pos.1 >= rect.pos.1 + rect.size.1 as i32);
+    }
+
+    #[test]
+    fn inspector() {
+        use crate::test_util::{DrawCommand, RecordingDrawHandle};
+        use crate::widget::CheckBoxBare;
+        let _ = crate::text::fonts::fonts().load_default();
+
+        let boxes = vec![CheckBoxBare::new(), CheckBoxBare::new()];
+        let mut test = TestManager::new(Window::new("test", Column::new(boxes)));
+        let rect = Rect::new(Coord::ZERO, Size(200, 100));
+        test.set_rect(rect);
+        let (target, target_rect) = (test.widget.w[1].id(), test.widget.w[1].rect());
+        test.with(|mgr, _| {
+            mgr.set_inspector(true);
+            mgr.set_nav_focus(target);
+        });
+
+        // Details are computed only when about to be drawn, and only once
+        assert!(test.state.inspection().is_none());
+        let mut size_handle = TestSizeHandle::new();
+        let widget = test.widget.as_widget_mut();
+        test.state.update_inspector(widget, &mut size_handle);
+        let insp = test.state.inspection().unwrap();
+        assert_eq!((insp.id, insp.rect), (target, target_rect));
+        let calls = size_handle.text_bound_calls;
+        let widget = test.widget.as_widget_mut();
+        test.state.update_inspector(widget, &mut size_handle);
+        assert_eq!(size_handle.text_bound_calls, calls);
+
+        // Drawing uses theme elements, not fixed colours
+        let mut draw_handle = RecordingDrawHandle::new(rect);
+        test.widget.draw(&mut draw_handle, &test.state, false);
+        let commands = draw_handle.take_commands();
+        assert!(commands.contains(&DrawCommand::OuterFrame(target_rect)));
+        assert!(commands
+            .iter()
+            .any(|c| matches!(c, DrawCommand::MenuFrame(_))));
+        assert!(!commands.iter().any(|c| matches!(c, DrawCommand::Frame(..))));
+
+        // Any update makes the inspection stale
+        test.update();
+        assert!(test.state.inspection().is_none());
+    }
+
+    #[test]
+    fn close_request_veto() {
+        let allow = Rc::new(Cell::new(false));
+        let mut window = Window::new("test", Filler::new());
+        let allow2 = allow.clone();
+        window.on_close_request(Box::new(move |_, _| allow2.get()));
+        let mut test = TestManager::new(window);
+        fn request(test: &mut TestManager<Window<Filler>>) -> TkAction {
+            let window = &mut test.widget;
+            test.state
+                .with(&mut test.tkw, |mgr| mgr.handle_close_request(window));
+            test.update()
+        }
+
+        assert_eq!(request(&mut test), TkAction::None);
+        allow.set(true);
+        assert_eq!(request(&mut test), TkAction::Close);
+    }
+}